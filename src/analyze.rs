@@ -0,0 +1,196 @@
+use crate::{EmbeddedAsset, WasmBinary};
+use serde::Serialize;
+
+/// Strings shorter than this are too common to be worth reporting.
+pub const DEFAULT_MIN_STRING_LENGTH: usize = 64;
+
+/// Share of the module above which `//go:embed` assets are flagged.
+pub const EMBED_WARNING_SHARE: f64 = 0.25;
+
+const PREVIEW_LENGTH: usize = 72;
+
+/// What a string found in the data section most likely is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StringCategory {
+    /// Zoneinfo names or TZif payloads, usually from `time/tzdata`
+    TimezoneData,
+    /// Source paths baked in for panics and runtime.Caller
+    FilePath,
+    /// Error message tables
+    ErrorMessage,
+    Text,
+}
+
+impl StringCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StringCategory::TimezoneData => "timezone data",
+            StringCategory::FilePath => "file paths",
+            StringCategory::ErrorMessage => "error messages",
+            StringCategory::Text => "other text",
+        }
+    }
+}
+
+/// A run of printable bytes inside a data segment.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedString {
+    /// Linear memory address, for active segments with a constant offset
+    pub memory_address: Option<i64>,
+    pub file_offset: usize,
+    pub length: usize,
+    pub category: StringCategory,
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryTotal {
+    pub category: StringCategory,
+    pub count: usize,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeAnalysis {
+    pub wasm_size: usize,
+    pub code_size: usize,
+    pub data_size: usize,
+    /// Largest strings, longest first
+    pub largest_strings: Vec<EmbeddedString>,
+    pub string_count: usize,
+    pub string_bytes: usize,
+    pub categories: Vec<CategoryTotal>,
+    /// `//go:embed` assets of the source package, when it was given
+    pub embedded_assets: Vec<EmbeddedAsset>,
+}
+
+impl SizeAnalysis {
+    pub fn embedded_asset_bytes(&self) -> u64 {
+        self.embedded_assets
+            .iter()
+            .map(|asset| asset.total_size)
+            .sum()
+    }
+
+    /// Embedded files are stored verbatim in the data section, so their size
+    /// is a lower bound on the data they account for.
+    pub fn embedded_assets_dominate(&self) -> bool {
+        let embedded_bytes = self.embedded_asset_bytes() as f64;
+        embedded_bytes > 0.0
+            && (embedded_bytes >= self.wasm_size as f64 * EMBED_WARNING_SHARE
+                || embedded_bytes >= self.data_size as f64 * 0.5)
+    }
+}
+
+/// Looks for binary bloat in the data segments of a core module.
+pub struct SizeAnalyzer;
+
+impl SizeAnalyzer {
+    pub fn analyze(binary: &WasmBinary, min_length: usize, top: usize) -> SizeAnalysis {
+        let mut strings = Self::embedded_strings(binary, min_length);
+        strings.sort_by_key(|string| std::cmp::Reverse(string.length));
+
+        let mut categories: Vec<CategoryTotal> = Vec::new();
+        for string in &strings {
+            match categories
+                .iter_mut()
+                .find(|total| total.category == string.category)
+            {
+                Some(total) => {
+                    total.count += 1;
+                    total.bytes += string.length;
+                }
+                None => categories.push(CategoryTotal {
+                    category: string.category,
+                    count: 1,
+                    bytes: string.length,
+                }),
+            }
+        }
+        categories.sort_by_key(|total| std::cmp::Reverse(total.bytes));
+
+        SizeAnalysis {
+            wasm_size: binary.size,
+            code_size: binary.section_size("code"),
+            data_size: binary.section_size("data"),
+            string_count: strings.len(),
+            string_bytes: strings.iter().map(|string| string.length).sum(),
+            largest_strings: strings.into_iter().take(top).collect(),
+            categories,
+            embedded_assets: Vec::new(),
+        }
+    }
+
+    /// Runs of at least `min_length` printable ASCII or UTF-8 bytes in the data segments.
+    ///
+    /// Go lays string constants out back to back without terminators, so a
+    /// single run often spans a whole table of related strings.
+    pub fn embedded_strings(binary: &WasmBinary, min_length: usize) -> Vec<EmbeddedString> {
+        let mut strings = Vec::new();
+        for segment in &binary.data_segments {
+            let mut start = 0;
+            for (index, &byte) in segment.bytes.iter().chain([&0]).enumerate() {
+                let printable = matches!(byte, b' '..=b'~' | b'\t' | b'\n' | b'\r' | 0x80..);
+                if printable {
+                    continue;
+                }
+                if index - start >= min_length {
+                    let text = String::from_utf8_lossy(&segment.bytes[start..index]);
+                    strings.push(EmbeddedString {
+                        memory_address: segment.memory_offset.map(|offset| offset + start as i64),
+                        file_offset: segment.file_offset + start,
+                        length: index - start,
+                        category: Self::categorize(&text),
+                        preview: Self::preview(&text),
+                    });
+                }
+                start = index + 1;
+            }
+        }
+        strings
+    }
+
+    fn categorize(text: &str) -> StringCategory {
+        let zoneinfo_names = ["America/", "Europe/", "Asia/", "Africa/", "Pacific/"]
+            .iter()
+            .filter(|region| text.contains(*region))
+            .count();
+        if text.contains("TZif") || zoneinfo_names >= 3 {
+            return StringCategory::TimezoneData;
+        }
+
+        if text.contains(".go:") || text.contains("/src/") || text.contains("/pkg/mod/") {
+            return StringCategory::FilePath;
+        }
+
+        let lowercase = text.to_lowercase();
+        if [
+            "error",
+            "invalid",
+            "failed",
+            "unexpected",
+            "panic",
+            "cannot",
+        ]
+        .iter()
+        .any(|word| lowercase.contains(word))
+        {
+            return StringCategory::ErrorMessage;
+        }
+
+        StringCategory::Text
+    }
+
+    fn preview(text: &str) -> String {
+        let mut preview: String = text
+            .chars()
+            .take(PREVIEW_LENGTH)
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        if text.chars().count() > PREVIEW_LENGTH {
+            preview.push('…');
+        }
+        preview
+    }
+}
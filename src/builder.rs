@@ -1,10 +1,34 @@
 use crate::{
-    CommandExecutor, CompileConfig, CompileResult, PathResolver, Plugin, PluginCapabilities,
-    PluginInfo, PluginResult, PluginSource, PluginType, WasmBuilder,
+    Artifact, ArtifactKind, BugReport, BuildCache, BuildManifest, BuildMetrics, BuildMode,
+    BuildSettings, BuildSpan, BuildWarning, CommandExecutor, CommandLog, CompileConfig,
+    CompileResult, ComponentTools, DetectSettings, Dirs, ExportDirective, ExportScanner,
+    ExtismValidator, FailureSummary, FrameworkRegistry, FreestandingValidator, GoModFile,
+    GoVersion, HashedArtifacts, HermeticEnvironment, HostInfo, InterruptHandler, LlvmFeature,
+    LoaderGenerator, Logger, Minifier, OptimizationLevel, OptimizationPipeline, PathResolver,
+    Plugin, PluginCapabilities, PluginInfo, PluginResult, PluginSource, PluginType, ProjectMatch,
+    ProvenanceGenerator, ReproductionScript, SmokeTest, SourceMirror, SourceWatcher,
+    StateDirectory, TargetType, ThreadsTarget, ToolCommand, ToolRegistry, Toolchain,
+    ToolchainEnvironment, VersionStamp, WapcValidator, WasmBuilder, WatchEvent, WitGenerator,
+    WorkerGenerator, CONTENT_HASH_LENGTH, EXTISM_PLUGIN_FILENAME, PROJECT_CONFIG_FILENAME,
+    THREADS_LLVM_FEATURES, WASI_HTTP_WORLD, WATCH_INTERVAL,
 };
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::ffi::{OsStr, OsString};
 use std::fs;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Other plugins' project manifests, which lower the claim on loose Go files.
+const OTHER_LANGUAGE_MANIFESTS: &[(&str, &str)] = &[
+    ("Cargo.toml", "Rust"),
+    ("package.json", "JavaScript"),
+    ("pyproject.toml", "Python"),
+    ("build.zig", "Zig"),
+    ("CMakeLists.txt", "C/C++"),
+    ("asconfig.json", "AssemblyScript"),
+];
 
 #[derive(Debug, Deserialize)]
 struct CargoToml {
@@ -59,8 +83,13 @@ struct WasmPluginDependencies {
     tools: Vec<String>,
 }
 
+#[derive(Clone)]
 pub struct GoPlugin {
     plugin_info: PluginInfo,
+    /// Set by [`Plugin::initialize`]
+    host_info: Option<HostInfo>,
+    /// Set by [`Plugin::configure`]; applied over each build's [`CompileConfig`]
+    host_settings: Option<BuildSettings>,
 }
 
 impl GoPlugin {
@@ -68,7 +97,22 @@ impl GoPlugin {
         let plugin_info = Self::load_plugin_info()
             .expect("Failed to load plugin configuration from Cargo.toml [package.metadata.wasm-plugin] section");
 
-        Self { plugin_info }
+        Self {
+            plugin_info,
+            host_info: None,
+            host_settings: None,
+        }
+    }
+
+    /// Live reload: builds `configuration`, then rebuilds whenever its `.go`
+    /// sources or module files change, reporting each change and build to
+    /// `on_event` until it breaks.
+    pub fn watch(
+        &self,
+        configuration: &CompileConfig,
+        on_event: impl FnMut(WatchEvent) -> ControlFlow<()>,
+    ) {
+        SourceWatcher::rebuild(self, configuration, WATCH_INTERVAL, on_event);
     }
 
     fn load_plugin_info() -> Result<PluginInfo, Box<dyn std::error::Error>> {
@@ -120,7 +164,7 @@ impl GoPlugin {
         }
     }
 
-    fn find_entry_file(&self, project_directory: &str) -> PluginResult<PathBuf> {
+    fn find_entry_file(&self, project_directory: &Path) -> PluginResult<PathBuf> {
         let entry_file_candidates: Vec<&str> = self
             .plugin_info
             .entry_files
@@ -129,7 +173,7 @@ impl GoPlugin {
             .collect();
 
         for entry_filename in entry_file_candidates.iter() {
-            let entry_file_path = Path::new(project_directory).join(entry_filename);
+            let entry_file_path = project_directory.join(entry_filename);
             if entry_file_path.exists() {
                 return Ok(entry_file_path);
             }
@@ -149,6 +193,416 @@ impl GoPlugin {
             candidates: self.plugin_info.entry_files.clone(),
         })
     }
+
+    fn require_go_version(required: GoVersion, feature: &str) -> PluginResult<()> {
+        match GoVersion::installed() {
+            Some(installed) if installed >= required => Ok(()),
+            Some(installed) => Err(crate::PluginError::UnsupportedToolchainVersion {
+                reason: format!(
+                    "{feature} requires Go {required} or newer, but Go {installed} is installed"
+                ),
+            }),
+            None => Err(crate::PluginError::UnsupportedToolchainVersion {
+                reason: format!("{feature} requires Go {required} or newer, but the installed Go version could not be determined"),
+            }),
+        }
+    }
+
+    /// Fails when the toolchain's Go is older than the `go` directive in go.mod,
+    /// unless Go's automatic toolchain switching will fetch a new enough one.
+    fn require_module_go_version(compile_configuration: &CompileConfig) -> PluginResult<()> {
+        let toolchain = compile_configuration.toolchain;
+        let Some(required) =
+            GoModFile::read(&compile_configuration.project_path).and_then(|go_mod| go_mod.go)
+        else {
+            return Ok(());
+        };
+        // An unknown version is left to the toolchain to report
+        let Some(installed) = GoVersion::for_toolchain(toolchain) else {
+            return Ok(());
+        };
+        if installed >= required {
+            return Ok(());
+        }
+
+        if Self::switches_toolchain(compile_configuration, installed) {
+            Logger::info(format_args!(
+                "⬇️  go.mod requires Go {required}; Go {installed} will switch to it automatically (GOTOOLCHAIN)"
+            ));
+            return Ok(());
+        }
+        let installed_go = match toolchain {
+            Toolchain::TinyGo => format!("Go {installed} (used by TinyGo)"),
+            Toolchain::Go => format!("Go {installed}"),
+        };
+        Err(crate::PluginError::UnsupportedToolchainVersion {
+            reason: format!(
+                "your {installed_go} is older than the module's required {required} (go directive in go.mod)"
+            ),
+        })
+    }
+
+    /// Whether Go will fetch a newer toolchain go.mod asks for (Go 1.21+ with
+    /// GOTOOLCHAIN not set to `local`).
+    fn switches_toolchain(compile_configuration: &CompileConfig, installed: GoVersion) -> bool {
+        compile_configuration.toolchain == Toolchain::Go
+            && installed >= GoVersion::new(1, 21, 0)
+            && !compile_configuration.no_network
+            && std::env::var("GOTOOLCHAIN").map_or(true, |value| value != "local")
+    }
+
+    /// Compares the go.mod `toolchain` directive with the toolchain's Go: an
+    /// exact pin goes through GOTOOLCHAIN, an older local Go switches
+    /// automatically where it can, and any other difference is a warning.
+    fn check_module_toolchain(compile_configuration: &CompileConfig) -> PluginResult<()> {
+        let toolchain = compile_configuration.toolchain;
+        let Some(go_mod) = GoModFile::read(&compile_configuration.project_path) else {
+            return Ok(());
+        };
+        let (Some(pinned_name), Some(pinned)) = (&go_mod.toolchain, go_mod.toolchain_version())
+        else {
+            return Ok(());
+        };
+        let Some(installed) = GoVersion::for_toolchain(toolchain) else {
+            return Ok(());
+        };
+
+        if toolchain == Toolchain::Go && compile_configuration.exact_toolchain {
+            if compile_configuration.no_network && installed != pinned {
+                return Err(crate::PluginError::UnsupportedToolchainVersion {
+                    reason: format!(
+                        "--exact-toolchain needs {pinned_name} from go.mod, but Go {installed} is installed and --no-network forbids the download"
+                    ),
+                });
+            }
+            if installed != pinned {
+                Logger::info(format_args!(
+                    "📌 Building with {pinned_name} as pinned in go.mod (GOTOOLCHAIN)"
+                ));
+            }
+        } else if installed < pinned && Self::switches_toolchain(compile_configuration, installed) {
+            Logger::info(format_args!(
+                "⬇️  go.mod pins {pinned_name}; Go {installed} will switch to it automatically (GOTOOLCHAIN)"
+            ));
+        } else if installed != pinned {
+            let local = match toolchain {
+                Toolchain::TinyGo => format!("TinyGo builds with Go {installed}"),
+                Toolchain::Go => format!("the local Go is {installed}"),
+            };
+            Logger::warn(format_args!(
+                "⚠️  go.mod pins toolchain {pinned_name}, but {local}; the build may differ from the module authors'"
+            ));
+            if toolchain == Toolchain::Go && installed > pinned {
+                Logger::warn(format_args!(
+                    "   Use --exact-toolchain to build with {pinned_name}"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Variables from [`HostInfo::environment`], set before the build's own.
+    fn host_environment(&self) -> Vec<(&str, &OsStr)> {
+        self.host_info
+            .iter()
+            .flat_map(|host_info| &host_info.environment)
+            .map(|(key, value)| (key.as_str(), OsStr::new(value)))
+            .collect()
+    }
+
+    fn tidy_module(&self, compile_configuration: &CompileConfig) -> PluginResult<()> {
+        let project_path = &compile_configuration.project_path;
+        if GoModFile::read(project_path).is_none() {
+            Logger::warn(format_args!(
+                "⚠️  Skipping go mod tidy: no go.mod in {}",
+                project_path.display()
+            ));
+            return Ok(());
+        }
+        let hermetic_environment = HermeticEnvironment::for_config(compile_configuration)?;
+        let configured_environment = compile_configuration.build_environment();
+        let mut environment: Vec<(&str, &OsStr)> = self.host_environment();
+        environment.extend(
+            configured_environment
+                .iter()
+                .map(|(key, value)| (*key, OsStr::new(value))),
+        );
+        if let Some(hermetic_environment) = &hermetic_environment {
+            hermetic_environment.apply_to(&mut environment);
+        }
+
+        let report = GoModFile::tidy(project_path, &environment, compile_configuration.verbose)?;
+        if report.is_unchanged() {
+            Logger::info(format_args!("🧹 go mod tidy: module files already tidy"));
+            return Ok(());
+        }
+        Logger::info(format_args!(
+            "🧹 go mod tidy: go.mod +{} -{}, go.sum +{} -{}",
+            report.go_mod_added.len(),
+            report.go_mod_removed.len(),
+            report.go_sum_added,
+            report.go_sum_removed
+        ));
+        for line in &report.go_mod_removed {
+            Logger::info(format_args!("   - {line}"));
+        }
+        for line in &report.go_mod_added {
+            Logger::info(format_args!("   + {line}"));
+        }
+        Ok(())
+    }
+
+    /// A TinyGo build without TinyGo installed that Go can do instead: no
+    /// components, threads, freestanding or waPC modules, and no TinyGo-only
+    /// LLVM tuning.
+    fn falls_back_to_go(compile_configuration: &CompileConfig) -> bool {
+        compile_configuration.toolchain == Toolchain::TinyGo
+            && !CommandExecutor::is_tool_installed(Toolchain::TinyGo.command_name())
+            && CommandExecutor::is_tool_installed(Toolchain::Go.command_name())
+            && compile_configuration.wit_world.is_none()
+            && !matches!(
+                compile_configuration.target_type,
+                TargetType::WasiHttp
+                    | TargetType::Wapc
+                    | TargetType::Threads
+                    | TargetType::WasmUnknown
+            )
+            && compile_configuration.llvm.validate(Toolchain::Go).is_ok()
+    }
+
+    /// Falls back to the per-user scratch directory when the build would write
+    /// somewhere it cannot: results go to a stand-in output directory, and a
+    /// read-only project is built from a copy. `None` when no fallback is
+    /// needed; an error when one is, but there is no scratch directory.
+    fn redirect_unwritable(
+        compile_configuration: &CompileConfig,
+    ) -> PluginResult<Option<CompileConfig>> {
+        let project_path = &compile_configuration.project_path;
+        let project_name = Self::project_name(project_path);
+        let output_dir = project_path.join(&compile_configuration.output_directory);
+        let scratch_directory = |purpose: &str, unwritable: &Path| {
+            Self::scratch_directory(purpose, project_path).ok_or_else(|| {
+                crate::PluginError::InvalidConfiguration {
+                    reason: format!(
+                        "{} is not writable and there is no per-user scratch directory to build in instead; set HOME or WASMGO_HOME",
+                        unwritable.display()
+                    ),
+                }
+            })
+        };
+
+        let mut redirected = compile_configuration.clone();
+        let mut changed = false;
+        if !PathResolver::is_writable_directory(&output_dir) {
+            let fallback_output = scratch_directory("output", &output_dir)?.join(&project_name);
+            if fallback_output != output_dir {
+                Logger::warn(format_args!(
+                    "⚠️  Output directory {} is not writable; writing the build to {} instead",
+                    output_dir.display(),
+                    fallback_output.display()
+                ));
+                redirected.output_directory = fallback_output;
+                changed = true;
+            }
+        }
+        if !PathResolver::is_writable_directory(project_path) {
+            let build_directory = scratch_directory("build", project_path)?;
+            Logger::warn(format_args!(
+                "⚠️  {} is read-only; building from a copy in {}",
+                project_path.display(),
+                build_directory.join(&project_name).display()
+            ));
+            redirected.build_directory = Some(build_directory);
+            changed = true;
+        }
+        Ok(changed.then_some(redirected))
+    }
+
+    /// Mirrors the sources into the build directory, if one is set, and
+    /// points the build at the mirror, so relative outputs and everything the
+    /// toolchain writes land there.
+    fn mirror_out_of_tree(compile_configuration: CompileConfig) -> PluginResult<CompileConfig> {
+        let Some(build_directory) = &compile_configuration.build_directory else {
+            return Ok(compile_configuration);
+        };
+        // Relative paths the build resolves in the project directory
+        let excluded: Vec<PathBuf> = std::iter::once(&compile_configuration.output_directory)
+            .chain(&compile_configuration.hermetic_directory)
+            .map(|path| {
+                path.components()
+                    .filter(|component| *component != std::path::Component::CurDir)
+                    .collect::<PathBuf>()
+            })
+            .filter(|path| path.is_relative() && !path.starts_with(".."))
+            .collect();
+        // Named after the project, which names the module
+        let project_directory = fs::canonicalize(&compile_configuration.project_path)
+            .map_err(crate::PluginError::Io)?;
+        let mirror_directory =
+            build_directory.join(project_directory.file_name().unwrap_or_default());
+        let report = SourceMirror::sync(&project_directory, &mirror_directory, &excluded)?;
+        Logger::info(format_args!(
+            "📦 Building out of tree in {} ({} copied, {} unchanged, {} removed)",
+            mirror_directory.display(),
+            report.copied,
+            report.unchanged,
+            report.removed
+        ));
+
+        Ok(CompileConfig {
+            project_path: mirror_directory,
+            build_directory: None,
+            ..compile_configuration
+        })
+    }
+
+    fn is_go_file(path: &Path) -> bool {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("go"))
+    }
+
+    /// Writes one `.go` file into a generated module directory named after it,
+    /// so `snippet.go` builds `snippet.wasm`. Relative outputs resolve next to
+    /// the file. Inside a module the generated go.mod keeps its requirements
+    /// and replaces the module with its root, so its packages stay importable.
+    fn single_file_module(compile_configuration: &CompileConfig) -> PluginResult<CompileConfig> {
+        let file = fs::canonicalize(&compile_configuration.project_path)
+            .map_err(crate::PluginError::Io)?;
+        if !Self::is_go_file(&file) {
+            return Err(crate::PluginError::InvalidProjectStructure {
+                reason: format!("{} is not a .go file", file.display()),
+            });
+        }
+        let file_directory = file.parent().unwrap_or(Path::new("/"));
+        let stem = file.file_stem().unwrap_or_default();
+
+        // Stable per source file, so repeated builds reuse the toolchain's caches
+        let module_directory = Self::scratch_directory("single", &file)
+            .ok_or_else(|| crate::PluginError::InvalidConfiguration {
+                reason: format!(
+                    "no per-user directory to build {} in; set HOME or WASMGO_HOME",
+                    file.display()
+                ),
+            })?
+            .join(stem);
+        fs::create_dir_all(&module_directory).map_err(crate::PluginError::Io)?;
+        fs::copy(
+            &file,
+            module_directory.join(file.file_name().unwrap_or_default()),
+        )
+        .map_err(crate::PluginError::Io)?;
+
+        let module_path = GoModFile::suggested_module_path(&module_directory);
+        let enclosing_module = file_directory
+            .ancestors()
+            .find(|directory| directory.join("go.mod").is_file());
+        let go_mod = match enclosing_module {
+            Some(module_root) => {
+                let go_sum = module_root.join("go.sum");
+                if go_sum.is_file() {
+                    fs::copy(&go_sum, module_directory.join("go.sum"))
+                        .map_err(crate::PluginError::Io)?;
+                }
+                Self::single_file_go_mod(module_root, &module_path)?
+            }
+            None => {
+                let _ = fs::remove_file(module_directory.join("go.sum"));
+                // Without a go directive Go assumes 1.16 and disables newer language features
+                match GoVersion::for_toolchain(compile_configuration.toolchain) {
+                    Some(version) => format!(
+                        "module {module_path}\n\ngo {}.{}\n",
+                        version.major, version.minor
+                    ),
+                    None => format!("module {module_path}\n"),
+                }
+            }
+        };
+        fs::write(module_directory.join("go.mod"), go_mod).map_err(crate::PluginError::Io)?;
+
+        Logger::info(format_args!(
+            "📄 Building {} as a single-file module in {}",
+            file.display(),
+            module_directory.display()
+        ));
+        Ok(CompileConfig {
+            project_path: module_directory,
+            output_directory: file_directory.join(&compile_configuration.output_directory),
+            build_directory: None,
+            hermetic_directory: compile_configuration
+                .hermetic_directory
+                .as_ref()
+                .map(|directory| file_directory.join(directory)),
+            ..compile_configuration.clone()
+        })
+    }
+
+    /// The enclosing module's go.mod for a single-file module: renamed, and
+    /// requiring the original module from its root on disk.
+    fn single_file_go_mod(module_root: &Path, module_path: &str) -> PluginResult<String> {
+        let original =
+            fs::read_to_string(module_root.join("go.mod")).map_err(crate::PluginError::Io)?;
+        let enclosing_module = GoModFile::parse(&original).module;
+        let mut go_mod: String = original
+            .lines()
+            .map(|line| {
+                if line.trim_start().starts_with("module ") {
+                    format!("module {module_path}\n")
+                } else {
+                    format!("{line}\n")
+                }
+            })
+            .collect();
+        if let Some(enclosing_module) = enclosing_module {
+            // Debug formatting quotes the path the way go.mod strings are quoted
+            go_mod.push_str(&format!(
+                "\nrequire {enclosing_module} v0.0.0\n\nreplace {enclosing_module} => {:?}\n",
+                module_root.to_string_lossy()
+            ));
+        }
+        Ok(go_mod)
+    }
+
+    /// `<scratch>/<purpose>/<hash of the path>`: private to the user, and
+    /// stable per project so repeated builds reuse it.
+    fn scratch_directory(purpose: &str, path: &Path) -> Option<PathBuf> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let path_hash: String = Sha256::digest(path.as_os_str().as_encoded_bytes())
+            .iter()
+            .take(CONTENT_HASH_LENGTH / 2)
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Dirs::discover().map(|dirs| {
+            dirs.state(StateDirectory::Scratch)
+                .join(purpose)
+                .join(path_hash)
+        })
+    }
+
+    /// The directory's own name, which names the module. Canonicalized so `.`
+    /// resolves to the actual name.
+    fn project_name(project_path: &Path) -> OsString {
+        fs::canonicalize(project_path)
+            .unwrap_or_else(|_| project_path.to_path_buf())
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string()
+    }
+
+    /// The line of toolchain output showing a blocked download, if any.
+    fn network_access_attempt(output: &str) -> Option<&str> {
+        const MARKERS: [&str; 4] = [
+            "GOPROXY=off",
+            "GOTOOLCHAIN=local",
+            "go: downloading",
+            "dial tcp",
+        ];
+        output
+            .lines()
+            .find(|line| MARKERS.iter().any(|marker| line.contains(marker)))
+            .map(str::trim)
+    }
 }
 
 impl Plugin for GoPlugin {
@@ -156,33 +610,159 @@ impl Plugin for GoPlugin {
         &self.plugin_info
     }
 
-    fn can_handle_project(&self, project_directory: &str) -> bool {
-        let go_module_path = PathResolver::join_paths(project_directory, "go.mod");
-        if Path::new(&go_module_path).exists() {
-            return true;
+    fn can_handle_project(&self, project_directory: &Path) -> bool {
+        self.assess_project(project_directory).claims()
+    }
+
+    /// A go.mod or go.work carries most of the weight, Go sources and a known
+    /// Go Wasm framework the rest.
+    fn assess_project(&self, project_directory: &Path) -> ProjectMatch {
+        let mut assessment = ProjectMatch::default();
+        if project_directory.is_file() {
+            if Self::is_go_file(project_directory) {
+                assessment.confidence = 1.0;
+                assessment.reasons.push("single Go source file".to_string());
+            }
+            return assessment;
         }
 
-        if let Ok(directory_entries) = fs::read_dir(project_directory) {
-            for directory_entry in directory_entries.flatten() {
-                if let Some(file_extension) = directory_entry.path().extension() {
-                    let extension_lowercase = file_extension.to_string_lossy().to_lowercase();
-                    if self
-                        .plugin_info
-                        .extensions
-                        .iter()
-                        .any(|ext| ext == &extension_lowercase)
-                    {
-                        return true;
-                    }
-                }
+        let detect = DetectSettings::of_project(project_directory);
+        match detect.claim {
+            Some(true) => {
+                assessment.confidence = 1.0;
+                assessment.reasons.push(format!(
+                    "claimed by [detect] claim = true in {PROJECT_CONFIG_FILENAME}"
+                ));
+                return assessment;
+            }
+            Some(false) => {
+                assessment.reasons.push(format!(
+                    "declined by [detect] claim = false in {PROJECT_CONFIG_FILENAME}"
+                ));
+                return assessment;
             }
+            None => {}
+        }
+        if let Some(root) = &detect.root {
+            let mut assessment = self.assess_project(&project_directory.join(root));
+            assessment.reasons.insert(
+                0,
+                format!(
+                    "scoped to {} by [detect] root in {PROJECT_CONFIG_FILENAME}",
+                    root.display()
+                ),
+            );
+            return assessment;
+        }
+
+        let go_mod = GoModFile::read(project_directory);
+        if let Some(go_mod) = &go_mod {
+            assessment.confidence += 0.6;
+            assessment.reasons.push(match &go_mod.module {
+                Some(module) => format!("go.mod found (module {module})"),
+                None => "go.mod found".to_string(),
+            });
+        } else if project_directory.join("go.work").is_file() {
+            assessment.confidence += 0.5;
+            assessment.reasons.push("go.work found".to_string());
         }
 
-        false
+        let source_count = fs::read_dir(project_directory)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| {
+                        entry.path().extension().is_some_and(|extension| {
+                            let extension = extension.to_string_lossy().to_lowercase();
+                            self.plugin_info.extensions.contains(&extension)
+                        })
+                    })
+                    .count()
+            })
+            .unwrap_or(0);
+        if source_count > 0 {
+            assessment.confidence += if assessment.confidence > 0.0 {
+                0.3
+            } else {
+                0.5
+            };
+            assessment.reasons.push(match source_count {
+                1 => "1 .go file".to_string(),
+                count => format!("{count} .go files"),
+            });
+        }
+
+        // Loose .go files next to another language's manifest are likely tooling or vendored
+        let other_languages: Vec<String> = OTHER_LANGUAGE_MANIFESTS
+            .iter()
+            .filter(|(manifest, _)| project_directory.join(manifest).is_file())
+            .map(|(manifest, language)| format!("{manifest} ({language})"))
+            .collect();
+        if assessment.claims() && !other_languages.is_empty() {
+            if go_mod.is_none() && !project_directory.join("go.work").is_file() {
+                assessment.confidence /= 2.0;
+            }
+            assessment
+                .reasons
+                .push(format!("also contains {}", other_languages.join(", ")));
+        }
+
+        if assessment.claims() {
+            let registry = FrameworkRegistry::load().unwrap_or_else(|e| {
+                Logger::warn(format_args!("⚠️  {e}; using the built-in frameworks"));
+                FrameworkRegistry::builtin()
+            });
+            for (framework, rule) in registry.detect(project_directory) {
+                assessment.confidence += 0.1;
+                assessment
+                    .reasons
+                    .push(format!("{} detected ({rule})", framework.title));
+            }
+        }
+        assessment.confidence = (assessment.confidence.min(1.0) * 100.0).round() / 100.0;
+        assessment
     }
 
     fn get_builder(&self) -> Box<dyn WasmBuilder> {
-        Box::new(GoPlugin::new())
+        Box::new(self.clone())
+    }
+
+    /// Host variables go to every toolchain subprocess. Tools are probed again,
+    /// since the host may have changed PATH.
+    fn initialize(&mut self, host_info: &HostInfo) -> PluginResult<()> {
+        self.host_info = Some(host_info.clone());
+        ToolRegistry::refresh();
+        Ok(())
+    }
+
+    /// Takes the keys of `[build]` in wasmgo.toml, e.g.
+    /// `{"toolchain": "go", "opt": "size", "output": "public"}`; `null` clears
+    /// them.
+    fn configure(&mut self, settings: serde_json::Value) -> PluginResult<()> {
+        if settings.is_null() {
+            self.host_settings = None;
+            return Ok(());
+        }
+        let settings: BuildSettings = serde_json::from_value(settings).map_err(|e| {
+            crate::PluginError::InvalidConfiguration {
+                reason: format!("host settings: {e}"),
+            }
+        })?;
+        // Rejects values the schema accepts but a build wouldn't, like configs do
+        settings
+            .apply_to(&mut CompileConfig::default())
+            .map_err(|e| crate::PluginError::InvalidConfiguration {
+                reason: format!("host settings: {e}"),
+            })?;
+        self.host_settings = Some(settings);
+        Ok(())
+    }
+
+    /// Stops builds still running on other threads and removes their partial
+    /// outputs.
+    fn shutdown(&mut self) {
+        InterruptHandler::stop_all();
+        self.host_info = None;
     }
 }
 
@@ -202,7 +782,17 @@ impl WasmBuilder for GoPlugin {
     fn check_dependencies(&self) -> Vec<String> {
         let mut missing_dependencies = Vec::new();
 
+        // Without TinyGo, builds fall back to the Go toolchain unless configured not to
+        let falls_back = self
+            .host_settings
+            .as_ref()
+            .and_then(|settings| settings.toolchain_fallback)
+            != Some(false)
+            && CommandExecutor::is_tool_installed("go");
         for tool in &self.plugin_info.dependencies {
+            if tool == "tinygo" && falls_back {
+                continue;
+            }
             if !CommandExecutor::is_tool_installed(tool) {
                 let install_hint = match tool.as_str() {
                     "tinygo" => format!("{tool} (install from https://tinygo.org)"),
@@ -216,80 +806,568 @@ impl WasmBuilder for GoPlugin {
         missing_dependencies
     }
 
-    fn validate_project(&self, project_directory: &str) -> PluginResult<()> {
+    fn validate_project(&self, project_directory: &Path) -> PluginResult<()> {
+        if Self::is_go_file(project_directory) {
+            return Ok(());
+        }
+        if let Some(root) = DetectSettings::scoped_root(project_directory) {
+            return self.validate_project(&root);
+        }
         PathResolver::validate_directory_exists(project_directory)?;
         let _ = self.find_entry_file(project_directory)?;
         Ok(())
     }
 
     fn compile(&self, compile_configuration: &CompileConfig) -> PluginResult<CompileResult> {
-        if !CommandExecutor::is_tool_installed("tinygo") {
+        let mut span = BuildSpan::start_root("wasmgo.build");
+        if span.is_recording() {
+            span.set_attribute(
+                "wasmgo.project",
+                Self::project_name(&compile_configuration.project_path).to_string_lossy(),
+            );
+            span.set_attribute("wasmgo.target", compile_configuration.target_type.name());
+            span.set_attribute(
+                "wasmgo.optimization",
+                compile_configuration.optimization_level.name(),
+            );
+            span.set_toolchain(compile_configuration.toolchain.command_name());
+        }
+        let result = self.compile_project(compile_configuration);
+        span.record_result(&result);
+        result
+    }
+}
+
+impl GoPlugin {
+    /// Resolves the configuration, builds it once and then applies the checks
+    /// that judge the finished build.
+    fn compile_project(
+        &self,
+        compile_configuration: &CompileConfig,
+    ) -> PluginResult<CompileResult> {
+        let configured = self.resolve_configuration(compile_configuration)?;
+        // Measured around the layout steps too, which tidy and copy sources
+        let started = Instant::now();
+        let result = self.resolve_layout(&configured).and_then(|placed| {
+            if placed.cache || placed.remote_cache.is_some() {
+                BuildCache::build_with(&placed, || self.build_resolved(&placed))
+            } else {
+                self.build_resolved(&placed)
+            }
+        });
+        // Judged on the finished build, so warnings replayed from the cache count
+        let result = result.and_then(|result| Self::deny_warnings(&configured, result));
+        if configured.metrics {
+            let metric = BuildMetrics::measure(&configured, &result, started.elapsed());
+            if let Err(e) = BuildMetrics::append(&metric) {
+                Logger::warn(format_args!("⚠️  Could not record build metrics: {e}"));
+            }
+        }
+        result
+    }
+
+    /// The options the build runs with: host settings, the detected version
+    /// stamp and the toolchain after any fallback. Metrics and the cache key
+    /// record these, so they are settled before either.
+    fn resolve_configuration(
+        &self,
+        compile_configuration: &CompileConfig,
+    ) -> PluginResult<CompileConfig> {
+        let mut configured = compile_configuration.clone();
+        if let Some(host_settings) = &self.host_settings {
+            host_settings.apply_to(&mut configured)?;
+        }
+        // Read from the project itself; mirrors and fallback copies have no .git
+        if configured.version_variable.is_some() && configured.version_stamp.is_none() {
+            configured.version_stamp = VersionStamp::detect(&configured.project_path);
+        }
+        if configured.toolchain_fallback && Self::falls_back_to_go(&configured) {
+            Logger::warn(format_args!(
+                "⚠️  TinyGo is not installed; building with the Go toolchain (larger modules). Install TinyGo, or set toolchain-fallback = false to require it"
+            ));
+            configured.toolchain = Toolchain::Go;
+        }
+        configured.toolchain_fallback = false;
+        Ok(configured)
+    }
+
+    /// Where the build runs, in order: a single file's generated module or a
+    /// scoped project's root, tidied in place, then mirrored out of tree when
+    /// asked to, and redirected to scratch directories when the project or
+    /// its output cannot be written.
+    fn resolve_layout(&self, compile_configuration: &CompileConfig) -> PluginResult<CompileConfig> {
+        let mut placed = if compile_configuration.project_path.is_file() {
+            Self::single_file_module(compile_configuration)?
+        } else if let Some(root) = DetectSettings::scoped_root(&compile_configuration.project_path)
+        {
+            let project_path = &compile_configuration.project_path;
+            CompileConfig {
+                project_path: root,
+                output_directory: project_path.join(&compile_configuration.output_directory),
+                hermetic_directory: compile_configuration
+                    .hermetic_directory
+                    .as_ref()
+                    .map(|directory| project_path.join(directory)),
+                ..compile_configuration.clone()
+            }
+        } else {
+            compile_configuration.clone()
+        };
+        // Tidies the project itself, before any out-of-tree or fallback copy
+        if placed.tidy {
+            BuildSpan::trace("wasmgo.tidy", || self.tidy_module(&placed))?;
+            placed.tidy = false;
+        }
+        placed = Self::mirror_out_of_tree(placed)?;
+        // A read-only project is built from a mirror of its own
+        if let Some(redirected) = Self::redirect_unwritable(&placed)? {
+            placed = Self::mirror_out_of_tree(redirected)?;
+        }
+        Ok(placed)
+    }
+
+    /// Fails a build with warnings under `deny_warnings`.
+    fn deny_warnings(
+        compile_configuration: &CompileConfig,
+        result: CompileResult,
+    ) -> PluginResult<CompileResult> {
+        if !compile_configuration.deny_warnings || result.warnings.is_empty() {
+            return Ok(result);
+        }
+        let listed: String = result
+            .warnings
+            .iter()
+            .map(|warning| format!("\n   • {warning}"))
+            .collect();
+        Err(crate::PluginError::CompilationFailed {
+            reason: format!(
+                "{} toolchain warnings and --deny-warnings is set:{listed}",
+                result.warnings.len()
+            ),
+        })
+    }
+
+    /// Builds a resolved configuration: its project is a module directory,
+    /// and the other settings are final.
+    fn build_resolved(&self, compile_configuration: &CompileConfig) -> PluginResult<CompileResult> {
+        let toolchain = compile_configuration.toolchain;
+        compile_configuration.llvm.validate(toolchain)?;
+        if !CommandExecutor::is_tool_installed(toolchain.command_name()) {
             return Err(crate::PluginError::CompileToolNotFound {
-                tool: "tinygo".to_string(),
+                tool: toolchain.command_name().to_string(),
             });
         }
 
         let _entry_file_path = self.find_entry_file(&compile_configuration.project_path)?;
+        Self::require_module_go_version(compile_configuration)?;
+        Self::check_module_toolchain(compile_configuration)?;
 
         // Resolve output directory relative to project path, not current working directory
-        let output_dir = if Path::new(&compile_configuration.output_directory).is_absolute() {
-            compile_configuration.output_directory.clone()
+        // (joining an absolute path replaces the base)
+        let output_dir = compile_configuration
+            .project_path
+            .join(&compile_configuration.output_directory);
+
+        PathResolver::ensure_output_directory_exists(&output_dir)?;
+        // For the reproduction scripts; earlier steps such as tidying changed the
+        // project rather than building it
+        let command_log = CommandLog::start();
+
+        // Go 1.24 only builds reactor modules (and thus usable exports) for wasip1
+        let exports = ExportScanner::scan_package(&compile_configuration.project_path);
+        let uses_wasmexport = toolchain == Toolchain::Go && ExportScanner::has_wasmexport(&exports);
+        if uses_wasmexport {
+            Self::require_go_version(GoVersion::new(1, 24, 0), "//go:wasmexport")?;
+        }
+        // Extism plugins are wasip1 reactors whose exports the host calls directly
+        let builds_extism = matches!(compile_configuration.target_type, TargetType::ExtismPlugin);
+        // waPC guests register their handlers in main, which the host runs first
+        let builds_wapc = matches!(compile_configuration.target_type, TargetType::Wapc);
+        // Run by a WASI runtime rather than a browser, so no JS glue
+        let builds_wasi = matches!(compile_configuration.target_type, TargetType::Wasi);
+        let builds_threads = matches!(compile_configuration.target_type, TargetType::Threads);
+        // Freestanding modules for custom hosts import only what the embedder provides
+        let builds_freestanding =
+            matches!(compile_configuration.target_type, TargetType::WasmUnknown);
+        let reactor = compile_configuration.build_mode == BuildMode::Reactor
+            || uses_wasmexport
+            || builds_extism;
+        if reactor && toolchain == Toolchain::Go && !uses_wasmexport {
+            Self::require_go_version(GoVersion::new(1, 24, 0), "Reactor builds")?;
+        }
+        let serves_wasi_http = matches!(compile_configuration.target_type, TargetType::WasiHttp);
+        let component_world = match &compile_configuration.wit_world {
+            Some(wit_world) => Some(ComponentTools::resolve_world(
+                &compile_configuration.project_path,
+                wit_world,
+            )?),
+            None if serves_wasi_http => Some((
+                ComponentTools::resolve_wasi_http_wit(&compile_configuration.project_path)?,
+                WASI_HTTP_WORLD.to_string(),
+            )),
+            None => None,
+        };
+        if component_world.is_some() {
+            if toolchain == Toolchain::Go {
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason:
+                        "Components require TinyGo; the standard Go toolchain cannot build wasip2"
+                            .to_string(),
+                });
+            }
+            ComponentTools::ensure_wasm_tools()?;
+        }
+        if let (Some(_), Some((wit_directory, world))) =
+            (&compile_configuration.wit_world, &component_world)
+        {
+            BuildSpan::trace("wasmgo.bindings", || {
+                ComponentTools::generate_bindings(
+                    &compile_configuration.project_path,
+                    wit_directory,
+                    world,
+                    compile_configuration.verbose,
+                )
+            })?;
+        }
+
+        if builds_threads {
+            if toolchain == Toolchain::Go {
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason: "The threads target needs TinyGo; the standard Go toolchain's wasm ports are single-threaded".to_string(),
+                });
+            }
+            if reactor || component_world.is_some() {
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason:
+                        "Threads builds run main on a worker and cannot be reactors or components"
+                            .to_string(),
+                });
+            }
+            Logger::warn(format_args!(
+                "🧵 The threads target is experimental and needs a TinyGo with wasi-threads support"
+            ));
+        }
+
+        if builds_freestanding {
+            if toolchain == Toolchain::Go {
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason: "The wasm-unknown target needs TinyGo; the standard Go toolchain only builds for js and wasip1".to_string(),
+                });
+            }
+            if component_world.is_some() {
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason: "wasm-unknown modules are core modules and cannot be components"
+                        .to_string(),
+                });
+            }
+            let host_imports =
+                FreestandingValidator::host_imports(&compile_configuration.project_path);
+            if !host_imports.is_empty() {
+                let listed: Vec<String> = host_imports
+                    .iter()
+                    .map(|(source_file, package)| format!("{source_file} imports {package}"))
+                    .collect();
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason: format!(
+                        "wasm-unknown modules have no operating system or JS host: {}",
+                        listed.join(", ")
+                    ),
+                });
+            }
+        }
+
+        // Workers run js/wasm modules through wasm_exec.js, so they need a command build
+        let serves_worker = matches!(
+            compile_configuration.target_type,
+            TargetType::CloudflareWorker
+        );
+        let worker_runtime = if serves_worker {
+            if reactor || component_world.is_some() {
+                return Err(crate::PluginError::UnsupportedTarget {
+                    reason: "Cloudflare Workers builds run main through wasm_exec.js and cannot be reactors or components".to_string(),
+                });
+            }
+            Some(toolchain.locate_wasm_exec()?)
         } else {
-            Path::new(&compile_configuration.project_path)
-                .join(&compile_configuration.output_directory)
-                .to_string_lossy()
-                .to_string()
+            None
         };
 
-        PathResolver::ensure_output_directory_exists(&output_dir)?;
+        if (compile_configuration.web_worker || compile_configuration.js_framework.is_some())
+            && (!uses_wasmexport || builds_extism || builds_wapc || builds_wasi || serves_worker)
+        {
+            return Err(crate::PluginError::UnsupportedTarget {
+                reason: "Web Worker loaders and framework wrappers wrap the //go:wasmexport loader; export functions with //go:wasmexport and build with --toolchain go".to_string(),
+            });
+        }
+
+        if compile_configuration.single_file
+            && (!matches!(compile_configuration.target_type, TargetType::WebApp)
+                || component_world.is_some()
+                || (reactor && !uses_wasmexport))
+        {
+            return Err(crate::PluginError::UnsupportedTarget {
+                reason: "--single-file needs the web-app target and a js/wasm command or //go:wasmexport module".to_string(),
+            });
+        }
+
+        let (build_goos, build_goarch) = if component_world.is_some() {
+            ("wasip2", "wasm")
+        } else if builds_wasi
+            || builds_extism
+            || builds_wapc
+            || builds_threads
+            || (toolchain == Toolchain::Go && reactor)
+        {
+            ("wasip1", "wasm")
+        } else {
+            ("js", "wasm")
+        };
 
         // Use a predictable output filename instead of relying on entry file name
         // TinyGo with directory input uses the directory name, not the entry file name
-        let project_name = Path::new(&compile_configuration.project_path)
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-        let output_filename = format!("{project_name}.wasm");
+        let project_directory_name = Self::project_name(&compile_configuration.project_path);
+        // Generated glue and manifests are text; only they see a lossy name
+        let project_name = project_directory_name.to_string_lossy().to_string();
+        let output_filename = if builds_extism {
+            OsString::from(EXTISM_PLUGIN_FILENAME)
+        } else {
+            let mut output_filename = project_directory_name;
+            output_filename.push(".wasm");
+            output_filename
+        };
 
-        println!("🔨 Compiling with TinyGo...");
+        Logger::info(format_args!(
+            "🔨 Compiling with {}...",
+            toolchain.display_name()
+        ));
 
-        let output_path = Path::new(&output_dir).join(&output_filename);
+        let output_path = output_dir.join(&output_filename);
 
-        // For TinyGo command, use relative path from project directory
-        let tinygo_output_path = if Path::new(&compile_configuration.output_directory).is_absolute()
-        {
-            output_path.to_string_lossy().to_string()
-        } else {
-            Path::new(&compile_configuration.output_directory)
-                .join(&output_filename)
-                .to_string_lossy()
-                .to_string()
+        // For the toolchain command, use relative path from project directory
+        let toolchain_output_path = compile_configuration
+            .output_directory
+            .join(&output_filename);
+
+        let hermetic_environment = HermeticEnvironment::for_config(compile_configuration)?;
+        let configured_environment = compile_configuration.build_environment();
+        let mut build_environment: Vec<(&str, &OsStr)> = self.host_environment();
+        build_environment.extend(
+            configured_environment
+                .iter()
+                .map(|(key, value)| (*key, OsStr::new(value))),
+        );
+        if let Some(hermetic_environment) = &hermetic_environment {
+            Logger::info(format_args!(
+                "🔒 Hermetic build, Go caches in {}",
+                hermetic_environment.root().display()
+            ));
+            hermetic_environment.apply_to(&mut build_environment);
+        }
+        let tinygo_target = match &component_world {
+            Some(_) => "wasip2",
+            None if builds_wasi || builds_extism || builds_wapc || builds_threads => "wasip1",
+            None if builds_freestanding => "wasm-unknown",
+            None => "wasm",
+        };
+        let mut llvm = compile_configuration.llvm.clone();
+        let mut target_properties = None;
+        if builds_threads {
+            for name in THREADS_LLVM_FEATURES {
+                if !llvm.features.iter().any(|feature| feature.name == *name) {
+                    llvm.features.push(LlvmFeature::parse(name)?);
+                }
+            }
+            target_properties = Some(ThreadsTarget::tinygo_target_properties());
+        }
+        // Kept until the build finishes; the reproduction scripts write the file back
+        let llvm_target_directory = match toolchain {
+            Toolchain::TinyGo => Some(PathResolver::private_directory(
+                &std::env::temp_dir(),
+                "wasmgo-target-",
+            )?),
+            Toolchain::Go => None,
+        };
+        let llvm_target_file = match &llvm_target_directory {
+            Some(directory) => llvm.write_tinygo_target(
+                tinygo_target,
+                target_properties.as_ref(),
+                directory.path(),
+            )?,
+            None => None,
         };
+        let mut target_argument = OsString::from("-target=");
+        match &llvm_target_file {
+            Some(target_file) => target_argument.push(target_file),
+            None => target_argument.push(tinygo_target),
+        }
+        let llvm_features_argument = llvm.tinygo_features_argument();
+        let gowasm = llvm.gowasm();
 
-        let compile_command_output = CommandExecutor::execute_command(
-            "tinygo",
-            &["build", "-o", &tinygo_output_path, "-target=wasm", "."],
-            &compile_configuration.project_path,
-            compile_configuration.verbose,
-        )?;
+        let mut compile_command = ToolCommand::new(toolchain.command_name());
+        compile_command
+            .current_dir(&compile_configuration.project_path)
+            .args(["build", "-o"])
+            .arg(&toolchain_output_path);
+        match toolchain {
+            Toolchain::TinyGo => {
+                compile_command.arg(&target_argument);
+                // TinyGo embeds the world and componentizes the module itself
+                if let Some((wit_directory, world)) = &component_world {
+                    compile_command
+                        .arg("--wit-package")
+                        .arg(wit_directory)
+                        .args(["--wit-world", world]);
+                }
+                if let Some(llvm_features_argument) = &llvm_features_argument {
+                    compile_command.arg(llvm_features_argument);
+                }
+            }
+            Toolchain::Go => {
+                build_environment.push(("GOOS", OsStr::new(build_goos)));
+                build_environment.push(("GOARCH", OsStr::new(build_goarch)));
+                if let Some(gowasm) = &gowasm {
+                    build_environment.push(("GOWASM", OsStr::new(gowasm)));
+                }
+            }
+        }
+        compile_command
+            .args(toolchain.optimization_arguments(&compile_configuration.optimization_level));
+        if let Some(jobs) = compile_configuration.jobs {
+            compile_command.arg("-p").arg(jobs.to_string());
+        }
+        if !compile_configuration.build_tags.is_empty() {
+            // TinyGo takes a space-separated list; Go prefers commas
+            let separator = match toolchain {
+                Toolchain::TinyGo => " ",
+                Toolchain::Go => ",",
+            };
+            compile_command
+                .arg("-tags")
+                .arg(compile_configuration.build_tags.join(separator));
+        }
+        // Reactor modules export _initialize instead of running main on instantiation.
+        // TinyGo has that build mode only for wasip1 and builds reactors without a
+        // scheduler; a -scheduler in the extra arguments comes later and wins.
+        if reactor && (toolchain == Toolchain::Go || tinygo_target == "wasip1") {
+            compile_command.arg("-buildmode=c-shared");
+        }
+        if reactor && toolchain == Toolchain::TinyGo {
+            compile_command.arg("-scheduler=none");
+        }
+        let version_stamp = compile_configuration
+            .version_variable
+            .as_ref()
+            .zip(compile_configuration.version_stamp.as_ref());
+        if let Some((variable, version_stamp)) = version_stamp {
+            // A command-line -ldflags would replace the one in GOFLAGS, and
+            // a later one in the extra arguments would replace ours
+            if compile_configuration
+                .goflags
+                .as_deref()
+                .is_some_and(|goflags| goflags.contains("-ldflags"))
+            {
+                Logger::warn(format_args!(
+                    "⚠️  GOFLAGS sets -ldflags; not stamping version {}",
+                    version_stamp.describe
+                ));
+            } else if compile_configuration
+                .extra_build_args
+                .iter()
+                .any(|argument| argument.trim_start_matches('-').starts_with("ldflags"))
+            {
+                Logger::warn(format_args!(
+                    "⚠️  The extra build arguments set -ldflags; not stamping version {}",
+                    version_stamp.describe
+                ));
+            } else {
+                if compile_configuration.verbose {
+                    Logger::debug(format_args!(
+                        "Stamping {variable} = {}",
+                        version_stamp.describe
+                    ));
+                }
+                compile_command.arg(version_stamp.ldflags_argument(variable));
+            }
+        }
+        compile_command
+            .args(&compile_configuration.extra_build_args)
+            .arg(".")
+            .envs(build_environment.iter().copied());
+
+        // An interrupted compile leaves a truncated module and the target file behind
+        let _partial_outputs: Vec<_> = std::iter::once(output_path.clone())
+            .chain(
+                llvm_target_directory
+                    .iter()
+                    .map(|directory| directory.path().to_path_buf()),
+            )
+            .map(InterruptHandler::remove_on_interrupt)
+            .collect();
+        let mut toolchain_span = BuildSpan::start("wasmgo.toolchain");
+        toolchain_span.set_attribute("wasmgo.toolchain", toolchain.command_name());
+        let (compile_command_output, stopped_at) = if compile_configuration.fail_fast {
+            compile_command.output_until(compile_configuration.verbose, FailureSummary::is_fatal)?
+        } else {
+            (compile_command.output(compile_configuration.verbose)?, None)
+        };
+        BugReport::record_build(&compile_command, &compile_command_output);
+        let generated_files: Vec<(PathBuf, String)> = llvm_target_file
+            .iter()
+            .filter_map(|path| Some((path.clone(), fs::read_to_string(path).ok()?)))
+            .collect();
+        drop(llvm_target_directory);
 
+        if let Some(code) = compile_command_output.status.code() {
+            toolchain_span.set_attribute("process.exit.code", code);
+        }
         if !compile_command_output.status.success() {
+            toolchain_span.fail(format!("{} build failed", toolchain.command_name()));
+            // Best effort: the failure is what gets reported
+            let _ = ReproductionScript {
+                generated_files,
+                commands: command_log.commands(),
+            }
+            .write_to(&output_dir);
+            let stderr = String::from_utf8_lossy(&compile_command_output.stderr);
+            if let Some(attempt) = compile_configuration
+                .no_network
+                .then(|| Self::network_access_attempt(&stderr))
+                .flatten()
+            {
+                return Err(crate::PluginError::CompilationFailed {
+                    reason: format!(
+                        "network access is disabled (--no-network) but the build needed it: {attempt}\n   Vendor the dependencies (go mod vendor) or populate the module cache before building offline"
+                    ),
+                });
+            }
+            let mut summary = FailureSummary::new(&compile_command_output, &compile_command);
+            if let Some(line) = stopped_at {
+                summary.status = format!(
+                    "{} stopped at the first error (--fail-fast)",
+                    toolchain.command_name()
+                );
+                summary.first_error = Some(line.trim().to_string());
+            }
             return Err(crate::PluginError::CompilationFailed {
-                reason: format!(
-                    "Compilation failed: {}",
-                    String::from_utf8_lossy(&compile_command_output.stderr)
-                ),
+                reason: summary.to_string(),
             });
         }
 
+        drop(toolchain_span);
+        let warnings = BuildWarning::collect(&compile_command_output);
+        // Glue, validation, manifests and minification
+        let _postprocess_span = BuildSpan::start("wasmgo.postprocess");
+
         let actual_wasm_file = if output_path.exists() {
             output_path
         } else {
-            let search_dir = Path::new(&output_dir);
+            let search_dir = output_dir.as_path();
             if compile_configuration.verbose {
-                println!("Looking for WASM files in: {}", search_dir.display());
-                println!("Expected file was: {}", output_path.display());
+                Logger::debug(format_args!(
+                    "Looking for WASM files in: {}",
+                    search_dir.display()
+                ));
+                Logger::debug(format_args!("Expected file was: {}", output_path.display()));
             }
             if let Ok(entries) = std::fs::read_dir(search_dir) {
                 let wasm_files: Vec<_> = entries
@@ -298,8 +1376,7 @@ impl WasmBuilder for GoPlugin {
                         entry
                             .path()
                             .extension()
-                            .map(|ext| ext.to_string_lossy().to_lowercase() == "wasm")
-                            .unwrap_or(false)
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("wasm"))
                     })
                     .collect();
 
@@ -307,22 +1384,376 @@ impl WasmBuilder for GoPlugin {
                     wasm_file.path()
                 } else {
                     return Err(crate::PluginError::CompilationFailed {
-                        reason: "TinyGo compilation completed but no WASM file was found in output directory".to_string(),
+                        reason: format!(
+                            "{} compilation completed but no WASM file was found in output directory",
+                            toolchain.display_name()
+                        ),
                     });
                 }
             } else {
                 return Err(crate::PluginError::CompilationFailed {
-                    reason: "TinyGo compilation completed but output directory could not be read"
-                        .to_string(),
+                    reason: format!(
+                        "{} compilation completed but output directory could not be read",
+                        toolchain.display_name()
+                    ),
                 });
             }
         };
 
+        // Size builds get binaryen's -Oz on top of the toolchain's optimizations;
+        // wasm-opt doesn't read components
+        let wasm_opt = if compile_configuration.optimization_level == OptimizationLevel::Size
+            && component_world.is_none()
+        {
+            if CommandExecutor::is_tool_installed("wasm-opt") {
+                let mut wasm_opt_passes = compile_configuration.wasm_opt_passes.clone();
+                if builds_threads {
+                    wasm_opt_passes.insert(0, "--enable-threads".to_string());
+                }
+                let pipeline = OptimizationPipeline {
+                    wasm_opt_level: Some("Oz".to_string()),
+                    wasm_opt_passes,
+                    ..OptimizationPipeline::default()
+                };
+                let report = BuildSpan::trace("wasmgo.optimize", || {
+                    pipeline.run(
+                        &actual_wasm_file,
+                        &actual_wasm_file,
+                        compile_configuration.verbose,
+                    )
+                })?;
+                Logger::info(format_args!(
+                    "🗜️  wasm-opt -Oz: {} → {} bytes ({:.1}% smaller)",
+                    report.input_size,
+                    report.output_size,
+                    report.percent_saved()
+                ));
+                Some(report)
+            } else {
+                if !compile_configuration.wasm_opt_passes.is_empty() {
+                    Logger::warn(format_args!(
+                        "⚠️  wasm-opt-passes is set but wasm-opt is not installed (install binaryen)"
+                    ));
+                }
+                None
+            }
+        } else {
+            None
+        };
+
+        let actual_wasm_file = if compile_configuration.hashed_filenames {
+            HashedArtifacts::rename_with_hash(&actual_wasm_file)?
+        } else {
+            actual_wasm_file
+        };
+        let wasm_filename = actual_wasm_file
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let mut additional_files = Vec::new();
+        let glue_span = BuildSpan::start("wasmgo.glue");
+        let js_file_path = if let Some(wasm_exec_path) = &worker_runtime {
+            let (entry_path, layout_files) =
+                WorkerGenerator::write_layout(&actual_wasm_file, &project_name, wasm_exec_path)?;
+            additional_files.extend(
+                layout_files
+                    .into_iter()
+                    .zip([ArtifactKind::JsGlue, ArtifactKind::Asset]),
+            );
+            Some(entry_path)
+        } else if builds_threads {
+            let (loader_path, worker_path) = ThreadsTarget::write_glue(&actual_wasm_file)?;
+            additional_files.push((worker_path, ArtifactKind::JsGlue));
+            Some(loader_path)
+        } else if uses_wasmexport && !builds_extism && !builds_wapc && !builds_wasi {
+            let loader_source = LoaderGenerator::wasmexport_loader(&wasm_filename, &exports);
+            let loader_path = LoaderGenerator::write_loader(&actual_wasm_file, &loader_source)?;
+            if compile_configuration.web_worker {
+                additional_files.extend(
+                    LoaderGenerator::write_web_worker(&actual_wasm_file, &exports)?
+                        .into_iter()
+                        .map(|path| (path, ArtifactKind::JsGlue)),
+                );
+            }
+            if let Some(framework) = compile_configuration.js_framework {
+                additional_files.push((
+                    LoaderGenerator::write_framework_wrapper(
+                        &actual_wasm_file,
+                        &project_name,
+                        framework,
+                    )?,
+                    ArtifactKind::JsGlue,
+                ));
+            }
+            Some(loader_path)
+        } else if matches!(
+            compile_configuration.target_type,
+            TargetType::Web | TargetType::WebApp
+        ) && build_goos == "js"
+        {
+            // The runtime must match the toolchain that compiled the module
+            Some(LoaderGenerator::copy_wasm_exec(
+                &actual_wasm_file,
+                &toolchain.locate_wasm_exec()?,
+            )?)
+        } else {
+            None
+        };
+
+        if compile_configuration.single_file {
+            let wasmexport_loader = uses_wasmexport
+                .then(|| LoaderGenerator::wasmexport_loader(&wasm_filename, &exports));
+            let wasm_exec = match &wasmexport_loader {
+                Some(_) => String::new(),
+                None => fs::read_to_string(toolchain.locate_wasm_exec()?)
+                    .map_err(crate::PluginError::Io)?,
+            };
+            let html_path = LoaderGenerator::write_single_file(
+                &actual_wasm_file,
+                &project_name,
+                wasmexport_loader.as_deref(),
+                &wasm_exec,
+            )?;
+            Logger::info(format_args!("📄 Single-file page: {}", html_path.display()));
+            additional_files.push((html_path, ArtifactKind::Html));
+        } else if let (TargetType::WebApp, Some(glue_path)) =
+            (&compile_configuration.target_type, &js_file_path)
+        {
+            let html_path = LoaderGenerator::write_index(
+                &actual_wasm_file,
+                &project_name,
+                glue_path,
+                uses_wasmexport,
+            )?;
+            Logger::info(format_args!("📄 Page: {}", html_path.display()));
+            additional_files.push((html_path, ArtifactKind::Html));
+        }
+        drop(glue_span);
+
+        if builds_extism {
+            let plugin_functions = BuildSpan::trace("wasmgo.validate", || {
+                ExtismValidator::validate(&actual_wasm_file, &exports)
+            })?;
+            Logger::info(format_args!(
+                "🧩 Extism plugin functions: {}",
+                plugin_functions.join(", ")
+            ));
+        }
+        if builds_wapc {
+            let host_functions = BuildSpan::trace("wasmgo.validate", || {
+                WapcValidator::validate(&actual_wasm_file)
+            })?;
+            Logger::info(format_args!("🧩 waPC guest validated"));
+            if !host_functions.is_empty() {
+                Logger::info(format_args!(
+                    "   Host functions used: {}",
+                    host_functions.join(", ")
+                ));
+            }
+        }
+
+        if builds_freestanding {
+            let host_functions = BuildSpan::trace("wasmgo.validate", || {
+                FreestandingValidator::validate(&actual_wasm_file)
+            })?;
+            Logger::info(format_args!("🧩 Freestanding module validated"));
+            if !host_functions.is_empty() {
+                Logger::info(format_args!(
+                    "   Host functions used: {}",
+                    host_functions.join(", ")
+                ));
+            }
+        }
+
+        let primary_wasm_file = match &compile_configuration.preview2_adapter {
+            Some(adapter_path) => {
+                let component_path = BuildSpan::trace("wasmgo.componentize", || {
+                    ComponentTools::adapt_preview1(
+                        &actual_wasm_file,
+                        adapter_path,
+                        compile_configuration.verbose,
+                    )
+                })?;
+                additional_files.push((actual_wasm_file.clone(), ArtifactKind::Wasm));
+                component_path
+            }
+            None => actual_wasm_file.clone(),
+        };
+
+        if compile_configuration.smoke_test {
+            // Freestanding modules have no entry point, only their exports
+            let entry_point = if builds_freestanding {
+                None
+            } else if reactor {
+                Some("_initialize")
+            } else if toolchain == Toolchain::Go && build_goos == "js" {
+                Some("run")
+            } else {
+                Some("_start")
+            };
+            // Go only turns //go:wasmexport into module exports
+            let expected_exports: Vec<String> = entry_point
+                .map(str::to_string)
+                .into_iter()
+                .chain(
+                    exports
+                        .iter()
+                        .filter(|export| {
+                            toolchain == Toolchain::TinyGo
+                                || export.directive == ExportDirective::WasmExport
+                        })
+                        .map(|export| export.export_name.clone()),
+                )
+                .collect();
+            let report = BuildSpan::trace("wasmgo.smoke-test", || {
+                SmokeTest::run(&primary_wasm_file, &expected_exports)
+            })?;
+            match &report.skipped {
+                Some(reason) if report.exports.is_empty() => {
+                    Logger::warn(format_args!("⚠️  Smoke test skipped: {reason}"))
+                }
+                // Warned about, so a host without Node.js doesn't pass unnoticed
+                Some(reason) => Logger::warn(format_args!(
+                    "⚠️  Exports present ({}), but instantiation was skipped: {reason}",
+                    report.exports.join(", ")
+                )),
+                None => Logger::info(format_args!(
+                    "✅ Module instantiates, exports present: {}",
+                    report.exports.join(", ")
+                )),
+            }
+        }
+
+        // Before the manifest queries the toolchain's environment
+        let reproduction_script = ReproductionScript {
+            generated_files,
+            commands: command_log.commands(),
+        };
+        let manifest = BuildManifest {
+            plugin: self.plugin_info.name.clone(),
+            plugin_version: self.plugin_info.version.clone(),
+            wasm_file: primary_wasm_file
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            toolchain,
+            component_world: component_world.map(|(_, world)| world),
+            preview2_adapter: compile_configuration
+                .preview2_adapter
+                .as_ref()
+                .map(|adapter| {
+                    adapter
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                }),
+            build_mode: if reactor {
+                BuildMode::Reactor
+            } else {
+                BuildMode::Command
+            },
+            target: compile_configuration.target_type.clone(),
+            optimization: compile_configuration.optimization_level,
+            version: compile_configuration.version_stamp.clone(),
+            environment: ToolchainEnvironment::capture(
+                &compile_configuration.project_path,
+                &build_environment,
+                build_goos,
+                build_goarch,
+            ),
+        };
+        additional_files.push((manifest.write_to(&output_dir)?, ArtifactKind::Manifest));
+        let (shell_script, powershell_script) = reproduction_script.write_to(&output_dir)?;
+        additional_files.push((shell_script, ArtifactKind::Manifest));
+        additional_files.push((powershell_script, ArtifactKind::Manifest));
+        // Components already carry the WIT world they were built against
+        if !exports.is_empty()
+            && manifest.component_world.is_none()
+            && !builds_extism
+            && !builds_wapc
+        {
+            let wit_source = WitGenerator::from_exports(&project_name, &exports);
+            additional_files.push((
+                WitGenerator::write_next_to(&actual_wasm_file, &wit_source)?,
+                ArtifactKind::Manifest,
+            ));
+        }
+        if serves_wasi_http {
+            additional_files.push((
+                ComponentTools::write_wasi_http_smoke_test(&actual_wasm_file)?,
+                ArtifactKind::Asset,
+            ));
+        }
+
+        // Debug builds keep readable glue
+        if compile_configuration.optimization_level != OptimizationLevel::Debug {
+            let glue_files: Vec<PathBuf> = js_file_path
+                .iter()
+                .chain(additional_files.iter().map(|(path, _)| path))
+                .cloned()
+                .collect();
+            let report = BuildSpan::trace("wasmgo.minify", || {
+                Minifier::minify_files(&glue_files, compile_configuration.source_maps)
+            })?;
+            if !report.files.is_empty() {
+                Logger::info(format_args!(
+                    "🗜️  Minified {} glue files ({} → {} bytes)",
+                    report.files.len(),
+                    report.bytes_before,
+                    report.bytes_after
+                ));
+            }
+            additional_files.extend(
+                report
+                    .source_maps
+                    .into_iter()
+                    .map(|path| (path, ArtifactKind::SourceMap)),
+            );
+        }
+
+        if compile_configuration.provenance {
+            let artifacts: Vec<PathBuf> = std::iter::once(primary_wasm_file.as_path())
+                .chain(js_file_path.iter().map(PathBuf::as_path))
+                .chain(additional_files.iter().map(|(path, _)| path.as_path()))
+                .map(Path::to_path_buf)
+                .collect();
+            let statement = BuildSpan::trace("wasmgo.provenance", || {
+                ProvenanceGenerator::generate(compile_configuration, &manifest, &artifacts)
+            })?;
+            additional_files.push((
+                ProvenanceGenerator::write_next_to(&primary_wasm_file, &statement)?,
+                ArtifactKind::Manifest,
+            ));
+        }
+
+        if let (true, Some(keep)) = (
+            compile_configuration.hashed_filenames,
+            compile_configuration.retain_builds,
+        ) {
+            let pruned = HashedArtifacts::prune(&output_dir, &project_name, keep)?;
+            if !pruned.is_empty() {
+                Logger::info(format_args!(
+                    "🧹 Pruned {} artifacts of builds older than the last {keep}",
+                    pruned.len()
+                ));
+            }
+        }
+
         Ok(CompileResult {
-            wasm_file_path: actual_wasm_file.to_string_lossy().to_string(),
-            js_file_path: None,
-            additional_files: vec![],
+            wasm_file_path: primary_wasm_file,
+            js_file_path,
+            // Sized last, after minification rewrote the glue
+            additional_files: additional_files
+                .into_iter()
+                .map(|(path, kind)| Artifact::new(kind, path))
+                .collect(),
             is_wasm_bindgen: false,
+            warnings,
+            wasm_opt,
         })
     }
 }
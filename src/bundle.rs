@@ -0,0 +1,239 @@
+use crate::{PluginError, PluginResult, CONTENT_HASH_LENGTH, LAYOUT_FILENAME, MANIFEST_FILENAME};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha384};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const INTEGRITY_FILENAME: &str = "wasmgo.integrity.json";
+
+const HTACCESS_TEMPLATE: &str = include_str!("templates/deploy_htaccess");
+
+/// Build outputs that only make sense next to the sources, not on a static host.
+const EXCLUDED_EXTENSIONS: &[&str] = &["sh", "wit", "gz", "jsonl"];
+
+/// Assets whose references to other assets are rewritten to the hashed names.
+const TEXT_EXTENSIONS: &[&str] = &["html", "js", "mjs", "css", "json"];
+
+/// Assets smaller than this are not worth precompressing.
+const MIN_COMPRESSED_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundledFile {
+    /// Name in the build output directory
+    pub source: String,
+    /// Name in the bundle, content-hashed for deploy bundles
+    pub file: String,
+    pub size: u64,
+    pub gzip_size: Option<u64>,
+    /// Subresource Integrity value (`sha384-...`)
+    pub integrity: String,
+}
+
+/// Integrity manifest of a deploy bundle, written as `wasmgo.integrity.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployBundle {
+    pub directory: String,
+    pub files: Vec<BundledFile>,
+}
+
+/// Copies build output into a directory ready for static hosting.
+pub struct DeployBundler;
+
+impl DeployBundler {
+    /// Bundles build outputs (typically the files of a `CompileResult`) into `bundle_directory`.
+    ///
+    /// With `deploy`, assets other than HTML entry points get content-hashed
+    /// names (and references to them are rewritten), compressible files get a
+    /// `.gz` sibling, and `_headers`/`.htaccess` files set the wasm content
+    /// type and a long-lived cache policy for the hashed assets.
+    pub fn bundle(
+        asset_paths: &[PathBuf],
+        bundle_directory: &Path,
+        deploy: bool,
+    ) -> PluginResult<DeployBundle> {
+        let mut sources: Vec<(String, Vec<u8>)> = Vec::new();
+        for path in asset_paths {
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            if name == MANIFEST_FILENAME
+                || name == LAYOUT_FILENAME
+                || name == INTEGRITY_FILENAME
+                || EXCLUDED_EXTENSIONS.contains(&Self::extension(&name))
+            {
+                continue;
+            }
+            sources.push((name, fs::read(path).map_err(PluginError::Io)?));
+        }
+        sources.sort_by(|a, b| a.0.cmp(&b.0));
+        if sources.is_empty() {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: "No assets to bundle".to_string(),
+            });
+        }
+
+        fs::create_dir_all(bundle_directory).map_err(PluginError::Io)?;
+
+        // Binary assets are named first so text assets referencing them can be
+        // rewritten (and hashed) afterwards; HTML entry points keep their names.
+        let is_text = |name: &str| TEXT_EXTENSIONS.contains(&Self::extension(name));
+        let mut renames: Vec<(String, String)> = Vec::new();
+        let mut outputs: BTreeMap<String, (String, Vec<u8>)> = BTreeMap::new();
+        for pass in [false, true] {
+            for (name, contents) in sources.iter().filter(|(name, _)| is_text(name) == pass) {
+                let mut contents = contents.clone();
+                if pass {
+                    contents = Self::rewrite_references(&contents, &renames);
+                }
+                let file = if deploy && Self::extension(name) != "html" && !Self::is_hashed(name) {
+                    Self::hashed_name(name, &contents)
+                } else {
+                    name.clone()
+                };
+                if file != *name {
+                    renames.push((name.clone(), file.clone()));
+                }
+                outputs.insert(name.clone(), (file, contents));
+            }
+        }
+        // HTML may reference hashed text assets too
+        for (name, (_, contents)) in outputs.iter_mut() {
+            if Self::extension(name) == "html" {
+                *contents = Self::rewrite_references(contents, &renames);
+            }
+        }
+
+        let mut files = Vec::new();
+        for (source, (file, contents)) in outputs {
+            let file_path = bundle_directory.join(&file);
+            fs::write(&file_path, &contents).map_err(PluginError::Io)?;
+
+            let gzip_size = if deploy && contents.len() >= MIN_COMPRESSED_SIZE {
+                let compressed = Self::gzip(&contents)?;
+                fs::write(bundle_directory.join(format!("{file}.gz")), &compressed)
+                    .map_err(PluginError::Io)?;
+                Some(compressed.len() as u64)
+            } else {
+                None
+            };
+
+            files.push(BundledFile {
+                source,
+                integrity: Self::integrity(&contents),
+                size: contents.len() as u64,
+                gzip_size,
+                file,
+            });
+        }
+
+        if deploy {
+            fs::write(bundle_directory.join("_headers"), Self::headers(&files))
+                .map_err(PluginError::Io)?;
+            fs::write(bundle_directory.join(".htaccess"), HTACCESS_TEMPLATE)
+                .map_err(PluginError::Io)?;
+        }
+
+        let bundle = DeployBundle {
+            directory: bundle_directory.to_string_lossy().to_string(),
+            files,
+        };
+        let integrity_json =
+            serde_json::to_string_pretty(&bundle).map_err(|e| PluginError::Io(e.into()))?;
+        fs::write(bundle_directory.join(INTEGRITY_FILENAME), integrity_json)
+            .map_err(PluginError::Io)?;
+        Ok(bundle)
+    }
+
+    /// `_headers` rules in the Netlify / Cloudflare Pages format.
+    fn headers(files: &[BundledFile]) -> String {
+        let mut headers = String::from(
+            "# Generated by wasmgo for Netlify / Cloudflare Pages.\n/*\n  X-Content-Type-Options: nosniff\n\n",
+        );
+        for file in files {
+            headers.push_str(&format!("/{}\n", file.file));
+            if Self::extension(&file.file) == "wasm" {
+                headers.push_str("  Content-Type: application/wasm\n");
+            }
+            if Self::extension(&file.file) == "html" {
+                headers.push_str("  Cache-Control: no-cache\n\n");
+            } else {
+                headers.push_str("  Cache-Control: public, max-age=31536000, immutable\n\n");
+            }
+        }
+        headers
+    }
+
+    fn hashed_name(name: &str, contents: &[u8]) -> String {
+        let digest = Sha256::digest(contents);
+        let hash: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        match name.rsplit_once('.') {
+            Some((stem, extension)) => {
+                format!("{stem}.{}.{extension}", &hash[..CONTENT_HASH_LENGTH])
+            }
+            None => format!("{name}.{}", &hash[..CONTENT_HASH_LENGTH]),
+        }
+    }
+
+    // Already hashed by a `--hash` build
+    fn is_hashed(name: &str) -> bool {
+        name.split('.').any(|segment| {
+            segment.len() == CONTENT_HASH_LENGTH && segment.chars().all(|c| c.is_ascii_hexdigit())
+        })
+    }
+
+    fn rewrite_references(contents: &[u8], renames: &[(String, String)]) -> Vec<u8> {
+        let Ok(text) = std::str::from_utf8(contents) else {
+            return contents.to_vec();
+        };
+        let mut text = text.to_string();
+        for (original, hashed) in renames {
+            text = text.replace(original.as_str(), hashed);
+        }
+        text.into_bytes()
+    }
+
+    fn gzip(contents: &[u8]) -> PluginResult<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(contents).map_err(PluginError::Io)?;
+        encoder.finish().map_err(PluginError::Io)
+    }
+
+    fn integrity(contents: &[u8]) -> String {
+        format!("sha384-{}", Self::base64(&Sha384::digest(contents)))
+    }
+
+    /// Standard base64 with padding.
+    pub fn base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut encoded = String::new();
+        for chunk in bytes.chunks(3) {
+            let group = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |group, (index, &byte)| {
+                    group | (byte as u32) << (16 - 8 * index)
+                });
+            for index in 0..4 {
+                if index <= chunk.len() {
+                    encoded.push(ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+        encoded
+    }
+
+    fn extension(name: &str) -> &str {
+        name.rsplit_once('.')
+            .map(|(_, extension)| extension)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,602 @@
+use crate::{
+    Artifact, ArtifactKind, BuildWarning, CommandExecutor, CompileConfig, CompileResult, Dirs,
+    GoModFile, GoWorkFile, Logger, OptimizationReport, PathResolver, PluginError, PluginResult,
+    StateDirectory, ToolCommand, ToolRegistry,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bearer token sent to an HTTP remote cache.
+pub const REMOTE_CACHE_TOKEN_VARIABLE: &str = "WASMGO_REMOTE_CACHE_TOKEN";
+
+/// Written last, so an entry without it is incomplete.
+const ENTRY_FILENAME: &str = "entry.json";
+
+/// Process variables that change what the toolchain produces.
+const KEYED_VARIABLES: &[&str] = &["GOFLAGS", "GOEXPERIMENT", "GOWASM", "GOTOOLCHAIN"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Paths are relative to the output directory, `/`-separated
+    wasm_file: String,
+    js_file: Option<String>,
+    files: Vec<CachedFile>,
+    is_wasm_bindgen: bool,
+    #[serde(default)]
+    warnings: Vec<BuildWarning>,
+    #[serde(default)]
+    wasm_opt: Option<OptimizationReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    kind: ArtifactKind,
+    path: String,
+}
+
+/// Where a restored build came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheHit {
+    Local,
+    Remote,
+}
+
+/// Incremental build cache: a build's artifacts stored under a key hashed
+/// from the project's sources, the configuration and the toolchain version,
+/// and copied back into the output directory when nothing changed.
+///
+/// Entries live in the `builds` state directory. With a remote cache
+/// configured, local misses are looked up there and fresh builds uploaded,
+/// so CI runners and teammates reuse each other's artifacts. Cache problems
+/// are reported as warnings; the build itself never fails because of them.
+pub struct BuildCache;
+
+impl BuildCache {
+    /// Restores the build for `configuration` if an entry exists, else runs
+    /// `build` and stores what it produced.
+    pub fn build_with(
+        configuration: &CompileConfig,
+        build: impl FnOnce() -> PluginResult<CompileResult>,
+    ) -> PluginResult<CompileResult> {
+        let Some(root) = Dirs::discover().map(|dirs| dirs.state(StateDirectory::Builds)) else {
+            return build();
+        };
+        let remote = match configuration
+            .remote_cache
+            .as_deref()
+            .map(RemoteCache::parse)
+        {
+            Some(Ok(remote)) => Some(remote),
+            Some(Err(e)) => return Err(e),
+            None => None,
+        };
+        let key = match Self::key(configuration) {
+            Ok(key) => key,
+            Err(e) => {
+                Logger::warn(format_args!("⚠️  Build cache skipped: {e}"));
+                return build();
+            }
+        };
+        let entry_directory = root.join(&key);
+        let output_directory = configuration
+            .project_path
+            .join(&configuration.output_directory);
+
+        let mut hit = CacheHit::Local;
+        if !entry_directory.join(ENTRY_FILENAME).is_file() {
+            hit = CacheHit::Remote;
+            if let Some(remote) = &remote {
+                if let Err(e) = remote.fetch(&key, &entry_directory, configuration.verbose) {
+                    Logger::warn(format_args!("⚠️  Remote cache lookup failed: {e}"));
+                }
+            }
+        }
+        if let Some(result) = Self::restore(&entry_directory, &output_directory) {
+            let source = match hit {
+                CacheHit::Local => "local",
+                CacheHit::Remote => "remote",
+            };
+            Logger::info(format_args!(
+                "♻️  Restored from the {source} build cache ({})",
+                &key[..12]
+            ));
+            return Ok(result);
+        }
+
+        let result = build()?;
+        match Self::store(&entry_directory, &output_directory, &result) {
+            Ok(true) => {
+                if let Some(remote) = remote.filter(|_| !configuration.remote_cache_read_only) {
+                    if let Err(e) = remote.upload(&key, &entry_directory, configuration.verbose) {
+                        Logger::warn(format_args!("⚠️  Remote cache upload failed: {e}"));
+                    }
+                }
+            }
+            Ok(false) => {}
+            Err(e) => Logger::warn(format_args!("⚠️  Could not cache the build: {e}")),
+        }
+        Ok(result)
+    }
+
+    /// Hex key of everything that determines a build's output.
+    pub fn key(configuration: &CompileConfig) -> PluginResult<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("wasmgo {}\n", env!("CARGO_PKG_VERSION")));
+        let toolchain = configuration.toolchain.command_name();
+        hasher.update(ToolRegistry::version_output(toolchain).unwrap_or_default());
+        for variable in KEYED_VARIABLES {
+            hasher.update(format!(
+                "{variable}={}\n",
+                std::env::var(variable).unwrap_or_default()
+            ));
+        }
+        if let Some(adapter) = &configuration.preview2_adapter {
+            hasher.update(fs::read(adapter).map_err(PluginError::Io)?);
+        }
+
+        // Locations don't change the artifacts, so projects checked out
+        // elsewhere share entries
+        let normalized = CompileConfig {
+            project_path: PathBuf::from("."),
+            output_directory: PathBuf::from("."),
+            build_directory: None,
+            verbose: false,
+            preview2_adapter: None,
+            hermetic_directory: None,
+            gocache: None,
+            gomodcache: None,
+            metrics: false,
+            deny_warnings: false,
+            // The toolchain built with is in the key already
+            toolchain_fallback: false,
+            cache: false,
+            remote_cache: None,
+            remote_cache_read_only: false,
+            ..configuration.clone()
+        };
+        hasher.update(format!("{normalized:?}\n"));
+
+        let project = &configuration.project_path;
+        let excluded: Vec<PathBuf> = [
+            Some(project.join(&configuration.output_directory)),
+            configuration
+                .build_directory
+                .as_ref()
+                .map(|directory| project.join(directory)),
+            configuration
+                .hermetic_directory
+                .as_ref()
+                .map(|directory| project.join(directory)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|directory| fs::canonicalize(directory).ok())
+        .collect();
+        Self::hash_sources(&mut hasher, "", project, &excluded)?;
+
+        // Sources outside the project that still reach the compiler: local
+        // `replace` targets and the modules of an enclosing workspace. They
+        // are named as the go.mod or go.work writes them, so checkouts laid
+        // out alike share entries.
+        let mut dependencies: Vec<(String, PathBuf)> = GoModFile::read(project)
+            .map(|go_mod| go_mod.local_replacements)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|directory| (Self::slash_path(&directory), project.join(directory)))
+            .collect();
+        if let Some(go_work) = GoWorkFile::discover(project) {
+            hasher.update(fs::read(&go_work.path).map_err(PluginError::Io)?);
+            let workspace = go_work.path.parent().unwrap_or(Path::new("."));
+            dependencies.extend(go_work.uses.iter().chain(&go_work.local_replacements).map(
+                |directory| {
+                    (
+                        format!("go.work:{}", Self::slash_path(directory)),
+                        workspace.join(directory),
+                    )
+                },
+            ));
+        }
+        let mut hashed = vec![fs::canonicalize(project).map_err(PluginError::Io)?];
+        for (label, directory) in dependencies {
+            let directory = fs::canonicalize(&directory).map_err(PluginError::Io)?;
+            if hashed.contains(&directory) {
+                continue;
+            }
+            Self::hash_sources(&mut hasher, &format!("{label}/"), &directory, &excluded)?;
+            hashed.push(directory);
+        }
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .take(16)
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+
+    /// Hashes the sources under `root`, each named with `label` and its path.
+    fn hash_sources(
+        hasher: &mut Sha256,
+        label: &str,
+        root: &Path,
+        excluded: &[PathBuf],
+    ) -> PluginResult<()> {
+        let mut files = Vec::new();
+        Self::collect_sources(root, Path::new(""), excluded, &mut files)?;
+        files.sort();
+        for relative in files {
+            let contents = fs::read(root.join(&relative)).map_err(PluginError::Io)?;
+            hasher.update(format!(
+                "\n{label}{} {}\n",
+                Self::slash_path(&relative),
+                contents.len()
+            ));
+            hasher.update(contents);
+        }
+        Ok(())
+    }
+
+    /// Every file that can reach the compiler, skipping hidden directories,
+    /// `node_modules` and the build's own output.
+    fn collect_sources(
+        root: &Path,
+        relative: &Path,
+        excluded: &[PathBuf],
+        files: &mut Vec<PathBuf>,
+    ) -> PluginResult<()> {
+        let directory = root.join(relative);
+        if fs::canonicalize(&directory).is_ok_and(|directory| excluded.contains(&directory)) {
+            return Ok(());
+        }
+        for entry in fs::read_dir(&directory).map_err(PluginError::Io)? {
+            let entry = entry.map_err(PluginError::Io)?;
+            let name = entry.file_name();
+            let path = relative.join(&name);
+            let file_type = entry.file_type().map_err(PluginError::Io)?;
+            if file_type.is_dir() {
+                let skipped = name.to_string_lossy().starts_with('.') || name == "node_modules";
+                if !skipped {
+                    Self::collect_sources(root, &path, excluded, files)?;
+                }
+            } else if file_type.is_file() || root.join(&path).is_file() {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    fn restore(entry_directory: &Path, output_directory: &Path) -> Option<CompileResult> {
+        let entry_path = entry_directory.join(ENTRY_FILENAME);
+        let contents = fs::read_to_string(&entry_path).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+        // Refreshes the entry's age for `wasmgo prune`
+        let _ = fs::File::options()
+            .append(true)
+            .open(&entry_path)
+            .and_then(|file| file.set_modified(std::time::SystemTime::now()));
+        let copy = |relative: &str| -> Option<PathBuf> {
+            let source = entry_directory.join("files").join(relative);
+            let destination = output_directory.join(relative);
+            fs::create_dir_all(destination.parent()?).ok()?;
+            fs::copy(source, &destination).ok()?;
+            Some(destination)
+        };
+        let wasm_file_path = copy(&entry.wasm_file)?;
+        let js_file_path = match &entry.js_file {
+            Some(js_file) => Some(copy(js_file)?),
+            None => None,
+        };
+        let mut additional_files = Vec::new();
+        for file in &entry.files {
+            additional_files.push(Artifact::new(file.kind, copy(&file.path)?));
+        }
+        Some(CompileResult {
+            wasm_file_path,
+            js_file_path,
+            additional_files,
+            is_wasm_bindgen: entry.is_wasm_bindgen,
+            warnings: entry.warnings,
+            wasm_opt: entry.wasm_opt,
+        })
+    }
+
+    /// `Ok(false)` when an artifact lies outside the output directory and the
+    /// build can't be restored from a copy.
+    fn store(
+        entry_directory: &Path,
+        output_directory: &Path,
+        result: &CompileResult,
+    ) -> PluginResult<bool> {
+        let output_directory = fs::canonicalize(output_directory).map_err(PluginError::Io)?;
+        let relative = |path: &Path| -> Option<String> {
+            let path = fs::canonicalize(path).ok()?;
+            Some(Self::slash_path(path.strip_prefix(&output_directory).ok()?))
+        };
+        let Some(wasm_file) = relative(&result.wasm_file_path) else {
+            return Ok(false);
+        };
+        let js_file = match &result.js_file_path {
+            Some(path) => match relative(path) {
+                Some(js_file) => Some(js_file),
+                None => return Ok(false),
+            },
+            None => None,
+        };
+        let mut files = Vec::new();
+        for artifact in &result.additional_files {
+            let Some(path) = relative(&artifact.path) else {
+                return Ok(false);
+            };
+            files.push(CachedFile {
+                kind: artifact.kind,
+                path,
+            });
+        }
+        let entry = CacheEntry {
+            wasm_file,
+            js_file,
+            files,
+            is_wasm_bindgen: result.is_wasm_bindgen,
+            warnings: result.warnings.clone(),
+            wasm_opt: result.wasm_opt.clone(),
+        };
+
+        // Assembled next to the entry and renamed into place
+        let staging = entry_directory.with_extension(format!("{}.tmp", std::process::id()));
+        let _ = fs::remove_dir_all(&staging);
+        for file in entry.paths() {
+            let destination = staging.join("files").join(file);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent).map_err(PluginError::Io)?;
+            }
+            fs::copy(output_directory.join(file), destination).map_err(PluginError::Io)?;
+        }
+        let contents =
+            serde_json::to_string_pretty(&entry).map_err(|e| PluginError::Io(e.into()))?;
+        fs::write(staging.join(ENTRY_FILENAME), contents).map_err(PluginError::Io)?;
+        let _ = fs::remove_dir_all(entry_directory);
+        fs::rename(&staging, entry_directory).map_err(PluginError::Io)?;
+        Ok(true)
+    }
+
+    fn slash_path(path: &Path) -> String {
+        path.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl CacheEntry {
+    fn paths(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.wasm_file.as_str())
+            .chain(self.js_file.as_deref())
+            .chain(self.files.iter().map(|file| file.path.as_str()))
+    }
+}
+
+/// A remote cache: `http(s)://host/prefix`, or `s3://bucket/prefix` for
+/// S3-compatible storage. Entries are stored as `<prefix>/<key>/<file>` and
+/// transferred with `curl`.
+///
+/// HTTP caches get [`REMOTE_CACHE_TOKEN_VARIABLE`] as a bearer token. S3 uses
+/// the standard `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+/// `AWS_SESSION_TOKEN` and `AWS_REGION` variables, and `AWS_ENDPOINT_URL_S3`
+/// or `AWS_ENDPOINT_URL` for other providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteCache {
+    base_url: String,
+    /// Signing region for S3
+    s3_region: Option<String>,
+}
+
+impl RemoteCache {
+    pub fn parse(url: &str) -> PluginResult<Self> {
+        let url = url.trim_end_matches('/');
+        if let Some(location) = url.strip_prefix("s3://") {
+            let variable = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+            let region = variable("AWS_REGION")
+                .or_else(|| variable("AWS_DEFAULT_REGION"))
+                .unwrap_or_else(|| "us-east-1".to_string());
+            let endpoint = variable("AWS_ENDPOINT_URL_S3")
+                .or_else(|| variable("AWS_ENDPOINT_URL"))
+                .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+            if location.is_empty() {
+                return Err(PluginError::InvalidConfiguration {
+                    reason: format!("remote-cache {url} names no bucket"),
+                });
+            }
+            return Ok(Self {
+                base_url: format!("{}/{location}", endpoint.trim_end_matches('/')),
+                s3_region: Some(region),
+            });
+        }
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(Self {
+                base_url: url.to_string(),
+                s3_region: None,
+            });
+        }
+        Err(PluginError::InvalidConfiguration {
+            reason: format!("remote-cache must be an http(s):// or s3:// URL, not {url}"),
+        })
+    }
+
+    /// Downloads the entry for `key` into `entry_directory` if the remote has
+    /// it.
+    fn fetch(&self, key: &str, entry_directory: &Path, verbose: bool) -> PluginResult<()> {
+        let staging = entry_directory.with_extension(format!("{}.remote", std::process::id()));
+        let _ = fs::remove_dir_all(&staging);
+        fs::create_dir_all(&staging).map_err(PluginError::Io)?;
+        let result = (|| {
+            let entry_file = staging.join(ENTRY_FILENAME);
+            if !self.transfer(
+                &self.url_for(key, ENTRY_FILENAME),
+                &entry_file,
+                false,
+                verbose,
+            )? {
+                return Ok(());
+            }
+            let contents = fs::read_to_string(&entry_file).map_err(PluginError::Io)?;
+            let entry: CacheEntry =
+                serde_json::from_str(&contents).map_err(|e| PluginError::Io(e.into()))?;
+            for file in entry.paths() {
+                let relative = Path::new(file);
+                if !PathResolver::is_safe_path(relative) || relative.is_absolute() {
+                    return Err(PluginError::InvalidConfiguration {
+                        reason: format!("remote cache entry {key} names the unsafe path {file}"),
+                    });
+                }
+                let destination = staging.join("files").join(relative);
+                if let Some(parent) = destination.parent() {
+                    fs::create_dir_all(parent).map_err(PluginError::Io)?;
+                }
+                let url = self.url_for(key, &format!("files/{file}"));
+                if !self.transfer(&url, &destination, false, verbose)? {
+                    return Err(PluginError::ExecutionFailed {
+                        reason: format!("{url} is missing from the remote cache"),
+                    });
+                }
+            }
+            let _ = fs::remove_dir_all(entry_directory);
+            fs::rename(&staging, entry_directory).map_err(PluginError::Io)
+        })();
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+
+    /// Uploads a stored entry, its `entry.json` last.
+    fn upload(&self, key: &str, entry_directory: &Path, verbose: bool) -> PluginResult<()> {
+        let contents =
+            fs::read_to_string(entry_directory.join(ENTRY_FILENAME)).map_err(PluginError::Io)?;
+        let entry: CacheEntry =
+            serde_json::from_str(&contents).map_err(|e| PluginError::Io(e.into()))?;
+        for file in entry.paths() {
+            let source = entry_directory.join("files").join(file);
+            self.transfer(
+                &self.url_for(key, &format!("files/{file}")),
+                &source,
+                true,
+                verbose,
+            )?;
+        }
+        self.transfer(
+            &self.url_for(key, ENTRY_FILENAME),
+            &entry_directory.join(ENTRY_FILENAME),
+            true,
+            verbose,
+        )?;
+        Ok(())
+    }
+
+    fn url_for(&self, key: &str, file: &str) -> String {
+        let encoded: String = file
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                    (byte as char).to_string()
+                }
+                _ => format!("%{byte:02X}"),
+            })
+            .collect();
+        format!("{}/{key}/{encoded}", self.base_url)
+    }
+
+    /// Uploads `file` or downloads into it. A download answered with 404
+    /// returns `false`.
+    fn transfer(&self, url: &str, file: &Path, upload: bool, verbose: bool) -> PluginResult<bool> {
+        if !CommandExecutor::is_tool_installed("curl") {
+            return Err(PluginError::CompileToolNotFound {
+                tool: "curl".to_string(),
+            });
+        }
+        // Credentials go in a curl config file so they never show in a
+        // printed command line
+        let mut config = String::new();
+        let quote = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"");
+        match &self.s3_region {
+            Some(region) => {
+                if let (Ok(access_key), Ok(secret_key)) = (
+                    std::env::var("AWS_ACCESS_KEY_ID"),
+                    std::env::var("AWS_SECRET_ACCESS_KEY"),
+                ) {
+                    config.push_str(&format!(
+                        "user = \"{}:{}\"\naws-sigv4 = \"aws:amz:{region}:s3\"\n",
+                        quote(&access_key),
+                        quote(&secret_key)
+                    ));
+                    if let Ok(session_token) = std::env::var("AWS_SESSION_TOKEN") {
+                        config.push_str(&format!(
+                            "header = \"x-amz-security-token: {}\"\n",
+                            quote(&session_token)
+                        ));
+                    }
+                }
+            }
+            None => {
+                if let Some(token) = std::env::var(REMOTE_CACHE_TOKEN_VARIABLE)
+                    .ok()
+                    .filter(|token| !token.is_empty())
+                {
+                    config.push_str(&format!(
+                        "header = \"Authorization: Bearer {}\"\n",
+                        quote(&token)
+                    ));
+                }
+            }
+        }
+        let config_directory =
+            PathResolver::private_directory(&std::env::temp_dir(), ".wasmgo-remote-cache-")?;
+        let config_file = config_directory.path().join("curlrc");
+        PathResolver::write_private(&config_file, &config)?;
+
+        let mut command = ToolCommand::new("curl");
+        command
+            .args([
+                "-sS",
+                "--retry",
+                "2",
+                "--max-time",
+                "120",
+                "-w",
+                "%{http_code}",
+            ])
+            .arg("-K")
+            .arg(&config_file);
+        if upload {
+            command
+                .arg("-o")
+                .arg(if cfg!(windows) { "NUL" } else { "/dev/null" });
+            command.arg("-T").arg(file);
+        } else {
+            command.arg("-o").arg(file);
+        }
+        command.arg(url);
+        let output = command.output(verbose);
+        drop(config_directory);
+        let output = output?;
+
+        let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        match status.as_str() {
+            _ if !output.status.success() => Err(PluginError::ExecutionFailed {
+                reason: format!("{url}: {}", String::from_utf8_lossy(&output.stderr).trim()),
+            }),
+            "404" if !upload => {
+                let _ = fs::remove_file(file);
+                Ok(false)
+            }
+            code if code.starts_with('2') => Ok(true),
+            code => {
+                if !upload {
+                    let _ = fs::remove_file(file);
+                }
+                Err(PluginError::ExecutionFailed {
+                    reason: format!("{url}: HTTP {code}"),
+                })
+            }
+        }
+    }
+}
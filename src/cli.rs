@@ -0,0 +1,3717 @@
+use crate::{
+    ApiDocGenerator, BugReport, BuildComparison, BuildLayout, BuildMetrics, BuildMode,
+    BuildPipeline, BuildSettings, BuildWarning, CommandExecutor, CompileConfig, CompileResult,
+    ComponentTools, ComponentWorld, ConfigEditor, DeployBundler, DetectSettings, Dirs,
+    EmbedScanner, Examples, ExportScanner, FrameworkRegistry, GhPagesDeployer, GoCacheUsage,
+    GoCaches, GoModFile, GoldenFile, GoldenOutcome, InterruptHandler, JsFramework, LogLevel,
+    LogSink, Logger, Messages, ModuleRunner, OciPublisher, OptimizationLevel, OptimizationPipeline,
+    Playground, Plugin, PostBuildCommand, ProcessPriority, ProjectConfig, PrunePolicy,
+    ReleaseSource, ReproducibilityCheck, ResolvedConfig, SelfTest, SelfUpdater, SizeAnalyzer,
+    SourceWatcher, StateDirectory, StatePruner, StateUsage, StaticServer, StringCategory,
+    TargetType, ToolRegistry, Toolchain, ToolchainEnvironment, UpdateNotifier, UpgradeMethod,
+    WasiRunConfig, WasmBinary, WasmBuilder, WasmKind, WasmRuntime, WatchEvent, WitWorld,
+    DEFAULT_MIN_STRING_LENGTH, HOME_VARIABLE, INTEGRITY_FILENAME, MANIFEST_FILENAME,
+    PROJECT_CONFIG_FILENAME, REFRESH_SUBCOMMAND, SELFTEST_GREETING, WASM_OPT_LEVELS,
+    WATCH_INTERVAL,
+};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(name = "wasmgo")]
+#[command(about = "Go WebAssembly plugin for Wasmrun")]
+#[command(version)]
+struct Cli {
+    /// Language of messages, e.g. en or pt-BR (default: WASMGO_LANG, then LANG)
+    #[arg(long, global = true, value_name = "LOCALE")]
+    lang: Option<String>,
+
+    /// Print a JSON report on stdout instead of the usual output (compile, inspect,
+    /// check-deps, can-handle, info, metrics, frameworks); messages go to stderr
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Run a Go WebAssembly project for execution (default command)
+    #[command(alias = "r")]
+    Run {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// WIT package directory to componentize against
+        #[arg(long, value_name = "DIR")]
+        wit: Option<PathBuf>,
+
+        /// WIT world to implement (defaults to the package's only world)
+        #[arg(long, value_name = "WORLD", requires = "wit")]
+        world: Option<String>,
+
+        /// Wrap wasip1 output into a preview2 component using this preview1 adapter module
+        #[arg(long, value_name = "ADAPTER")]
+        adapt: Option<PathBuf>,
+
+        /// Execute the built module instead of printing its path
+        #[arg(long)]
+        exec: bool,
+
+        /// Runtime to execute with (default: the first installed one that can run the module)
+        #[arg(long, value_enum, requires = "exec")]
+        runtime: Option<CliRuntime>,
+
+        /// Argument passed to the program (repeatable)
+        #[arg(
+            long = "arg",
+            value_name = "ARG",
+            allow_hyphen_values = true,
+            requires = "exec"
+        )]
+        args: Vec<String>,
+
+        /// Guest environment variable, KEY=VALUE or KEY to pass the host value (repeatable)
+        #[arg(long = "env", value_name = "KEY[=VALUE]", requires = "exec")]
+        envs: Vec<String>,
+
+        /// Preopen a host directory for a WASI module, optionally under another guest path (repeatable)
+        #[arg(long = "dir", value_name = "HOST[::GUEST]", requires = "exec")]
+        dirs: Vec<String>,
+
+        /// Rebuild whenever Go sources or go.mod change, rerunning the module with --exec
+        #[arg(long)]
+        watch: bool,
+
+        /// Milliseconds between source scans with --watch
+        #[arg(long, value_name = "MS", default_value_t = WATCH_INTERVAL.as_millis() as u64, requires = "watch")]
+        interval: u64,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Build and run the module, comparing its stdout with a golden file
+    Test {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// File holding the expected stdout
+        #[arg(long, value_name = "FILE")]
+        golden: PathBuf,
+
+        /// Write the actual stdout to the golden file instead of comparing
+        #[arg(long)]
+        update: bool,
+
+        /// Runtime to execute with (default: the first installed one that can run the module)
+        #[arg(long, value_enum)]
+        runtime: Option<CliRuntime>,
+
+        /// Argument passed to the program (repeatable)
+        #[arg(long = "arg", value_name = "ARG", allow_hyphen_values = true)]
+        args: Vec<String>,
+
+        /// Guest environment variable, KEY=VALUE or KEY to pass the host value (repeatable)
+        #[arg(long = "env", value_name = "KEY[=VALUE]")]
+        envs: Vec<String>,
+
+        /// Preopen a host directory for a WASI module, optionally under another guest path (repeatable)
+        #[arg(long = "dir", value_name = "HOST[::GUEST]")]
+        dirs: Vec<String>,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Rebuild whenever Go sources or go.mod change
+    Watch {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Shell command to run after each successful rebuild; {wasm} is replaced by the module path
+        #[arg(long, value_name = "COMMAND")]
+        exec: Option<String>,
+
+        /// Milliseconds between source scans
+        #[arg(long, value_name = "MS", default_value_t = WATCH_INTERVAL.as_millis() as u64)]
+        interval: u64,
+
+        /// Rebuild at reduced CPU and I/O priority so the editor and browser stay responsive
+        #[arg(long)]
+        low_priority: bool,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Build, serve the output over HTTP and rebuild on changes or on Enter
+    Serve {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Address to listen on; port 0 picks a free one
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8000")]
+        address: String,
+
+        /// Milliseconds between source scans
+        #[arg(long, value_name = "MS", default_value_t = WATCH_INTERVAL.as_millis() as u64)]
+        interval: u64,
+
+        /// Only rebuild when Enter is pressed, not when sources change
+        #[arg(long)]
+        no_watch: bool,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Compile a Go project to WebAssembly
+    #[command(alias = "c")]
+    Compile {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Place artifacts under <output>/<profile>/ even for a single profile
+        #[arg(long)]
+        per_profile: bool,
+
+        /// Build each main package under examples/ into <output>/examples/<name>.wasm
+        #[arg(long, conflicts_with_all = ["stdin", "per_profile"])]
+        examples: bool,
+
+        /// Keep only the last N hashed builds in the output directory
+        #[arg(long, value_name = "N", requires = "hash")]
+        retain: Option<std::num::NonZeroUsize>,
+
+        /// Run the module in a dedicated Web Worker (<name>.worker.js) driven by <name>.client.js
+        #[arg(long)]
+        web_worker: bool,
+
+        /// Generate a wrapper around the loader: React hook, Vue composable or custom element
+        #[arg(long, value_enum, value_name = "FRAMEWORK")]
+        js_framework: Option<CliJsFramework>,
+
+        /// Web-app target: write one self-contained index.html with the glue and base64 module inlined
+        #[arg(long)]
+        single_file: bool,
+
+        /// WIT package directory to componentize against
+        #[arg(long, value_name = "DIR")]
+        wit: Option<PathBuf>,
+
+        /// WIT world to implement (defaults to the package's only world)
+        #[arg(long, value_name = "WORLD", requires = "wit")]
+        world: Option<String>,
+
+        /// Wrap wasip1 output into a preview2 component using this preview1 adapter module
+        #[arg(long, value_name = "ADAPTER")]
+        adapt: Option<PathBuf>,
+
+        /// Run go mod init with this module path first if the project has no go.mod
+        #[arg(long, value_name = "MODULE")]
+        init_module: Option<String>,
+
+        /// Read a single Go source file from standard input and build it in a temporary module
+        #[arg(long, conflicts_with_all = ["project", "init_module", "per_profile"])]
+        stdin: bool,
+
+        /// Arguments after `--`, passed to tinygo build or go build as given (e.g. -- -gc=leaking)
+        #[arg(last = true, value_name = "BUILD_ARGS")]
+        build_args: Vec<String>,
+
+        /// With --stdin, serve the built module over HTTP at ADDR until interrupted
+        #[arg(
+            long,
+            value_name = "ADDR",
+            num_args = 0..=1,
+            default_missing_value = "127.0.0.1:8000",
+            requires = "stdin"
+        )]
+        serve: Option<String>,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Compile and collect the output into a directory for static hosting
+    Bundle {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Bundle directory, relative to the project
+        #[arg(long, default_value = "./deploy", value_name = "DIR")]
+        bundle_dir: PathBuf,
+
+        /// Hashed assets, precompressed files, _headers/.htaccess and an integrity manifest
+        #[arg(long)]
+        deploy: bool,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Build the web app bundle and publish it
+    Deploy {
+        #[command(subcommand)]
+        destination: DeployDestination,
+    },
+
+    /// Build and push the module as an OCI artifact, e.g. ghcr.io/org/app:tag
+    Push {
+        /// Registry reference to push to
+        #[arg(value_name = "REFERENCE")]
+        reference: String,
+
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Push an already built module (and its sibling manifest) instead of building
+        #[arg(long, value_name = "FILE")]
+        wasm: Option<PathBuf>,
+
+        /// Manifest annotation (repeatable), e.g. --annotation org.opencontainers.image.source=https://github.com/org/app
+        #[arg(long = "annotation", value_name = "KEY=VALUE")]
+        annotations: Vec<String>,
+
+        /// Enable verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Build at each optimization level (default: all) and toolchain, compare size and build time
+    Compare {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Build the project twice in fresh hermetic environments and byte-compare the outputs
+    VerifyReproducible {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Inspect project structure, dependencies, and frameworks
+    #[command(alias = "check")]
+    Inspect {
+        /// Project path to inspect
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        /// Inspect a compiled module or component instead of the project
+        #[arg(long, value_name = "FILE")]
+        wasm: Option<PathBuf>,
+    },
+
+    /// Optimize an existing .wasm file with wasm-opt and strip
+    #[command(alias = "opt")]
+    Optimize {
+        /// WebAssembly module to optimize
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+
+        /// Output file (defaults to <name>.opt.wasm next to the input)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// wasm-opt optimization level
+        #[arg(short, long, default_value = "Oz", value_parser = clap::builder::PossibleValuesParser::new(WASM_OPT_LEVELS))]
+        level: String,
+
+        /// Additional wasm-opt pass or flag (repeatable), e.g. --pass=--converge
+        #[arg(long = "pass", value_name = "FLAG", allow_hyphen_values = true)]
+        passes: Vec<String>,
+
+        /// Skip wasm-opt and only run the remaining steps
+        #[arg(long)]
+        no_wasm_opt: bool,
+
+        /// Strip debug info and producer sections
+        #[arg(long)]
+        strip: bool,
+
+        /// Aggressive size mode: snip panic formatting functions before wasm-opt and strip
+        #[arg(long)]
+        aggressive: bool,
+
+        /// wasm-snip regex of functions to replace with `unreachable` (repeatable)
+        #[arg(long = "snip-pattern", value_name = "REGEX")]
+        snip_patterns: Vec<String>,
+
+        /// Enable verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Find large embedded strings and other bloat in a compiled module
+    Analyze {
+        /// WebAssembly module to analyze
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+
+        /// Number of strings to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Minimum length of a reported string, in bytes
+        #[arg(long, default_value_t = DEFAULT_MIN_STRING_LENGTH)]
+        min_length: usize,
+
+        /// Go package the module was built from, to account for its //go:embed assets
+        #[arg(short, long, value_name = "PATH")]
+        project: Option<PathBuf>,
+    },
+
+    /// Generate a reference of the module's exported wasm functions
+    Docgen {
+        /// Go package declaring the exports
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        /// Compiled module, to include the wasm and JavaScript signatures
+        #[arg(long, value_name = "FILE")]
+        wasm: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: CliDocFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Check if wasmgo can handle the project
+    CanHandle {
+        /// Project path to check
+        #[arg(value_name = "PATH")]
+        project: PathBuf,
+    },
+
+    /// Graph of the build phases the effective configuration runs
+    Pipeline {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[arg(long, value_enum, default_value = "mermaid")]
+        format: CliGraphFormat,
+
+        /// Build first and label each phase with how long it took
+        #[arg(long)]
+        timed: bool,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+    },
+
+    /// Show the effective build configuration and the toolchain environment it builds in
+    Env {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+    },
+
+    /// Read or change keys in wasmgo.toml or the global config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// List the wasm targets the installed toolchains support
+    ListTargets {
+        /// Toolchain to query (repeatable); defaults to both
+        #[arg(long = "toolchain", value_enum)]
+        toolchains: Vec<CliToolchain>,
+    },
+
+    /// Check dependencies and system requirements, and report Go cache usage
+    #[command(alias = "doctor")]
+    CheckDeps {
+        /// Project whose gocache/gomodcache settings to report
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+    },
+
+    /// Build an embedded hello-world program to verify the local environment
+    Selftest {
+        /// Toolchain to test (repeatable); defaults to every installed one
+        #[arg(long = "toolchain", value_enum)]
+        toolchains: Vec<CliToolchain>,
+
+        /// Also run the built modules under Node.js
+        #[arg(long)]
+        instantiate: bool,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Clean build artifacts
+    Clean {
+        /// Project path to clean
+        #[arg(value_name = "PATH")]
+        project: PathBuf,
+    },
+
+    /// Report disk usage of the build cache, toolchains, templates and history, and remove old entries
+    Prune {
+        /// Remove entries unused for longer than this, e.g. 12h, 30d, 2w
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+
+        /// Remove the least recently used entries until each directory fits, e.g. 500M, 2G
+        #[arg(long, value_name = "SIZE")]
+        max_size: Option<String>,
+
+        /// Directory to prune (repeatable); defaults to the caches, builds and toolchains
+        #[arg(long = "only", value_enum, value_name = "DIRECTORY")]
+        directories: Vec<CliStateDirectory>,
+
+        /// List what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write a .tar.gz of diagnostics (versions, config, go env, last build log) to attach to issues
+    Bugreport {
+        /// Project to describe
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        /// Archive to write (default: wasmgo-bugreport-<timestamp>.tar.gz)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Summarize the builds recorded with the `metrics` setting
+    Metrics {
+        /// Only the last N recorded builds
+        #[arg(long, value_name = "N")]
+        last: Option<usize>,
+
+        /// Delete the recorded metrics
+        #[arg(long, conflicts_with_all = ["last", "json"])]
+        clear: bool,
+    },
+
+    /// Update wasmgo to the newest release from GitHub or crates.io
+    Upgrade {
+        /// Only report whether a newer version exists
+        #[arg(long)]
+        check: bool,
+
+        /// Build the release with cargo install instead of downloading a prebuilt binary
+        #[arg(long)]
+        cargo: bool,
+
+        /// Enable verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Show plugin information and capabilities
+    Info,
+
+    /// Fetch the latest wasmgo and TinyGo versions for update notices
+    #[command(name = REFRESH_SUBCOMMAND, hide = true)]
+    RefreshUpdateCheck,
+
+    /// Create a project from a framework's template
+    New {
+        /// Directory to create
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Framework from `wasmgo frameworks`
+        #[arg(short, long, default_value = "standard", value_name = "NAME")]
+        framework: String,
+
+        /// Module path for go.mod (defaults to the directory name)
+        #[arg(short, long, value_name = "MODULE")]
+        module: Option<String>,
+    },
+
+    /// Show supported frameworks and project types
+    Frameworks,
+}
+
+/// Build settings that `wasmgo.toml`, its profiles and `WASMGO_*` variables
+/// can also provide; flags given here take precedence over all of them.
+#[derive(clap::Args, Debug)]
+struct CliBuildSettings {
+    /// Apply this [profile.<name>] from wasmgo.toml (also WASMGO_PROFILE)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Output directory for compiled files [default: ./dist]
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<String>,
+
+    /// Build in a copy of the sources under DIR, leaving the project directory untouched
+    #[arg(long, value_name = "DIR")]
+    build_dir: Option<String>,
+
+    /// Optimization level(s) for compilation; several build one profile each [default: release;
+    /// debug for watch and serve, size for bundle, deploy and push]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    optimization: Vec<CliOptimization>,
+
+    /// Target type(s) for compilation; several build one profile each [default: wasm; web-app for
+    /// serve and deploy]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    target: Vec<CliTarget>,
+
+    /// Compiler backend used for the build; compare takes several [default: tinygo]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    toolchain: Vec<CliToolchain>,
+
+    /// Fail instead of building with Go when TinyGo isn't installed
+    #[arg(long)]
+    no_toolchain_fallback: bool,
+
+    /// Build an application or a callable library module [default: command]
+    #[arg(long, value_enum)]
+    mode: Option<CliBuildMode>,
+
+    /// GOFLAGS value for the build subprocess
+    #[arg(long, value_name = "FLAGS", allow_hyphen_values = true)]
+    goflags: Option<String>,
+
+    /// GOEXPERIMENT value for the build subprocess
+    #[arg(long, value_name = "EXPERIMENTS")]
+    goexperiment: Option<String>,
+
+    /// Build tags for //go:build constraints, comma-separated (-tags for tinygo and go)
+    #[arg(long, value_name = "TAGS", value_delimiter = ',')]
+    tags: Vec<String>,
+
+    /// GOCACHE for the build, e.g. a cache volume persisted between CI runs
+    #[arg(long, value_name = "DIR")]
+    gocache: Option<String>,
+
+    /// GOMODCACHE for the build, e.g. a cache volume persisted between CI runs
+    #[arg(long, value_name = "DIR")]
+    gomodcache: Option<String>,
+
+    /// Parallel compile jobs (-p and GOMAXPROCS for the build)
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<std::num::NonZeroUsize>,
+
+    /// Stop the compiler as soon as it reports its first error
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Fail the build when the toolchain prints warnings
+    #[arg(long)]
+    deny_warnings: bool,
+
+    /// Build with private GOCACHE/GOMODCACHE/GOPATH (a fresh temp directory unless --hermetic-dir)
+    #[arg(long)]
+    hermetic: bool,
+
+    /// Keep the hermetic caches in this directory (relative to the project) across builds
+    #[arg(long, value_name = "DIR")]
+    hermetic_dir: Option<String>,
+
+    /// Forbid module and toolchain downloads (GOPROXY=off, GOFLAGS=-mod=readonly, GOTOOLCHAIN=local)
+    #[arg(long)]
+    no_network: bool,
+
+    /// Build with exactly the go.mod toolchain version (GOTOOLCHAIN=<version>; Go toolchain only)
+    #[arg(long)]
+    exact_toolchain: bool,
+
+    /// Run go mod tidy before building and summarize the go.mod/go.sum changes
+    #[arg(long)]
+    tidy: bool,
+
+    /// Enable (+name) or disable (-name) wasm features, e.g. +tail-call,+nontrapping-fptoint
+    #[arg(
+        long,
+        value_name = "FEATURES",
+        value_delimiter = ',',
+        allow_hyphen_values = true
+    )]
+    llvm_features: Vec<String>,
+
+    /// Wasm CPU model to tune for (generic, mvp, lime1, bleeding-edge; TinyGo only)
+    #[arg(long, value_name = "CPU")]
+    cpu: Option<String>,
+
+    /// Extra wasm-opt pass or flag for size builds (repeatable), e.g. --wasm-opt-pass=--converge
+    #[arg(
+        long = "wasm-opt-pass",
+        value_name = "FLAG",
+        allow_hyphen_values = true
+    )]
+    wasm_opt_passes: Vec<String>,
+
+    /// Content-hash the module filename (<name>.<hash>.wasm)
+    #[arg(long)]
+    hash: bool,
+
+    /// Write SLSA provenance (<name>.intoto.jsonl) describing the source, toolchain and parameters
+    #[arg(long)]
+    provenance: bool,
+
+    /// Write source maps (<file>.map) for the glue minified in release and size builds
+    #[arg(long)]
+    source_maps: bool,
+
+    /// Instantiate the built module (under Node.js, imports stubbed) and check its exports
+    #[arg(long)]
+    smoke_test: bool,
+
+    /// Record this build's duration, size and outcome in the local metrics file (see `wasmgo metrics`)
+    #[arg(long)]
+    metrics: bool,
+
+    /// Restore the artifacts of an earlier identical build instead of compiling
+    #[arg(long)]
+    cache: bool,
+
+    /// Shared build cache to consult and fill: http(s)://host/prefix or s3://bucket/prefix
+    #[arg(long, value_name = "URL")]
+    remote_cache: Option<String>,
+
+    /// Download from the remote cache without uploading builds to it
+    #[arg(long)]
+    remote_cache_read_only: bool,
+
+    /// Don't link the git version into the module
+    #[arg(long)]
+    no_stamp_version: bool,
+
+    /// Variable the git version is linked into (default main.version)
+    #[arg(
+        long,
+        value_name = "IMPORTPATH.NAME",
+        conflicts_with = "no_stamp_version"
+    )]
+    version_variable: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print a key, e.g. profile.release.opt
+    Get {
+        /// build.<key>, profile.<name>.<key>, or just <key> for build.<key>
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// Project path containing wasmgo.toml
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        /// Use the global config file instead of the project's
+        #[arg(long)]
+        global: bool,
+    },
+
+    /// Set a key after validating it against the config schema (rewrites the file without comments)
+    Set {
+        /// build.<key>, profile.<name>.<key>, or just <key> for build.<key>
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// Value as on the command line; comma-separated for llvm-features
+        #[arg(value_name = "VALUE", allow_hyphen_values = true)]
+        value: String,
+
+        /// Project path containing wasmgo.toml
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        /// Use the global config file instead of the project's
+        #[arg(long)]
+        global: bool,
+    },
+
+    /// Remove a key
+    Unset {
+        /// build.<key>, profile.<name>.<key>, or just <key> for build.<key>
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// Project path containing wasmgo.toml
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        /// Use the global config file instead of the project's
+        #[arg(long)]
+        global: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DeployDestination {
+    /// Commit the site, staged in <output>/gh-pages/, to a gh-pages branch and push it
+    GhPages {
+        /// Project path containing go.mod or main.go
+        #[arg(short, long, default_value = ".", value_name = "PATH")]
+        project: PathBuf,
+
+        #[command(flatten)]
+        settings: CliBuildSettings,
+
+        /// Branch GitHub Pages serves from
+        #[arg(long, default_value = "gh-pages")]
+        branch: String,
+
+        /// Remote to push to
+        #[arg(long, default_value = "origin")]
+        remote: String,
+
+        /// URL path the site is served under (defaults to /<repository>/ for project pages)
+        #[arg(long, value_name = "PATH")]
+        base_path: Option<String>,
+
+        /// Write the site into this directory instead of committing; it must be empty or a checkout of the pages branch
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+
+        /// Commit to the branch but don't push
+        #[arg(long)]
+        no_push: bool,
+
+        /// Enable verbose compilation output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum CliOptimization {
+    /// Fast compilation with debug symbols
+    Debug,
+    /// Balanced optimization for production
+    Release,
+    /// Smallest possible output size
+    Size,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum CliTarget {
+    /// Standard WebAssembly module
+    Wasm,
+    /// Complete web application bundle, with an index.html loading the module
+    WebApp,
+    /// WASI preview 1 module for wasmtime, wazero and other runtimes
+    Wasi,
+    /// wasi-http proxy component for serverless handlers
+    WasiHttp,
+    /// Cloudflare Worker entry, wasm_exec.js and wrangler.toml
+    CloudflareWorker,
+    /// Extism plugin (Go PDK), written as plugin.wasm
+    Extism,
+    /// waPC guest module
+    Wapc,
+    /// Experimental: shared memory and atomics on a worker pool (TinyGo with wasi-threads)
+    Threads,
+    /// Freestanding module with no JS or WASI imports, for custom hosts (TinyGo)
+    WasmUnknown,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum CliDocFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CliGraphFormat {
+    /// Graphviz source (`dot -Tsvg`)
+    Dot,
+    /// Mermaid flowchart for Markdown
+    Mermaid,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CliStateDirectory {
+    /// Incremental build cache and memoized optimizations
+    Builds,
+    /// Toolchains downloaded by wasmgo
+    Toolchains,
+    /// Project template registries
+    Templates,
+    /// Build logs and metrics
+    History,
+    /// Single-file and playground modules, fallback build and output directories
+    Scratch,
+}
+
+impl From<CliStateDirectory> for StateDirectory {
+    fn from(directory: CliStateDirectory) -> Self {
+        match directory {
+            CliStateDirectory::Builds => StateDirectory::Builds,
+            CliStateDirectory::Toolchains => StateDirectory::Toolchains,
+            CliStateDirectory::Templates => StateDirectory::Templates,
+            CliStateDirectory::History => StateDirectory::History,
+            CliStateDirectory::Scratch => StateDirectory::Scratch,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CliToolchain {
+    /// TinyGo compiler (small binaries)
+    Tinygo,
+    /// Standard Go toolchain (full reflection and runtime support)
+    Go,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CliRuntime {
+    Wasmtime,
+    Wasmer,
+    Wazero,
+    /// Node.js: node:wasi for WASI modules, wasm_exec.js for js/wasm
+    Node,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum CliBuildMode {
+    /// Runs main on instantiation
+    Command,
+    /// Library module: exports _initialize, main is not run
+    Reactor,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum CliJsFramework {
+    /// useGoWasm() hook
+    React,
+    /// useGoWasm() composable
+    Vue,
+    /// Vanilla custom element
+    Element,
+}
+
+impl From<CliJsFramework> for JsFramework {
+    fn from(framework: CliJsFramework) -> Self {
+        match framework {
+            CliJsFramework::React => JsFramework::React,
+            CliJsFramework::Vue => JsFramework::Vue,
+            CliJsFramework::Element => JsFramework::Element,
+        }
+    }
+}
+
+impl From<CliOptimization> for OptimizationLevel {
+    fn from(opt: CliOptimization) -> Self {
+        match opt {
+            CliOptimization::Debug => OptimizationLevel::Debug,
+            CliOptimization::Release => OptimizationLevel::Release,
+            CliOptimization::Size => OptimizationLevel::Size,
+        }
+    }
+}
+
+impl From<CliTarget> for TargetType {
+    fn from(target: CliTarget) -> Self {
+        match target {
+            CliTarget::Wasm => TargetType::Standard,
+            CliTarget::WebApp => TargetType::WebApp,
+            CliTarget::Wasi => TargetType::Wasi,
+            CliTarget::WasiHttp => TargetType::WasiHttp,
+            CliTarget::CloudflareWorker => TargetType::CloudflareWorker,
+            CliTarget::Extism => TargetType::ExtismPlugin,
+            CliTarget::Wapc => TargetType::Wapc,
+            CliTarget::Threads => TargetType::Threads,
+            CliTarget::WasmUnknown => TargetType::WasmUnknown,
+        }
+    }
+}
+
+impl From<CliToolchain> for Toolchain {
+    fn from(toolchain: CliToolchain) -> Self {
+        match toolchain {
+            CliToolchain::Tinygo => Toolchain::TinyGo,
+            CliToolchain::Go => Toolchain::Go,
+        }
+    }
+}
+
+impl From<CliRuntime> for WasmRuntime {
+    fn from(runtime: CliRuntime) -> Self {
+        match runtime {
+            CliRuntime::Wasmtime => WasmRuntime::Wasmtime,
+            CliRuntime::Wasmer => WasmRuntime::Wasmer,
+            CliRuntime::Wazero => WasmRuntime::Wazero,
+            CliRuntime::Node => WasmRuntime::Node,
+        }
+    }
+}
+
+impl From<CliBuildMode> for BuildMode {
+    fn from(mode: CliBuildMode) -> Self {
+        match mode {
+            CliBuildMode::Command => BuildMode::Command,
+            CliBuildMode::Reactor => BuildMode::Reactor,
+        }
+    }
+}
+
+impl CliBuildSettings {
+    /// Only the flags actually given, so unset ones fall through to lower layers.
+    fn to_build_settings(&self) -> BuildSettings {
+        let single = |count: usize| count == 1;
+        BuildSettings {
+            output: self.output.clone(),
+            build_dir: self.build_dir.clone(),
+            opt: single(self.optimization.len()).then(|| self.optimization[0].clone().into()),
+            target: single(self.target.len()).then(|| self.target[0].clone().into()),
+            toolchain: single(self.toolchain.len()).then(|| self.toolchain[0].into()),
+            toolchain_fallback: self.no_toolchain_fallback.then_some(false),
+            mode: self.mode.clone().map(Into::into),
+            goflags: self.goflags.clone(),
+            goexperiment: self.goexperiment.clone(),
+            tags: (!self.tags.is_empty()).then(|| self.tags.clone()),
+            gocache: self.gocache.clone(),
+            gomodcache: self.gomodcache.clone(),
+            jobs: self.jobs,
+            fail_fast: self.fail_fast.then_some(true),
+            deny_warnings: self.deny_warnings.then_some(true),
+            hermetic: self.hermetic.then_some(true),
+            hermetic_dir: self.hermetic_dir.clone(),
+            no_network: self.no_network.then_some(true),
+            exact_toolchain: self.exact_toolchain.then_some(true),
+            tidy: self.tidy.then_some(true),
+            llvm_features: (!self.llvm_features.is_empty()).then(|| self.llvm_features.clone()),
+            cpu: self.cpu.clone(),
+            wasm_opt_passes: (!self.wasm_opt_passes.is_empty())
+                .then(|| self.wasm_opt_passes.clone()),
+            hash: self.hash.then_some(true),
+            provenance: self.provenance.then_some(true),
+            source_maps: self.source_maps.then_some(true),
+            smoke_test: self.smoke_test.then_some(true),
+            metrics: self.metrics.then_some(true),
+            cache: self.cache.then_some(true),
+            remote_cache: self.remote_cache.clone(),
+            remote_cache_read_only: self.remote_cache_read_only.then_some(true),
+            stamp_version: self.no_stamp_version.then_some(false),
+            version_variable: self.version_variable.clone(),
+        }
+    }
+}
+
+fn load_frameworks() -> FrameworkRegistry {
+    FrameworkRegistry::load().unwrap_or_else(|e| {
+        eprintln!("❌ {e}");
+        std::process::exit(1);
+    })
+}
+
+/// `command_defaults` are the subcommand's own, e.g. debug builds for `watch`;
+/// wasmgo.toml, its profiles, `WASMGO_*` and the flags all override them.
+fn resolve_build_settings(
+    project: &Path,
+    settings: &CliBuildSettings,
+    command_defaults: &BuildSettings,
+) -> ResolvedConfig {
+    match ResolvedConfig::resolve_for_command(
+        project,
+        settings.profile.as_deref(),
+        command_defaults,
+        &settings.to_build_settings(),
+    ) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exits when a subcommand that builds once is given several levels, targets
+/// or toolchains, which only `compile` (and `compare`) build side by side.
+fn require_single_build(command: &str, settings: &CliBuildSettings) {
+    if settings.optimization.len() > 1 || settings.target.len() > 1 || settings.toolchain.len() > 1
+    {
+        eprintln!(
+            "❌ {command} builds one optimization level, target and toolchain; use compile to build several"
+        );
+        std::process::exit(2);
+    }
+}
+
+fn print_header() {
+    println!(
+        "🐹 {} v{}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    println!("   {}", env!("CARGO_PKG_DESCRIPTION"));
+    println!();
+}
+
+fn check_project_validity<B: WasmBuilder + Plugin>(plugin: &B, project: &Path) -> bool {
+    if !plugin.can_handle_project(project) {
+        let language = plugin.language_name();
+        eprintln!(
+            "{}",
+            Messages::format("project.invalid", &[("language", &language)])
+        );
+        eprintln!(
+            "{}",
+            Messages::format(
+                "project.looking_for",
+                &[
+                    ("entries", &plugin.entry_file_candidates().join(", ")),
+                    ("extensions", &plugin.supported_extensions().join(", .")),
+                    ("path", &project.display()),
+                ]
+            )
+        );
+        eprintln!(
+            "{}",
+            Messages::format("project.hint", &[("language", &language)])
+        );
+        return false;
+    }
+    true
+}
+
+/// Offers `go mod init` for Go sources without a module: with `--init-module`,
+/// by asking on a terminal, or else as a hint. False when init fails.
+fn ensure_go_module(project: &Path, init_module: Option<&str>, verbose: bool) -> bool {
+    let scoped_root = DetectSettings::scoped_root(project);
+    let project = scoped_root.as_deref().unwrap_or(project);
+    if !GoModFile::needs_init(project) {
+        return true;
+    }
+    let suggested = GoModFile::suggested_module_path(project);
+    let directory = std::fs::canonicalize(project).unwrap_or_else(|_| project.to_path_buf());
+    let module_path = match init_module {
+        Some(module_path) => module_path.to_string(),
+        None if std::io::stdin().is_terminal() => {
+            eprint!(
+                "{}",
+                Messages::format(
+                    "module.prompt",
+                    &[("path", &directory.display()), ("module", &suggested)]
+                )
+            );
+            let mut answer = String::new();
+            if std::io::stdin().read_line(&mut answer).is_err() {
+                return true;
+            }
+            match answer.trim() {
+                "" | "y" | "Y" | "yes" => suggested,
+                "n" | "N" | "no" => return true,
+                module_path => module_path.to_string(),
+            }
+        }
+        None => {
+            eprintln!(
+                "{}",
+                Messages::format(
+                    "module.hint",
+                    &[("path", &directory.display()), ("module", &suggested)]
+                )
+            );
+            return true;
+        }
+    };
+
+    match GoModFile::init(project, &module_path, verbose) {
+        Ok(()) => {
+            println!(
+                "{}",
+                Messages::format("module.created", &[("module", &module_path)])
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("❌ {e}");
+            false
+        }
+    }
+}
+
+/// Writes Go source read from standard input to a temporary `main.go`.
+fn read_stdin_source() -> crate::PluginResult<PathBuf> {
+    let mut source = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut source)
+        .map_err(crate::PluginError::Io)?;
+    if source.trim().is_empty() {
+        return Err(crate::PluginError::InvalidProjectStructure {
+            reason: "no Go source on standard input".to_string(),
+        });
+    }
+    Playground::write_source(&source)
+}
+
+/// Serves the directory holding a built module until interrupted.
+fn serve_artifact(wasm_file: &Path, address: &str) -> crate::PluginResult<()> {
+    let directory = wasm_file.parent().unwrap_or(Path::new("."));
+    let server = StaticServer::bind(directory, address)?;
+    println!(
+        "{}",
+        Messages::format(
+            "serve.serving",
+            &[
+                ("directory", &directory.display()),
+                ("url", &server.url_for(wasm_file)?),
+            ]
+        )
+    );
+    println!("{}", Messages::text("serve.stop"));
+    server.serve()
+}
+
+fn check_dependencies<B: WasmBuilder + Plugin>(plugin: &B) -> bool {
+    report_missing_dependencies(&plugin.check_dependencies())
+}
+
+/// Like [`check_dependencies`], which lets Go stand in for a missing TinyGo,
+/// but requiring TinyGo when the configuration turns the fallback off.
+fn check_build_dependencies<B: WasmBuilder + Plugin>(
+    plugin: &B,
+    compile_config: &CompileConfig,
+) -> bool {
+    let mut missing_deps = plugin.check_dependencies();
+    let tinygo = Toolchain::TinyGo.command_name();
+    if compile_config.toolchain == Toolchain::TinyGo
+        && !compile_config.toolchain_fallback
+        && !CommandExecutor::is_tool_installed(tinygo)
+        && !missing_deps.iter().any(|dep| dep.starts_with(tinygo))
+    {
+        missing_deps.push(format!("{tinygo} (install from https://tinygo.org)"));
+    }
+    report_missing_dependencies(&missing_deps)
+}
+
+fn report_missing_dependencies(missing_deps: &[String]) -> bool {
+    if !missing_deps.is_empty() {
+        eprintln!("{}", Messages::text("dependencies.missing"));
+        for dep in missing_deps {
+            eprintln!("   • {dep}");
+        }
+        eprintln!();
+        eprintln!("{}", Messages::text("dependencies.suggestions"));
+        if missing_deps.iter().any(|d| d.contains("go")) {
+            eprintln!("{}", Messages::text("dependencies.install_go"));
+        }
+        if missing_deps.iter().any(|d| d.contains("tinygo")) {
+            eprintln!("{}", Messages::text("dependencies.install_tinygo"));
+        }
+        return false;
+    }
+    true
+}
+
+fn parse_run_config(
+    runtime: Option<CliRuntime>,
+    args: Vec<String>,
+    envs: &[String],
+    dirs: &[String],
+) -> WasiRunConfig {
+    let mut run_config = WasiRunConfig {
+        args,
+        runtime: runtime.map(Into::into),
+        ..WasiRunConfig::default()
+    };
+    let parsed = envs
+        .iter()
+        .try_for_each(|env| run_config.add_env(env))
+        .and_then(|()| dirs.iter().try_for_each(|dir| run_config.add_dir(dir)));
+    if let Err(e) = parsed {
+        eprintln!("❌ {e}");
+        std::process::exit(1);
+    }
+    run_config
+}
+
+fn print_compile_result(result: CompileResult, verbose: bool) {
+    println!(
+        "{}",
+        Messages::format(
+            "compile.wasm_file",
+            &[("path", &result.wasm_file_path.display())]
+        )
+    );
+
+    if let Some(js_path) = result.js_file_path {
+        println!(
+            "{}",
+            Messages::format("compile.js_bindings", &[("path", &js_path.display())])
+        );
+    }
+
+    if !result.additional_files.is_empty() {
+        println!(
+            "{}",
+            Messages::format(
+                "compile.additional_files",
+                &[("count", &result.additional_files.len())]
+            )
+        );
+        if verbose {
+            for artifact in result.additional_files {
+                println!(
+                    "   • {} ({:?}, {} bytes)",
+                    artifact.path.display(),
+                    artifact.kind,
+                    artifact.size
+                );
+            }
+        }
+    }
+    print_warnings(&result.warnings);
+}
+
+/// A build in the `--json` report of a multi-profile compile.
+#[derive(Serialize)]
+struct ProfileReport {
+    profile: String,
+    result: CompileResult,
+}
+
+/// An example in the `--json` report of `compile --examples`.
+#[derive(Serialize)]
+struct ExampleReport {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<CompileResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// `inspect --wasm --json`: the module's structure, plus the world of a component.
+#[derive(Serialize)]
+struct WasmInspection {
+    #[serde(flatten)]
+    binary: WasmBinary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    world: Option<ComponentWorld>,
+}
+
+/// `inspect --json` for a project.
+#[derive(Serialize)]
+struct ProjectInspection {
+    project: PathBuf,
+    go_project: bool,
+    go_files: Vec<String>,
+    go_mod: bool,
+    frameworks: Vec<DetectedFramework>,
+    missing_dependencies: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct DetectedFramework {
+    name: String,
+    /// What matched, e.g. the go.mod requirement
+    rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recipe: Option<String>,
+}
+
+/// `check-deps --json`.
+#[derive(Serialize)]
+struct DependencyReport {
+    missing: Vec<String>,
+    tools: Vec<ToolStatus>,
+    caches: Vec<GoCacheUsage>,
+}
+
+#[derive(Serialize)]
+struct ToolStatus {
+    name: &'static str,
+    installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+}
+
+fn print_json(report: &impl Serialize) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
+
+/// Go's caches as a build of `project` would use them, or `None` when its
+/// configuration doesn't resolve.
+fn project_go_caches(project: &Path) -> Option<Vec<GoCacheUsage>> {
+    let mut compile_config = CompileConfig {
+        project_path: project.to_path_buf(),
+        ..CompileConfig::default()
+    };
+    ResolvedConfig::resolve(project, None, &BuildSettings::default())
+        .and_then(|resolved| resolved.settings.apply_to(&mut compile_config))
+        .ok()?;
+    Some(GoCaches::inspect(&compile_config))
+}
+
+/// Prints every library message to stderr.
+struct StderrSink;
+
+impl LogSink for StderrSink {
+    fn log(&self, _level: LogLevel, message: &str) {
+        eprintln!("{message}");
+    }
+}
+
+/// What a watcher saw change before a rebuild.
+fn print_changes(changed: &[PathBuf]) {
+    match changed {
+        [single] => println!("\n🔄 Changed: {}", single.display()),
+        multiple => println!("\n🔄 {} files changed", multiple.len()),
+    }
+}
+
+fn print_rebuilt(result: &CompileResult) {
+    println!(
+        "{}",
+        Messages::format(
+            "compile.rebuilt",
+            &[("path", &result.wasm_file_path.display())]
+        )
+    );
+}
+
+/// The build's deduplicated toolchain warnings, after its other output.
+fn print_warnings(warnings: &[BuildWarning]) {
+    if warnings.is_empty() {
+        return;
+    }
+    eprintln!();
+    eprintln!(
+        "{}",
+        Messages::format("warnings.summary", &[("count", &warnings.len())])
+    );
+    for warning in warnings {
+        eprintln!("   • {warning}");
+    }
+}
+
+fn print_wasm_inspection(wasm_path: &Path) -> crate::PluginResult<()> {
+    let binary = WasmBinary::read(wasm_path)?;
+    println!("🔍 Inspecting {}...", wasm_path.display());
+    println!();
+
+    if binary.kind == WasmKind::Component {
+        let world = ComponentTools::inspect_component(wasm_path)?;
+        println!("🧩 Component ({} bytes)", binary.size);
+        println!("═══════════════════");
+        println!("📥 Imports: {}", world.imports.len());
+        for import in &world.imports {
+            println!("   • {import}");
+        }
+        println!("📤 Exports: {}", world.exports.len());
+        for export in &world.exports {
+            println!("   • {export}");
+        }
+        println!();
+        println!("📄 World");
+        println!("════════");
+        print!("{}", world.wit);
+        return Ok(());
+    }
+
+    println!("📦 Core module ({} bytes)", binary.size);
+    println!("═══════════════════");
+    println!("📊 Sections:");
+    for section in &binary.sections {
+        println!("   • {:<24} {:>10} bytes", section.name, section.size);
+    }
+    println!("📥 Imports: {}", binary.imports.len());
+    for import in &binary.imports {
+        println!("   • {}.{} ({:?})", import.module, import.name, import.kind);
+    }
+    println!("📤 Exports: {}", binary.exports.len());
+    for export in &binary.exports {
+        println!("   • {} ({:?})", export.name, export.kind);
+    }
+    Ok(())
+}
+
+fn print_size_analysis(
+    wasm_path: &Path,
+    top: usize,
+    min_length: usize,
+    project: Option<&Path>,
+) -> crate::PluginResult<()> {
+    let binary = WasmBinary::read(wasm_path)?;
+    if binary.kind == WasmKind::Component {
+        return Err(crate::PluginError::UnsupportedTarget {
+            reason: "size analysis works on core modules; analyze the core module the component was built from".to_string(),
+        });
+    }
+
+    let mut analysis = SizeAnalyzer::analyze(&binary, min_length, top);
+    if let Some(project) = project {
+        analysis.embedded_assets = EmbedScanner::scan_package(project);
+    }
+    let percent = |bytes: usize| bytes as f64 * 100.0 / analysis.wasm_size.max(1) as f64;
+    println!("📊 Size Analysis: {}", wasm_path.display());
+    println!("═══════════════════");
+    println!("📦 Total:  {:>10} bytes", analysis.wasm_size);
+    println!(
+        "⚙️  Code:   {:>10} bytes ({:.1}%)",
+        analysis.code_size,
+        percent(analysis.code_size)
+    );
+    println!(
+        "🗃️  Data:   {:>10} bytes ({:.1}%)",
+        analysis.data_size,
+        percent(analysis.data_size)
+    );
+    println!();
+
+    println!(
+        "🔤 Strings of {min_length}+ bytes: {} totalling {} bytes ({:.1}%)",
+        analysis.string_count,
+        analysis.string_bytes,
+        percent(analysis.string_bytes)
+    );
+    for total in &analysis.categories {
+        println!(
+            "   • {:<16} {:>5} strings {:>10} bytes",
+            total.category.label(),
+            total.count,
+            total.bytes
+        );
+    }
+    println!();
+
+    if !analysis.largest_strings.is_empty() {
+        println!("🏆 Largest strings:");
+        for string in &analysis.largest_strings {
+            println!(
+                "   {:>8} bytes @ 0x{:08x} [{}] {}",
+                string.length,
+                string.file_offset,
+                string.category.label(),
+                string.preview
+            );
+        }
+        println!();
+    }
+
+    if !analysis.embedded_assets.is_empty() {
+        println!(
+            "📎 go:embed assets: {} bytes ({:.1}% of the module)",
+            analysis.embedded_asset_bytes(),
+            percent(analysis.embedded_asset_bytes() as usize)
+        );
+        for asset in &analysis.embedded_assets {
+            println!(
+                "   • {:<24} {:>4} files {:>10} bytes ({})",
+                asset.pattern,
+                asset.files.len(),
+                asset.total_size,
+                asset.source_file
+            );
+        }
+        println!();
+        if analysis.embedded_assets_dominate() {
+            println!("⚠️  Embedded assets dominate the wasm payload");
+            println!(
+                "   Serve them as separate static files and fetch them at runtime instead of //go:embed"
+            );
+            println!();
+        }
+    }
+
+    for total in &analysis.categories {
+        match total.category {
+            StringCategory::TimezoneData => println!(
+                "💡 time/tzdata embeds the zoneinfo database; drop the import if the host provides timezones"
+            ),
+            StringCategory::FilePath => println!(
+                "💡 Source paths are embedded for panics; build with -trimpath or TinyGo's -no-debug"
+            ),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Runs the wasmgo command line with `plugin` as the plugin and builder, so
+/// other wasmrun language plugins can reuse the whole CLI surface.
+pub fn run_cli<B: WasmBuilder + Plugin>(plugin: B) -> Result<(), Box<dyn std::error::Error>> {
+    let command = Cli::command().about(plugin.info().description.clone());
+    let cli = Cli::from_arg_matches(&command.get_matches())?;
+    InterruptHandler::install()?;
+    let selected = Messages::select(cli.lang.as_deref());
+    if let Some(requested) = &cli.lang {
+        // pt-BR may be served by pt, but never by the English fallback
+        if !requested
+            .to_ascii_lowercase()
+            .replace('_', "-")
+            .starts_with(&selected)
+        {
+            eprintln!(
+                "{}",
+                Messages::format("locale.unavailable", &[("locale", requested)])
+            );
+        }
+    }
+
+    if cli.json {
+        if !matches!(
+            cli.command,
+            Commands::Compile { .. }
+                | Commands::Inspect { .. }
+                | Commands::CheckDeps { .. }
+                | Commands::CanHandle { .. }
+                | Commands::Info
+                | Commands::Metrics { .. }
+                | Commands::Frameworks
+        ) {
+            eprintln!("❌ --json is supported by compile, inspect, check-deps, can-handle, info, metrics and frameworks");
+            std::process::exit(2);
+        }
+        // Keeps stdout to the report
+        Logger::set_sink(StderrSink);
+    }
+
+    // Notices would interleave with upgrade's own report or the JSON
+    let notifier = (!cli.json
+        && !matches!(
+            cli.command,
+            Commands::Upgrade { .. } | Commands::RefreshUpdateCheck
+        ))
+    .then(UpdateNotifier::start);
+    run_command(cli.command, plugin, cli.json)?;
+    if let Some(notifier) = notifier {
+        notifier.print_notices();
+    }
+    Ok(())
+}
+
+fn run_command<B: WasmBuilder + Plugin>(
+    command: Commands,
+    plugin: B,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Default to Run command if no subcommand is provided
+    // Note: this would require making command optional in Cli struct
+    match command {
+        Commands::Run {
+            project,
+            settings,
+            wit,
+            world,
+            adapt,
+            exec,
+            runtime,
+            args,
+            envs,
+            dirs,
+            watch,
+            interval,
+            verbose,
+        } => {
+            require_single_build("run", &settings);
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                wit_world: wit.map(|wit_directory| WitWorld {
+                    wit_directory,
+                    world,
+                }),
+                preview2_adapter: adapt,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+
+            if verbose {
+                print_header();
+                println!("🚀 Preparing Go project for execution...");
+                println!("📁 Project: {}", project.display());
+                println!("📦 Output: {}", compile_config.output_directory.display());
+                println!("🎯 Optimization: {:?}", compile_config.optimization_level);
+                println!();
+            }
+
+            if !check_project_validity(&plugin, &project) {
+                std::process::exit(1);
+            }
+
+            if !check_build_dependencies(&plugin, &compile_config) {
+                std::process::exit(1);
+            }
+
+            let run_config = parse_run_config(runtime, args, &envs, &dirs);
+
+            let builder = plugin.get_builder();
+
+            if watch {
+                println!(
+                    "👀 Watching {} for changes (Ctrl-C to stop)",
+                    project.display()
+                );
+                let interval = std::time::Duration::from_millis(interval);
+                SourceWatcher::rebuild(&*builder, &compile_config, interval, |event| {
+                    match event {
+                        WatchEvent::Changed(changed) => print_changes(&changed),
+                        WatchEvent::Built(Ok(result)) if exec => {
+                            match ModuleRunner::run(
+                                &result.wasm_file_path,
+                                compile_config.toolchain,
+                                &run_config,
+                                verbose,
+                            ) {
+                                Ok(0) => {}
+                                Ok(code) => eprintln!("⚠️  Module exited with code {code}"),
+                                Err(e) => eprintln!(
+                                    "❌ Failed to execute {}: {e}",
+                                    result.wasm_file_path.display()
+                                ),
+                            }
+                        }
+                        WatchEvent::Built(Ok(result)) => {
+                            print_rebuilt(&result);
+                            print_warnings(&result.warnings);
+                        }
+                        WatchEvent::Built(Err(e)) => {
+                            eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]))
+                        }
+                    }
+                    std::ops::ControlFlow::Continue(())
+                });
+                return Ok(());
+            }
+
+            match builder.compile(&compile_config) {
+                Ok(result) if exec => {
+                    match ModuleRunner::run(
+                        &result.wasm_file_path,
+                        compile_config.toolchain,
+                        &run_config,
+                        verbose,
+                    ) {
+                        Ok(code) => std::process::exit(code),
+                        Err(e) => {
+                            eprintln!(
+                                "❌ Failed to execute {}: {e}",
+                                result.wasm_file_path.display()
+                            );
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                Ok(result) => {
+                    if verbose {
+                        println!("{}", Messages::text("compile.ready"));
+                        println!("🎯 Entry point: {}", result.wasm_file_path.display());
+                    } else {
+                        println!("{}", result.wasm_file_path.display());
+                    }
+                    print_warnings(&result.warnings);
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to prepare project for execution: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Test {
+            project,
+            settings,
+            golden,
+            update,
+            runtime,
+            args,
+            envs,
+            dirs,
+            verbose,
+        } => {
+            require_single_build("test", &settings);
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            if !check_project_validity(&plugin, &project)
+                || !check_build_dependencies(&plugin, &compile_config)
+            {
+                std::process::exit(1);
+            }
+            let run_config = parse_run_config(runtime, args, &envs, &dirs);
+
+            let result = match plugin.get_builder().compile(&compile_config) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                    std::process::exit(1);
+                }
+            };
+
+            let (exit_code, stdout) = match ModuleRunner::capture(
+                &result.wasm_file_path,
+                compile_config.toolchain,
+                &run_config,
+                verbose,
+            ) {
+                Ok(captured) => captured,
+                Err(e) => {
+                    eprintln!(
+                        "❌ Failed to execute {}: {e}",
+                        result.wasm_file_path.display()
+                    );
+                    std::process::exit(1);
+                }
+            };
+            if exit_code != 0 {
+                eprintln!("❌ Module exited with code {exit_code}");
+            }
+
+            match GoldenFile::check(&golden, &stdout, update) {
+                Ok(GoldenOutcome::Matched) if exit_code == 0 => {
+                    println!("✅ Output matches {}", golden.display());
+                }
+                Ok(GoldenOutcome::Matched) => std::process::exit(1),
+                Ok(GoldenOutcome::Updated) => {
+                    println!("📝 Updated {} ({} bytes)", golden.display(), stdout.len());
+                    if exit_code != 0 {
+                        std::process::exit(1);
+                    }
+                }
+                Ok(GoldenOutcome::Mismatch { diff }) => {
+                    eprintln!(
+                        "❌ Output differs from {} (- expected, + actual):",
+                        golden.display()
+                    );
+                    eprint!("{diff}");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Watch {
+            project,
+            settings,
+            exec,
+            interval,
+            low_priority,
+            verbose,
+        } => {
+            require_single_build("watch", &settings);
+            let resolved = resolve_build_settings(
+                &project,
+                &settings,
+                &BuildSettings {
+                    opt: Some(OptimizationLevel::Debug),
+                    ..BuildSettings::default()
+                },
+            );
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            if !check_project_validity(&plugin, &project)
+                || !check_build_dependencies(&plugin, &compile_config)
+            {
+                std::process::exit(1);
+            }
+            let post_build = exec.as_deref().map(PostBuildCommand::new);
+            let builder = plugin.get_builder();
+
+            if low_priority {
+                match ProcessPriority::lower() {
+                    Ok(()) => println!("🐢 Rebuilding at low priority"),
+                    Err(e) => eprintln!("⚠️  Could not lower the priority: {e}"),
+                }
+            }
+            println!(
+                "👀 Watching {} for changes (Ctrl-C to stop)",
+                project.display()
+            );
+            SourceWatcher::rebuild(
+                &*builder,
+                &compile_config,
+                std::time::Duration::from_millis(interval),
+                |event| {
+                    match event {
+                        WatchEvent::Changed(changed) => print_changes(&changed),
+                        WatchEvent::Built(Ok(result)) => {
+                            print_rebuilt(&result);
+                            if let Some(post_build) = &post_build {
+                                let wasm_path = result.wasm_file_path.as_path();
+                                println!("▶️  {}", post_build.render(wasm_path));
+                                match post_build.run(wasm_path) {
+                                    Ok(0) => {}
+                                    Ok(code) => eprintln!("⚠️  Command exited with code {code}"),
+                                    Err(e) => eprintln!("❌ Failed to run command: {e}"),
+                                }
+                            }
+                        }
+                        WatchEvent::Built(Err(e)) => {
+                            eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]))
+                        }
+                    }
+                    std::ops::ControlFlow::Continue(())
+                },
+            );
+        }
+
+        Commands::Serve {
+            project,
+            settings,
+            address,
+            interval,
+            no_watch,
+            verbose,
+        } => {
+            require_single_build("serve", &settings);
+            let resolved = resolve_build_settings(
+                &project,
+                &settings,
+                &BuildSettings {
+                    opt: Some(OptimizationLevel::Debug),
+                    target: Some(TargetType::WebApp),
+                    ..BuildSettings::default()
+                },
+            );
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            if !check_project_validity(&plugin, &project)
+                || !check_build_dependencies(&plugin, &compile_config)
+            {
+                std::process::exit(1);
+            }
+            let output_directory = project.join(&compile_config.output_directory);
+            let builder = plugin.get_builder();
+
+            // Serving starts before the first build, so a failed one is replaced by the next
+            if let Err(e) = std::fs::create_dir_all(&output_directory) {
+                eprintln!("❌ Failed to create {}: {e}", output_directory.display());
+                std::process::exit(1);
+            }
+            let server = match StaticServer::bind(&output_directory, &address) {
+                Ok(server) => server,
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            };
+            match server.url_for(&output_directory) {
+                Ok(url) => println!(
+                    "{}",
+                    Messages::format(
+                        "serve.serving",
+                        &[("directory", &output_directory.display()), ("url", &url)]
+                    )
+                ),
+                Err(e) => eprintln!("⚠️  {e}"),
+            }
+            println!("{}", Messages::text("serve.rebuild"));
+            println!("{}", Messages::text("serve.stop"));
+            std::thread::spawn(move || server.serve());
+
+            // An empty list is a rebuild requested with Enter
+            let (sender, receiver) = std::sync::mpsc::channel::<Vec<PathBuf>>();
+            let requests = sender.clone();
+            std::thread::spawn(move || {
+                for _ in std::io::stdin().lines().map_while(Result::ok) {
+                    if requests.send(Vec::new()).is_err() {
+                        break;
+                    }
+                }
+            });
+            if !no_watch {
+                let changes = sender.clone();
+                let mut watcher = SourceWatcher::for_build(
+                    &compile_config,
+                    std::time::Duration::from_millis(interval),
+                );
+                std::thread::spawn(
+                    move || {
+                        while changes.send(watcher.wait_for_change()).is_ok() {}
+                    },
+                );
+            }
+            // `sender` stays alive so the server keeps running after stdin closes
+            SourceWatcher::rebuild_on(&*builder, &compile_config, receiver.iter(), |event| {
+                match event {
+                    WatchEvent::Changed(changed) if changed.is_empty() => {
+                        println!("\n🔄 Rebuilding")
+                    }
+                    WatchEvent::Changed(changed) => print_changes(&changed),
+                    WatchEvent::Built(Ok(result)) => print_rebuilt(&result),
+                    WatchEvent::Built(Err(e)) => {
+                        eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]))
+                    }
+                }
+                std::ops::ControlFlow::Continue(())
+            });
+            drop(sender);
+        }
+
+        Commands::Compile {
+            project,
+            settings,
+            wit,
+            world,
+            adapt,
+            per_profile,
+            examples,
+            retain,
+            web_worker,
+            js_framework,
+            single_file,
+            init_module,
+            stdin,
+            build_args,
+            serve,
+            verbose,
+        } => {
+            let project = if stdin {
+                match read_stdin_source() {
+                    Ok(file) => file,
+                    Err(e) => {
+                        eprintln!("❌ {e}");
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                project
+            };
+            if settings.toolchain.len() > 1 {
+                eprintln!(
+                    "❌ compile builds with one toolchain; use compare to build with several"
+                );
+                std::process::exit(2);
+            }
+            if !ensure_go_module(&project, init_module.as_deref(), verbose) {
+                std::process::exit(1);
+            }
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                wit_world: wit.map(|wit_directory| WitWorld {
+                    wit_directory,
+                    world,
+                }),
+                preview2_adapter: adapt,
+                retain_builds: retain,
+                web_worker,
+                js_framework: js_framework.map(Into::into),
+                single_file,
+                extra_build_args: build_args,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            // The source file is temporary; an explicit output directory is the caller's
+            if stdin && settings.output.is_some() && compile_config.output_directory.is_relative() {
+                if let Ok(current_directory) = std::env::current_dir() {
+                    compile_config.output_directory =
+                        current_directory.join(&compile_config.output_directory);
+                }
+            }
+            // Several levels or targets on the command line build one profile each
+            let levels: Vec<OptimizationLevel> = match settings.optimization.len() {
+                0 | 1 => vec![compile_config.optimization_level],
+                _ => settings.optimization.into_iter().map(Into::into).collect(),
+            };
+            let targets: Vec<TargetType> = match settings.target.len() {
+                0 | 1 => vec![compile_config.target_type.clone()],
+                _ => settings.target.into_iter().map(Into::into).collect(),
+            };
+
+            if verbose && !json {
+                print_header();
+                println!("🔨 Compiling Go project to WebAssembly...");
+                println!("📁 Project: {}", project.display());
+                println!("📦 Output: {}", compile_config.output_directory.display());
+                println!("🎯 Optimization: {levels:?}");
+                println!("🏗️  Target: {targets:?}");
+                if let Some((profile, _)) = &resolved.profile {
+                    println!("🗂️  Profile: {profile}");
+                }
+                println!();
+            }
+
+            if !check_project_validity(&plugin, &project) {
+                std::process::exit(1);
+            }
+
+            if !check_build_dependencies(&plugin, &compile_config) {
+                std::process::exit(1);
+            }
+
+            let builder = plugin.get_builder();
+
+            if examples {
+                let results = match Examples::build_all(builder.as_ref(), &compile_config) {
+                    Ok(results) => results,
+                    Err(e) => {
+                        eprintln!("❌ {e}");
+                        std::process::exit(1);
+                    }
+                };
+                if json {
+                    let reports: Vec<ExampleReport> = results
+                        .into_iter()
+                        .map(|(example, result)| match result {
+                            Ok(result) => ExampleReport {
+                                name: example.name,
+                                result: Some(result),
+                                error: None,
+                            },
+                            Err(e) => ExampleReport {
+                                name: example.name,
+                                result: None,
+                                error: Some(e.to_string()),
+                            },
+                        })
+                        .collect();
+                    print_json(&reports)?;
+                    if reports.iter().any(|report| report.error.is_some()) {
+                        std::process::exit(1);
+                    }
+                    return Ok(());
+                }
+                let mut failed = Vec::new();
+                for (example, result) in results {
+                    println!();
+                    println!("🧪 {}", example.name);
+                    match result {
+                        Ok(result) => print_compile_result(result, verbose),
+                        Err(e) => {
+                            eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                            failed.push(example.name);
+                        }
+                    }
+                }
+                println!();
+                if !failed.is_empty() {
+                    eprintln!(
+                        "{}",
+                        Messages::format(
+                            "examples.failed",
+                            &[("count", &failed.len()), ("names", &failed.join(", "))]
+                        )
+                    );
+                    std::process::exit(1);
+                }
+                println!("{}", Messages::text("examples.built_all"));
+                return Ok(());
+            }
+
+            if per_profile || targets.len() > 1 || levels.len() > 1 {
+                match BuildLayout::build_profiles(
+                    builder.as_ref(),
+                    &compile_config,
+                    &targets,
+                    &levels,
+                ) {
+                    Ok(results) if json => {
+                        let reports: Vec<ProfileReport> = results
+                            .into_iter()
+                            .map(|(profile, result)| ProfileReport { profile, result })
+                            .collect();
+                        print_json(&reports)?;
+                    }
+                    Ok(results) => {
+                        println!(
+                            "{}",
+                            Messages::format(
+                                "compile.profiles_built",
+                                &[("count", &results.len())]
+                            )
+                        );
+                        for (profile, result) in results {
+                            println!();
+                            println!("🗂️  {profile}");
+                            print_compile_result(result, verbose);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+
+            match builder.compile(&compile_config) {
+                Ok(result) => {
+                    let wasm_file = result.wasm_file_path.clone();
+                    if json {
+                        print_json(&result)?;
+                    } else {
+                        println!("{}", Messages::text("compile.success"));
+                        print_compile_result(result, verbose);
+                    }
+                    if let Some(address) = serve {
+                        if let Err(e) = serve_artifact(&wasm_file, &address) {
+                            eprintln!("❌ {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Bundle {
+            project,
+            settings,
+            bundle_dir,
+            deploy,
+            verbose,
+        } => {
+            print_header();
+            require_single_build("bundle", &settings);
+            let resolved = resolve_build_settings(
+                &project,
+                &settings,
+                &BuildSettings {
+                    opt: Some(OptimizationLevel::Size),
+                    ..BuildSettings::default()
+                },
+            );
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            if !check_project_validity(&plugin, &project)
+                || !check_build_dependencies(&plugin, &compile_config)
+            {
+                std::process::exit(1);
+            }
+
+            let builder = plugin.get_builder();
+            let result = match builder.compile(&compile_config) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                    std::process::exit(1);
+                }
+            };
+
+            let asset_paths: Vec<PathBuf> = std::iter::once(result.wasm_file_path)
+                .chain(result.js_file_path)
+                .chain(
+                    result
+                        .additional_files
+                        .into_iter()
+                        .map(|artifact| artifact.path),
+                )
+                .collect();
+            let bundle_directory = project.join(&bundle_dir);
+            println!("📦 Bundling into {}...", bundle_directory.display());
+            match DeployBundler::bundle(&asset_paths, &bundle_directory, deploy) {
+                Ok(bundle) => {
+                    for file in &bundle.files {
+                        let gzip = file
+                            .gzip_size
+                            .map(|size| format!(" (gzip {size})"))
+                            .unwrap_or_default();
+                        println!("   • {:<40} {:>10} bytes{gzip}", file.file, file.size);
+                    }
+                    println!("✅ Bundle ready: {}", bundle.directory);
+                    if deploy {
+                        println!("🔒 Integrity manifest: {INTEGRITY_FILENAME}");
+                        println!("📝 Hosting rules: _headers, .htaccess");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ Bundling failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Deploy {
+            destination:
+                DeployDestination::GhPages {
+                    project,
+                    settings,
+                    branch,
+                    remote,
+                    base_path,
+                    dir,
+                    no_push,
+                    verbose,
+                },
+        } => {
+            print_header();
+            require_single_build("deploy", &settings);
+            let resolved = resolve_build_settings(
+                &project,
+                &settings,
+                &BuildSettings {
+                    opt: Some(OptimizationLevel::Size),
+                    target: Some(TargetType::WebApp),
+                    ..BuildSettings::default()
+                },
+            );
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            if !check_project_validity(&plugin, &project)
+                || !check_build_dependencies(&plugin, &compile_config)
+            {
+                std::process::exit(1);
+            }
+
+            let builder = plugin.get_builder();
+            let result = match builder.compile(&compile_config) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                    std::process::exit(1);
+                }
+            };
+            let asset_paths: Vec<PathBuf> = std::iter::once(result.wasm_file_path)
+                .chain(result.js_file_path)
+                .chain(
+                    result
+                        .additional_files
+                        .into_iter()
+                        .map(|artifact| artifact.path),
+                )
+                .collect();
+
+            let base_path =
+                base_path.unwrap_or_else(|| GhPagesDeployer::default_base_path(&project));
+            let site_directory = match &dir {
+                Some(dir) => PathBuf::from(dir),
+                None => {
+                    // wasmgo's own staging directory, rewritten on every deploy
+                    let site_directory = project
+                        .join(&compile_config.output_directory)
+                        .join("gh-pages");
+                    if let Err(e) = std::fs::remove_dir_all(&site_directory) {
+                        if e.kind() != std::io::ErrorKind::NotFound {
+                            eprintln!("❌ Failed to prepare site: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                    site_directory
+                }
+            };
+            println!(
+                "📄 Preparing site in {} (base path {base_path})...",
+                site_directory.display()
+            );
+            if let Err(e) =
+                GhPagesDeployer::prepare_site(&asset_paths, &site_directory, &base_path, &branch)
+            {
+                eprintln!("❌ Failed to prepare site: {e}");
+                std::process::exit(1);
+            }
+
+            if dir.is_some() {
+                println!(
+                    "✅ Site written to {}; commit it to publish",
+                    site_directory.display()
+                );
+                return Ok(());
+            }
+            match GhPagesDeployer::publish(&project, &site_directory, &branch, &remote, !no_push) {
+                Ok(commit) => println!("✅ Deployed {commit} to {branch}"),
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Push {
+            reference,
+            project,
+            settings,
+            wasm,
+            annotations,
+            verbose,
+        } => {
+            print_header();
+            let wasm_file_path = match wasm {
+                Some(wasm) => wasm,
+                None => {
+                    require_single_build("push", &settings);
+                    let resolved = resolve_build_settings(
+                        &project,
+                        &settings,
+                        &BuildSettings {
+                            opt: Some(OptimizationLevel::Size),
+                            ..BuildSettings::default()
+                        },
+                    );
+                    let mut compile_config = CompileConfig {
+                        project_path: project.clone(),
+                        verbose,
+                        ..CompileConfig::default()
+                    };
+                    if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                        eprintln!("❌ {e}");
+                        std::process::exit(1);
+                    }
+                    if !check_project_validity(&plugin, &project)
+                        || !check_build_dependencies(&plugin, &compile_config)
+                    {
+                        std::process::exit(1);
+                    }
+                    match plugin.get_builder().compile(&compile_config) {
+                        Ok(result) => result.wasm_file_path,
+                        Err(e) => {
+                            eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            };
+            let build_manifest_path = wasm_file_path.with_file_name(MANIFEST_FILENAME);
+            let build_manifest_path = build_manifest_path
+                .is_file()
+                .then_some(build_manifest_path.as_path());
+
+            println!("📤 Pushing {} to {reference}...", wasm_file_path.display());
+            match OciPublisher::push(
+                &reference,
+                &wasm_file_path,
+                build_manifest_path,
+                &annotations,
+                verbose,
+            ) {
+                Ok(digest) => {
+                    println!("✅ Pushed {reference}");
+                    if !digest.is_empty() {
+                        println!("🔖 Digest: {digest}");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Compare {
+            project,
+            settings,
+            verbose,
+        } => {
+            print_header();
+            if settings.target.len() > 1 {
+                eprintln!("❌ compare builds one target; use compile to build several");
+                std::process::exit(2);
+            }
+            if !check_project_validity(&plugin, &project) {
+                std::process::exit(1);
+            }
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project,
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+
+            let builder = plugin.get_builder();
+            // Every level, and the configured toolchain, unless the flags name several
+            let toolchains: Vec<Toolchain> = match settings.toolchain.len() {
+                0 | 1 => vec![compile_config.toolchain],
+                _ => settings.toolchain.into_iter().map(Into::into).collect(),
+            };
+            let levels: Vec<OptimizationLevel> = match settings.optimization.len() {
+                0 => OptimizationLevel::ALL.to_vec(),
+                _ => settings.optimization.into_iter().map(Into::into).collect(),
+            };
+            let entries =
+                BuildComparison::run(builder.as_ref(), &compile_config, &toolchains, &levels);
+
+            println!();
+            println!("📊 Build Comparison");
+            println!("═══════════════════");
+            println!(
+                "   {:<10} {:<10} {:>12} {:>10}",
+                "Toolchain", "Level", "Size", "Time"
+            );
+            for entry in &entries {
+                let size = entry
+                    .wasm_size
+                    .map(|size| format!("{size} B"))
+                    .unwrap_or_else(|| "failed".to_string());
+                println!(
+                    "   {:<10} {:<10} {:>12} {:>9.1}s",
+                    entry.toolchain.display_name(),
+                    entry.optimization.name(),
+                    size,
+                    entry.build_time.as_secs_f64()
+                );
+            }
+            for entry in entries.iter().filter(|entry| entry.error.is_some()) {
+                println!(
+                    "❌ {} / {}: {}",
+                    entry.toolchain.display_name(),
+                    entry.optimization.name(),
+                    entry.error.as_deref().unwrap_or_default()
+                );
+            }
+            if let Some(smallest) = BuildComparison::smallest(&entries) {
+                println!();
+                println!(
+                    "🏆 Smallest: {} / {} ({} bytes)",
+                    smallest.toolchain.display_name(),
+                    smallest.optimization.name(),
+                    smallest.wasm_size.unwrap_or_default()
+                );
+            }
+        }
+
+        Commands::VerifyReproducible {
+            project,
+            settings,
+            verbose,
+        } => {
+            print_header();
+            if !check_project_validity(&plugin, &project) {
+                std::process::exit(1);
+            }
+            require_single_build("verify-reproducible", &settings);
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project,
+                verbose,
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+
+            let builder = plugin.get_builder();
+            let report = match ReproducibilityCheck::run(builder.as_ref(), &compile_config) {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("❌ Build failed: {e}");
+                    std::process::exit(1);
+                }
+            };
+
+            println!();
+            if report.is_reproducible() {
+                println!(
+                    "✅ Reproducible: {} identical in both builds",
+                    report.compared.join(", ")
+                );
+                return Ok(());
+            }
+            println!("❌ Builds differ");
+            for difference in &report.differences {
+                println!(
+                    "   {}: {} vs {} bytes, first difference at byte {}",
+                    difference.file_name,
+                    difference.first_size,
+                    difference.second_size,
+                    difference.first_difference
+                );
+                for section in &difference.sections {
+                    println!(
+                        "      • {} section ({} vs {} bytes)",
+                        section.name, section.first_size, section.second_size
+                    );
+                }
+            }
+            println!(
+                "📂 Builds kept in {} and {}",
+                report.first_build.display(),
+                report.second_build.display()
+            );
+            std::process::exit(1);
+        }
+
+        Commands::Inspect {
+            wasm: Some(wasm_path),
+            ..
+        } if json => {
+            let inspection = WasmBinary::read(&wasm_path).and_then(|binary| {
+                let world = match binary.kind {
+                    WasmKind::Component => Some(ComponentTools::inspect_component(&wasm_path)?),
+                    WasmKind::CoreModule => None,
+                };
+                Ok(WasmInspection { binary, world })
+            });
+            match inspection {
+                Ok(inspection) => print_json(&inspection)?,
+                Err(e) => {
+                    eprintln!("❌ Failed to inspect {}: {e}", wasm_path.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Inspect {
+            wasm: Some(wasm_path),
+            ..
+        } => {
+            print_header();
+            if let Err(e) = print_wasm_inspection(&wasm_path) {
+                eprintln!("❌ Failed to inspect {}: {e}", wasm_path.display());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Optimize {
+            input,
+            output,
+            level,
+            passes,
+            no_wasm_opt,
+            strip,
+            aggressive,
+            snip_patterns,
+            verbose,
+        } => {
+            print_header();
+            let output_path = output.unwrap_or_else(|| input.with_extension("opt.wasm"));
+            let mut pipeline = if aggressive {
+                OptimizationPipeline::aggressive()
+            } else {
+                OptimizationPipeline::default()
+            };
+            pipeline.wasm_opt_level = (!no_wasm_opt).then_some(level);
+            pipeline.wasm_opt_passes = passes;
+            pipeline.strip |= strip;
+            pipeline.snip_patterns.extend(snip_patterns);
+            if pipeline.is_empty() {
+                eprintln!("❌ Nothing to do: enable wasm-opt, --strip or --snip-pattern");
+                std::process::exit(1);
+            }
+
+            println!("⚡ Optimizing {}...", input.display());
+            match pipeline.run(&input, &output_path, verbose) {
+                Ok(report) => {
+                    if report.memoized {
+                        println!("♻️  Module unchanged since an earlier run; reused its output");
+                    }
+                    for step in &report.steps {
+                        println!(
+                            "   • {:<16} {:>10} → {:>10} bytes",
+                            step.name, step.size_before, step.size_after
+                        );
+                    }
+                    println!("✅ Wrote {}", output_path.display());
+                    println!(
+                        "📦 {} → {} bytes ({:.1}% smaller)",
+                        report.input_size,
+                        report.output_size,
+                        report.percent_saved()
+                    );
+                }
+                Err(e) => {
+                    eprintln!("❌ Optimization failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Analyze {
+            input,
+            top,
+            min_length,
+            project,
+        } => {
+            print_header();
+            if let Err(e) = print_size_analysis(&input, top, min_length, project.as_deref()) {
+                eprintln!("❌ Failed to analyze {}: {e}", input.display());
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Docgen {
+            project,
+            wasm,
+            format,
+            output,
+        } => {
+            let binary = match wasm
+                .as_deref()
+                .map(|wasm| WasmBinary::read(Path::new(wasm)))
+            {
+                Some(Ok(binary)) => Some(binary),
+                Some(Err(e)) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+                None => None,
+            };
+            let exports = ExportScanner::scan_package(&project);
+            let functions = ApiDocGenerator::collect(&exports, binary.as_ref());
+            let title = std::fs::canonicalize(&project)
+                .ok()
+                .and_then(|path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| project.to_string_lossy().to_string());
+            let document = match format {
+                CliDocFormat::Markdown => ApiDocGenerator::markdown(&title, &functions),
+                CliDocFormat::Html => ApiDocGenerator::html(&title, &functions),
+            };
+
+            match output {
+                Some(output) => {
+                    if let Err(e) = std::fs::write(&output, document) {
+                        eprintln!("❌ Failed to write {}: {e}", output.display());
+                        std::process::exit(1);
+                    }
+                    println!(
+                        "📚 Documented {} exported functions in {}",
+                        functions.len(),
+                        output.display()
+                    );
+                }
+                None => print!("{document}"),
+            }
+        }
+
+        Commands::Inspect { project, .. } if json => {
+            let go_project = plugin.can_handle_project(&project);
+            let mut go_files: Vec<String> = std::fs::read_dir(&project)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .filter(|name| name.to_lowercase().ends_with(".go"))
+                        .collect()
+                })
+                .unwrap_or_default();
+            go_files.sort();
+            let frameworks = load_frameworks()
+                .detect(&project)
+                .into_iter()
+                .map(|(framework, rule)| DetectedFramework {
+                    name: framework.name.clone(),
+                    rule,
+                    recipe: framework.recipe.clone(),
+                })
+                .collect();
+            let missing_dependencies = plugin.get_builder().check_dependencies();
+            let ready = go_project && missing_dependencies.is_empty();
+            print_json(&ProjectInspection {
+                go_mod: project.join("go.mod").exists(),
+                project,
+                go_project,
+                go_files,
+                frameworks,
+                missing_dependencies,
+            })?;
+            if !ready {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Inspect { project, .. } => {
+            print_header();
+            println!("🔍 Inspecting Go project...");
+            println!();
+
+            if plugin.can_handle_project(&project) {
+                println!("📊 Project Analysis");
+                println!("═══════════════════");
+
+                if let Ok(directory_entries) = std::fs::read_dir(&project) {
+                    let go_files: Vec<_> = directory_entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| {
+                            entry
+                                .path()
+                                .extension()
+                                .map(|extension| extension.to_string_lossy().to_lowercase() == "go")
+                                .unwrap_or(false)
+                        })
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .collect();
+
+                    if !go_files.is_empty() {
+                        println!("📁 Go files: {}", go_files.join(", "));
+                    }
+                }
+
+                if project.join("go.mod").exists() {
+                    println!("📦 Module: Found go.mod");
+                }
+
+                println!("🎯 Type: Go WebAssembly project");
+                println!("🔧 Build Tool: TinyGo");
+                for (framework, rule) in load_frameworks().detect(&project) {
+                    println!("🧩 Framework: {} ({rule})", framework.title);
+                    if let Some(recipe) = &framework.recipe {
+                        println!("   Build with: {recipe}");
+                    }
+                }
+
+                println!();
+                println!("📋 Dependencies");
+                println!("═══════════════");
+
+                let missing = plugin.get_builder().check_dependencies();
+                if missing.is_empty() {
+                    println!("✅ go - Go compiler");
+                    println!("✅ tinygo - WebAssembly compiler for Go");
+                    println!();
+                    println!("🎉 Project is ready to compile!");
+                } else {
+                    for dep in &missing {
+                        println!("❌ {dep}");
+                    }
+                    println!();
+                    println!(
+                        "⚠️  Some required dependencies are missing. Install them to proceed."
+                    );
+                    std::process::exit(1);
+                }
+            } else {
+                eprintln!("❌ Invalid project: Not a Go project");
+                eprintln!(
+                    "   Looking for go.mod or .go files in: {}",
+                    project.display()
+                );
+                std::process::exit(1);
+            }
+        }
+
+        Commands::CanHandle { project } => {
+            let assessment = plugin.assess_project(&project);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&assessment)?);
+            } else if assessment.claims() {
+                println!(
+                    "✅ Yes, wasmgo can handle this project ({:.0}% confidence)",
+                    assessment.confidence * 100.0
+                );
+                for reason in &assessment.reasons {
+                    println!("   • {reason}");
+                }
+            } else {
+                println!("❌ No, wasmgo cannot handle this project");
+                if assessment.reasons.is_empty() {
+                    println!(
+                        "🔍 Looking for go.mod or .go files in: {}",
+                        project.display()
+                    );
+                }
+                for reason in &assessment.reasons {
+                    println!("   • {reason}");
+                }
+            }
+            if !assessment.claims() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Pipeline {
+            project,
+            format,
+            timed,
+            settings,
+        } => {
+            require_single_build("pipeline", &settings);
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+            let pipeline = if timed {
+                if !check_project_validity(&plugin, &project) || !check_dependencies(&plugin) {
+                    std::process::exit(1);
+                }
+                // Keeps stdout to the graph, so it can be redirected to a file
+                Logger::set_sink(StderrSink);
+                let (pipeline, result) =
+                    BuildPipeline::timed(plugin.get_builder().as_ref(), &compile_config);
+                if let Err(e) = result {
+                    eprintln!("{}", Messages::format("compile.failed", &[("error", &e)]));
+                    std::process::exit(1);
+                }
+                pipeline
+            } else {
+                BuildPipeline::plan(&compile_config)
+            };
+            match format {
+                CliGraphFormat::Dot => print!("{}", pipeline.to_dot()),
+                CliGraphFormat::Mermaid => print!("{}", pipeline.to_mermaid()),
+            }
+        }
+
+        Commands::Env { project, settings } => {
+            require_single_build("env", &settings);
+            let resolved = resolve_build_settings(&project, &settings, &BuildSettings::default());
+            let mut compile_config = CompileConfig {
+                project_path: project.clone(),
+                ..CompileConfig::default()
+            };
+            if let Err(e) = resolved.settings.apply_to(&mut compile_config) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+
+            println!("⚙️  Effective configuration for {}", project.display());
+            if resolved.config_files.is_empty() {
+                println!("📄 Config files: none ({PROJECT_CONFIG_FILENAME} not found)");
+            }
+            for path in &resolved.config_files {
+                println!("📄 Config file: {}", path.display());
+            }
+            match &resolved.profile {
+                Some((profile, selected_by)) => {
+                    println!("🗂️  Profile: {profile} (selected by {selected_by})")
+                }
+                None => println!("🗂️  Profile: none"),
+            }
+            println!();
+            let width = resolved
+                .sources
+                .iter()
+                .map(|setting| setting.key.len())
+                .max()
+                .unwrap_or(0);
+            let value_width = resolved
+                .sources
+                .iter()
+                .map(|setting| setting.value.to_string().len())
+                .max()
+                .unwrap_or(0);
+            for setting in &resolved.sources {
+                println!(
+                    "   {:width$} = {:value_width$}  # {}",
+                    setting.key,
+                    setting.value.to_string(),
+                    setting.source
+                );
+            }
+            let unset: Vec<&str> = BuildSettings::KEYS
+                .iter()
+                .copied()
+                .filter(|key| resolved.sources.iter().all(|setting| setting.key != *key))
+                .collect();
+            if !unset.is_empty() {
+                println!("   (unset: {})", unset.join(", "));
+            }
+
+            if let Some(dirs) = Dirs::discover() {
+                println!();
+                println!("📂 wasmgo directories:");
+                println!("   config: {}", dirs.config.display());
+                for directory in StateDirectory::ALL {
+                    println!(
+                        "   {}: {}",
+                        directory.name(),
+                        dirs.state(directory).display()
+                    );
+                }
+            }
+
+            println!();
+            println!("🧰 Build environment set by wasmgo:");
+            let build_environment = compile_config.build_environment();
+            if build_environment.is_empty() && !compile_config.hermetic {
+                println!("   (none)");
+            }
+            for (key, value) in &build_environment {
+                println!("   {key}={value}");
+            }
+            if compile_config.hermetic {
+                match &compile_config.hermetic_directory {
+                    Some(directory) => println!(
+                        "   GOCACHE, GOMODCACHE, GOPATH, XDG_CACHE_HOME under {}, GOENV=off", directory.display()
+                    ),
+                    None => println!(
+                        "   GOCACHE, GOMODCACHE, GOPATH, XDG_CACHE_HOME in a fresh temp directory, GOENV=off"
+                    ),
+                }
+            }
+
+            let environment: Vec<(&str, &OsStr)> = build_environment
+                .iter()
+                .map(|(key, value)| (*key, OsStr::new(value)))
+                .collect();
+            let toolchain = ToolchainEnvironment::capture(&project, &environment, "", "");
+            let or_unset =
+                |value: &Option<String>| value.clone().unwrap_or_else(|| "(unset)".to_string());
+            println!();
+            println!(
+                "🔧 Toolchain ({}):",
+                compile_config.toolchain.display_name()
+            );
+            println!("   go version: {}", or_unset(&toolchain.go_version));
+            println!("   tinygo version: {}", or_unset(&toolchain.tinygo_version));
+            println!("   GOFLAGS: {}", or_unset(&toolchain.goflags));
+            println!("   GOEXPERIMENT: {}", or_unset(&toolchain.goexperiment));
+            println!("   GOPROXY: {}", or_unset(&toolchain.goproxy));
+            if let (Some(goos), Some(goarch)) = (&toolchain.host_goos, &toolchain.host_goarch) {
+                println!("   host: {goos}/{goarch}");
+            }
+        }
+
+        Commands::Config { action } => {
+            let (project, global) = match &action {
+                ConfigAction::Get {
+                    project, global, ..
+                }
+                | ConfigAction::Set {
+                    project, global, ..
+                }
+                | ConfigAction::Unset {
+                    project, global, ..
+                } => (project.clone(), *global),
+            };
+            let path = if global {
+                match ProjectConfig::global_path() {
+                    Some(path) => path,
+                    None => {
+                        eprintln!(
+                            "❌ Cannot determine the global config directory; set {HOME_VARIABLE}"
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                ProjectConfig::project_path(&project)
+            };
+            let mut editor = match ConfigEditor::open(&path) {
+                Ok(editor) => editor,
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            };
+
+            let result = match action {
+                ConfigAction::Get { key, .. } => editor.get(&key).map(|value| match value {
+                    Some(value) => println!("{value}"),
+                    None => {
+                        eprintln!("{key} is not set in {}", editor.path().display());
+                        std::process::exit(1);
+                    }
+                }),
+                ConfigAction::Set { key, value, .. } => editor
+                    .set(&key, &value)
+                    .and_then(|value| editor.save().map(|()| value))
+                    .map(|value| println!("✅ {key} = {value} ({})", editor.path().display())),
+                ConfigAction::Unset { key, .. } => editor.unset(&key).and_then(|removed| {
+                    if removed {
+                        editor.save()?;
+                        println!("✅ Removed {key} from {}", editor.path().display());
+                    } else {
+                        println!("ℹ️  {key} is not set in {}", editor.path().display());
+                    }
+                    Ok(())
+                }),
+            };
+            if let Err(e) = result {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+        }
+
+        Commands::ListTargets { toolchains } => {
+            let toolchains: Vec<Toolchain> = if toolchains.is_empty() {
+                vec![Toolchain::TinyGo, Toolchain::Go]
+            } else {
+                toolchains.into_iter().map(Into::into).collect()
+            };
+            println!("🎯 WebAssembly targets of the installed toolchains");
+            let mut failed = false;
+            for toolchain in toolchains {
+                println!();
+                if !CommandExecutor::is_tool_installed(toolchain.command_name()) {
+                    println!("⚠️  {}: not installed", toolchain.display_name());
+                    continue;
+                }
+                match toolchain.wasm_targets() {
+                    Ok(targets) => {
+                        println!("🔧 {}:", toolchain.display_name());
+                        if targets.is_empty() {
+                            println!("   (no wasm targets reported)");
+                        }
+                        let width = targets
+                            .iter()
+                            .map(|target| target.name.len())
+                            .max()
+                            .unwrap_or(0);
+                        for target in targets {
+                            println!("   {:width$}  {}", target.name, target.description);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("❌ {}: {e}", toolchain.display_name());
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::CheckDeps { project } if json => {
+            ToolRegistry::refresh();
+            let missing = plugin.get_builder().check_dependencies();
+            let tools = ["go", "tinygo", "wasm-opt", "wasm-tools"]
+                .into_iter()
+                .map(|name| ToolStatus {
+                    name,
+                    installed: CommandExecutor::is_tool_installed(name),
+                    version: ToolRegistry::version_output(name)
+                        .map(|version| version.trim().to_string()),
+                })
+                .collect();
+            let failed = !missing.is_empty();
+            print_json(&DependencyReport {
+                missing,
+                tools,
+                caches: project_go_caches(&project).unwrap_or_default(),
+            })?;
+            if failed {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::CheckDeps { project } => {
+            print_header();
+            ToolRegistry::refresh();
+            println!("🔍 Checking system dependencies...");
+            println!();
+
+            let missing = plugin.get_builder().check_dependencies();
+
+            if missing.is_empty() {
+                println!("{}", Messages::text("dependencies.available"));
+                println!();
+                println!("📋 Available tools:");
+                println!("   ✅ go - Go compiler");
+                if CommandExecutor::is_tool_installed("tinygo") {
+                    println!("   ✅ tinygo - WebAssembly compiler for Go");
+                } else {
+                    println!("   ➖ tinygo - not installed; builds that don't need it use the Go toolchain");
+                }
+
+                if let Some(caches) = project_go_caches(&project) {
+                    if !caches.is_empty() {
+                        println!();
+                        println!("🗄️  Go caches:");
+                    }
+                    for cache in caches {
+                        let origin = if cache.configured {
+                            "configured"
+                        } else {
+                            "Go default"
+                        };
+                        if cache.exists {
+                            println!(
+                                "   {} ({origin}): {} — {} files, {:.1} MB",
+                                cache.variable,
+                                cache.path.display(),
+                                cache.files,
+                                cache.size as f64 / (1024.0 * 1024.0)
+                            );
+                        } else {
+                            println!(
+                                "   {} ({origin}): {} — empty (not created yet)",
+                                cache.variable,
+                                cache.path.display()
+                            );
+                        }
+                    }
+                    println!("   (Go keeps no hit counts; a persisted cache that stays warm shows a steady size)");
+                }
+            } else {
+                println!("❌ Missing required dependencies:");
+                for dep in &missing {
+                    println!("   • {dep}");
+                }
+
+                println!();
+                println!("💡 Installation suggestions:");
+                println!("   • Install Go: https://golang.org/dl/");
+                println!("   • Install TinyGo: https://tinygo.org/getting-started/install/");
+                println!("   • On macOS with Homebrew: brew install go tinygo");
+                println!("   • On Ubuntu/Debian: sudo apt install golang-go && follow TinyGo instructions");
+
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Selftest {
+            toolchains,
+            instantiate,
+            verbose,
+        } => {
+            print_header();
+            ToolRegistry::refresh();
+            let toolchains: Vec<Toolchain> = if toolchains.is_empty() {
+                SelfTest::detect_toolchains()
+            } else {
+                toolchains.into_iter().map(Into::into).collect()
+            };
+            if toolchains.is_empty() {
+                eprintln!("❌ Neither tinygo nor go is installed");
+                std::process::exit(1);
+            }
+
+            let entries = match SelfTest::run(
+                plugin.get_builder().as_ref(),
+                &toolchains,
+                instantiate,
+                verbose,
+            ) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    eprintln!("❌ Selftest failed: {e}");
+                    std::process::exit(1);
+                }
+            };
+
+            println!();
+            for entry in &entries {
+                let status = if entry.passed() { "✅" } else { "❌" };
+                match (&entry.error, entry.wasm_size) {
+                    (Some(error), _) => {
+                        println!(
+                            "{status} {:<8} build failed: {error}",
+                            entry.toolchain.display_name()
+                        )
+                    }
+                    (None, size) => println!(
+                        "{status} {:<8} built {} bytes in {:.2}s",
+                        entry.toolchain.display_name(),
+                        size.unwrap_or_default(),
+                        entry.build_time.as_secs_f64()
+                    ),
+                }
+                match &entry.instantiated {
+                    Some(Ok(())) => println!("   ▶️  ran under Node.js: \"{SELFTEST_GREETING}\""),
+                    Some(Err(error)) => println!("   ▶️  run failed: {error}"),
+                    None if instantiate && entry.error.is_none() => {
+                        println!("   ⏭️  not run: node is not installed")
+                    }
+                    None => {}
+                }
+            }
+            if !entries.iter().all(|entry| entry.passed()) {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Clean { project } => {
+            println!("🧹 Cleaning project artifacts: {}", project.display());
+
+            // For Go projects, we mainly clean any built WASM files
+            let dist_path = project.join("dist");
+            if dist_path.exists() {
+                match std::fs::remove_dir_all(&dist_path) {
+                    Ok(_) => println!("✅ Cleaned dist directory"),
+                    Err(e) => println!("⚠️  Failed to clean dist directory: {e}"),
+                }
+            }
+
+            println!("✅ Project cleaned successfully!");
+        }
+
+        Commands::Prune {
+            older_than,
+            max_size,
+            directories,
+            dry_run,
+        } => {
+            let Some(dirs) = Dirs::discover() else {
+                eprintln!("❌ Could not determine wasmgo's directories; set {HOME_VARIABLE}");
+                std::process::exit(1);
+            };
+            let parsed = older_than
+                .as_deref()
+                .map(PrunePolicy::parse_age)
+                .transpose()
+                .and_then(|older_than| {
+                    Ok(PrunePolicy {
+                        older_than,
+                        max_size: max_size
+                            .as_deref()
+                            .map(PrunePolicy::parse_size)
+                            .transpose()?,
+                    })
+                });
+            let policy = match parsed {
+                Ok(policy) => policy,
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            };
+            let megabytes = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+
+            println!("🗄️  wasmgo state:");
+            let usages: Vec<StateUsage> = StateDirectory::ALL
+                .iter()
+                .map(|directory| StatePruner::usage(&dirs, *directory))
+                .collect();
+            for usage in &usages {
+                println!(
+                    "   {}: {} — {} entries, {:.1} MB",
+                    usage.directory.name(),
+                    usage.path.display(),
+                    usage.entries.len(),
+                    megabytes(usage.size())
+                );
+            }
+            if policy.is_empty() {
+                println!();
+                println!("💡 Pass --older-than or --max-size to remove entries");
+                return Ok(());
+            }
+
+            let selected: Vec<StateDirectory> = if directories.is_empty() {
+                StateDirectory::ALL
+                    .into_iter()
+                    .filter(StateDirectory::is_cache)
+                    .collect()
+            } else {
+                directories.into_iter().map(Into::into).collect()
+            };
+            let now = std::time::SystemTime::now();
+            let mut removed = 0;
+            let mut freed = 0;
+            println!();
+            for usage in usages
+                .iter()
+                .filter(|usage| selected.contains(&usage.directory))
+            {
+                let entries = StatePruner::select(usage, &policy, now);
+                for entry in &entries {
+                    println!(
+                        "   {} {} ({:.1} MB, unused for {} days)",
+                        if dry_run { "would remove" } else { "🗑️ " },
+                        entry.paths[0].display(),
+                        megabytes(entry.size),
+                        entry.age(now).as_secs() / (24 * 60 * 60)
+                    );
+                }
+                if dry_run {
+                    removed += entries.len();
+                    freed += entries.iter().map(|entry| entry.size).sum::<u64>();
+                    continue;
+                }
+                match StatePruner::remove(&entries) {
+                    Ok(bytes) => {
+                        removed += entries.len();
+                        freed += bytes;
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Could not prune {}: {e}", usage.directory.name());
+                        std::process::exit(1);
+                    }
+                }
+            }
+            if dry_run {
+                println!(
+                    "🔍 Would remove {removed} entries, freeing {:.1} MB",
+                    megabytes(freed)
+                );
+            } else {
+                println!(
+                    "🧹 Removed {removed} entries, freeing {:.1} MB",
+                    megabytes(freed)
+                );
+            }
+        }
+
+        Commands::Bugreport { project, output } => {
+            let output = output.unwrap_or_else(|| {
+                let timestamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs());
+                PathBuf::from(format!("wasmgo-bugreport-{timestamp}.tar.gz"))
+            });
+            println!("🩺 Collecting diagnostics for {}...", project.display());
+            match BugReport::write(&project, &output) {
+                Ok(files) => {
+                    println!("📦 Wrote {}", output.display());
+                    for file in &files {
+                        println!("   • {file}");
+                    }
+                    println!();
+                    println!("🔒 Tokens, passwords and URL credentials were removed; review the files before attaching them to an issue");
+                }
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Metrics { last, clear } => {
+            if clear {
+                if let Err(e) = BuildMetrics::clear() {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+                println!("🧹 Cleared build metrics");
+                return Ok(());
+            }
+            let mut metrics = match BuildMetrics::read() {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            };
+            if let Some(last) = last {
+                metrics.drain(..metrics.len().saturating_sub(last));
+            }
+            let summaries = BuildMetrics::summarize(&metrics);
+            if json {
+                match serde_json::to_string_pretty(&summaries) {
+                    Ok(json) => println!("{json}"),
+                    Err(e) => {
+                        eprintln!("❌ {e}");
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+            if metrics.is_empty() {
+                println!("📊 No build metrics recorded");
+                println!("   Turn recording on with `wasmgo config set metrics true --global`");
+                return Ok(());
+            }
+
+            let failures = metrics.iter().filter(|metric| !metric.success).count();
+            println!(
+                "📊 Build Metrics ({} builds, {failures} failed)",
+                metrics.len()
+            );
+            println!("══════════════════════════════");
+            println!(
+                "   {:<18} {:<8} {:<8} {:>6} {:>7} {:>9} {:>9} {:>12}",
+                "Target", "Tool", "Level", "Builds", "Failed", "Median", "Max", "Avg size"
+            );
+            for summary in &summaries {
+                println!(
+                    "   {:<18} {:<8} {:<8} {:>6} {:>7} {:>8.2}s {:>8.2}s {:>12}",
+                    summary.target.name(),
+                    summary.toolchain.command_name(),
+                    summary.optimization.name(),
+                    summary.builds,
+                    summary.failures,
+                    summary.median_duration_ms as f64 / 1000.0,
+                    summary.max_duration_ms as f64 / 1000.0,
+                    summary
+                        .mean_wasm_size
+                        .map(|size| format!("{size} B"))
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+            }
+            if let Some(path) = BuildMetrics::path() {
+                println!();
+                println!("📄 Recorded in {}", path.display());
+            }
+        }
+
+        Commands::Upgrade {
+            check,
+            cargo,
+            verbose,
+        } => {
+            let current = SelfUpdater::current_version();
+            println!("🔍 Checking for a newer wasmgo (installed: {current})...");
+            let release = match SelfUpdater::latest_release(verbose) {
+                Ok(release) => release,
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            };
+            if !release.is_newer() {
+                println!("✅ wasmgo {current} is up to date");
+                return Ok(());
+            }
+            let source = match release.source {
+                ReleaseSource::GitHub => "GitHub releases",
+                ReleaseSource::CratesIo => "crates.io",
+            };
+            println!("⬆️  wasmgo {} is available on {source}", release.version);
+            if check {
+                println!("   Run `wasmgo upgrade` to install it");
+                return Ok(());
+            }
+            if release.asset_url.is_none() || release.checksum_url.is_none() || cargo {
+                println!("🔨 Building {} with cargo install...", release.version);
+            } else {
+                println!("⬇️  Downloading the prebuilt binary...");
+            }
+            match SelfUpdater::install(&release, cargo, verbose) {
+                Ok(UpgradeMethod::Prebuilt(executable)) => {
+                    println!(
+                        "✅ Upgraded {} to wasmgo {}",
+                        executable.display(),
+                        release.version
+                    );
+                }
+                Ok(UpgradeMethod::CargoInstall) => {
+                    println!("✅ Installed wasmgo {} with cargo", release.version);
+                }
+                Err(e) => {
+                    eprintln!("❌ Upgrade failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::RefreshUpdateCheck => UpdateNotifier::refresh()?,
+
+        Commands::Info if json => print_json(plugin.info())?,
+
+        Commands::Info => {
+            print_header();
+            println!("🔧 Plugin Information");
+            println!("═════════════════════");
+            let plugin_info = plugin.info();
+            println!("Name: {}", plugin_info.name);
+            println!("Version: {}", plugin_info.version);
+            println!("Description: {}", plugin_info.description);
+            println!("Author: {}", plugin_info.author);
+
+            println!();
+            println!("🎯 Capabilities");
+            println!("═══════════════");
+            println!("✅ Standard WASM compilation");
+            println!("✅ TinyGo integration");
+            println!("✅ Multiple optimization levels");
+            println!("✅ Go module support");
+            println!();
+
+            println!("📄 Usage");
+            println!("════════");
+            println!("Primary (via Wasmrun):");
+            println!("   wasmrun run ./my-go-project");
+            println!("   wasmrun compile ./my-project --optimization size");
+            println!();
+            println!("Standalone (testing/development):");
+            println!("   {} run ./my-project", env!("CARGO_PKG_NAME"));
+            println!(
+                "   {} compile ./my-project --target webapp",
+                env!("CARGO_PKG_NAME")
+            );
+            println!("   {} inspect ./my-project", env!("CARGO_PKG_NAME"));
+        }
+
+        Commands::New {
+            path,
+            framework,
+            module,
+        } => {
+            let registry = load_frameworks();
+            let Some(framework) = registry.get(&framework) else {
+                eprintln!(
+                    "{}",
+                    Messages::format("new.unknown_framework", &[("framework", &framework)])
+                );
+                std::process::exit(1);
+            };
+            let module = module.unwrap_or_else(|| {
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            });
+            match framework.scaffold(&path, &module) {
+                Ok(files) => {
+                    println!(
+                        "{}",
+                        Messages::format(
+                            "new.created",
+                            &[("path", &path.display()), ("framework", &framework.title)]
+                        )
+                    );
+                    for file in &files {
+                        println!("   • {}", file.display());
+                    }
+                    let missing = framework.missing_tools();
+                    if !missing.is_empty() {
+                        println!(
+                            "{}",
+                            Messages::format(
+                                "new.missing_tools",
+                                &[("tools", &missing.join(", "))]
+                            )
+                        );
+                    }
+                    println!();
+                    println!("{}", Messages::text("new.next_steps"));
+                    println!("   cd {}", path.display());
+                    if !framework.requires.is_empty() {
+                        println!("   go mod tidy");
+                    }
+                    println!(
+                        "   {}",
+                        framework.recipe.as_deref().unwrap_or("wasmgo compile")
+                    );
+                }
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Frameworks => {
+            let registry = load_frameworks();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&registry)?);
+                return Ok(());
+            }
+            print_header();
+            println!("🌐 Supported Frameworks & Project Types");
+            println!("═══════════════════════════════════════");
+            for framework in &registry.frameworks {
+                println!();
+                println!("📦 {} ({})", framework.title, framework.name);
+                println!("   {}", framework.description);
+                let rules: Vec<String> = framework
+                    .detect
+                    .modules
+                    .iter()
+                    .map(|module| format!("{module} in go.mod"))
+                    .chain(framework.detect.files.iter().cloned())
+                    .collect();
+                if !rules.is_empty() {
+                    println!("   Detected by: {}", rules.join(", "));
+                }
+                if !framework.tools.is_empty() {
+                    let tools: Vec<String> = framework
+                        .tools
+                        .iter()
+                        .map(|tool| {
+                            let mark = if CommandExecutor::is_tool_installed(tool) {
+                                "✅"
+                            } else {
+                                "❌"
+                            };
+                            format!("{mark} {tool}")
+                        })
+                        .collect();
+                    println!("   Tools: {}", tools.join("  "));
+                }
+                if let Some(recipe) = &framework.recipe {
+                    println!("   Build: {recipe}");
+                }
+                if !framework.template.is_empty() {
+                    println!("   New: wasmgo new <dir> --framework {}", framework.name);
+                }
+            }
+            if let Some(path) = FrameworkRegistry::user_path() {
+                println!();
+                println!("📝 Add or override entries in {}", path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
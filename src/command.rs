@@ -0,0 +1,318 @@
+use crate::{InterruptHandler, Logger, PluginResult};
+use std::cell::RefCell;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+thread_local! {
+    static COMMAND_LOG: RefCell<Option<Vec<ToolCommand>>> = const { RefCell::new(None) };
+}
+
+/// Quoting rules used when a command is shown as a single line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellStyle {
+    /// `sh`-compatible single quotes
+    Posix,
+    /// Double quotes as parsed by `CommandLineToArgvW` and `cmd.exe`
+    Windows,
+}
+
+impl ShellStyle {
+    /// The style of the platform's shell.
+    pub fn host() -> Self {
+        if cfg!(windows) {
+            Self::Windows
+        } else {
+            Self::Posix
+        }
+    }
+
+    /// Quotes one argument so the shell passes it through as a single word.
+    pub fn quote(self, argument: &OsStr) -> String {
+        let argument = argument.to_string_lossy();
+        match self {
+            Self::Posix => {
+                let plain = !argument.is_empty()
+                    && argument.chars().all(|c| {
+                        c.is_ascii_alphanumeric()
+                            || matches!(c, '-' | '_' | '.' | '/' | '=' | ',' | ':' | '+')
+                    });
+                if plain {
+                    argument.to_string()
+                } else {
+                    format!("'{}'", argument.replace('\'', r"'\''"))
+                }
+            }
+            Self::Windows => Self::quote_windows(&argument),
+        }
+    }
+
+    // Backslashes are literal unless they precede a quote, so only those
+    // runs (and the run before the closing quote) are doubled
+    fn quote_windows(argument: &str) -> String {
+        let plain = !argument.is_empty()
+            && !argument.chars().any(|c| {
+                c.is_whitespace() || matches!(c, '"' | '&' | '|' | '<' | '>' | '^' | '(' | ')')
+            });
+        if plain {
+            return argument.to_string();
+        }
+
+        let mut quoted = String::from("\"");
+        let mut backslashes = 0;
+        for c in argument.chars() {
+            match c {
+                '\\' => backslashes += 1,
+                '"' => {
+                    quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                    quoted.push('"');
+                    backslashes = 0;
+                }
+                _ => {
+                    quoted.push_str(&"\\".repeat(backslashes));
+                    quoted.push(c);
+                    backslashes = 0;
+                }
+            }
+        }
+        quoted.push_str(&"\\".repeat(backslashes * 2));
+        quoted.push('"');
+        quoted
+    }
+}
+
+/// A subprocess invocation assembled from separate arguments.
+///
+/// Arguments and environment values stay `OsString`s and reach the process
+/// as given, so paths with spaces or unusual bytes are never split or
+/// re-parsed; only the display lines join them, quoted for a [`ShellStyle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCommand {
+    program: OsString,
+    arguments: Vec<OsString>,
+    environment: Vec<(OsString, OsString)>,
+    working_directory: Option<PathBuf>,
+}
+
+impl ToolCommand {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            program: program.as_ref().to_os_string(),
+            arguments: Vec::new(),
+            environment: Vec::new(),
+            working_directory: None,
+        }
+    }
+
+    pub fn arg(&mut self, argument: impl AsRef<OsStr>) -> &mut Self {
+        self.arguments.push(argument.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, A>(&mut self, arguments: I) -> &mut Self
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<OsStr>,
+    {
+        self.arguments.extend(
+            arguments
+                .into_iter()
+                .map(|argument| argument.as_ref().to_os_string()),
+        );
+        self
+    }
+
+    /// Sets a variable for the subprocess, replacing an earlier value.
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Self {
+        let key = key.as_ref().to_os_string();
+        self.environment.retain(|(existing, _)| existing != &key);
+        self.environment.push((key, value.as_ref().to_os_string()));
+        self
+    }
+
+    pub fn envs<I, K, V>(&mut self, variables: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        for (key, value) in variables {
+            self.env(key, value);
+        }
+        self
+    }
+
+    pub fn current_dir(&mut self, directory: impl AsRef<Path>) -> &mut Self {
+        self.working_directory = Some(directory.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn program(&self) -> &OsStr {
+        &self.program
+    }
+
+    pub fn arguments(&self) -> &[OsString] {
+        &self.arguments
+    }
+
+    pub fn environment(&self) -> &[(OsString, OsString)] {
+        &self.environment
+    }
+
+    pub fn working_directory(&self) -> Option<&Path> {
+        self.working_directory.as_deref()
+    }
+
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.arguments)
+            .envs(self.environment.iter().map(|(key, value)| (key, value)));
+        if let Some(directory) = &self.working_directory {
+            command.current_dir(directory);
+        }
+        command
+    }
+
+    /// Runs the command to completion, capturing its output.
+    pub fn output(&self, verbose_output: bool) -> PluginResult<Output> {
+        self.print_command(verbose_output);
+        CommandLog::record(self);
+        let output = InterruptHandler::output(
+            self.to_command()
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )?;
+        Self::print_output(&output, verbose_output);
+        Ok(output)
+    }
+
+    /// Like [`Self::output`], but stops the command (and its subprocesses) at the
+    /// first stderr line `stop_at` accepts, returning that line with the output so far.
+    pub fn output_until(
+        &self,
+        verbose_output: bool,
+        stop_at: impl FnMut(&str) -> bool,
+    ) -> PluginResult<(Output, Option<String>)> {
+        self.print_command(verbose_output);
+        CommandLog::record(self);
+        let (output, stopped_at) = InterruptHandler::output_until(&mut self.to_command(), stop_at)?;
+        Self::print_output(&output, verbose_output);
+        Ok((output, stopped_at))
+    }
+
+    fn print_command(&self, verbose_output: bool) {
+        if !verbose_output {
+            return;
+        }
+        match &self.working_directory {
+            Some(directory) => Logger::debug(format_args!(
+                "Executing: {} in {}",
+                self.display_line(ShellStyle::host()),
+                directory.display()
+            )),
+            None => Logger::debug(format_args!(
+                "Executing: {}",
+                self.display_line(ShellStyle::host())
+            )),
+        }
+    }
+
+    fn print_output(output: &Output, verbose_output: bool) {
+        if !verbose_output {
+            return;
+        }
+        Logger::debug(format_args!(
+            "Command output: {}",
+            String::from_utf8_lossy(&output.stdout)
+        ));
+        if !output.stderr.is_empty() {
+            Logger::debug(format_args!(
+                "Command stderr: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+
+    /// `KEY=value program arguments...` quoted for `style`; cmd.exe sets
+    /// variables with `set "KEY=value" &&` instead.
+    pub fn display_line(&self, style: ShellStyle) -> String {
+        let environment = self.environment.iter().map(|(key, value)| {
+            let key = key.to_string_lossy();
+            match style {
+                ShellStyle::Posix => format!("{key}={}", style.quote(value)),
+                ShellStyle::Windows => {
+                    format!("set \"{key}={}\" &&", value.to_string_lossy())
+                }
+            }
+        });
+        environment
+            .chain(std::iter::once(style.quote(&self.program)))
+            .chain(self.arguments.iter().map(|argument| style.quote(argument)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// [`Self::display_line`] preceded by a change into the (canonical) working
+    /// directory, so it reruns the command from anywhere.
+    pub fn reproduce_line(&self, style: ShellStyle) -> String {
+        let Some(directory) = &self.working_directory else {
+            return self.display_line(style);
+        };
+        let directory = std::fs::canonicalize(directory).unwrap_or_else(|_| directory.clone());
+        let directory = directory.to_string_lossy();
+        let change_directory = match style {
+            ShellStyle::Posix => "cd",
+            ShellStyle::Windows => "cd /d",
+        };
+        // cmd.exe cannot change into the verbatim paths canonicalize returns on Windows
+        let directory = directory.strip_prefix(r"\\?\").unwrap_or(&directory);
+        format!(
+            "{change_directory} {} && {}",
+            style.quote(OsStr::new(directory)),
+            self.display_line(style)
+        )
+    }
+}
+
+/// Records the [`ToolCommand`]s this thread runs until dropped. Logs nest: an
+/// outer log also gets the commands run while an inner one was open.
+#[derive(Debug)]
+pub struct CommandLog {
+    outer: Option<Vec<ToolCommand>>,
+}
+
+impl CommandLog {
+    pub fn start() -> Self {
+        Self {
+            outer: COMMAND_LOG.with(|log| log.borrow_mut().replace(Vec::new())),
+        }
+    }
+
+    /// The commands run since [`Self::start`], in order.
+    pub fn commands(&self) -> Vec<ToolCommand> {
+        COMMAND_LOG.with(|log| log.borrow().clone().unwrap_or_default())
+    }
+
+    fn record(command: &ToolCommand) {
+        COMMAND_LOG.with(|log| {
+            if let Some(commands) = log.borrow_mut().as_mut() {
+                commands.push(command.clone());
+            }
+        });
+    }
+}
+
+impl Drop for CommandLog {
+    fn drop(&mut self) {
+        let mut outer = self.outer.take();
+        COMMAND_LOG.with(|log| {
+            let inner = log.borrow_mut().take();
+            if let (Some(outer), Some(inner)) = (outer.as_mut(), inner) {
+                outer.extend(inner);
+            }
+            *log.borrow_mut() = outer;
+        });
+    }
+}
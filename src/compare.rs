@@ -0,0 +1,88 @@
+use crate::{CompileConfig, Logger, OptimizationLevel, Toolchain, WasmBuilder};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Outcome of building one configuration of a comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonEntry {
+    pub toolchain: Toolchain,
+    pub optimization: OptimizationLevel,
+    pub wasm_file_path: Option<PathBuf>,
+    pub wasm_size: Option<u64>,
+    pub build_time: Duration,
+    pub error: Option<String>,
+}
+
+/// Builds a project in several configurations to compare size and build time.
+pub struct BuildComparison;
+
+impl BuildComparison {
+    /// Builds every toolchain/level pair into `<output>/compare/<toolchain>-<level>/`.
+    pub fn run(
+        builder: &dyn WasmBuilder,
+        base_configuration: &CompileConfig,
+        toolchains: &[Toolchain],
+        levels: &[OptimizationLevel],
+    ) -> Vec<ComparisonEntry> {
+        let mut entries = Vec::new();
+        for &toolchain in toolchains {
+            for &optimization in levels {
+                let output_directory =
+                    base_configuration
+                        .output_directory
+                        .join("compare")
+                        .join(format!(
+                            "{}-{}",
+                            toolchain.command_name(),
+                            optimization.name()
+                        ));
+                let configuration = CompileConfig {
+                    output_directory,
+                    optimization_level: optimization,
+                    toolchain,
+                    ..base_configuration.clone()
+                };
+
+                Logger::info(format_args!(
+                    "📏 Building {} / {}...",
+                    toolchain.display_name(),
+                    optimization.name()
+                ));
+                let started = Instant::now();
+                let result = builder.compile(&configuration);
+                let build_time = started.elapsed();
+
+                entries.push(match result {
+                    Ok(compile_result) => ComparisonEntry {
+                        toolchain,
+                        optimization,
+                        wasm_size: std::fs::metadata(&compile_result.wasm_file_path)
+                            .map(|metadata| metadata.len())
+                            .ok(),
+                        wasm_file_path: Some(compile_result.wasm_file_path),
+                        build_time,
+                        error: None,
+                    },
+                    Err(e) => ComparisonEntry {
+                        toolchain,
+                        optimization,
+                        wasm_file_path: None,
+                        wasm_size: None,
+                        build_time,
+                        error: Some(e.to_string()),
+                    },
+                });
+            }
+        }
+        entries
+    }
+
+    /// The successful entry with the smallest module, if any.
+    pub fn smallest(entries: &[ComparisonEntry]) -> Option<&ComparisonEntry> {
+        entries
+            .iter()
+            .filter(|entry| entry.wasm_size.is_some())
+            .min_by_key(|entry| entry.wasm_size)
+    }
+}
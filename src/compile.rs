@@ -0,0 +1,63 @@
+use crate::{
+    BuildSettings, CompileConfig, CompileResult, Plugin, PluginError, PluginResult, ResolvedConfig,
+    WasmBuilder, WasmGoPlugin,
+};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Options for [`compile`]. They layer over the project's `wasmgo.toml` the way
+/// command-line flags do, so the defaults build what `wasmgo compile` would.
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions {
+    pub settings: BuildSettings,
+    /// `[profile.<name>]` to apply
+    pub profile: Option<String>,
+    pub verbose: bool,
+}
+
+/// Outcome of [`compile`].
+#[derive(Debug, Clone)]
+pub struct BuildReport {
+    pub result: CompileResult,
+    /// The configuration the build ran with, after merging every config layer
+    pub configuration: CompileConfig,
+    pub profile: Option<String>,
+    pub duration: Duration,
+}
+
+/// Builds the Go project at `path` in one call: resolves its configuration,
+/// checks that it is a Go project and that the toolchain is installed, then
+/// compiles it.
+pub fn compile(path: impl AsRef<Path>, options: CompileOptions) -> PluginResult<BuildReport> {
+    let project_path = path.as_ref().to_path_buf();
+    let plugin = WasmGoPlugin::new();
+    if !plugin.can_handle_project(&project_path) {
+        return Err(PluginError::InvalidProjectStructure {
+            reason: format!("no go.mod or .go files in {}", project_path.display()),
+        });
+    }
+    let missing = plugin.check_dependencies();
+    if !missing.is_empty() {
+        return Err(PluginError::CompileToolNotFound {
+            tool: missing.join(", "),
+        });
+    }
+
+    let resolved =
+        ResolvedConfig::resolve(&project_path, options.profile.as_deref(), &options.settings)?;
+    let mut configuration = CompileConfig {
+        project_path,
+        verbose: options.verbose,
+        ..CompileConfig::default()
+    };
+    resolved.settings.apply_to(&mut configuration)?;
+
+    let started = Instant::now();
+    let result = plugin.compile(&configuration)?;
+    Ok(BuildReport {
+        result,
+        configuration,
+        profile: resolved.profile.map(|(name, _)| name),
+        duration: started.elapsed(),
+    })
+}
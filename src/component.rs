@@ -0,0 +1,308 @@
+use crate::{CommandExecutor, Logger, PluginError, PluginResult, WasmBinary, WasmKind, WitWorld};
+use serde::Serialize;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const WASI_HTTP_WORLD: &str = "wasi:http/proxy";
+
+/// Go module shipping the WIT definitions for the wasi-http proxy world.
+const WASI_HTTP_GO_MODULE: &str = "github.com/ydnar/wasi-http-go";
+
+const WASI_HTTP_SMOKE_TEST_TEMPLATE: &str = include_str!("templates/wasi_http_smoke_test.sh");
+
+/// Imported and exported items of a component's world.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComponentWorld {
+    pub wit: String,
+    pub imports: Vec<String>,
+    pub exports: Vec<String>,
+}
+
+/// Steps for producing and checking WebAssembly components.
+pub struct ComponentTools;
+
+impl ComponentTools {
+    /// TinyGo delegates component embedding to `wasm-tools`.
+    pub fn ensure_wasm_tools() -> PluginResult<()> {
+        if CommandExecutor::is_tool_installed("wasm-tools") {
+            Ok(())
+        } else {
+            Err(PluginError::CompileToolNotFound {
+                tool: "wasm-tools (install with `cargo install wasm-tools`)".to_string(),
+            })
+        }
+    }
+
+    /// Wraps a wasip1 core module with the preview1 adapter, producing a
+    /// preview2 component next to it as `<name>.component.wasm`.
+    pub fn adapt_preview1(
+        core_module_path: &Path,
+        adapter_path: &Path,
+        verbose: bool,
+    ) -> PluginResult<PathBuf> {
+        Self::ensure_wasm_tools()?;
+        if !adapter_path.is_file() {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: format!(
+                    "Preview1 adapter not found: {} (download wasi_snapshot_preview1.command.wasm or .reactor.wasm from the wasmtime releases)",
+                    adapter_path.display()
+                ),
+            });
+        }
+
+        let core_module = WasmBinary::read(core_module_path)?;
+        let imported_modules = core_module.imported_modules();
+        if core_module.kind != WasmKind::CoreModule
+            || !imported_modules.contains(&"wasi_snapshot_preview1")
+            || imported_modules.contains(&"gojs")
+        {
+            return Err(PluginError::UnsupportedTarget {
+                reason: format!(
+                    "The preview1 adapter needs a wasip1 core module, but {} is not one (imports: {})",
+                    core_module_path.display(),
+                    imported_modules.join(", ")
+                ),
+            });
+        }
+
+        let component_path = core_module_path.with_extension("component.wasm");
+        let mut adapt_argument = OsString::from("wasi_snapshot_preview1=");
+        adapt_argument.push(adapter_path);
+
+        Logger::info(format_args!(
+            "🧩 Adapting wasip1 module to a preview2 component..."
+        ));
+        let adapt_output = CommandExecutor::execute_command(
+            "wasm-tools",
+            &[
+                OsStr::new("component"),
+                OsStr::new("new"),
+                core_module_path.as_os_str(),
+                OsStr::new("--adapt"),
+                &adapt_argument,
+                OsStr::new("-o"),
+                component_path.as_os_str(),
+            ],
+            Path::new("."),
+            verbose,
+        )?;
+
+        if !adapt_output.status.success() {
+            return Err(PluginError::CompilationFailed {
+                reason: format!(
+                    "Preview2 adaptation failed: {}",
+                    String::from_utf8_lossy(&adapt_output.stderr)
+                ),
+            });
+        }
+        Ok(component_path)
+    }
+
+    /// Extracts the world of a component artifact with `wasm-tools component wit`.
+    pub fn inspect_component(component_path: &Path) -> PluginResult<ComponentWorld> {
+        Self::ensure_wasm_tools()?;
+
+        let wit_output = CommandExecutor::execute_command(
+            "wasm-tools",
+            &[
+                OsStr::new("component"),
+                OsStr::new("wit"),
+                component_path.as_os_str(),
+            ],
+            Path::new("."),
+            false,
+        )?;
+        if !wit_output.status.success() {
+            return Err(PluginError::InvalidWasm {
+                path: component_path.display().to_string(),
+                reason: String::from_utf8_lossy(&wit_output.stderr)
+                    .trim()
+                    .to_string(),
+            });
+        }
+
+        let wit = String::from_utf8_lossy(&wit_output.stdout).to_string();
+        let mut world = ComponentWorld {
+            wit: wit.clone(),
+            ..Default::default()
+        };
+        for line in wit.lines().map(str::trim) {
+            let item = |declaration: &str| declaration.trim_end_matches(';').trim().to_string();
+            if let Some(import) = line.strip_prefix("import ") {
+                world.imports.push(item(import));
+            } else if let Some(export) = line.strip_prefix("export ") {
+                world.exports.push(item(export));
+            }
+        }
+        Ok(world)
+    }
+
+    /// Resolves a user-provided WIT package to its directory and world name,
+    /// picking the package's only world when none was given.
+    pub fn resolve_world(
+        project_path: &Path,
+        wit_world: &WitWorld,
+    ) -> PluginResult<(PathBuf, String)> {
+        // An absolute WIT directory replaces the project path
+        let wit_directory = project_path.join(&wit_world.wit_directory);
+        if !wit_directory.is_dir() {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: format!("WIT directory does not exist: {}", wit_directory.display()),
+            });
+        }
+
+        let world = match &wit_world.world {
+            Some(world) => world.clone(),
+            None => {
+                let worlds = Self::declared_worlds(&wit_directory);
+                match worlds.as_slice() {
+                    [world] => world.clone(),
+                    [] => {
+                        return Err(PluginError::InvalidProjectStructure {
+                            reason: format!("No world declared in {}", wit_directory.display()),
+                        })
+                    }
+                    _ => {
+                        return Err(PluginError::InvalidProjectStructure {
+                            reason: format!(
+                                "Multiple worlds declared in {} ({}); pick one with --world",
+                                wit_directory.display(),
+                                worlds.join(", ")
+                            ),
+                        })
+                    }
+                }
+            }
+        };
+
+        Ok((wit_directory, world))
+    }
+
+    /// Names of the worlds declared by the top-level `.wit` files of a package.
+    pub fn declared_worlds(wit_directory: &Path) -> Vec<String> {
+        let mut worlds: Vec<String> = fs::read_dir(wit_directory)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|extension| extension == "wit"))
+                    .filter_map(|path| fs::read_to_string(path).ok())
+                    .flat_map(|source| {
+                        source
+                            .lines()
+                            .filter_map(|line| {
+                                let name = line.trim().strip_prefix("world ")?;
+                                Some(name.trim_end_matches('{').trim().to_string())
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        worlds.sort();
+        worlds
+    }
+
+    /// Generates Go bindings for the world with `wit-bindgen-go` into `internal/`,
+    /// where the project wires its functions to the world's exports.
+    pub fn generate_bindings(
+        project_path: &Path,
+        wit_directory: &Path,
+        world: &str,
+        verbose: bool,
+    ) -> PluginResult<()> {
+        if !CommandExecutor::is_tool_installed("wit-bindgen-go") {
+            Logger::warn(format_args!(
+                "⚠️  wit-bindgen-go not found, using existing bindings in internal/"
+            ));
+            Logger::warn(format_args!(
+                "   Install with: go install go.bytecodealliance.org/cmd/wit-bindgen-go@latest"
+            ));
+            return Ok(());
+        }
+
+        Logger::info(format_args!("🧬 Generating bindings for {world}..."));
+        let bindgen_output = CommandExecutor::execute_command(
+            "wit-bindgen-go",
+            &[
+                OsStr::new("generate"),
+                OsStr::new("--world"),
+                OsStr::new(world),
+                OsStr::new("--out"),
+                OsStr::new("internal"),
+                wit_directory.as_os_str(),
+            ],
+            project_path,
+            verbose,
+        )?;
+
+        if !bindgen_output.status.success() {
+            return Err(PluginError::CompilationFailed {
+                reason: format!(
+                    "Binding generation failed: {}",
+                    String::from_utf8_lossy(&bindgen_output.stderr)
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Locates the WIT package defining the wasi-http proxy world: a `wit/`
+    /// directory in the project, or the one shipped with the wasi-http Go module.
+    pub fn resolve_wasi_http_wit(project_path: &Path) -> PluginResult<PathBuf> {
+        let project_wit = project_path.join("wit");
+        if project_wit.is_dir() {
+            return Ok(project_wit);
+        }
+
+        let module_directory = CommandExecutor::execute_command(
+            "go",
+            &[
+                "list",
+                "-mod=readonly",
+                "-m",
+                "-f",
+                "{{.Dir}}",
+                WASI_HTTP_GO_MODULE,
+            ],
+            project_path,
+            false,
+        )
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| CommandExecutor::output_path(&output.stdout))
+        .filter(|directory| !directory.as_os_str().is_empty());
+
+        match module_directory {
+            Some(directory) if directory.join("wit").is_dir() => Ok(directory.join("wit")),
+            _ => Err(PluginError::InvalidProjectStructure {
+                reason: format!(
+                    "No WIT package found for {WASI_HTTP_WORLD}. Add a wit/ directory or require {WASI_HTTP_GO_MODULE} in go.mod"
+                ),
+            }),
+        }
+    }
+
+    /// Writes a script that serves the component with `wasmtime serve` and
+    /// issues a single request against it.
+    pub fn write_wasi_http_smoke_test(component_path: &Path) -> PluginResult<PathBuf> {
+        let component_filename = component_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let script = WASI_HTTP_SMOKE_TEST_TEMPLATE.replace("{{COMPONENT}}", &component_filename);
+
+        let script_path = component_path.with_file_name("smoke-test.sh");
+        fs::write(&script_path, script).map_err(PluginError::Io)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))
+                .map_err(PluginError::Io)?;
+        }
+
+        Ok(script_path)
+    }
+}
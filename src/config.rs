@@ -0,0 +1,917 @@
+use crate::{
+    BuildMode, CompileConfig, Dirs, LlvmFeature, LlvmTuning, OptimizationLevel, PlatformCondition,
+    PluginError, PluginResult, RemoteCache, TargetType, Toolchain, DEFAULT_VERSION_VARIABLE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+/// Project configuration file, looked up in the project directory.
+pub const PROJECT_CONFIG_FILENAME: &str = "wasmgo.toml";
+
+/// User-wide config file in [`Dirs::config`].
+pub const GLOBAL_CONFIG_FILENAME: &str = "config.toml";
+
+/// Selects a `[profile.<name>]` section when `--profile` is not given.
+pub const PROFILE_VARIABLE: &str = "WASMGO_PROFILE";
+
+/// Every setting `<key>` can also be set through `WASMGO_<KEY>` (upper case,
+/// dashes as underscores).
+const ENVIRONMENT_PREFIX: &str = "WASMGO_";
+
+/// Build settings shared by the config file, its profiles, `WASMGO_*`
+/// variables and the command line. Unset keys fall through to the next layer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BuildSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opt: Option<OptimizationLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<TargetType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolchain_fallback: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<BuildMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goflags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goexperiment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gocache: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gomodcache: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<NonZeroUsize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_fast: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deny_warnings: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hermetic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hermetic_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_network: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exact_toolchain: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tidy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llvm_features: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wasm_opt_passes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_maps: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smoke_test: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_cache: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_cache_read_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stamp_version: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_variable: Option<String>,
+}
+
+impl BuildSettings {
+    /// Setting keys in the order they are displayed.
+    pub const KEYS: &'static [&'static str] = &[
+        "output",
+        "build-dir",
+        "opt",
+        "target",
+        "toolchain",
+        "toolchain-fallback",
+        "mode",
+        "goflags",
+        "goexperiment",
+        "tags",
+        "gocache",
+        "gomodcache",
+        "jobs",
+        "fail-fast",
+        "deny-warnings",
+        "hermetic",
+        "hermetic-dir",
+        "no-network",
+        "exact-toolchain",
+        "tidy",
+        "llvm-features",
+        "cpu",
+        "wasm-opt-passes",
+        "hash",
+        "provenance",
+        "source-maps",
+        "smoke-test",
+        "metrics",
+        "cache",
+        "remote-cache",
+        "remote-cache-read-only",
+        "stamp-version",
+        "version-variable",
+    ];
+
+    /// Values used when no layer sets a key, matching [`CompileConfig::default`].
+    pub fn defaults() -> Self {
+        let defaults = CompileConfig::default();
+        Self {
+            output: Some(defaults.output_directory.to_string_lossy().to_string()),
+            opt: Some(defaults.optimization_level),
+            target: Some(defaults.target_type),
+            toolchain: Some(defaults.toolchain),
+            toolchain_fallback: Some(defaults.toolchain_fallback),
+            mode: Some(defaults.build_mode),
+            fail_fast: Some(defaults.fail_fast),
+            deny_warnings: Some(defaults.deny_warnings),
+            hermetic: Some(defaults.hermetic),
+            no_network: Some(defaults.no_network),
+            exact_toolchain: Some(defaults.exact_toolchain),
+            tidy: Some(defaults.tidy),
+            hash: Some(defaults.hashed_filenames),
+            provenance: Some(defaults.provenance),
+            source_maps: Some(defaults.source_maps),
+            smoke_test: Some(defaults.smoke_test),
+            metrics: Some(defaults.metrics),
+            cache: Some(defaults.cache),
+            remote_cache_read_only: Some(defaults.remote_cache_read_only),
+            stamp_version: Some(defaults.version_variable.is_some()),
+            version_variable: defaults.version_variable,
+            ..Self::default()
+        }
+    }
+
+    /// The `WASMGO_*` variable for a setting key.
+    pub fn environment_variable(key: &str) -> String {
+        format!(
+            "{ENVIRONMENT_PREFIX}{}",
+            key.to_uppercase().replace('-', "_")
+        )
+    }
+
+    /// Writes the settings that are set into a build configuration.
+    pub fn apply_to(&self, configuration: &mut CompileConfig) -> PluginResult<()> {
+        if let Some(output) = &self.output {
+            configuration.output_directory = PathBuf::from(output);
+        }
+        if let Some(build_dir) = &self.build_dir {
+            configuration.build_directory = Some(PathBuf::from(build_dir));
+        }
+        if let Some(opt) = self.opt {
+            configuration.optimization_level = opt;
+        }
+        if let Some(target) = &self.target {
+            configuration.target_type = target.clone();
+        }
+        if let Some(toolchain) = self.toolchain {
+            configuration.toolchain = toolchain;
+        }
+        if let Some(toolchain_fallback) = self.toolchain_fallback {
+            configuration.toolchain_fallback = toolchain_fallback;
+        }
+        if let Some(mode) = self.mode {
+            configuration.build_mode = mode;
+        }
+        if let Some(goflags) = &self.goflags {
+            configuration.goflags = Some(goflags.clone());
+        }
+        if let Some(goexperiment) = &self.goexperiment {
+            configuration.goexperiment = Some(goexperiment.clone());
+        }
+        if let Some(tags) = &self.tags {
+            configuration.build_tags = tags.clone();
+        }
+        if let Some(gocache) = &self.gocache {
+            configuration.gocache = Some(PathBuf::from(gocache));
+        }
+        if let Some(gomodcache) = &self.gomodcache {
+            configuration.gomodcache = Some(PathBuf::from(gomodcache));
+        }
+        if let Some(jobs) = self.jobs {
+            configuration.jobs = Some(jobs);
+        }
+        if let Some(fail_fast) = self.fail_fast {
+            configuration.fail_fast = fail_fast;
+        }
+        if let Some(deny_warnings) = self.deny_warnings {
+            configuration.deny_warnings = deny_warnings;
+        }
+        if let Some(hermetic) = self.hermetic {
+            configuration.hermetic = hermetic;
+        }
+        if let Some(hermetic_dir) = &self.hermetic_dir {
+            configuration.hermetic_directory = Some(PathBuf::from(hermetic_dir));
+        }
+        if let Some(no_network) = self.no_network {
+            configuration.no_network = no_network;
+        }
+        if let Some(exact_toolchain) = self.exact_toolchain {
+            configuration.exact_toolchain = exact_toolchain;
+        }
+        if let Some(tidy) = self.tidy {
+            configuration.tidy = tidy;
+        }
+        if let Some(features) = &self.llvm_features {
+            configuration.llvm.features = features
+                .iter()
+                .map(|feature| LlvmFeature::parse(feature))
+                .collect::<PluginResult<_>>()?;
+        }
+        if let Some(cpu) = &self.cpu {
+            configuration.llvm = LlvmTuning {
+                cpu: Some(cpu.clone()),
+                ..configuration.llvm.clone()
+            };
+        }
+        if let Some(passes) = &self.wasm_opt_passes {
+            configuration.wasm_opt_passes = passes.clone();
+        }
+        if let Some(hash) = self.hash {
+            configuration.hashed_filenames = hash;
+        }
+        if let Some(provenance) = self.provenance {
+            configuration.provenance = provenance;
+        }
+        if let Some(source_maps) = self.source_maps {
+            configuration.source_maps = source_maps;
+        }
+        if let Some(smoke_test) = self.smoke_test {
+            configuration.smoke_test = smoke_test;
+        }
+        if let Some(metrics) = self.metrics {
+            configuration.metrics = metrics;
+        }
+        if let Some(cache) = self.cache {
+            configuration.cache = cache;
+        }
+        if let Some(remote_cache) = &self.remote_cache {
+            RemoteCache::parse(remote_cache)?;
+            configuration.remote_cache = Some(remote_cache.clone());
+        }
+        if let Some(remote_cache_read_only) = self.remote_cache_read_only {
+            configuration.remote_cache_read_only = remote_cache_read_only;
+        }
+        if let Some(variable) = &self.version_variable {
+            // -X takes importpath.name
+            if !variable.contains('.')
+                || variable.ends_with('.')
+                || variable.contains(|c: char| c.is_whitespace() || c == '=')
+            {
+                return Err(PluginError::InvalidConfiguration {
+                    reason: format!(
+                        "version-variable {variable:?} is not an importpath.name such as {DEFAULT_VERSION_VARIABLE}"
+                    ),
+                });
+            }
+            configuration.version_variable = Some(variable.clone());
+        }
+        match self.stamp_version {
+            Some(false) => configuration.version_variable = None,
+            Some(true) if configuration.version_variable.is_none() => {
+                configuration.version_variable = Some(DEFAULT_VERSION_VARIABLE.to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Reads a value as written on the command line or in a variable: as TOML
+    /// (`true`, `4`) when that fits the key and as a plain string otherwise;
+    /// list keys take comma-separated values. `origin` names it in errors.
+    pub fn parse_value(key: &str, raw: &str, origin: &str) -> PluginResult<toml::Value> {
+        let candidates: Vec<toml::Value> =
+            if matches!(key, "tags" | "llvm-features" | "wasm-opt-passes") {
+                vec![toml::Value::Array(
+                    raw.split(',')
+                        .filter(|item| !item.is_empty())
+                        .map(|item| toml::Value::String(item.to_string()))
+                        .collect(),
+                )]
+            } else {
+                toml::from_str::<toml::Table>(&format!("value = {raw}"))
+                    .ok()
+                    .and_then(|mut parsed| parsed.remove("value"))
+                    .into_iter()
+                    .chain([toml::Value::String(raw.to_string())])
+                    .collect()
+            };
+        let mut first_error = None;
+        for value in candidates {
+            let single = toml::Table::from_iter([(key.to_string(), value.clone())]);
+            match Self::from_table(single, origin) {
+                Ok(_) => return Ok(value),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(
+            first_error.unwrap_or_else(|| PluginError::InvalidConfiguration {
+                reason: format!("{origin}: no value"),
+            }),
+        )
+    }
+
+    fn to_table(&self) -> toml::Table {
+        match toml::Value::try_from(self) {
+            Ok(toml::Value::Table(table)) => table,
+            _ => toml::Table::new(),
+        }
+    }
+
+    fn from_table(table: toml::Table, origin: &str) -> PluginResult<Self> {
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| PluginError::InvalidConfiguration {
+                reason: format!("{origin}: {e}"),
+            })
+    }
+}
+
+/// `[detect]`: how plugin detection treats a repository that mixes Go with
+/// other languages. Only read from the project's `wasmgo.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DetectSettings {
+    /// `true` claims the project whatever it contains, `false` declines it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim: Option<bool>,
+    /// Subdirectory holding the Go module; only it is looked at, and building
+    /// the project builds it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+}
+
+impl DetectSettings {
+    /// The project's `[detect]` section; empty when the config file is
+    /// missing or invalid, which the build reports separately.
+    pub fn of_project(project_path: &Path) -> Self {
+        ProjectConfig::load(&ProjectConfig::project_path(project_path))
+            .ok()
+            .flatten()
+            .map(|config| config.detect)
+            .unwrap_or_default()
+    }
+
+    /// The scoped subdirectory of a project directory, if `root` is set.
+    pub fn scoped_root(project_path: &Path) -> Option<PathBuf> {
+        if !project_path.is_dir() {
+            return None;
+        }
+        Self::of_project(project_path)
+            .root
+            .map(|root| project_path.join(root))
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Contents of `wasmgo.toml` and of the global config file.
+///
+/// ```toml
+/// [build]
+/// toolchain = "go"
+/// output = "public"
+///
+/// [profile.release]
+/// opt = "size"
+///
+/// [target.'cfg(windows)']
+/// output = "dist-windows"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub build: BuildSettings,
+    #[serde(skip_serializing_if = "DetectSettings::is_empty")]
+    pub detect: DetectSettings,
+    pub profile: BTreeMap<String, BuildSettings>,
+    /// `[target.'cfg(...)']` sections, applied over `[build]` on matching hosts
+    pub target: BTreeMap<String, BuildSettings>,
+}
+
+impl ProjectConfig {
+    /// The project's `wasmgo.toml`.
+    /// A single-file project uses the config next to the file.
+    pub fn project_path(project_path: &Path) -> PathBuf {
+        let directory = match project_path.parent() {
+            Some(parent) if project_path.is_file() => parent,
+            _ => project_path,
+        };
+        directory.join(PROJECT_CONFIG_FILENAME)
+    }
+
+    /// The user's config file, shared by every project; see [`Dirs`].
+    pub fn global_path() -> Option<PathBuf> {
+        Dirs::discover().map(|dirs| dirs.config_file())
+    }
+
+    /// Reads a config file, `None` when there is none. `${VAR}` references in
+    /// string values are replaced with environment variables; see
+    /// [`Self::interpolate`].
+    pub fn load(path: &Path) -> PluginResult<Option<Self>> {
+        match Self::read_table(path)? {
+            Some(mut table) => {
+                Self::interpolate_table(&mut table, path, "")?;
+                Self::from_table(table, path).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Expands `${VAR}` and `${VAR:-default}` from the environment; `$${`
+    /// writes a literal `${`. Any other `$` is kept as is.
+    pub fn interpolate(text: &str) -> Result<String, String> {
+        let mut expanded = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('$') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if let Some(after) = rest.strip_prefix("$${") {
+                expanded.push_str("${");
+                rest = after;
+                continue;
+            }
+            let Some(after) = rest.strip_prefix("${") else {
+                expanded.push('$');
+                rest = &rest[1..];
+                continue;
+            };
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("unterminated `${{` in `{text}`"))?;
+            let reference = &after[..end];
+            let (name, default) = match reference.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (reference, None),
+            };
+            let valid_name = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_name {
+                return Err(format!("invalid variable name `{name}` in `{text}`"));
+            }
+            match (std::env::var(name), default) {
+                (Ok(value), _) => expanded.push_str(&value),
+                (Err(_), Some(default)) => expanded.push_str(default),
+                (Err(_), None) => {
+                    return Err(format!(
+                        "environment variable `{name}` is not set (use ${{{name}:-default}} for a fallback)"
+                    ))
+                }
+            }
+            rest = &after[end + 1..];
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+
+    fn interpolate_table(table: &mut toml::Table, path: &Path, prefix: &str) -> PluginResult<()> {
+        for (key, value) in table.iter_mut() {
+            let key_path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            Self::interpolate_value(value, path, &key_path)?;
+        }
+        Ok(())
+    }
+
+    fn interpolate_value(value: &mut toml::Value, path: &Path, key_path: &str) -> PluginResult<()> {
+        match value {
+            toml::Value::String(text) => {
+                *text = Self::interpolate(text).map_err(|reason| {
+                    PluginError::InvalidConfiguration {
+                        reason: format!("{} `{key_path}`: {reason}", path.display()),
+                    }
+                })?;
+            }
+            toml::Value::Array(items) => {
+                for item in items {
+                    Self::interpolate_value(item, path, key_path)?;
+                }
+            }
+            toml::Value::Table(table) => Self::interpolate_table(table, path, key_path)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn read_table(path: &Path) -> PluginResult<Option<toml::Table>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(PluginError::Io(e)),
+        };
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| PluginError::InvalidConfiguration {
+                reason: format!("{}: {e}", path.display()),
+            })
+    }
+
+    fn from_table(table: toml::Table, path: &Path) -> PluginResult<Self> {
+        let config: Self = toml::Value::Table(table).try_into().map_err(|e| {
+            PluginError::InvalidConfiguration {
+                reason: format!("{}: {e}", path.display()),
+            }
+        })?;
+        if let Some(root) = &config.detect.root {
+            // Strictly below the project, so scoping can't loop back to it
+            if !root
+                .components()
+                .all(|component| matches!(component, std::path::Component::Normal(_)))
+                || root.as_os_str().is_empty()
+            {
+                return Err(PluginError::InvalidConfiguration {
+                    reason: format!(
+                        "{}: [detect] root must be a subdirectory of the project, got {}",
+                        path.display(),
+                        root.display()
+                    ),
+                });
+            }
+        }
+        for condition in config.target.keys() {
+            PlatformCondition::parse(condition).map_err(|e| match e {
+                PluginError::InvalidConfiguration { reason } => PluginError::InvalidConfiguration {
+                    reason: format!("{}: {reason}", path.display()),
+                },
+                e => e,
+            })?;
+        }
+        // Values the schema accepts but a build would reject, such as unknown wasm features
+        for (section, settings) in std::iter::once(("build".to_string(), &config.build))
+            .chain(
+                config
+                    .profile
+                    .iter()
+                    .map(|(name, settings)| (format!("profile.{name}"), settings)),
+            )
+            .chain(
+                config
+                    .target
+                    .iter()
+                    .map(|(condition, settings)| (format!("target.'{condition}'"), settings)),
+            )
+        {
+            settings
+                .apply_to(&mut CompileConfig::default())
+                .map_err(|e| PluginError::InvalidConfiguration {
+                    reason: format!("{} [{section}]: {e}", path.display()),
+                })?;
+        }
+        Ok(config)
+    }
+
+    /// The `[target.'cfg(...)']` sections whose condition holds on this host.
+    pub fn host_targets(&self) -> impl Iterator<Item = (&String, &BuildSettings)> {
+        self.target.iter().filter(|(condition, _)| {
+            PlatformCondition::parse(condition).is_ok_and(|condition| condition.matches_host())
+        })
+    }
+}
+
+/// Reads and writes single keys of a config file for `wasmgo config get/set`.
+///
+/// Keys are `build.<key>` (or just `<key>`), `profile.<name>.<key>` and
+/// `target.<cfg(...)>.<key>`. The
+/// file is rewritten on save, so comments in it are not kept.
+pub struct ConfigEditor {
+    path: PathBuf,
+    document: toml::Table,
+}
+
+impl ConfigEditor {
+    /// Opens the file at `path`, starting empty if it does not exist yet.
+    pub fn open(path: &Path) -> PluginResult<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            document: ProjectConfig::read_table(path)?.unwrap_or_default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, key: &str) -> PluginResult<Option<&toml::Value>> {
+        let (section, setting) = Self::parse_key(key)?;
+        Ok(self.section(&section).and_then(|table| table.get(setting)))
+    }
+
+    /// Sets `key` from its command-line spelling and returns the stored value.
+    /// The whole file is validated before anything changes.
+    pub fn set(&mut self, key: &str, raw: &str) -> PluginResult<toml::Value> {
+        let (section, setting) = Self::parse_key(key)?;
+        let value = BuildSettings::parse_value(setting, raw, key)?;
+
+        let mut document = self.document.clone();
+        let mut table = &mut document;
+        for name in &section {
+            table = match table
+                .entry(name.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            {
+                toml::Value::Table(table) => table,
+                _ => {
+                    return Err(PluginError::InvalidConfiguration {
+                        reason: format!("{}: `{name}` is not a table", self.path.display()),
+                    })
+                }
+            };
+        }
+        table.insert(setting.to_string(), value.clone());
+        let mut expanded = document.clone();
+        ProjectConfig::interpolate_table(&mut expanded, &self.path, "")?;
+        ProjectConfig::from_table(expanded, &self.path)?;
+        self.document = document;
+        Ok(value)
+    }
+
+    /// Removes `key`, returning whether it was set.
+    pub fn unset(&mut self, key: &str) -> PluginResult<bool> {
+        let (section, setting) = Self::parse_key(key)?;
+        let mut table = &mut self.document;
+        for name in &section {
+            table = match table.get_mut(*name) {
+                Some(toml::Value::Table(table)) => table,
+                _ => return Ok(false),
+            };
+        }
+        Ok(table.remove(setting).is_some())
+    }
+
+    pub fn save(&self) -> PluginResult<()> {
+        if let Some(parent) = self
+            .path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(PluginError::Io)?;
+        }
+        let content = toml::to_string_pretty(&self.document).map_err(|e| {
+            PluginError::InvalidConfiguration {
+                reason: e.to_string(),
+            }
+        })?;
+        fs::write(&self.path, content).map_err(PluginError::Io)
+    }
+
+    fn section(&self, section: &[&str]) -> Option<&toml::Table> {
+        section
+            .iter()
+            .try_fold(&self.document, |table, name| match table.get(*name)? {
+                toml::Value::Table(table) => Some(table),
+                _ => None,
+            })
+    }
+
+    /// Splits a key into its table path and setting name.
+    fn parse_key(key: &str) -> PluginResult<(Vec<&str>, &str)> {
+        // The condition may contain dots inside quotes, so split at the last one
+        if let Some((condition, setting)) = key
+            .strip_prefix("target.")
+            .and_then(|rest| rest.rsplit_once('.'))
+        {
+            let condition = condition.trim_matches('\'');
+            PlatformCondition::parse(condition)?;
+            return Self::known_setting(setting)
+                .map(|setting| (vec!["target", condition], setting));
+        }
+        let parts: Vec<&str> = key.split('.').collect();
+        let (section, setting) = match parts.as_slice() {
+            [setting] => (vec!["build"], *setting),
+            ["build", setting] => (vec!["build"], *setting),
+            ["profile", name, setting] if !name.is_empty() => (vec!["profile", *name], *setting),
+            _ => {
+                return Err(PluginError::InvalidConfiguration {
+                    reason: format!(
+                        "invalid key `{key}`; expected build.<key>, profile.<name>.<key> or target.<cfg(...)>.<key>"
+                    ),
+                })
+            }
+        };
+        Self::known_setting(setting).map(|setting| (section, setting))
+    }
+
+    fn known_setting(setting: &str) -> PluginResult<&str> {
+        if BuildSettings::KEYS.contains(&setting) {
+            Ok(setting)
+        } else {
+            Err(PluginError::InvalidConfiguration {
+                reason: format!(
+                    "unknown setting `{setting}`; known settings: {}",
+                    BuildSettings::KEYS.join(", ")
+                ),
+            })
+        }
+    }
+}
+
+/// One resolved setting and the layer that decided it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedSetting {
+    pub key: &'static str,
+    pub value: toml::Value,
+    pub source: String,
+}
+
+/// Build settings after merging, lowest precedence first: defaults, the
+/// subcommand's defaults, the global and then the project config's `[build]`, the selected
+/// `[profile.<name>]` (global, then project), `WASMGO_*` variables and the
+/// command line.
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    /// Config files that were found, global first
+    pub config_files: Vec<PathBuf>,
+    /// Selected profile and what selected it
+    pub profile: Option<(String, String)>,
+    pub settings: BuildSettings,
+    /// Set keys in [`BuildSettings::KEYS`] order
+    pub sources: Vec<ResolvedSetting>,
+}
+
+impl ResolvedConfig {
+    /// Merges every layer for the project at `project_path`.
+    ///
+    /// The profile is `profile`, else [`PROFILE_VARIABLE`], else the one
+    /// named after the optimization level if a config file defines it.
+    pub fn resolve(
+        project_path: &Path,
+        profile: Option<&str>,
+        command_line: &BuildSettings,
+    ) -> PluginResult<Self> {
+        Self::resolve_for_command(
+            project_path,
+            profile,
+            &BuildSettings::default(),
+            command_line,
+        )
+    }
+
+    /// Like [`resolve`](Self::resolve), with a subcommand's own defaults
+    /// (e.g. `serve` building a debug web app) layered over the global
+    /// defaults and under everything configured.
+    pub fn resolve_for_command(
+        project_path: &Path,
+        profile: Option<&str>,
+        command_defaults: &BuildSettings,
+        command_line: &BuildSettings,
+    ) -> PluginResult<Self> {
+        let mut config_files = Vec::new();
+        for path in ProjectConfig::global_path()
+            .into_iter()
+            .chain([ProjectConfig::project_path(project_path)])
+        {
+            if let Some(config) = ProjectConfig::load(&path)? {
+                config_files.push((path, config));
+            }
+        }
+        let environment = Self::environment_settings()?;
+
+        let mut merged = toml::Table::new();
+        let mut sources: BTreeMap<String, String> = BTreeMap::new();
+        let mut layer = |table: toml::Table, source: &str| {
+            for (key, value) in table {
+                sources.insert(key.clone(), source.to_string());
+                merged.insert(key, value);
+            }
+        };
+
+        layer(BuildSettings::defaults().to_table(), "default");
+        layer(command_defaults.to_table(), "command default");
+        for (path, config) in &config_files {
+            layer(
+                config.build.to_table(),
+                &format!("{} [build]", path.display()),
+            );
+            for (condition, settings) in config.host_targets() {
+                layer(
+                    settings.to_table(),
+                    &format!("{} [target.'{condition}']", path.display()),
+                );
+            }
+        }
+
+        let profile_selection = match profile {
+            Some(name) => Some((name.to_string(), "--profile".to_string())),
+            None => match std::env::var(PROFILE_VARIABLE) {
+                Ok(name) if !name.is_empty() => Some((name, PROFILE_VARIABLE.to_string())),
+                _ => {
+                    let opt = command_line
+                        .opt
+                        .or(environment.opt)
+                        .or(config_files
+                            .iter()
+                            .rev()
+                            .find_map(|(_, config)| config.build.opt))
+                        .or(command_defaults.opt)
+                        .unwrap_or(OptimizationLevel::Release);
+                    let name = opt.name().to_string();
+                    config_files
+                        .iter()
+                        .any(|(_, config)| config.profile.contains_key(&name))
+                        .then(|| (name, "optimization level".to_string()))
+                }
+            },
+        };
+        if let Some((name, _)) = &profile_selection {
+            let mut defined = false;
+            for (path, config) in &config_files {
+                if let Some(settings) = config.profile.get(name) {
+                    defined = true;
+                    layer(
+                        settings.to_table(),
+                        &format!("{} [profile.{name}]", path.display()),
+                    );
+                }
+            }
+            if !defined {
+                return Err(PluginError::InvalidConfiguration {
+                    reason: format!(
+                        "profile `{name}` is not defined; defined profiles: {}",
+                        Self::profile_names(&config_files)
+                    ),
+                });
+            }
+        }
+        for (key, value) in environment.to_table() {
+            let source = BuildSettings::environment_variable(&key);
+            layer(toml::Table::from_iter([(key, value)]), &source);
+        }
+        layer(command_line.to_table(), "command line");
+
+        let settings = BuildSettings::from_table(merged.clone(), "merged configuration")?;
+        let sources = BuildSettings::KEYS
+            .iter()
+            .filter_map(|key| {
+                merged.get(*key).map(|value| ResolvedSetting {
+                    key,
+                    value: value.clone(),
+                    source: sources.get(*key).cloned().unwrap_or_default(),
+                })
+            })
+            .collect();
+        Ok(Self {
+            config_files: config_files.into_iter().map(|(path, _)| path).collect(),
+            profile: profile_selection,
+            settings,
+            sources,
+        })
+    }
+
+    fn profile_names(config_files: &[(PathBuf, ProjectConfig)]) -> String {
+        let mut names: Vec<&str> = config_files
+            .iter()
+            .flat_map(|(_, config)| config.profile.keys().map(String::as_str))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+
+    /// Settings from `WASMGO_*` variables.
+    fn environment_settings() -> PluginResult<BuildSettings> {
+        let mut table = toml::Table::new();
+        for key in BuildSettings::KEYS {
+            let variable = BuildSettings::environment_variable(key);
+            if let Ok(raw) = std::env::var(&variable) {
+                table.insert(
+                    key.to_string(),
+                    BuildSettings::parse_value(key, &raw, &variable)?,
+                );
+            }
+        }
+        BuildSettings::from_table(table, "WASMGO_* variables")
+    }
+}
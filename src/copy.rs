@@ -0,0 +1,170 @@
+use crate::{PluginError, PluginResult};
+use sha2::{Digest, Sha256};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Buffer size of the streaming fallback.
+const COPY_BUFFER_SIZE: usize = 1 << 20;
+
+/// How [`ArtifactCopier::copy`] produced the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    /// The destination already had the same contents
+    Unchanged,
+    /// Copy-on-write clone sharing the source's blocks (FICLONE, clonefile)
+    Reflink,
+    /// Second name for the source file; rewriting the source in place shows
+    /// through, so it suits outputs the toolchain replaces on every build
+    HardLink,
+    Streamed,
+}
+
+impl CopyMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CopyMethod::Unchanged => "unchanged",
+            CopyMethod::Reflink => "reflink",
+            CopyMethod::HardLink => "hard link",
+            CopyMethod::Streamed => "copied",
+        }
+    }
+}
+
+/// Copies build artifacts, which can be tens of MB for debug builds, as
+/// cheaply as the filesystem allows.
+///
+/// Identical destinations are left alone. Otherwise the copy is a reflink, a
+/// hard link or a buffered stream, in that order. Each is written next to the
+/// destination and renamed over it, so readers never see a partial file and an
+/// existing hard link is replaced rather than written through.
+pub struct ArtifactCopier;
+
+impl ArtifactCopier {
+    pub fn copy(source: &Path, destination: &Path) -> PluginResult<CopyMethod> {
+        if Self::same_contents(source, destination)? {
+            return Ok(CopyMethod::Unchanged);
+        }
+
+        let temporary = Self::temporary_path(destination);
+        let _ = fs::remove_file(&temporary);
+        let method = if platform::reflink(source, &temporary) {
+            CopyMethod::Reflink
+        } else if fs::hard_link(source, &temporary).is_ok() {
+            CopyMethod::HardLink
+        } else {
+            Self::stream(source, &temporary)
+                .inspect_err(|_| {
+                    let _ = fs::remove_file(&temporary);
+                })
+                .map_err(PluginError::Io)?;
+            CopyMethod::Streamed
+        };
+        fs::rename(&temporary, destination)
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&temporary);
+            })
+            .map_err(PluginError::Io)?;
+        Ok(method)
+    }
+
+    /// Same file, or equal length and SHA-256.
+    fn same_contents(source: &Path, destination: &Path) -> PluginResult<bool> {
+        let source_metadata = fs::metadata(source).map_err(PluginError::Io)?;
+        let Ok(destination_metadata) = fs::metadata(destination) else {
+            return Ok(false);
+        };
+        if !destination_metadata.is_file() || destination_metadata.len() != source_metadata.len() {
+            return Ok(false);
+        }
+        if fs::canonicalize(source).ok() == fs::canonicalize(destination).ok() {
+            return Ok(true);
+        }
+        let source_hash = Self::sha256(source).map_err(PluginError::Io)?;
+        Ok(Self::sha256(destination).is_ok_and(|hash| hash == source_hash))
+    }
+
+    fn sha256(path: &Path) -> io::Result<[u8; 32]> {
+        let mut hasher = Sha256::new();
+        io::copy(
+            &mut BufReader::with_capacity(COPY_BUFFER_SIZE, File::open(path)?),
+            &mut hasher,
+        )?;
+        Ok(hasher.finalize().into())
+    }
+
+    fn stream(source: &Path, destination: &Path) -> io::Result<()> {
+        let input = File::open(source)?;
+        let permissions = input.metadata()?.permissions();
+        let mut output = BufWriter::with_capacity(COPY_BUFFER_SIZE, File::create(destination)?);
+        io::copy(
+            &mut BufReader::with_capacity(COPY_BUFFER_SIZE, input),
+            &mut output,
+        )?;
+        output.flush()?;
+        drop(output);
+        fs::set_permissions(destination, permissions)
+    }
+
+    fn temporary_path(destination: &Path) -> PathBuf {
+        let mut name = OsString::from(".");
+        name.push(destination.file_name().unwrap_or_default());
+        name.push(format!(".{}.tmp", std::process::id()));
+        destination.with_file_name(name)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod platform {
+    use std::fs::File;
+    use std::os::fd::AsRawFd;
+    use std::path::Path;
+
+    pub fn reflink(source: &Path, destination: &Path) -> bool {
+        let Ok(input) = File::open(source) else {
+            return false;
+        };
+        let Ok(output) = File::create_new(destination) else {
+            return false;
+        };
+        let cloned =
+            unsafe { libc::ioctl(output.as_raw_fd(), libc::FICLONE, input.as_raw_fd()) } == 0;
+        drop(output);
+        if cloned {
+            if let Ok(metadata) = input.metadata() {
+                let _ = std::fs::set_permissions(destination, metadata.permissions());
+            }
+        } else {
+            let _ = std::fs::remove_file(destination);
+        }
+        cloned
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    // clonefile copies permissions itself
+    pub fn reflink(source: &Path, destination: &Path) -> bool {
+        let (Ok(source), Ok(destination)) = (
+            CString::new(source.as_os_str().as_bytes()),
+            CString::new(destination.as_os_str().as_bytes()),
+        ) else {
+            return false;
+        };
+        unsafe { libc::clonefile(source.as_ptr(), destination.as_ptr(), 0) == 0 }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+mod platform {
+    use std::path::Path;
+
+    pub fn reflink(_source: &Path, _destination: &Path) -> bool {
+        false
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{CommandExecutor, DeployBundler, Logger, PluginError, PluginResult};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Publishes a web bundle to GitHub Pages.
+pub struct GhPagesDeployer;
+
+impl GhPagesDeployer {
+    /// `/` for user and organization sites (`<owner>.github.io` repositories),
+    /// `/<repository>/` for project pages.
+    pub fn default_base_path(project_path: &Path) -> String {
+        let remote_url =
+            Self::git(project_path, &["remote", "get-url", "origin"], &[]).unwrap_or_default();
+        let repository = remote_url
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .rsplit(['/', ':'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        if repository.is_empty() || repository.ends_with(".github.io") {
+            "/".to_string()
+        } else {
+            format!("/{repository}/")
+        }
+    }
+
+    /// Writes the site into `site_directory`, which must be empty, missing, or
+    /// a checkout of the pages `branch`. A checkout is cleared of everything
+    /// but its `.git` entry, so a worktree of the pages branch can be reused.
+    pub fn prepare_site(
+        asset_paths: &[PathBuf],
+        site_directory: &Path,
+        base_path: &str,
+        branch: &str,
+    ) -> PluginResult<()> {
+        let is_empty = fs::read_dir(site_directory)
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(true);
+        if !is_empty {
+            if !Self::is_pages_checkout(site_directory, branch) {
+                return Err(PluginError::DeploymentFailed {
+                    reason: format!(
+                        "{} is not empty and not a checkout of {branch}; pass an empty directory or a worktree of the pages branch",
+                        site_directory.display()
+                    ),
+                });
+            }
+            for entry in fs::read_dir(site_directory)
+                .map_err(PluginError::Io)?
+                .flatten()
+            {
+                if entry.file_name() == ".git" {
+                    continue;
+                }
+                let path = entry.path();
+                if path.is_dir() {
+                    fs::remove_dir_all(&path).map_err(PluginError::Io)?;
+                } else {
+                    fs::remove_file(&path).map_err(PluginError::Io)?;
+                }
+            }
+        }
+
+        let bundle = DeployBundler::bundle(asset_paths, site_directory, false)?;
+        // Jekyll would otherwise skip files starting with `_`
+        fs::write(site_directory.join(".nojekyll"), "").map_err(PluginError::Io)?;
+
+        if base_path != "/" {
+            for file in &bundle.files {
+                if !matches!(
+                    Path::new(&file.file)
+                        .extension()
+                        .and_then(|extension| extension.to_str()),
+                    Some("html" | "js" | "mjs" | "css")
+                ) {
+                    continue;
+                }
+                let path = site_directory.join(&file.file);
+                let contents = fs::read_to_string(&path).map_err(PluginError::Io)?;
+                fs::write(&path, Self::rewrite_base_path(&contents, base_path))
+                    .map_err(PluginError::Io)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `directory` is the top of a git worktree (`git worktree add`
+    /// leaves a `.git` file pointing into `worktrees/`) or a clone with
+    /// `branch` checked out.
+    fn is_pages_checkout(directory: &Path, branch: &str) -> bool {
+        let git_entry = directory.join(".git");
+        if git_entry.is_file() {
+            let worktree = fs::read_to_string(&git_entry).unwrap_or_default();
+            if worktree
+                .trim()
+                .strip_prefix("gitdir:")
+                .is_some_and(|git_directory| {
+                    Path::new(git_directory.trim())
+                        .parent()
+                        .is_some_and(|parent| parent.ends_with("worktrees"))
+                })
+            {
+                return true;
+            }
+        } else if !git_entry.is_dir() {
+            return false;
+        }
+        Self::git(directory, &["symbolic-ref", "--short", "HEAD"], &[])
+            .is_ok_and(|checked_out| checked_out == branch)
+    }
+
+    /// Prefixes root-relative URLs (`src="/app.wasm"`, `url(/font.woff)`) with the
+    /// base path, leaving protocol-relative `//host` URLs alone.
+    pub fn rewrite_base_path(contents: &str, base_path: &str) -> String {
+        let mut rewritten = contents.to_string();
+        for prefix in [
+            "src=\"/",
+            "href=\"/",
+            "src='/",
+            "href='/",
+            "url(/",
+            "fetch(\"/",
+            "fetch('/",
+        ] {
+            let replacement = format!("{}{base_path}", &prefix[..prefix.len() - 1]);
+            let mut result = String::with_capacity(rewritten.len());
+            let mut rest = rewritten.as_str();
+            while let Some(index) = rest.find(prefix) {
+                let after = &rest[index + prefix.len()..];
+                result.push_str(&rest[..index]);
+                if after.starts_with('/') || after.starts_with(base_path.trim_start_matches('/')) {
+                    result.push_str(prefix);
+                } else {
+                    result.push_str(&replacement);
+                }
+                rest = after;
+            }
+            result.push_str(rest);
+            rewritten = result;
+        }
+        rewritten
+    }
+
+    /// Commits `site_directory` as the new tip of `branch` without touching the
+    /// project's working tree or index, then pushes it to `remote`.
+    ///
+    /// Returns the created commit.
+    pub fn publish(
+        project_path: &Path,
+        site_directory: &Path,
+        branch: &str,
+        remote: &str,
+        push: bool,
+    ) -> PluginResult<String> {
+        let git_directory = Self::git(project_path, &["rev-parse", "--absolute-git-dir"], &[])?;
+        let branch_ref = format!("refs/heads/{branch}");
+        let remote_ref = format!("refs/remotes/{remote}/{branch}");
+
+        // The branch may not exist yet, so a failed fetch is not an error
+        let fetched = push
+            && Self::git(
+                project_path,
+                &["fetch", remote, &format!("+{branch}:{remote_ref}")],
+                &[],
+            )
+            .is_ok();
+        // Building on the fetched remote tip keeps the push a fast-forward,
+        // however far the local branch has fallen behind
+        let references = if fetched {
+            [remote_ref.as_str(), branch_ref.as_str()]
+        } else {
+            [branch_ref.as_str(), remote_ref.as_str()]
+        };
+        let parent = references.iter().find_map(|reference| {
+            Self::git(
+                project_path,
+                &["rev-parse", "--verify", "-q", reference],
+                &[],
+            )
+            .ok()
+        });
+
+        let git_directory = PathBuf::from(git_directory);
+        let index_file = git_directory.join("wasmgo-gh-pages.index");
+        let site_directory = fs::canonicalize(site_directory).map_err(PluginError::Io)?;
+        let environment = [
+            ("GIT_DIR", git_directory.as_os_str()),
+            ("GIT_INDEX_FILE", index_file.as_os_str()),
+            ("GIT_WORK_TREE", site_directory.as_os_str()),
+        ];
+        let _ = fs::remove_file(&index_file);
+        Self::git(
+            &site_directory,
+            &["add", "--all", "--force", "."],
+            &environment,
+        )?;
+        let tree = Self::git(&site_directory, &["write-tree"], &environment)?;
+        let _ = fs::remove_file(&index_file);
+
+        let source_commit = Self::git(project_path, &["rev-parse", "--short", "HEAD"], &[])
+            .unwrap_or_else(|_| "working tree".to_string());
+        let message = format!("Deploy {source_commit} with wasmgo");
+        let mut commit_arguments = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+        if let Some(parent) = &parent {
+            commit_arguments.extend(["-p", parent.as_str()]);
+        }
+        let commit = Self::git(project_path, &commit_arguments, &[])?;
+        Self::git(project_path, &["update-ref", &branch_ref, &commit], &[])?;
+
+        if push {
+            Logger::info(format_args!("🚀 Pushing {branch} to {remote}..."));
+            Self::git(
+                project_path,
+                &["push", remote, &format!("{commit}:{branch_ref}")],
+                &[],
+            )?;
+        }
+        Ok(commit)
+    }
+
+    fn git(
+        working_directory: &Path,
+        arguments: &[&str],
+        environment: &[(&str, &OsStr)],
+    ) -> PluginResult<String> {
+        let output = CommandExecutor::execute_command_with_env(
+            "git",
+            arguments,
+            working_directory,
+            environment,
+            false,
+        )?;
+        if !output.status.success() {
+            return Err(PluginError::DeploymentFailed {
+                reason: format!(
+                    "git {} failed: {}",
+                    arguments.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
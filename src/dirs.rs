@@ -0,0 +1,140 @@
+use crate::GLOBAL_CONFIG_FILENAME;
+use std::path::{Path, PathBuf};
+
+/// Puts all of wasmgo's directories under one root (`config/`, `cache/`, `data/`).
+pub const HOME_VARIABLE: &str = "WASMGO_HOME";
+
+/// A subsystem's directory. Each is self-contained, so removing one only
+/// costs that subsystem its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateDirectory {
+    /// Incremental build cache
+    Builds,
+    /// Toolchains downloaded by wasmgo
+    Toolchains,
+    /// Project template registries
+    Templates,
+    /// Build and command history
+    History,
+    /// Generated modules and stand-in build and output directories
+    Scratch,
+}
+
+impl StateDirectory {
+    pub const ALL: [StateDirectory; 5] = [
+        StateDirectory::Builds,
+        StateDirectory::Toolchains,
+        StateDirectory::Templates,
+        StateDirectory::History,
+        StateDirectory::Scratch,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StateDirectory::Builds => "builds",
+            StateDirectory::Toolchains => "toolchains",
+            StateDirectory::Templates => "templates",
+            StateDirectory::History => "history",
+            StateDirectory::Scratch => "scratch",
+        }
+    }
+
+    /// Caches can be rebuilt or re-downloaded; the rest is user data.
+    pub fn is_cache(&self) -> bool {
+        matches!(
+            self,
+            StateDirectory::Builds | StateDirectory::Toolchains | StateDirectory::Scratch
+        )
+    }
+}
+
+/// Where wasmgo keeps state outside projects: a `wasmgo` directory in each
+/// platform's standard locations, or everything under [`HOME_VARIABLE`].
+///
+/// - Linux and other Unix: `$XDG_CONFIG_HOME`, `$XDG_CACHE_HOME` and
+///   `$XDG_DATA_HOME`, defaulting to `~/.config`, `~/.cache` and `~/.local/share`
+/// - macOS: `~/Library/Application Support` and `~/Library/Caches`, unless the
+///   XDG variables are set
+/// - Windows: `%APPDATA%` for config, `%LOCALAPPDATA%` for caches and data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirs {
+    pub config: PathBuf,
+    pub cache: PathBuf,
+    pub data: PathBuf,
+}
+
+impl Dirs {
+    /// `None` when the platform's base directories cannot be determined.
+    pub fn discover() -> Option<Self> {
+        if let Some(home) = Self::variable(HOME_VARIABLE) {
+            return Some(Self::under(&home));
+        }
+
+        let (config, cache, data) = if cfg!(windows) {
+            let local = Self::variable("LOCALAPPDATA");
+            (
+                Self::variable("APPDATA")?,
+                local.clone()?.join("wasmgo").join("cache"),
+                local?.join("wasmgo").join("data"),
+            )
+        } else {
+            let home = Self::variable("HOME");
+            let base = |variable: &str, fallback: &str, macos: &str| {
+                Self::variable(variable).or_else(|| {
+                    let relative = if cfg!(target_os = "macos") {
+                        macos
+                    } else {
+                        fallback
+                    };
+                    home.as_ref().map(|home| home.join(relative))
+                })
+            };
+            (
+                base("XDG_CONFIG_HOME", ".config", "Library/Application Support")?,
+                base("XDG_CACHE_HOME", ".cache", "Library/Caches")?.join("wasmgo"),
+                base(
+                    "XDG_DATA_HOME",
+                    ".local/share",
+                    "Library/Application Support",
+                )?
+                .join("wasmgo"),
+            )
+        };
+        Some(Self {
+            config: config.join("wasmgo"),
+            cache,
+            data,
+        })
+    }
+
+    /// The layout [`HOME_VARIABLE`] selects.
+    pub fn under(root: &Path) -> Self {
+        Self {
+            config: root.join("config"),
+            cache: root.join("cache"),
+            data: root.join("data"),
+        }
+    }
+
+    pub fn config_file(&self) -> PathBuf {
+        self.config.join(GLOBAL_CONFIG_FILENAME)
+    }
+
+    /// Caches live under [`Self::cache`], everything else under [`Self::data`].
+    pub fn state(&self, directory: StateDirectory) -> PathBuf {
+        let base = if directory.is_cache() {
+            &self.cache
+        } else {
+            &self.data
+        };
+        base.join(directory.name())
+    }
+
+    fn variable(name: &str) -> Option<PathBuf> {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            // Relative values are invalid per the XDG spec and would depend on the working directory
+            .filter(|path| path.is_absolute())
+    }
+}
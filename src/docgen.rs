@@ -0,0 +1,203 @@
+use crate::{ExternalKind, GoExport, WasmBinary};
+use serde::Serialize;
+
+/// Reference entry for one function on the wasm boundary.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedFunctionDoc {
+    pub export_name: String,
+    /// `func add(a, b int32) int32`
+    pub go_signature: String,
+    pub parameter_names: Vec<String>,
+    /// Core wasm signature, when a compiled module was given
+    pub wasm_params: Option<Vec<String>>,
+    pub wasm_results: Option<Vec<String>>,
+    pub doc_comment: Vec<String>,
+    pub source_file: String,
+}
+
+impl ExportedFunctionDoc {
+    /// JavaScript-side signature: i64 values cross the boundary as BigInt.
+    pub fn js_signature(&self) -> Option<String> {
+        let js_type = |wasm_type: &String| match wasm_type.as_str() {
+            "i64" => "bigint",
+            _ => "number",
+        };
+        let params = self.wasm_params.as_ref()?;
+        let results = self.wasm_results.as_ref()?;
+        let result = match results.as_slice() {
+            [] => "void".to_string(),
+            [single] => js_type(single).to_string(),
+            multiple => format!(
+                "[{}]",
+                multiple.iter().map(js_type).collect::<Vec<_>>().join(", ")
+            ),
+        };
+        let params: Vec<String> = params
+            .iter()
+            .enumerate()
+            .map(|(index, wasm_type)| {
+                // Go strings and slices lower to several wasm values, so Go names
+                // only apply when the parameters line up one to one
+                let name = match self.parameter_names.get(index) {
+                    Some(name) if self.parameter_names.len() == params.len() => name.clone(),
+                    _ => format!("arg{index}"),
+                };
+                format!("{name}: {}", js_type(wasm_type))
+            })
+            .collect();
+        Some(format!(
+            "{}({}): {result}",
+            self.export_name,
+            params.join(", ")
+        ))
+    }
+}
+
+/// Generates a reference of a module's exported functions for JS consumers.
+pub struct ApiDocGenerator;
+
+impl ApiDocGenerator {
+    /// Merges the exports declared in the Go sources with the function exports
+    /// of the compiled module, if given. Runtime exports of the module that
+    /// have no Go declaration (`_start`, `malloc`, ...) are left out.
+    pub fn collect(exports: &[GoExport], binary: Option<&WasmBinary>) -> Vec<ExportedFunctionDoc> {
+        exports
+            .iter()
+            .map(|export| {
+                let function_type = binary.and_then(|binary| {
+                    binary
+                        .exports
+                        .iter()
+                        .find(|wasm_export| {
+                            wasm_export.kind == ExternalKind::Function
+                                && wasm_export.name == export.export_name
+                        })
+                        .and_then(|wasm_export| binary.function_type(wasm_export.index))
+                });
+                let parameters: Vec<String> = export
+                    .parameters
+                    .iter()
+                    .map(|parameter| format!("{} {}", parameter.name, parameter.go_type))
+                    .collect();
+                let results = match export.results.as_slice() {
+                    [] => String::new(),
+                    [single] => format!(" {single}"),
+                    multiple => format!(" ({})", multiple.join(", ")),
+                };
+
+                ExportedFunctionDoc {
+                    export_name: export.export_name.clone(),
+                    go_signature: format!(
+                        "func {}({}){results}",
+                        export.function_name,
+                        parameters.join(", ")
+                    ),
+                    parameter_names: export
+                        .parameters
+                        .iter()
+                        .map(|parameter| parameter.name.clone())
+                        .collect(),
+                    wasm_params: function_type.map(|function_type| function_type.params.clone()),
+                    wasm_results: function_type.map(|function_type| function_type.results.clone()),
+                    // The blank `//` line separating the comment from the directive
+                    doc_comment: export
+                        .doc_comment
+                        .iter()
+                        .rev()
+                        .skip_while(|line| line.is_empty())
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .rev()
+                        .cloned()
+                        .collect(),
+                    source_file: export.source_file.clone(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn markdown(title: &str, functions: &[ExportedFunctionDoc]) -> String {
+        let mut markdown = format!(
+            "<!-- Generated by wasmgo docgen -->\n# {title}\n\nFunctions exported across the WebAssembly boundary.\n"
+        );
+        if functions.is_empty() {
+            markdown.push_str("\nThis module exports no functions.\n");
+        }
+        for function in functions {
+            markdown.push_str(&format!("\n## `{}`\n\n", function.export_name));
+            if let Some(js_signature) = function.js_signature() {
+                markdown.push_str(&format!("```ts\n{js_signature}\n```\n\n"));
+            }
+            for line in &function.doc_comment {
+                markdown.push_str(line);
+                markdown.push('\n');
+            }
+            if !function.doc_comment.is_empty() {
+                markdown.push('\n');
+            }
+            markdown.push_str(&format!(
+                "- Go: `{}` ({})\n",
+                function.go_signature, function.source_file
+            ));
+            if let (Some(params), Some(results)) = (&function.wasm_params, &function.wasm_results) {
+                markdown.push_str(&format!(
+                    "- Wasm: `({}) -> ({})`\n",
+                    params.join(", "),
+                    results.join(", ")
+                ));
+            }
+        }
+        markdown
+    }
+
+    pub fn html(title: &str, functions: &[ExportedFunctionDoc]) -> String {
+        let title = Self::escape(title);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<!-- Generated by wasmgo docgen -->\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n<p>Functions exported across the WebAssembly boundary.</p>\n"
+        );
+        if functions.is_empty() {
+            html.push_str("<p>This module exports no functions.</p>\n");
+        }
+        for function in functions {
+            let name = Self::escape(&function.export_name);
+            html.push_str(&format!(
+                "<section id=\"{name}\">\n<h2><code>{name}</code></h2>\n"
+            ));
+            if let Some(js_signature) = function.js_signature() {
+                html.push_str(&format!(
+                    "<pre><code>{}</code></pre>\n",
+                    Self::escape(&js_signature)
+                ));
+            }
+            if !function.doc_comment.is_empty() {
+                html.push_str(&format!(
+                    "<p>{}</p>\n",
+                    Self::escape(&function.doc_comment.join("\n"))
+                ));
+            }
+            html.push_str("<ul>\n");
+            html.push_str(&format!(
+                "<li>Go: <code>{}</code> ({})</li>\n",
+                Self::escape(&function.go_signature),
+                Self::escape(&function.source_file)
+            ));
+            if let (Some(params), Some(results)) = (&function.wasm_params, &function.wasm_results) {
+                html.push_str(&format!(
+                    "<li>Wasm: <code>({}) -&gt; ({})</code></li>\n",
+                    params.join(", "),
+                    results.join(", ")
+                ));
+            }
+            html.push_str("</ul>\n</section>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
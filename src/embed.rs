@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files pulled into the binary by one `//go:embed` directive.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedAsset {
+    pub pattern: String,
+    pub source_file: String,
+    /// Matched files relative to the package directory, with their sizes
+    pub files: Vec<(String, u64)>,
+    pub total_size: u64,
+}
+
+/// Resolves the `//go:embed` directives of a package against its files.
+pub struct EmbedScanner;
+
+impl EmbedScanner {
+    /// Scans the non-test `.go` files of the package in `package_directory`.
+    pub fn scan_package(package_path: &Path) -> Vec<EmbeddedAsset> {
+        let mut source_files: Vec<_> = fs::read_dir(package_path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                        file_name.ends_with(".go") && !file_name.ends_with("_test.go")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        source_files.sort();
+
+        let mut assets = Vec::new();
+        for path in source_files {
+            let Ok(source) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let source_file = path.file_name().unwrap_or_default().to_string_lossy();
+            for line in source.lines().map(str::trim) {
+                let Some(patterns) = line.strip_prefix("//go:embed ") else {
+                    continue;
+                };
+                for pattern in Self::split_patterns(patterns) {
+                    let mut files = Self::resolve_pattern(package_path, &pattern);
+                    files.sort();
+                    files.dedup();
+                    assets.push(EmbeddedAsset {
+                        total_size: files.iter().map(|(_, size)| size).sum(),
+                        pattern,
+                        source_file: source_file.to_string(),
+                        files,
+                    });
+                }
+            }
+        }
+        assets
+    }
+
+    /// Splits a directive's arguments, honouring `"quoted"` and `` `raw` `` patterns.
+    fn split_patterns(arguments: &str) -> Vec<String> {
+        let mut patterns = Vec::new();
+        let mut current = String::new();
+        let mut quote = None;
+        for c in arguments.chars() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => current.push(c),
+                None if c == '"' || c == '`' => quote = Some(c),
+                None if c.is_whitespace() => {
+                    if !current.is_empty() {
+                        patterns.push(std::mem::take(&mut current));
+                    }
+                }
+                None => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            patterns.push(current);
+        }
+        patterns
+    }
+
+    fn resolve_pattern(package_path: &Path, pattern: &str) -> Vec<(String, u64)> {
+        let (pattern, include_hidden) = match pattern.strip_prefix("all:") {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+
+        let mut candidates = vec![package_path.to_path_buf()];
+        for component in pattern.split('/').filter(|component| !component.is_empty()) {
+            candidates = candidates
+                .iter()
+                .flat_map(|directory| {
+                    fs::read_dir(directory)
+                        .map(|entries| {
+                            entries
+                                .flatten()
+                                .filter(|entry| {
+                                    Self::component_matches(
+                                        component,
+                                        &entry.file_name().to_string_lossy(),
+                                    )
+                                })
+                                .map(|entry| entry.path())
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect();
+        }
+
+        let mut files = Vec::new();
+        for candidate in candidates {
+            Self::collect_files(package_path, &candidate, include_hidden, &mut files);
+        }
+        files
+    }
+
+    // Directories are embedded recursively, skipping `.` and `_` names unless `all:` was used
+    fn collect_files(
+        package_path: &Path,
+        path: &PathBuf,
+        include_hidden: bool,
+        files: &mut Vec<(String, u64)>,
+    ) {
+        if path.is_dir() {
+            for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if include_hidden || !(name.starts_with('.') || name.starts_with('_')) {
+                    Self::collect_files(package_path, &entry.path(), include_hidden, files);
+                }
+            }
+        } else if let Ok(metadata) = fs::metadata(path) {
+            let relative_path = path.strip_prefix(package_path).unwrap_or(path);
+            files.push((relative_path.to_string_lossy().to_string(), metadata.len()));
+        }
+    }
+
+    /// `path.Match` for a single path component, supporting `*` and `?`.
+    fn component_matches(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        let (mut p, mut n) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+
+        while n < name.len() {
+            if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+                p += 1;
+                n += 1;
+            } else if p < pattern.len() && pattern[p] == '*' {
+                backtrack = Some((p, n));
+                p += 1;
+            } else if let Some((star, matched)) = backtrack {
+                p = star + 1;
+                n = matched + 1;
+                backtrack = Some((star, matched + 1));
+            } else {
+                return false;
+            }
+        }
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{CompileConfig, CompileResult, PluginError, PluginResult, WasmBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the project, searched for example programs.
+pub const EXAMPLES_DIRECTORY: &str = "examples";
+
+/// A `main` package under [`EXAMPLES_DIRECTORY`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// Path below `examples/` with `/` separators, e.g. `hello` or `http/server`
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Builds each program under a library's `examples/` as its own module, into
+/// `<output>/examples/<name>.wasm`.
+pub struct Examples;
+
+impl Examples {
+    /// Main packages under `examples/`, sorted by name. Hidden directories,
+    /// `testdata` and `vendor` are skipped.
+    pub fn discover(project_path: &Path) -> Vec<Example> {
+        let root = project_path.join(EXAMPLES_DIRECTORY);
+        let mut examples = Vec::new();
+        let mut pending = vec![root.clone()];
+        while let Some(directory) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&directory) else {
+                continue;
+            };
+            for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                if path.is_dir()
+                    && !file_name.starts_with('.')
+                    && !matches!(file_name.as_ref(), "testdata" | "vendor")
+                {
+                    pending.push(path);
+                }
+            }
+            if directory != root && Self::is_main_package(&directory) {
+                let name = directory
+                    .strip_prefix(&root)
+                    .unwrap_or(&directory)
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                examples.push(Example {
+                    name,
+                    path: directory,
+                });
+            }
+        }
+        examples.sort_by(|a, b| a.name.cmp(&b.name));
+        examples
+    }
+
+    /// `configuration` retargeted at one example. Paths that were relative to
+    /// the project stay relative to it.
+    pub fn configure(configuration: &CompileConfig, example: &Example) -> CompileConfig {
+        // Relative paths resolve against each example's directory in the builder
+        let anchor = |path: &Path| {
+            let path = configuration.project_path.join(path);
+            std::path::absolute(&path).unwrap_or(path)
+        };
+        let output = anchor(&configuration.output_directory).join(EXAMPLES_DIRECTORY);
+        let output = match Path::new(&example.name).parent() {
+            Some(parent) => output.join(parent),
+            None => output,
+        };
+        CompileConfig {
+            project_path: example.path.clone(),
+            output_directory: output,
+            build_directory: configuration.build_directory.as_deref().map(anchor),
+            gocache: configuration.gocache.as_deref().map(anchor),
+            gomodcache: configuration.gomodcache.as_deref().map(anchor),
+            hermetic_directory: configuration.hermetic_directory.as_deref().map(anchor),
+            ..configuration.clone()
+        }
+    }
+
+    /// Builds every example, continuing past failures. Fails only when the
+    /// project has no examples.
+    pub fn build_all(
+        builder: &dyn WasmBuilder,
+        configuration: &CompileConfig,
+    ) -> PluginResult<Vec<(Example, PluginResult<CompileResult>)>> {
+        let examples = Self::discover(&configuration.project_path);
+        if examples.is_empty() {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: format!(
+                    "no main packages under {}",
+                    configuration
+                        .project_path
+                        .join(EXAMPLES_DIRECTORY)
+                        .display()
+                ),
+            });
+        }
+        Ok(examples
+            .into_iter()
+            .map(|example| {
+                let result = builder.compile(&Self::configure(configuration, &example));
+                (example, result)
+            })
+            .collect())
+    }
+
+    /// Whether a non-test source file in `directory` declares `package main`.
+    fn is_main_package(directory: &Path) -> bool {
+        let Ok(entries) = fs::read_dir(directory) else {
+            return false;
+        };
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                file_name.ends_with(".go") && !file_name.ends_with("_test.go")
+            })
+            .any(|path| {
+                fs::read_to_string(path).is_ok_and(|source| {
+                    source
+                        .lines()
+                        .map(str::trim)
+                        .find(|line| line.starts_with("package "))
+                        .is_some_and(|line| line.split_whitespace().nth(1) == Some("main"))
+                })
+            })
+    }
+}
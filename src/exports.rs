@@ -0,0 +1,203 @@
+use std::fs;
+use std::path::Path;
+
+/// Directive used to export a Go function to the WebAssembly host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportDirective {
+    /// `//go:wasmexport name` (Go 1.24+, TinyGo 0.34+)
+    WasmExport,
+    /// `//export name` (TinyGo, cgo-style)
+    Export,
+}
+
+/// A Go function exported to the WebAssembly host, as declared in source.
+#[derive(Debug, Clone)]
+pub struct GoExport {
+    pub export_name: String,
+    pub function_name: String,
+    pub directive: ExportDirective,
+    pub parameters: Vec<GoParameter>,
+    pub results: Vec<String>,
+    pub doc_comment: Vec<String>,
+    pub source_file: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoParameter {
+    pub name: String,
+    pub go_type: String,
+}
+
+pub struct ExportScanner;
+
+impl ExportScanner {
+    /// Scans the non-test `.go` files of the package in `package_directory`.
+    pub fn scan_package(package_directory: &Path) -> Vec<GoExport> {
+        let mut source_files: Vec<_> = fs::read_dir(package_directory)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                        file_name.ends_with(".go") && !file_name.ends_with("_test.go")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        source_files.sort();
+
+        source_files
+            .iter()
+            .filter_map(|path| {
+                let source = fs::read_to_string(path).ok()?;
+                Some(Self::scan_source(
+                    &source,
+                    &path.file_name()?.to_string_lossy(),
+                ))
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn scan_source(source: &str, source_file: &str) -> Vec<GoExport> {
+        let mut exports = Vec::new();
+        let mut doc_comment = Vec::new();
+        let mut pending_export: Option<(String, ExportDirective)> = None;
+
+        for line in source.lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix("//go:wasmexport ") {
+                pending_export = Some((name.trim().to_string(), ExportDirective::WasmExport));
+            } else if let Some(name) = line.strip_prefix("//export ") {
+                pending_export = Some((name.trim().to_string(), ExportDirective::Export));
+            } else if let Some(comment) = line.strip_prefix("//") {
+                if !comment.starts_with("go:") {
+                    doc_comment.push(comment.trim().to_string());
+                }
+            } else if line.starts_with("func ") {
+                if let Some((export_name, directive)) = pending_export.take() {
+                    if let Some((function_name, parameters, results)) = Self::parse_signature(line)
+                    {
+                        exports.push(GoExport {
+                            export_name,
+                            function_name,
+                            directive,
+                            parameters,
+                            results,
+                            doc_comment: std::mem::take(&mut doc_comment),
+                            source_file: source_file.to_string(),
+                        });
+                    }
+                }
+                doc_comment.clear();
+            } else {
+                pending_export = None;
+                doc_comment.clear();
+            }
+        }
+
+        exports
+    }
+
+    pub fn has_wasmexport(exports: &[GoExport]) -> bool {
+        exports
+            .iter()
+            .any(|export| export.directive == ExportDirective::WasmExport)
+    }
+
+    // Parses `func name(a, b int32, s string) (int32, error) {`
+    fn parse_signature(line: &str) -> Option<(String, Vec<GoParameter>, Vec<String>)> {
+        let signature = line.strip_prefix("func ")?.trim();
+        let open_paren = signature.find('(')?;
+        let function_name = signature[..open_paren].trim().to_string();
+        if function_name.is_empty() {
+            return None;
+        }
+
+        let close_paren = Self::matching_paren(signature, open_paren)?;
+        let parameters = Self::parse_parameters(&signature[open_paren + 1..close_paren]);
+
+        let result_list = signature[close_paren + 1..]
+            .split('{')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let results = if result_list.is_empty() {
+            vec![]
+        } else if let Some(inner) = result_list
+            .strip_prefix('(')
+            .and_then(|r| r.strip_suffix(')'))
+        {
+            Self::parse_parameters(inner)
+                .into_iter()
+                .map(|parameter| parameter.go_type)
+                .collect()
+        } else {
+            vec![result_list.to_string()]
+        };
+
+        Some((function_name, parameters, results))
+    }
+
+    fn matching_paren(text: &str, open_index: usize) -> Option<usize> {
+        let mut depth = 0;
+        for (index, c) in text.char_indices().skip(open_index) {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // Handles grouped names (`a, b int32`) and unnamed lists (`int32, string`).
+    fn parse_parameters(list: &str) -> Vec<GoParameter> {
+        let fields: Vec<&str> = list
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .collect();
+
+        let all_unnamed = fields
+            .iter()
+            .all(|field| field.split_whitespace().count() == 1);
+        if all_unnamed {
+            return fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| GoParameter {
+                    name: format!("arg{index}"),
+                    go_type: field.to_string(),
+                })
+                .collect();
+        }
+
+        let mut parameters = Vec::new();
+        let mut ungrouped_names = Vec::new();
+        for field in fields {
+            match field.split_once(char::is_whitespace) {
+                Some((name, go_type)) => {
+                    let go_type = go_type.trim().to_string();
+                    for name in ungrouped_names.drain(..) {
+                        parameters.push(GoParameter {
+                            name,
+                            go_type: go_type.clone(),
+                        });
+                    }
+                    parameters.push(GoParameter {
+                        name: name.to_string(),
+                        go_type,
+                    });
+                }
+                None => ungrouped_names.push(field.to_string()),
+            }
+        }
+        parameters
+    }
+}
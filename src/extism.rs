@@ -0,0 +1,72 @@
+use crate::{ExternalKind, GoExport, PluginError, PluginResult, WasmBinary, WasmKind};
+use std::path::Path;
+
+/// Host module the Extism Go PDK imports its kernel functions from.
+pub const EXTISM_HOST_MODULE: &str = "extism:host/env";
+
+/// Artifact name Extism hosts and the `extism` CLI expect.
+pub const EXTISM_PLUGIN_FILENAME: &str = "plugin.wasm";
+
+/// Checks that a module follows the Extism plugin ABI.
+pub struct ExtismValidator;
+
+impl ExtismValidator {
+    /// Returns the plugin functions: exports taking no parameters and returning
+    /// an `i32` status, with input and output passed through the PDK.
+    pub fn validate(
+        wasm_file_path: &Path,
+        declared_exports: &[GoExport],
+    ) -> PluginResult<Vec<String>> {
+        let binary = WasmBinary::read(wasm_file_path)?;
+        let invalid = |reason: String| PluginError::InvalidWasm {
+            path: wasm_file_path.to_string_lossy().to_string(),
+            reason,
+        };
+
+        if binary.kind != WasmKind::CoreModule {
+            return Err(invalid(
+                "Extism plugins are core modules, not components".to_string(),
+            ));
+        }
+        if !binary.imported_modules().contains(&EXTISM_HOST_MODULE) {
+            return Err(invalid(format!(
+                "no imports from {EXTISM_HOST_MODULE}; build the plugin with github.com/extism/go-pdk"
+            )));
+        }
+
+        let plugin_functions: Vec<String> = binary
+            .exports
+            .iter()
+            .filter(|export| {
+                export.kind == ExternalKind::Function
+                    && !export.name.starts_with('_')
+                    && binary
+                        .function_type(export.index)
+                        .is_some_and(|function_type| {
+                            function_type.params.is_empty() && function_type.results == ["i32"]
+                        })
+            })
+            .map(|export| export.name.clone())
+            .collect();
+
+        let missing: Vec<&str> = declared_exports
+            .iter()
+            .map(|export| export.export_name.as_str())
+            .filter(|name| !plugin_functions.iter().any(|function| function == name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(invalid(format!(
+                "exports {} are not plugin functions; they must have the signature `func() int32`",
+                missing.join(", ")
+            )));
+        }
+        if plugin_functions.is_empty() {
+            return Err(invalid(
+                "no plugin functions exported; mark `func() int32` functions with //go:wasmexport"
+                    .to_string(),
+            ));
+        }
+
+        Ok(plugin_functions)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{ShellStyle, ToolCommand};
+use std::fmt;
+use std::process::Output;
+
+/// Compiler lines kept in a [`FailureSummary`].
+pub const FAILURE_CONTEXT_LINES: usize = 20;
+
+/// The useful part of a failed compiler run: its last distinct output lines,
+/// the first error, and the command that reproduces the failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureSummary {
+    pub status: String,
+    /// The first line that looks like a compiler error, if any
+    pub first_error: Option<String>,
+    /// The last [`FAILURE_CONTEXT_LINES`] distinct non-empty lines
+    pub lines: Vec<String>,
+    /// Distinct lines left out of [`Self::lines`]
+    pub omitted: usize,
+    /// Shell command that reruns the build from anywhere
+    pub reproduce: String,
+}
+
+impl FailureSummary {
+    pub fn new(output: &Output, command: &ToolCommand) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut distinct: Vec<&str> = Vec::new();
+        for line in stderr.lines().chain(stdout.lines()) {
+            let line = line.trim_end();
+            if !line.trim().is_empty() && !distinct.contains(&line) {
+                distinct.push(line);
+            }
+        }
+
+        let first_error = distinct
+            .iter()
+            .find(|line| Self::is_error(line))
+            .map(|line| line.trim().to_string());
+        let omitted = distinct.len().saturating_sub(FAILURE_CONTEXT_LINES);
+        let command_name = command.program().to_string_lossy();
+        let status = match output.status.code() {
+            Some(code) => format!("{command_name} exited with status {code}"),
+            None => format!("{command_name} was terminated by a signal"),
+        };
+
+        Self {
+            status,
+            first_error,
+            lines: distinct[omitted..]
+                .iter()
+                .map(|line| line.to_string())
+                .collect(),
+            omitted,
+            reproduce: command.reproduce_line(ShellStyle::host()),
+        }
+    }
+
+    /// A compiler error the build cannot recover from: `file.go:12:5: message`
+    /// or `error: message`. Go's `go: ...` lines are left out, since most of
+    /// them (`go: downloading ...`) are progress.
+    pub fn is_fatal(line: &str) -> bool {
+        let line = line.trim();
+        if line.starts_with("error:") {
+            return true;
+        }
+        let mut parts = line.splitn(3, ':');
+        matches!(
+            (parts.next(), parts.next()),
+            (Some(file), Some(number))
+                if file.ends_with(".go") && number.parse::<u32>().is_ok()
+        ) && !line.contains(": warning:")
+    }
+
+    /// [`Self::is_fatal`] lines and Go's `go: ...` failures.
+    fn is_error(line: &str) -> bool {
+        Self::is_fatal(line) || line.trim().starts_with("go: ")
+    }
+}
+
+impl fmt::Display for FailureSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(first_error) = &self.first_error {
+            write!(f, "\n   👉 {first_error}")?;
+        }
+        if self.omitted > 0 {
+            write!(f, "\n   … {} earlier lines omitted", self.omitted)?;
+        }
+        for line in &self.lines {
+            write!(f, "\n   │ {line}")?;
+        }
+        write!(f, "\n   Reproduce with: {}", self.reproduce)
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{CommandExecutor, Dirs, GoVersion, PluginError, PluginResult, TargetType, Toolchain};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User additions to the registry, in [`Dirs::config`].
+pub const FRAMEWORKS_FILENAME: &str = "frameworks.toml";
+
+const BUILTIN_FRAMEWORKS: &str = include_str!("templates/frameworks.toml");
+
+// go directive of scaffolded modules when no Go is installed
+const DEFAULT_GO_DIRECTIVE: &str = "1.22";
+
+/// A framework or project type: how it is recognized, what it needs and how
+/// it is built.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Framework {
+    /// Identifier used by `wasmgo new --framework`
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub detect: FrameworkDetection,
+    /// Commands that must be on `PATH`
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<TargetType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+    /// How to build it, e.g. `wasmgo compile --target extism`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<String>,
+    /// go.mod requirements of new projects (`module version`)
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Files `wasmgo new` writes, by path relative to the project
+    #[serde(default)]
+    pub template: BTreeMap<String, String>,
+}
+
+/// Marks of a framework in a project; either kind matching is enough. A
+/// framework with neither is only chosen explicitly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrameworkDetection {
+    /// Module path prefixes required in go.mod
+    pub modules: Vec<String>,
+    /// Files or directories in the project
+    pub files: Vec<String>,
+}
+
+impl Framework {
+    /// The detection rule that matched, if any, for display.
+    pub fn detected_by(&self, project_path: &Path, go_mod: &str) -> Option<String> {
+        self.detect
+            .modules
+            .iter()
+            .find(|module| go_mod.contains(module.as_str()))
+            .or_else(|| {
+                self.detect
+                    .files
+                    .iter()
+                    .find(|file| project_path.join(file).exists())
+            })
+            .cloned()
+    }
+
+    pub fn missing_tools(&self) -> Vec<&str> {
+        self.tools
+            .iter()
+            .map(String::as_str)
+            .filter(|tool| !CommandExecutor::is_tool_installed(tool))
+            .collect()
+    }
+
+    /// Writes a new module at `directory` from the template: go.mod plus the
+    /// template files. Returns the files written.
+    pub fn scaffold(&self, directory: &Path, module_path: &str) -> PluginResult<Vec<PathBuf>> {
+        if self.template.is_empty() {
+            return Err(PluginError::InvalidConfiguration {
+                reason: format!("framework {} has no project template", self.name),
+            });
+        }
+        if fs::read_dir(directory).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: format!("{} already exists and is not empty", directory.display()),
+            });
+        }
+        let name = directory
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let fill = |template: &str| {
+            template
+                .replace("{{NAME}}", &name)
+                .replace("{{MODULE}}", module_path)
+        };
+
+        let go_directive = GoVersion::installed()
+            .map(|version| format!("{}.{}", version.major, version.minor))
+            .unwrap_or_else(|| DEFAULT_GO_DIRECTIVE.to_string());
+        let mut go_mod = format!("module {module_path}\n\ngo {go_directive}\n");
+        if !self.requires.is_empty() {
+            go_mod.push_str("\nrequire (\n");
+            for requirement in &self.requires {
+                go_mod.push_str(&format!("\t{requirement}\n"));
+            }
+            go_mod.push_str(")\n");
+        }
+
+        let mut written = Vec::new();
+        for (relative_path, contents) in std::iter::once(("go.mod", go_mod)).chain(
+            self.template
+                .iter()
+                .map(|(relative_path, template)| (relative_path.as_str(), fill(template))),
+        ) {
+            // Templates only write inside the new project
+            if !Path::new(relative_path)
+                .components()
+                .all(|component| matches!(component, std::path::Component::Normal(_)))
+            {
+                return Err(PluginError::InvalidConfiguration {
+                    reason: format!("template path {relative_path} leaves the project"),
+                });
+            }
+            let path = directory.join(relative_path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(PluginError::Io)?;
+            }
+            fs::write(&path, contents).map_err(PluginError::Io)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// The frameworks wasmgo knows about: the built-in list plus the user's
+/// [`FRAMEWORKS_FILENAME`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FrameworkRegistry {
+    #[serde(default, rename = "framework")]
+    pub frameworks: Vec<Framework>,
+}
+
+impl FrameworkRegistry {
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_FRAMEWORKS, "built-in frameworks")
+            .expect("Invalid built-in templates/frameworks.toml")
+    }
+
+    /// The built-in registry with the user's file merged in.
+    pub fn load() -> PluginResult<Self> {
+        let mut registry = Self::builtin();
+        if let Some(path) = Self::user_path() {
+            match fs::read_to_string(&path) {
+                Ok(contents) => {
+                    registry.merge(Self::parse(&contents, &path.display().to_string())?)
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(PluginError::Io(e)),
+            }
+        }
+        Ok(registry)
+    }
+
+    pub fn user_path() -> Option<PathBuf> {
+        Dirs::discover().map(|dirs| dirs.config.join(FRAMEWORKS_FILENAME))
+    }
+
+    pub fn parse(contents: &str, origin: &str) -> PluginResult<Self> {
+        toml::from_str(contents).map_err(|e| PluginError::InvalidConfiguration {
+            reason: format!("{origin}: {e}"),
+        })
+    }
+
+    /// Entries of `other` replace those with the same name; the rest are appended.
+    pub fn merge(&mut self, other: Self) {
+        for framework in other.frameworks {
+            match self
+                .frameworks
+                .iter_mut()
+                .find(|existing| existing.name == framework.name)
+            {
+                Some(existing) => *existing = framework,
+                None => self.frameworks.push(framework),
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Framework> {
+        self.frameworks
+            .iter()
+            .find(|framework| framework.name == name)
+    }
+
+    /// Frameworks the project uses, with the rule that matched each.
+    pub fn detect(&self, project_path: &Path) -> Vec<(&Framework, String)> {
+        let go_mod = fs::read_to_string(project_path.join("go.mod")).unwrap_or_default();
+        self.frameworks
+            .iter()
+            .filter_map(|framework| {
+                framework
+                    .detected_by(project_path, &go_mod)
+                    .map(|rule| (framework, rule))
+            })
+            .collect()
+    }
+}
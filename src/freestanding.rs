@@ -0,0 +1,134 @@
+use crate::{PluginError, PluginResult, WasmBinary, WasmKind};
+use std::fs;
+use std::path::Path;
+
+/// Standard library packages that need an operating system or a JS host,
+/// which freestanding modules don't have.
+pub const HOST_PACKAGES: &[&str] = &[
+    "log/syslog",
+    "net",
+    "net/http",
+    "net/rpc",
+    "net/smtp",
+    "os",
+    "os/exec",
+    "os/signal",
+    "os/user",
+    "plugin",
+    "syscall",
+    "syscall/js",
+];
+
+/// Import modules of the host ABIs other targets build against.
+const HOST_ABI_MODULES: &[&str] = &["wasi_snapshot_preview1", "wasi_unstable", "gojs", "go"];
+
+/// Checks `wasm-unknown` builds: freestanding modules that import nothing a
+/// JS or WASI host provides, only the functions of the custom host embedding
+/// them (`//go:wasmimport`).
+pub struct FreestandingValidator;
+
+impl FreestandingValidator {
+    /// `(source file, package)` for every import of a [`HOST_PACKAGES`]
+    /// package in the non-test `.go` files of `package_directory`.
+    pub fn host_imports(package_directory: &Path) -> Vec<(String, String)> {
+        let mut source_files: Vec<_> = fs::read_dir(package_directory)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                        file_name.ends_with(".go") && !file_name.ends_with("_test.go")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        source_files.sort();
+
+        let mut host_imports = Vec::new();
+        for path in source_files {
+            let Ok(source) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let source_file = path.file_name().unwrap_or_default().to_string_lossy();
+            for package in Self::imported_packages(&source) {
+                if HOST_PACKAGES.contains(&package.as_str()) {
+                    host_imports.push((source_file.to_string(), package));
+                }
+            }
+        }
+        host_imports
+    }
+
+    /// Packages imported by a Go source file, single imports and blocks,
+    /// with or without a name.
+    pub fn imported_packages(source: &str) -> Vec<String> {
+        let quoted = |line: &str| {
+            let (_, rest) = line.split_once('"')?;
+            let (package, _) = rest.split_once('"')?;
+            Some(package.to_string())
+        };
+        let mut packages = Vec::new();
+        let mut in_block = false;
+        for line in source.lines().map(str::trim) {
+            if in_block {
+                if line.starts_with(')') {
+                    in_block = false;
+                } else if !line.starts_with("//") {
+                    packages.extend(quoted(line));
+                }
+            } else if let Some(rest) = line.strip_prefix("import") {
+                let rest = rest.trim_start();
+                if let Some(block) = rest.strip_prefix('(') {
+                    packages.extend(quoted(block));
+                    in_block = !block.contains(')');
+                } else {
+                    packages.extend(quoted(rest));
+                }
+            } else if ["func ", "var ", "const ", "type "]
+                .iter()
+                .any(|keyword| line.starts_with(keyword))
+            {
+                // Imports precede all declarations
+                break;
+            }
+        }
+        packages
+    }
+
+    /// Returns the custom host functions the module imports, as
+    /// `module.name`.
+    pub fn validate(wasm_file_path: &Path) -> PluginResult<Vec<String>> {
+        let binary = WasmBinary::read(wasm_file_path)?;
+        let invalid = |reason: String| PluginError::InvalidWasm {
+            path: wasm_file_path.to_string_lossy().to_string(),
+            reason,
+        };
+
+        if binary.kind != WasmKind::CoreModule {
+            return Err(invalid(
+                "freestanding modules are core modules, not components".to_string(),
+            ));
+        }
+        let host_abi: Vec<String> = binary
+            .imports
+            .iter()
+            .filter(|import| {
+                HOST_ABI_MODULES.contains(&import.module.as_str())
+                    || import.module.starts_with("wasi:")
+            })
+            .map(|import| format!("{}.{}", import.module, import.name))
+            .collect();
+        if !host_abi.is_empty() {
+            return Err(invalid(format!(
+                "imports {} from a JS or WASI host; wasm-unknown modules can only import their embedder's functions",
+                host_abi.join(", ")
+            )));
+        }
+        Ok(binary
+            .imports
+            .iter()
+            .map(|import| format!("{}.{}", import.module, import.name))
+            .collect())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{CommandExecutor, CompileConfig};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One of Go's caches as a build would use it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GoCacheUsage {
+    /// `GOCACHE` or `GOMODCACHE`
+    pub variable: &'static str,
+    pub path: PathBuf,
+    /// Set by the `gocache`/`gomodcache` settings rather than Go's defaults
+    pub configured: bool,
+    pub exists: bool,
+    pub size: u64,
+    pub files: u64,
+}
+
+/// Where builds keep Go's build and module caches and how much they hold.
+///
+/// Go records no hit or miss counts, so sizes are all that can be reported
+/// about how well a persisted cache is working.
+pub struct GoCaches;
+
+impl GoCaches {
+    pub fn inspect(configuration: &CompileConfig) -> Vec<GoCacheUsage> {
+        let environment = configuration.build_environment();
+        let go_env = CommandExecutor::is_tool_installed("go")
+            .then(|| {
+                CommandExecutor::execute_command(
+                    "go",
+                    &["env", "GOCACHE", "GOMODCACHE"],
+                    &configuration.project_path,
+                    false,
+                )
+                .ok()
+            })
+            .flatten()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default();
+        let mut defaults = go_env.lines();
+
+        ["GOCACHE", "GOMODCACHE"]
+            .into_iter()
+            .filter_map(|variable| {
+                let default = defaults.next().filter(|line| !line.is_empty());
+                let configured = environment
+                    .iter()
+                    .find(|(key, _)| *key == variable)
+                    .map(|(_, value)| value.as_str());
+                let path = PathBuf::from(configured.or(default)?);
+                let (size, files) = Self::measure(&path);
+                Some(GoCacheUsage {
+                    variable,
+                    configured: configured.is_some(),
+                    exists: path.is_dir(),
+                    size,
+                    files,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// Bytes and files under `directory`, not following symlinks.
+    fn measure(directory: &Path) -> (u64, u64) {
+        let mut totals = (0, 0);
+        let mut pending = vec![directory.to_path_buf()];
+        while let Some(directory) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&directory) else {
+                continue;
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    pending.push(entry.path());
+                } else if metadata.is_file() {
+                    totals.0 += metadata.len();
+                    totals.1 += 1;
+                }
+            }
+        }
+        totals
+    }
+}
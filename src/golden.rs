@@ -0,0 +1,98 @@
+use crate::{PluginError, PluginResult};
+use std::fs;
+use std::path::Path;
+
+/// Result of comparing a run's output with its golden file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoldenOutcome {
+    Matched,
+    /// The golden file was (re)written with the actual output
+    Updated,
+    /// Line diff from the expected (`-`) to the actual (`+`) output
+    Mismatch {
+        diff: String,
+    },
+}
+
+/// Snapshot comparison of captured program output against a committed file.
+pub struct GoldenFile;
+
+impl GoldenFile {
+    /// Compares `actual` with the file at `path`, or writes it there with `update`.
+    /// Line endings are normalized so files checked out on Windows still match.
+    pub fn check(path: &Path, actual: &str, update: bool) -> PluginResult<GoldenOutcome> {
+        let actual = actual.replace("\r\n", "\n");
+        if update {
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                fs::create_dir_all(parent).map_err(PluginError::Io)?;
+            }
+            fs::write(path, &actual).map_err(PluginError::Io)?;
+            return Ok(GoldenOutcome::Updated);
+        }
+
+        let expected = match fs::read_to_string(path) {
+            Ok(expected) => expected.replace("\r\n", "\n"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(PluginError::ExecutionFailed {
+                    reason: format!(
+                        "golden file {} does not exist; run with --update to create it",
+                        path.display()
+                    ),
+                })
+            }
+            Err(e) => return Err(PluginError::Io(e)),
+        };
+        if expected == actual {
+            Ok(GoldenOutcome::Matched)
+        } else {
+            Ok(GoldenOutcome::Mismatch {
+                diff: Self::diff(&expected, &actual),
+            })
+        }
+    }
+
+    /// Line diff over the longest common subsequence; unchanged lines are
+    /// prefixed with two spaces.
+    pub fn diff(expected: &str, actual: &str) -> String {
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+
+        // common[i][j]: LCS length of expected[i..] and actual[j..]
+        let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+        for i in (0..expected.len()).rev() {
+            for j in (0..actual.len()).rev() {
+                common[i][j] = if expected[i] == actual[j] {
+                    common[i + 1][j + 1] + 1
+                } else {
+                    common[i + 1][j].max(common[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = String::new();
+        let (mut i, mut j) = (0, 0);
+        while i < expected.len() || j < actual.len() {
+            if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+                diff.push_str(&format!("  {}\n", expected[i]));
+                i += 1;
+                j += 1;
+            } else if i < expected.len()
+                && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+            {
+                diff.push_str(&format!("- {}\n", expected[i]));
+                i += 1;
+            } else {
+                diff.push_str(&format!("+ {}\n", actual[j]));
+                j += 1;
+            }
+        }
+        // Differences confined to the final newline don't show up as lines
+        if diff.lines().all(|line| line.starts_with("  ")) {
+            diff.push_str("(outputs differ only in the trailing newline)\n");
+        }
+        diff
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{CommandExecutor, FailureSummary, GoVersion, PluginError, PluginResult, ToolCommand};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What `go mod tidy` changed in the module files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TidyReport {
+    /// go.mod lines added, without indentation
+    pub go_mod_added: Vec<String>,
+    pub go_mod_removed: Vec<String>,
+    pub go_sum_added: usize,
+    pub go_sum_removed: usize,
+}
+
+impl TidyReport {
+    pub fn is_unchanged(&self) -> bool {
+        self.go_mod_added.is_empty()
+            && self.go_mod_removed.is_empty()
+            && self.go_sum_added == 0
+            && self.go_sum_removed == 0
+    }
+}
+
+/// The directives of a project's `go.mod` that affect how it is built.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoModFile {
+    /// Module path from the `module` directive
+    pub module: Option<String>,
+    /// Minimum Go version from the `go` directive
+    pub go: Option<GoVersion>,
+    /// Toolchain name from the `toolchain` directive, e.g. `go1.22.3`
+    pub toolchain: Option<String>,
+    /// Directories `replace` directives point at, as written (`../lib`)
+    pub local_replacements: Vec<PathBuf>,
+}
+
+/// The `go.work` a project builds in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoWorkFile {
+    pub path: PathBuf,
+    /// Module directories from `use` directives, relative to the go.work
+    pub uses: Vec<PathBuf>,
+    /// Directories `replace` directives point at, relative to the go.work
+    pub local_replacements: Vec<PathBuf>,
+}
+
+impl GoWorkFile {
+    /// The workspace `go` would use for the project: `GOWORK` if set (`off`
+    /// disables workspaces), else the nearest go.work above the project.
+    pub fn discover(project_path: &Path) -> Option<Self> {
+        let path = match std::env::var_os("GOWORK").filter(|gowork| !gowork.is_empty()) {
+            Some(gowork) if gowork == "off" => return None,
+            Some(gowork) => PathBuf::from(gowork),
+            None => fs::canonicalize(project_path)
+                .ok()?
+                .ancestors()
+                .map(|directory| directory.join("go.work"))
+                .find(|path| path.is_file())?,
+        };
+        let contents = fs::read_to_string(&path).ok()?;
+        let mut go_work = Self {
+            path,
+            ..Self::default()
+        };
+        for (directive, arguments) in directives(&contents) {
+            match directive.as_str() {
+                "use" => go_work.uses.push(PathBuf::from(unquote(&arguments))),
+                "replace" => go_work
+                    .local_replacements
+                    .extend(local_replacement(&arguments)),
+                _ => {}
+            }
+        }
+        Some(go_work)
+    }
+}
+
+/// `(directive, arguments)` for every line of a go.mod or go.work, with
+/// `directive ( ... )` blocks flattened into one entry per line.
+fn directives(contents: &str) -> Vec<(String, String)> {
+    let mut directives = Vec::new();
+    let mut block: Option<String> = None;
+    for line in contents.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(directive) = &block {
+            if line == ")" {
+                block = None;
+            } else {
+                directives.push((directive.clone(), line.to_string()));
+            }
+            continue;
+        }
+        let (directive, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let arguments = arguments.trim();
+        if arguments == "(" {
+            block = Some(directive.to_string());
+        } else {
+            directives.push((directive.to_string(), arguments.to_string()));
+        }
+    }
+    directives
+}
+
+/// The directory of `old [version] => new`, when `new` is a local path
+/// rather than a module.
+fn local_replacement(arguments: &str) -> Option<PathBuf> {
+    let (_, replacement) = arguments.split_once("=>")?;
+    let replacement = unquote(replacement.split_whitespace().next()?);
+    let is_local = replacement.starts_with("./")
+        || replacement.starts_with("../")
+        || Path::new(replacement).is_absolute();
+    is_local.then(|| PathBuf::from(replacement))
+}
+
+fn unquote(argument: &str) -> &str {
+    argument.trim().trim_matches(|c| c == '"' || c == '`')
+}
+
+impl GoModFile {
+    /// `go.mod` in the project directory; `None` when there is none.
+    pub fn read(project_path: &Path) -> Option<Self> {
+        fs::read_to_string(project_path.join("go.mod"))
+            .ok()
+            .map(|contents| Self::parse(&contents))
+    }
+
+    /// Reads the single-line directives and local `replace` targets,
+    /// skipping comments.
+    pub fn parse(contents: &str) -> Self {
+        let mut go_mod = Self::default();
+        for line in contents.lines() {
+            let line = line.split("//").next().unwrap_or_default().trim();
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("module"), Some(path)) => {
+                    go_mod.module = Some(path.trim_matches(|c| c == '"' || c == '`').to_string());
+                }
+                (Some("go"), Some(version)) => go_mod.go = GoVersion::parse(version),
+                (Some("toolchain"), Some(name)) => go_mod.toolchain = Some(name.to_string()),
+                _ => {}
+            }
+        }
+        go_mod.local_replacements = directives(contents)
+            .into_iter()
+            .filter(|(directive, _)| directive == "replace")
+            .filter_map(|(_, arguments)| local_replacement(&arguments))
+            .collect();
+        go_mod
+    }
+
+    /// Runs `go mod tidy` in the project and reports what it changed.
+    pub fn tidy(
+        project_path: &Path,
+        environment: &[(&str, &OsStr)],
+        verbose: bool,
+    ) -> PluginResult<TidyReport> {
+        if !CommandExecutor::is_tool_installed("go") {
+            return Err(PluginError::CompileToolNotFound {
+                tool: "go".to_string(),
+            });
+        }
+        let read = |name: &str| fs::read_to_string(project_path.join(name)).unwrap_or_default();
+        let (go_mod_before, go_sum_before) = (read("go.mod"), read("go.sum"));
+
+        let mut command = ToolCommand::new("go");
+        command
+            .args(["mod", "tidy"])
+            .current_dir(project_path)
+            .envs(environment.iter().copied());
+        let output = command.output(verbose)?;
+        if !output.status.success() {
+            return Err(PluginError::CompilationFailed {
+                reason: format!(
+                    "go mod tidy failed: {}",
+                    FailureSummary::new(&output, &command)
+                ),
+            });
+        }
+
+        let (go_mod_added, go_mod_removed) = Self::changed_lines(&go_mod_before, &read("go.mod"));
+        let (go_sum_added, go_sum_removed) = Self::changed_lines(&go_sum_before, &read("go.sum"));
+        Ok(TidyReport {
+            go_mod_added,
+            go_mod_removed,
+            go_sum_added: go_sum_added.len(),
+            go_sum_removed: go_sum_removed.len(),
+        })
+    }
+
+    /// Non-empty lines only in `after` and only in `before`, in file order.
+    fn changed_lines(before: &str, after: &str) -> (Vec<String>, Vec<String>) {
+        let lines = |contents: &str| -> Vec<String> {
+            contents
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        };
+        let only_in = |side: &[String], other: &[String]| {
+            let mut unmatched: HashMap<&str, usize> = HashMap::new();
+            for line in other {
+                *unmatched.entry(line).or_default() += 1;
+            }
+            side.iter()
+                .filter(|line| match unmatched.get_mut(line.as_str()) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let (before, after) = (lines(before), lines(after));
+        (only_in(&after, &before), only_in(&before, &after))
+    }
+
+    /// Whether the project has Go sources but no go.mod of its own, or of a
+    /// parent module or workspace, to build them in.
+    pub fn needs_init(project_path: &Path) -> bool {
+        let has_go_files = fs::read_dir(project_path).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().extension().is_some_and(|ext| ext == "go"))
+        });
+        let project_path = fs::canonicalize(project_path).unwrap_or_else(|_| project_path.into());
+        has_go_files
+            && !project_path.ancestors().any(|directory| {
+                directory.join("go.mod").is_file() || directory.join("go.work").is_file()
+            })
+    }
+
+    /// Module path to suggest for `go mod init`: the `origin` remote as
+    /// `host/owner/repo` plus the project's place in the repository, or else
+    /// the directory name.
+    pub fn suggested_module_path(project_path: &Path) -> String {
+        let git = |arguments: &[&str]| {
+            CommandExecutor::execute_command("git", arguments, project_path, false)
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        let repository = git(&["remote", "get-url", "origin"])
+            .and_then(|remote| Self::module_path_from_remote(&remote));
+        if let Some(repository) = repository {
+            let prefix = git(&["rev-parse", "--show-prefix"]).unwrap_or_default();
+            let prefix = prefix.trim_end_matches('/');
+            return if prefix.is_empty() {
+                repository
+            } else {
+                format!("{repository}/{prefix}")
+            };
+        }
+
+        let name = fs::canonicalize(project_path)
+            .ok()
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_default();
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let name = name.trim_matches(['-', '.']);
+        if name.is_empty() {
+            "example".to_string()
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// `https://github.com/owner/repo.git` and `git@github.com:owner/repo` both
+    /// become `github.com/owner/repo`.
+    fn module_path_from_remote(remote: &str) -> Option<String> {
+        let remote = remote.trim().trim_end_matches('/').trim_end_matches(".git");
+        let (location, scp_like) = match remote.split_once("://") {
+            Some((_, location)) => (location, false),
+            None => (remote, true),
+        };
+        let location = location.rsplit_once('@').map_or(location, |(_, host)| host);
+        let (host, path) = if scp_like {
+            location.split_once(':')?
+        } else {
+            location.split_once('/')?
+        };
+        // Drop a port
+        let host = host.split(':').next().unwrap_or(host).to_lowercase();
+        let path = path.trim_matches('/');
+        (!host.is_empty() && !path.is_empty()).then(|| format!("{host}/{path}"))
+    }
+
+    /// Runs `go mod init <module_path>` in the project.
+    pub fn init(project_path: &Path, module_path: &str, verbose: bool) -> PluginResult<()> {
+        if !CommandExecutor::is_tool_installed("go") {
+            return Err(PluginError::CompileToolNotFound {
+                tool: "go".to_string(),
+            });
+        }
+        let mut command = ToolCommand::new("go");
+        command
+            .args(["mod", "init", module_path])
+            .current_dir(project_path);
+        let output = command.output(verbose)?;
+        if !output.status.success() {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: format!(
+                    "go mod init failed: {}",
+                    FailureSummary::new(&output, &command)
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Version of the pinned toolchain; `None` for `toolchain default`.
+    pub fn toolchain_version(&self) -> Option<GoVersion> {
+        self.toolchain
+            .as_deref()
+            .filter(|name| name.starts_with("go"))
+            .and_then(GoVersion::parse)
+    }
+}
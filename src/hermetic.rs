@@ -0,0 +1,76 @@
+use crate::{
+    CompileConfig, InterruptHandler, PartialOutputGuard, PathResolver, PluginError, PluginResult,
+};
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Private Go state for one build: build cache, module cache and GOPATH under
+/// a single root, with the user's `go env -w` settings ignored. The build
+/// environment adds `-modcacherw` so the module cache can be removed.
+///
+/// Without a configured directory the root is a fresh private temp directory
+/// removed on drop, so every build starts cold.
+pub struct HermeticEnvironment {
+    root: PathBuf,
+    _temporary: Option<tempfile::TempDir>,
+    // Removes a temporary root if the build is interrupted
+    _interrupt_cleanup: Option<PartialOutputGuard>,
+    variables: Vec<(&'static str, OsString)>,
+}
+
+impl HermeticEnvironment {
+    /// `None` unless the configuration asks for a hermetic build.
+    pub fn for_config(configuration: &CompileConfig) -> PluginResult<Option<Self>> {
+        if !configuration.hermetic {
+            return Ok(None);
+        }
+        let (root, temporary) = match &configuration.hermetic_directory {
+            // Joining keeps an absolute directory as it is
+            Some(directory) => {
+                let root = configuration.project_path.join(directory);
+                fs::create_dir_all(&root).map_err(PluginError::Io)?;
+                (root, None)
+            }
+            None => {
+                let temporary =
+                    PathResolver::private_directory(&std::env::temp_dir(), "wasmgo-hermetic-")?;
+                (temporary.path().to_path_buf(), Some(temporary))
+            }
+        };
+        // Go resolves these relative to each subprocess's directory otherwise
+        let root = fs::canonicalize(&root).map_err(PluginError::Io)?;
+
+        let path_of = |name: &str| root.join(name).into_os_string();
+        let variables = vec![
+            ("GOCACHE", path_of("gocache")),
+            ("GOMODCACHE", path_of("gomodcache")),
+            ("GOPATH", path_of("gopath")),
+            // TinyGo keeps its compiled packages under the user cache directory
+            ("XDG_CACHE_HOME", path_of("cache")),
+            ("GOENV", OsString::from("off")),
+        ];
+        Ok(Some(Self {
+            _interrupt_cleanup: temporary
+                .is_some()
+                .then(|| InterruptHandler::remove_on_interrupt(&root)),
+            root,
+            _temporary: temporary,
+            variables,
+        }))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Adds the hermetic variables to a build environment, replacing any it already sets.
+    pub fn apply_to<'a>(&'a self, environment: &mut Vec<(&'a str, &'a OsStr)>) {
+        environment.retain(|(key, _)| self.variables.iter().all(|(variable, _)| variable != key));
+        environment.extend(
+            self.variables
+                .iter()
+                .map(|(key, value)| (*key, value.as_os_str())),
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{Logger, PluginError, PluginResult};
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Exit status after an interrupt, as shells report SIGINT.
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// How long interrupted process trees get to exit before they are killed.
+const TERMINATION_GRACE: Duration = Duration::from_secs(2);
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static TRACKED: Mutex<Tracked> = Mutex::new(Tracked {
+    processes: Vec::new(),
+    partial_outputs: Vec::new(),
+    next_id: 0,
+});
+
+struct Tracked {
+    /// Running children; those spawned by [`InterruptHandler::output`] lead their own process group
+    processes: Vec<(u32, bool)>,
+    partial_outputs: Vec<(usize, PathBuf)>,
+    next_id: usize,
+}
+
+fn tracked() -> std::sync::MutexGuard<'static, Tracked> {
+    TRACKED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Ctrl-C and termination handling for the CLI: stops every toolchain process
+/// tree still running, removes the outputs of unfinished builds and exits
+/// with [`INTERRUPTED_EXIT_CODE`].
+///
+/// Without [`Self::install`] children are spawned exactly as before, so
+/// embedders keep their own signal handling.
+pub struct InterruptHandler;
+
+impl InterruptHandler {
+    /// Installs the handler once for the whole process (SIGINT, SIGTERM and
+    /// SIGHUP on Unix, console control events on Windows).
+    pub fn install() -> PluginResult<()> {
+        if INSTALLED.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        platform::install().inspect_err(|_| INSTALLED.store(false, Ordering::SeqCst))
+    }
+
+    pub fn is_installed() -> bool {
+        INSTALLED.load(Ordering::SeqCst)
+    }
+
+    /// Like [`Command::output`], but the child is tracked while it runs. On Unix
+    /// it leads its own process group, so its whole tree can be stopped.
+    ///
+    /// Streams the caller leaves unset are inherited, so pipe the ones to capture.
+    pub fn output(command: &mut Command) -> PluginResult<Output> {
+        if !Self::is_installed() {
+            return command.output().map_err(PluginError::Io);
+        }
+        platform::own_process_group(command);
+        let child = Self::spawn(command, true)?;
+        let pid = child.id();
+        let output = child.wait_with_output();
+        Self::untrack(pid);
+        Self::park_if_interrupted();
+        output.map_err(PluginError::Io)
+    }
+
+    /// Captures the output like [`Self::output`], reading stderr line by line
+    /// and killing the child's tree at the first line `stop_at` accepts.
+    ///
+    /// Returns the output so far and the line that stopped the child, if any.
+    pub fn output_until(
+        command: &mut Command,
+        mut stop_at: impl FnMut(&str) -> bool,
+    ) -> PluginResult<(Output, Option<String>)> {
+        // Without the handler the child shares the terminal's group, so only it is killed
+        let process_group = Self::is_installed();
+        if process_group {
+            platform::own_process_group(command);
+        }
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = Self::spawn(command, process_group)?;
+        let pid = child.id();
+
+        let mut stdout = child.stdout.take();
+        let stdout_reader = std::thread::spawn(move || {
+            let mut contents = Vec::new();
+            if let Some(stdout) = &mut stdout {
+                let _ = stdout.read_to_end(&mut contents);
+            }
+            contents
+        });
+
+        let mut stderr = Vec::new();
+        let mut stopped_at = None;
+        if let Some(pipe) = child.stderr.take() {
+            let mut pipe = BufReader::new(pipe);
+            let mut line = Vec::new();
+            while stopped_at.is_none() && matches!(pipe.read_until(b'\n', &mut line), Ok(1..)) {
+                let text = String::from_utf8_lossy(&line);
+                if stop_at(text.trim_end()) {
+                    stopped_at = Some(text.trim_end().to_string());
+                    platform::kill(pid, process_group);
+                }
+                stderr.append(&mut line);
+            }
+        }
+
+        let status = child.wait();
+        Self::untrack(pid);
+        Self::park_if_interrupted();
+        let stdout = stdout_reader.join().unwrap_or_default();
+        Ok((
+            Output {
+                status: status.map_err(PluginError::Io)?,
+                stdout,
+                stderr,
+            },
+            stopped_at,
+        ))
+    }
+
+    /// Like [`Command::status`], for interactive children sharing the terminal;
+    /// they stay in this process's group and get Ctrl-C from the terminal too.
+    pub fn status(command: &mut Command) -> PluginResult<ExitStatus> {
+        if !Self::is_installed() {
+            return command.status().map_err(PluginError::Io);
+        }
+        let mut child = Self::spawn(command, false)?;
+        let pid = child.id();
+        let status = child.wait();
+        Self::untrack(pid);
+        Self::park_if_interrupted();
+        status.map_err(PluginError::Io)
+    }
+
+    /// Removes `path` (a file or directory) if the process is interrupted
+    /// before the returned guard is dropped.
+    pub fn remove_on_interrupt(path: impl Into<PathBuf>) -> PartialOutputGuard {
+        let mut tracked = tracked();
+        let id = tracked.next_id;
+        tracked.next_id += 1;
+        tracked.partial_outputs.push((id, path.into()));
+        PartialOutputGuard { id }
+    }
+
+    fn spawn(command: &mut Command, process_group: bool) -> PluginResult<Child> {
+        // Hold the lock so an interrupt cannot miss a child started meanwhile
+        let mut tracked = tracked();
+        let child = command.spawn().map_err(PluginError::Io)?;
+        tracked.processes.push((child.id(), process_group));
+        Ok(child)
+    }
+
+    fn untrack(pid: u32) {
+        tracked()
+            .processes
+            .retain(|(tracked_pid, _)| *tracked_pid != pid);
+    }
+
+    // A child stopped by the interrupt must not look like a failed build
+    // while the cleanup is still running; the handler exits the process.
+    fn park_if_interrupted() {
+        while INTERRUPTED.load(Ordering::SeqCst) {
+            std::thread::park();
+        }
+    }
+
+    /// Stops the tracked process trees and removes partial outputs, as an
+    /// interrupt would, without exiting. Returns how many of each.
+    pub fn stop_all() -> (usize, usize) {
+        let (processes, partial_outputs) = {
+            let mut tracked = tracked();
+            (
+                std::mem::take(&mut tracked.processes),
+                std::mem::take(&mut tracked.partial_outputs),
+            )
+        };
+
+        for &(pid, process_group) in &processes {
+            platform::terminate(pid, process_group);
+        }
+        let deadline = Instant::now() + TERMINATION_GRACE;
+        for &(pid, process_group) in &processes {
+            while platform::is_running(pid, process_group) && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            if platform::is_running(pid, process_group) {
+                platform::kill(pid, process_group);
+            }
+        }
+
+        let mut removed = 0;
+        for (_, path) in &partial_outputs {
+            let result = if path.is_dir() {
+                std::fs::remove_dir_all(path)
+            } else {
+                std::fs::remove_file(path)
+            };
+            removed += usize::from(result.is_ok());
+        }
+
+        (processes.len(), removed)
+    }
+
+    /// Stops the tracked process trees, removes partial outputs and exits.
+    fn interrupt() -> ! {
+        let (processes, removed) = Self::stop_all();
+        // Past the ^C the terminal echoed
+        if !Logger::has_sink() {
+            eprintln!();
+        }
+        Logger::error(format_args!(
+            "⏹️  Interrupted: stopped {processes} processes, removed {removed} partial outputs"
+        ));
+        std::process::exit(INTERRUPTED_EXIT_CODE)
+    }
+}
+
+/// Registration of [`InterruptHandler::remove_on_interrupt`]; dropping it keeps the path.
+pub struct PartialOutputGuard {
+    id: usize,
+}
+
+impl Drop for PartialOutputGuard {
+    fn drop(&mut self) {
+        tracked().partial_outputs.retain(|(id, _)| *id != self.id);
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::{InterruptHandler, INTERRUPTED, INTERRUPTED_EXIT_CODE};
+    use crate::{PluginError, PluginResult};
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+    // Only async-signal-safe calls here; the watcher thread does the cleanup
+    extern "C" fn on_signal(_: libc::c_int) {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            // A second Ctrl-C skips the cleanup
+            unsafe { libc::_exit(INTERRUPTED_EXIT_CODE) };
+        }
+        let byte = 1u8;
+        unsafe {
+            libc::write(
+                SIGNAL_PIPE.load(Ordering::SeqCst),
+                (&byte as *const u8).cast(),
+                1,
+            )
+        };
+    }
+
+    pub fn install() -> PluginResult<()> {
+        let mut descriptors = [0; 2];
+        if unsafe { libc::pipe(descriptors.as_mut_ptr()) } != 0 {
+            return Err(PluginError::Io(std::io::Error::last_os_error()));
+        }
+        // Kept out of the toolchain processes; `pipe2` is not available everywhere
+        for descriptor in descriptors {
+            if unsafe { libc::fcntl(descriptor, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+                return Err(PluginError::Io(std::io::Error::last_os_error()));
+            }
+        }
+        let [read_end, write_end] = descriptors;
+        SIGNAL_PIPE.store(write_end, Ordering::SeqCst);
+
+        std::thread::Builder::new()
+            .name("wasmgo-interrupt".to_string())
+            .spawn(move || loop {
+                let mut byte = 0u8;
+                let read = unsafe { libc::read(read_end, (&mut byte as *mut u8).cast(), 1) };
+                if read == 1 {
+                    InterruptHandler::interrupt();
+                }
+                if read == 0
+                    || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted
+                {
+                    return;
+                }
+            })
+            .map_err(PluginError::Io)?;
+
+        let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+            if unsafe { libc::signal(signal, handler) } == libc::SIG_ERR {
+                return Err(PluginError::Io(std::io::Error::last_os_error()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn own_process_group(command: &mut Command) {
+        command.process_group(0);
+    }
+
+    fn signal(pid: u32, process_group: bool, signal: libc::c_int) -> bool {
+        let pid = pid as libc::pid_t;
+        let target = if process_group { -pid } else { pid };
+        unsafe { libc::kill(target, signal) == 0 }
+    }
+
+    pub fn terminate(pid: u32, process_group: bool) {
+        signal(pid, process_group, libc::SIGTERM);
+    }
+
+    pub fn kill(pid: u32, process_group: bool) {
+        signal(pid, process_group, libc::SIGKILL);
+    }
+
+    pub fn is_running(pid: u32, process_group: bool) -> bool {
+        signal(pid, process_group, 0)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{InterruptHandler, INTERRUPTED, INTERRUPTED_EXIT_CODE};
+    use crate::{PluginError, PluginResult};
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::Ordering;
+
+    type ControlHandler = unsafe extern "system" fn(u32) -> i32;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetConsoleCtrlHandler(handler: Option<ControlHandler>, add: i32) -> i32;
+    }
+
+    // Console control handlers already run on their own thread
+    unsafe extern "system" fn on_control_event(_: u32) -> i32 {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        }
+        InterruptHandler::interrupt()
+    }
+
+    pub fn install() -> PluginResult<()> {
+        if unsafe { SetConsoleCtrlHandler(Some(on_control_event), 1) } == 0 {
+            return Err(PluginError::Io(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    pub fn own_process_group(_command: &mut Command) {}
+
+    fn taskkill(pid: u32, force: bool) -> bool {
+        let pid = pid.to_string();
+        let mut arguments = vec!["/T", "/PID", pid.as_str()];
+        if force {
+            arguments.push("/F");
+        }
+        Command::new("taskkill")
+            .args(arguments)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    pub fn terminate(pid: u32, _process_group: bool) {
+        taskkill(pid, false);
+    }
+
+    pub fn kill(pid: u32, _process_group: bool) {
+        taskkill(pid, true);
+    }
+
+    pub fn is_running(pid: u32, _process_group: bool) -> bool {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+    }
+}
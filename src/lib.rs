@@ -1,19 +1,179 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
 use std::fs;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::Output;
 use thiserror::Error;
 
+mod analyze;
+mod bugreport;
 mod builder;
-
+mod bundle;
+mod cache;
+#[cfg(feature = "cli")]
+mod cli;
+mod command;
+mod compare;
+mod compile;
+mod component;
+mod config;
+mod copy;
+mod deploy;
+mod dirs;
+mod docgen;
+mod embed;
+mod examples;
+mod exports;
+mod extism;
+mod failure;
+mod frameworks;
+mod freestanding;
+mod gocache;
+mod golden;
+mod gomod;
+mod hermetic;
+mod interrupt;
+mod llvm;
+mod loader;
+mod log;
+mod manifest;
+mod messages;
+mod metrics;
+mod minify;
+mod mirror;
+mod notify;
+mod oci;
+mod optimize;
+mod pipeline;
+mod platform;
+mod playground;
+mod provenance;
+mod prune;
+mod queue;
+mod repro;
+mod reproducible;
+mod retention;
+mod runtime;
+mod selftest;
+mod serve;
+mod smoke;
+mod stamp;
+mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod threads;
+mod toolchain;
+mod tools;
+mod upgrade;
+mod wapc;
+mod warnings;
+mod wasm;
+mod watch;
+mod wit;
+mod worker;
+
+pub use analyze::{
+    CategoryTotal, EmbeddedString, SizeAnalysis, SizeAnalyzer, StringCategory,
+    DEFAULT_MIN_STRING_LENGTH, EMBED_WARNING_SHARE,
+};
+pub use bugreport::{BugReport, LAST_BUILD_LOG_FILENAME};
 pub use builder::GoBuilder;
 pub use builder::GoPlugin as WasmGoPlugin;
+pub use bundle::{BundledFile, DeployBundle, DeployBundler, INTEGRITY_FILENAME};
+pub use cache::{BuildCache, RemoteCache, REMOTE_CACHE_TOKEN_VARIABLE};
+#[cfg(feature = "cli")]
+pub use cli::run_cli;
+pub use command::{CommandLog, ShellStyle, ToolCommand};
+pub use compare::{BuildComparison, ComparisonEntry};
+pub use compile::{compile, BuildReport, CompileOptions};
+pub use component::{ComponentTools, ComponentWorld, WASI_HTTP_WORLD};
+pub use config::{
+    BuildSettings, ConfigEditor, DetectSettings, ProjectConfig, ResolvedConfig, ResolvedSetting,
+    GLOBAL_CONFIG_FILENAME, PROFILE_VARIABLE, PROJECT_CONFIG_FILENAME,
+};
+pub use copy::{ArtifactCopier, CopyMethod};
+pub use deploy::GhPagesDeployer;
+pub use dirs::{Dirs, StateDirectory, HOME_VARIABLE};
+pub use docgen::{ApiDocGenerator, ExportedFunctionDoc};
+pub use embed::{EmbedScanner, EmbeddedAsset};
+pub use examples::{Example, Examples, EXAMPLES_DIRECTORY};
+pub use exports::{ExportDirective, ExportScanner, GoExport, GoParameter};
+pub use extism::{ExtismValidator, EXTISM_HOST_MODULE, EXTISM_PLUGIN_FILENAME};
+pub use failure::{FailureSummary, FAILURE_CONTEXT_LINES};
+pub use frameworks::{Framework, FrameworkDetection, FrameworkRegistry, FRAMEWORKS_FILENAME};
+pub use freestanding::{FreestandingValidator, HOST_PACKAGES};
+pub use gocache::{GoCacheUsage, GoCaches};
+pub use golden::{GoldenFile, GoldenOutcome};
+pub use gomod::{GoModFile, GoWorkFile, TidyReport};
+pub use hermetic::HermeticEnvironment;
+pub use interrupt::{InterruptHandler, PartialOutputGuard, INTERRUPTED_EXIT_CODE};
+pub use llvm::{LlvmFeature, LlvmTuning, WASM_LLVM_CPUS, WASM_LLVM_FEATURES};
+pub use loader::LoaderGenerator;
+pub use log::{LogLevel, LogSink, Logger};
+pub use manifest::{
+    BuildLayout, BuildManifest, ProfileArtifacts, ToolchainEnvironment, LAYOUT_FILENAME,
+    MANIFEST_FILENAME,
+};
+pub use messages::{Messages, DEFAULT_LOCALE, LANGUAGE_VARIABLE};
+pub use metrics::{BuildMetric, BuildMetrics, MetricsSummary, METRICS_FILENAME};
+pub use minify::{MinifiedScript, Minifier, MinifyReport};
+pub use mirror::{MirrorReport, SourceMirror};
+pub use notify::{
+    UpdateNotice, UpdateNotifier, NO_UPDATE_CHECK_VARIABLE, REFRESH_SUBCOMMAND,
+    UPDATE_CHECK_INTERVAL,
+};
+pub use oci::{
+    OciPublisher, BUILD_MANIFEST_MEDIA_TYPE, WASM_CONFIG_MEDIA_TYPE, WASM_LAYER_MEDIA_TYPE,
+};
+pub use optimize::{OptimizationPipeline, OptimizationReport, OptimizationStep, WASM_OPT_LEVELS};
+pub use pipeline::{BuildPipeline, PipelinePhase, PipelineStep};
+pub use platform::PlatformCondition;
+pub use playground::Playground;
+pub use provenance::{
+    BuildDefinition, BuildParameters, BuilderIdentity, ProvenanceGenerator, ProvenanceStatement,
+    ResourceDescriptor, RunDetails, SlsaProvenance, IN_TOTO_STATEMENT_TYPE,
+    SLSA_PROVENANCE_PREDICATE_TYPE,
+};
+pub use prune::{PrunePolicy, StateEntry, StatePruner, StateUsage};
+pub use queue::BuildQueue;
+pub use repro::{ReproductionScript, POWERSHELL_SCRIPT_FILENAME, SHELL_SCRIPT_FILENAME};
+pub use reproducible::{
+    ArtifactDifference, ReproducibilityCheck, ReproducibilityReport, SectionDifference,
+};
+pub use retention::{HashedArtifacts, CONTENT_HASH_LENGTH};
+pub use runtime::{ModuleRunner, PreopenedDir, WasiRunConfig, WasmRuntime};
+pub use selftest::{SelfTest, SelfTestEntry, SELFTEST_GREETING};
+pub use serve::StaticServer;
+pub use smoke::{SmokeTest, SmokeTestReport};
+pub use stamp::{VersionStamp, DEFAULT_VERSION_VARIABLE};
+pub use telemetry::{BuildSpan, OTLP_ENDPOINT_VARIABLE, OTLP_TRACES_ENDPOINT_VARIABLE};
+pub use threads::{ThreadsTarget, THREADS_LLVM_FEATURES, THREADS_MAXIMUM_MEMORY};
+pub use toolchain::{GoVersion, Toolchain, ToolchainTarget};
+pub use tools::{ToolProbe, ToolRegistry};
+pub use upgrade::{Release, ReleaseSource, SelfUpdater, UpgradeMethod, RELEASES_REPOSITORY};
+pub use wapc::{WapcValidator, WAPC_GUEST_CALL, WAPC_HOST_MODULE};
+pub use warnings::{BuildWarning, WarningKind};
+pub use wasm::{
+    DataSegment, ExternalKind, FunctionType, MemoryLimits, WasmBinary, WasmExport, WasmImport,
+    WasmKind, WasmSection,
+};
+pub use watch::{
+    PostBuildCommand, ProcessPriority, SourceWatcher, WatchEvent, ARTIFACT_PLACEHOLDER,
+    WATCH_INTERVAL,
+};
+pub use wit::WitGenerator;
+pub use worker::{WorkerGenerator, WORKER_HANDLER};
 
 #[derive(Error, Debug)]
 pub enum PluginError {
     #[error("Compilation failed: {reason}")]
     CompilationFailed { reason: String },
 
+    #[error("Deployment failed: {reason}")]
+    DeploymentFailed { reason: String },
+
     #[error("Compile tool not found: {tool}")]
     CompileToolNotFound { tool: String },
 
@@ -23,6 +183,21 @@ pub enum PluginError {
     #[error("Missing entry file. Expected one of: {candidates:?}")]
     MissingEntryFile { candidates: Vec<String> },
 
+    #[error("Unsupported toolchain version: {reason}")]
+    UnsupportedToolchainVersion { reason: String },
+
+    #[error("Unsupported target: {reason}")]
+    UnsupportedTarget { reason: String },
+
+    #[error("Invalid WebAssembly binary {path}: {reason}")]
+    InvalidWasm { path: String, reason: String },
+
+    #[error("Execution failed: {reason}")]
+    ExecutionFailed { reason: String },
+
+    #[error("Invalid configuration: {reason}")]
+    InvalidConfiguration { reason: String },
+
     #[error("Output directory creation failed: {path}")]
     OutputDirectoryCreationFailed { path: String },
 
@@ -83,41 +258,404 @@ pub struct PluginInfo {
     pub capabilities: PluginCapabilities,
 }
 
+/// What the host tells a plugin when loading it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostInfo {
+    /// Host program, e.g. `wasmrun`
+    pub name: String,
+    pub version: String,
+    /// Variables for every toolchain subprocess the plugin starts; build
+    /// settings override them
+    pub environment: BTreeMap<String, String>,
+}
+
+/// How strongly a plugin claims a project, and why, for hosts arbitrating
+/// between several plugins that claim the same directory.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectMatch {
+    /// From 0.0 (not this plugin's project) to 1.0 (certainly)
+    pub confidence: f64,
+    /// Evidence behind the score, e.g. "go.mod found"
+    pub reasons: Vec<String>,
+}
+
+impl ProjectMatch {
+    pub fn claims(&self) -> bool {
+        self.confidence > 0.0
+    }
+}
+
 pub trait Plugin: Send + Sync {
     fn info(&self) -> &PluginInfo;
-    fn can_handle_project(&self, project_path: &str) -> bool;
+    fn can_handle_project(&self, project_path: &Path) -> bool;
     fn get_builder(&self) -> Box<dyn WasmBuilder>;
+
+    /// Called once after loading, before any other method.
+    fn initialize(&mut self, host_info: &HostInfo) -> PluginResult<()> {
+        let _ = host_info;
+        Ok(())
+    }
+
+    /// Called once before unloading; releases whatever the plugin still holds.
+    fn shutdown(&mut self) {}
+
+    /// Takes user preferences from the host, replacing earlier ones. Plugins
+    /// without settings ignore them.
+    fn configure(&mut self, settings: serde_json::Value) -> PluginResult<()> {
+        let _ = settings;
+        Ok(())
+    }
+
+    /// Confidence and reasons behind [`Self::can_handle_project`]; plugins
+    /// without their own scoring claim with full confidence.
+    fn assess_project(&self, project_path: &Path) -> ProjectMatch {
+        let claims = self.can_handle_project(project_path);
+        ProjectMatch {
+            confidence: if claims { 1.0 } else { 0.0 },
+            reasons: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct CompileConfig {
-    pub project_path: String,
-    pub output_directory: String,
+    pub project_path: PathBuf,
+    pub output_directory: PathBuf,
+    /// Build in a mirror of the sources under `<build_directory>/<project>` so the
+    /// project directory is only read; relative outputs land in the mirror too
+    pub build_directory: Option<PathBuf>,
     pub verbose: bool,
     pub optimization_level: OptimizationLevel,
     pub target_type: TargetType,
+    pub toolchain: Toolchain,
+    /// Build with the Go toolchain when TinyGo isn't installed and the build
+    /// doesn't need it
+    pub toolchain_fallback: bool,
+    pub build_mode: BuildMode,
+    /// Componentize against a user-provided WIT world
+    pub wit_world: Option<WitWorld>,
+    /// Preview1 adapter module used to wrap wasip1 output into a preview2 component
+    pub preview2_adapter: Option<PathBuf>,
+    /// Value of `GOFLAGS` for the build subprocess
+    pub goflags: Option<String>,
+    /// Value of `GOEXPERIMENT` for the build subprocess
+    pub goexperiment: Option<String>,
+    /// Build tags for `//go:build` constraints, passed as `-tags`
+    pub build_tags: Vec<String>,
+    /// Passed to `tinygo build` or `go build` as given, before the package,
+    /// e.g. `-gc=leaking` or `-scheduler=none`
+    pub extra_build_args: Vec<String>,
+    /// `GOCACHE` for the build, relative to the project; hermetic builds use their own
+    pub gocache: Option<PathBuf>,
+    /// `GOMODCACHE` for the build, relative to the project; hermetic builds use their own
+    pub gomodcache: Option<PathBuf>,
+    /// Name the module `<name>.<content-hash>.wasm` for cache-busting deploys
+    pub hashed_filenames: bool,
+    /// With hashed filenames, keep only this many builds in the output directory
+    pub retain_builds: Option<NonZeroUsize>,
+    /// Write SLSA provenance (`<name>.intoto.jsonl`) for the build artifacts
+    pub provenance: bool,
+    /// Also generate a Web Worker host and main-thread client for the loader
+    pub web_worker: bool,
+    /// Also generate a framework wrapper around the loader
+    pub js_framework: Option<JsFramework>,
+    /// Webapp target: inline the glue and base64 module into one `index.html`
+    pub single_file: bool,
+    /// Write `<file>.map` source maps for glue minified in release and size builds
+    pub source_maps: bool,
+    /// Instantiate the module after building and check its expected exports exist
+    pub smoke_test: bool,
+    /// Append this build's anonymized metrics to the local metrics file
+    pub metrics: bool,
+    /// Reuse the artifacts of an earlier build with the same sources and configuration
+    pub cache: bool,
+    /// Shared cache (`http(s)://` or `s3://`) consulted on local misses; implies `cache`
+    pub remote_cache: Option<String>,
+    /// Only download from the remote cache, never upload
+    pub remote_cache_read_only: bool,
+    /// Build with private GOCACHE/GOMODCACHE/GOPATH instead of the user's
+    pub hermetic: bool,
+    /// Where hermetic builds keep their caches (relative to the project);
+    /// a fresh temp directory per build when unset
+    pub hermetic_directory: Option<PathBuf>,
+    /// Forbid module and toolchain downloads (GOPROXY=off, -mod=readonly)
+    pub no_network: bool,
+    /// Go toolchain: build with exactly the go.mod `toolchain` version
+    /// (`GOTOOLCHAIN=<version>`) rather than any version at least as new
+    pub exact_toolchain: bool,
+    /// Run `go mod tidy` before building
+    pub tidy: bool,
+    /// Parallel compile jobs (`-p` and `GOMAXPROCS`); the toolchain default when unset
+    pub jobs: Option<NonZeroUsize>,
+    /// Stop the compiler at the first error it reports instead of letting it finish
+    pub fail_fast: bool,
+    /// Fail a build whose toolchain printed warnings
+    pub deny_warnings: bool,
+    /// Wasm features and CPU to generate code for
+    pub llvm: LlvmTuning,
+    /// Extra wasm-opt arguments for the `-Oz` pass of size builds, e.g. `--converge`
+    pub wasm_opt_passes: Vec<String>,
+    /// Variable (`importpath.name`) the git version is linked into; `None`
+    /// disables stamping
+    pub version_variable: Option<String>,
+    /// Version to stamp; detected from the project's git checkout when unset
+    pub version_stamp: Option<VersionStamp>,
 }
 
-#[derive(Debug, Clone)]
+impl Default for CompileConfig {
+    fn default() -> Self {
+        Self {
+            project_path: PathBuf::from("."),
+            output_directory: PathBuf::from("./dist"),
+            build_directory: None,
+            verbose: false,
+            optimization_level: OptimizationLevel::Release,
+            target_type: TargetType::Standard,
+            toolchain: Toolchain::TinyGo,
+            toolchain_fallback: true,
+            build_mode: BuildMode::Command,
+            wit_world: None,
+            preview2_adapter: None,
+            goflags: None,
+            goexperiment: None,
+            build_tags: Vec::new(),
+            extra_build_args: Vec::new(),
+            gocache: None,
+            gomodcache: None,
+            hashed_filenames: false,
+            retain_builds: None,
+            provenance: false,
+            web_worker: false,
+            js_framework: None,
+            single_file: false,
+            source_maps: false,
+            smoke_test: false,
+            metrics: false,
+            cache: false,
+            remote_cache: None,
+            remote_cache_read_only: false,
+            hermetic: false,
+            hermetic_directory: None,
+            no_network: false,
+            exact_toolchain: false,
+            tidy: false,
+            jobs: None,
+            fail_fast: false,
+            deny_warnings: false,
+            llvm: LlvmTuning::default(),
+            wasm_opt_passes: Vec::new(),
+            version_variable: Some(DEFAULT_VERSION_VARIABLE.to_string()),
+            version_stamp: None,
+        }
+    }
+}
+
+impl CompileConfig {
+    /// Environment variables applied to every toolchain subprocess of the build.
+    pub fn build_environment(&self) -> Vec<(&'static str, String)> {
+        let mut environment = Vec::new();
+        let mut goflags: Vec<&str> = self.goflags.iter().map(String::as_str).collect();
+        // The module cache is read-only by default, which would keep hermetic caches from being removed
+        if self.hermetic {
+            goflags.push("-modcacherw");
+        }
+        if self.no_network && !goflags.iter().any(|flags| flags.contains("-mod=")) {
+            goflags.push("-mod=readonly");
+        }
+        if !goflags.is_empty() {
+            environment.push(("GOFLAGS", goflags.join(" ")));
+        }
+        if let Some(goexperiment) = &self.goexperiment {
+            environment.push(("GOEXPERIMENT", goexperiment.clone()));
+        }
+        // Go rejects relative cache directories
+        for (variable, directory) in [("GOCACHE", &self.gocache), ("GOMODCACHE", &self.gomodcache)]
+        {
+            if let Some(directory) = directory {
+                let directory = self.project_path.join(directory);
+                let directory = std::path::absolute(&directory).unwrap_or(directory);
+                environment.push((variable, directory.to_string_lossy().to_string()));
+            }
+        }
+        if let Some(jobs) = self.jobs {
+            environment.push(("GOMAXPROCS", jobs.to_string()));
+        }
+        if self.no_network {
+            environment.push(("GOPROXY", "off".to_string()));
+            // Never download the newer toolchain a go.mod may ask for
+            environment.push(("GOTOOLCHAIN", "local".to_string()));
+        } else if self.exact_toolchain && self.toolchain == Toolchain::Go {
+            if let Some(pinned) =
+                GoModFile::read(&self.project_path).and_then(|go_mod| go_mod.toolchain)
+            {
+                environment.push(("GOTOOLCHAIN", pinned));
+            }
+        }
+        environment
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct CompileResult {
-    pub wasm_file_path: String,
-    pub js_file_path: Option<String>,
-    pub additional_files: Vec<String>,
+    pub wasm_file_path: PathBuf,
+    pub js_file_path: Option<PathBuf>,
+    pub additional_files: Vec<Artifact>,
     pub is_wasm_bindgen: bool,
+    /// Distinct non-fatal toolchain warnings, also replayed from cached builds
+    pub warnings: Vec<BuildWarning>,
+    /// Sizes before and after wasm-opt, for size builds with binaryen installed
+    pub wasm_opt: Option<OptimizationReport>,
 }
 
-#[derive(Debug, Clone)]
+impl CompileResult {
+    /// Every file the build wrote: the module, the JS entry point and the additional files.
+    pub fn artifacts(&self) -> Vec<Artifact> {
+        std::iter::once(Artifact::new(ArtifactKind::Wasm, &self.wasm_file_path))
+            .chain(
+                self.js_file_path
+                    .iter()
+                    .map(|path| Artifact::new(ArtifactKind::JsGlue, path)),
+            )
+            .chain(self.additional_files.iter().cloned())
+            .collect()
+    }
+}
+
+/// What a build artifact is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactKind {
+    /// A WebAssembly module or component
+    Wasm,
+    /// JavaScript loaders, wrappers, runtimes and their type declarations
+    JsGlue,
+    Html,
+    SourceMap,
+    /// Other files the deployment needs, such as configs and scripts
+    Asset,
+    /// Metadata about the build: manifests, provenance, WIT descriptions
+    Manifest,
+}
+
+/// A file written by a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    pub path: PathBuf,
+    /// Size in bytes when the result was created
+    pub size: u64,
+}
+
+impl Artifact {
+    /// Describes the file at `path`, reading its current size.
+    pub fn new(kind: ArtifactKind, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let size = fs::metadata(&path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        Self { kind, path, size }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OptimizationLevel {
     Debug,
     Release,
     Size,
 }
 
-#[derive(Debug, Clone)]
+impl OptimizationLevel {
+    pub const ALL: [OptimizationLevel; 3] = [
+        OptimizationLevel::Debug,
+        OptimizationLevel::Release,
+        OptimizationLevel::Size,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizationLevel::Debug => "debug",
+            OptimizationLevel::Release => "release",
+            OptimizationLevel::Size => "size",
+        }
+    }
+}
+
+/// Frontend framework to generate a wrapper around the JavaScript loader for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsFramework {
+    /// `useGoWasm()` hook in `<name>.react.js`
+    React,
+    /// `useGoWasm()` composable in `<name>.vue.js`
+    Vue,
+    /// `<go-name>` custom element in `<name>.element.js`
+    Element,
+}
+
+/// Whether the module runs `main` on instantiation or is used as a callable library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildMode {
+    /// Runs `main` via `_start` (default)
+    #[default]
+    Command,
+    /// Exports `_initialize` and leaves `main` uncalled
+    Reactor,
+}
+
+/// WIT package directory and the world a component build implements.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WitWorld {
+    pub wit_directory: PathBuf,
+    /// Defaults to the package's only world
+    pub world: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TargetType {
+    #[serde(alias = "wasm")]
     Standard,
     Web,
+    #[serde(alias = "web-app")]
     WebApp,
+    /// WASI preview 1 (wasip1) module for wasmtime, wazero and other runtimes
+    #[serde(alias = "wasip1")]
+    Wasi,
+    /// wasi-http (`wasi:http/proxy`) component for serverless platforms
+    #[serde(rename = "wasi-http")]
+    WasiHttp,
+    /// Module worker plus wrangler layout for Cloudflare Workers
+    #[serde(rename = "cloudflare-worker")]
+    CloudflareWorker,
+    /// Extism plugin built with the Go PDK
+    #[serde(rename = "extism")]
+    ExtismPlugin,
+    /// waPC guest module
+    Wapc,
+    /// Experimental: shared memory, atomics and wasi-threads, run on a worker pool
+    Threads,
+    /// Freestanding TinyGo module with no JS or WASI imports, for custom hosts
+    #[serde(rename = "wasm-unknown")]
+    WasmUnknown,
+}
+
+impl TargetType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TargetType::Standard => "standard",
+            TargetType::Web => "web",
+            TargetType::WebApp => "webapp",
+            TargetType::Wasi => "wasi",
+            TargetType::WasiHttp => "wasi-http",
+            TargetType::CloudflareWorker => "cloudflare-worker",
+            TargetType::ExtismPlugin => "extism",
+            TargetType::Wapc => "wapc",
+            TargetType::Threads => "threads",
+            TargetType::WasmUnknown => "wasm-unknown",
+        }
+    }
 }
 
 pub trait WasmBuilder: Send + Sync {
@@ -125,69 +663,67 @@ pub trait WasmBuilder: Send + Sync {
     fn entry_file_candidates(&self) -> &[&str];
     fn supported_extensions(&self) -> &[&str];
     fn check_dependencies(&self) -> Vec<String>;
-    fn validate_project(&self, project_path: &str) -> PluginResult<()>;
+    fn validate_project(&self, project_path: &Path) -> PluginResult<()>;
     fn compile(&self, config: &CompileConfig) -> PluginResult<CompileResult>;
 }
 
 pub struct CommandExecutor;
 
 impl CommandExecutor {
+    /// Cached per process; see [`ToolRegistry`].
     pub fn is_tool_installed(tool_name: &str) -> bool {
-        let version_arg = match tool_name {
-            "tinygo" | "go" => "version",
-            _ => "--version",
-        };
-
-        Command::new(tool_name)
-            .arg(version_arg)
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+        ToolRegistry::is_installed(tool_name)
     }
 
-    pub fn execute_command(
+    pub fn execute_command<A: AsRef<OsStr>>(
         command_name: &str,
-        arguments: &[&str],
-        working_directory: &str,
+        arguments: &[A],
+        working_directory: &Path,
         verbose_output: bool,
     ) -> PluginResult<Output> {
-        if verbose_output {
-            println!(
-                "Executing: {} {} in {}",
-                command_name,
-                arguments.join(" "),
-                working_directory
-            );
-        }
+        Self::execute_command_with_env(
+            command_name,
+            arguments,
+            working_directory,
+            &[],
+            verbose_output,
+        )
+    }
 
-        let output = Command::new(command_name)
+    pub fn execute_command_with_env<A: AsRef<OsStr>>(
+        command_name: &str,
+        arguments: &[A],
+        working_directory: &Path,
+        environment: &[(&str, &OsStr)],
+        verbose_output: bool,
+    ) -> PluginResult<Output> {
+        ToolCommand::new(command_name)
             .args(arguments)
+            .envs(environment.iter().copied())
             .current_dir(working_directory)
-            .output()
-            .map_err(PluginError::Io)?;
-
-        if verbose_output {
-            println!(
-                "Command output: {}",
-                String::from_utf8_lossy(&output.stdout)
-            );
-            if !output.stderr.is_empty() {
-                println!(
-                    "Command stderr: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                );
-            }
-        }
+            .output(verbose_output)
+    }
 
-        Ok(output)
+    /// A path printed by a tool, such as `go env GOROOT`, without the trailing
+    /// newline. Unix paths are taken byte for byte.
+    pub fn output_path(output: &[u8]) -> PathBuf {
+        let trimmed = output.trim_ascii();
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            PathBuf::from(OsStr::from_bytes(trimmed))
+        }
+        #[cfg(not(unix))]
+        {
+            PathBuf::from(String::from_utf8_lossy(trimmed).to_string())
+        }
     }
 
     pub fn copy_to_output_directory(
-        source_file_path: &str,
-        output_directory: &str,
+        source_path: &Path,
+        output_directory: &Path,
         language_name: &str,
     ) -> PluginResult<()> {
-        let source_path = Path::new(source_file_path);
         if !source_path.exists() {
             return Err(PluginError::CompilationFailed {
                 reason: format!(
@@ -197,11 +733,15 @@ impl CommandExecutor {
         }
 
         let filename = source_path.file_name().unwrap();
-        let destination_path = Path::new(output_directory).join(filename);
+        let destination_path = output_directory.join(filename);
 
-        std::fs::copy(source_path, &destination_path).map_err(PluginError::Io)?;
+        let method = ArtifactCopier::copy(source_path, &destination_path)?;
 
-        println!("📁 Copied to: {}", destination_path.display());
+        Logger::info(format_args!(
+            "📁 Copied to: {} ({})",
+            destination_path.display(),
+            method.label()
+        ));
         Ok(())
     }
 }
@@ -209,37 +749,90 @@ impl CommandExecutor {
 pub struct PathResolver;
 
 impl PathResolver {
-    pub fn join_paths(base_path: &str, relative_path: &str) -> String {
-        Path::new(base_path)
-            .join(relative_path)
-            .to_string_lossy()
-            .to_string()
+    pub fn join_paths(base_path: &Path, relative_path: impl AsRef<Path>) -> PathBuf {
+        base_path.join(relative_path)
     }
 
-    pub fn validate_directory_exists(directory_path: &str) -> PluginResult<()> {
-        let directory = Path::new(directory_path);
+    pub fn validate_directory_exists(directory: &Path) -> PluginResult<()> {
         if !directory.exists() {
             return Err(PluginError::InvalidProjectStructure {
-                reason: format!("Directory does not exist: {directory_path}"),
+                reason: format!("Directory does not exist: {}", directory.display()),
             });
         }
         if !directory.is_dir() {
             return Err(PluginError::InvalidProjectStructure {
-                reason: format!("Path is not a directory: {directory_path}"),
+                reason: format!("Path is not a directory: {}", directory.display()),
             });
         }
         Ok(())
     }
 
-    pub fn ensure_output_directory_exists(directory_path: &str) -> PluginResult<()> {
-        fs::create_dir_all(directory_path).map_err(|_| PluginError::OutputDirectoryCreationFailed {
-            path: directory_path.to_string(),
+    pub fn ensure_output_directory_exists(directory: &Path) -> PluginResult<()> {
+        fs::create_dir_all(directory).map_err(|_| PluginError::OutputDirectoryCreationFailed {
+            path: directory.display().to_string(),
         })
     }
 
-    pub fn is_safe_path(path_to_check: &str) -> bool {
-        let path = Path::new(path_to_check);
-        !path.to_string_lossy().contains("..")
+    /// Whether files can be created in the directory, or in its nearest existing
+    /// ancestor when it does not exist yet. Probes with a real file, since
+    /// permission bits miss read-only mounts and ACLs.
+    pub fn is_writable_directory(directory: &Path) -> bool {
+        let Some(existing) = directory
+            .ancestors()
+            .find(|ancestor| ancestor.as_os_str().is_empty() || ancestor.is_dir())
+        else {
+            return false;
+        };
+        let existing = if existing.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            existing
+        };
+        let probe = existing.join(format!(".wasmgo-write-probe-{}", std::process::id()));
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&probe)
+        {
+            Ok(_) => {
+                let _ = fs::remove_file(&probe);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Creates a directory with a random name in `parent` that only the
+    /// current user can enter, failing rather than reusing an existing one.
+    /// It is removed with everything in it when the returned guard drops.
+    pub fn private_directory(parent: &Path, prefix: &str) -> PluginResult<tempfile::TempDir> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(prefix);
+        #[cfg(unix)]
+        builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+        builder.tempdir_in(parent).map_err(PluginError::Io)
+    }
+
+    /// Writes a new file only the current user can read, for credentials
+    /// handed to a subprocess instead of its command line. Fails if anything,
+    /// a symlink included, already exists at `path`; see
+    /// [`Self::private_directory`] for somewhere to put it.
+    pub fn write_private(path: &Path, contents: &str) -> PluginResult<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        std::io::Write::write_all(
+            &mut options.open(path).map_err(PluginError::Io)?,
+            contents.as_bytes(),
+        )
+        .map_err(PluginError::Io)
+    }
+
+    pub fn is_safe_path(path: &Path) -> bool {
+        !path
+            .components()
+            .any(|component| component == std::path::Component::ParentDir)
     }
 }
 
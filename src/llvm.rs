@@ -0,0 +1,179 @@
+use crate::{PathResolver, PluginError, PluginResult, Toolchain};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// WebAssembly features known to LLVM's wasm backend.
+pub const WASM_LLVM_FEATURES: &[&str] = &[
+    "atomics",
+    "bulk-memory",
+    "bulk-memory-opt",
+    "call-indirect-overlong",
+    "exception-handling",
+    "extended-const",
+    "fp16",
+    "multimemory",
+    "multivalue",
+    "mutable-globals",
+    "nontrapping-fptoint",
+    "reference-types",
+    "relaxed-simd",
+    "sign-ext",
+    "simd128",
+    "tail-call",
+];
+
+/// CPU models of LLVM's wasm backend, each enabling a feature baseline.
+pub const WASM_LLVM_CPUS: &[&str] = &["generic", "mvp", "lime1", "bleeding-edge"];
+
+/// Features the standard Go toolchain can enable itself, with their `GOWASM` names.
+const GOWASM_FEATURES: &[(&str, &str)] =
+    &[("nontrapping-fptoint", "satconv"), ("sign-ext", "signext")];
+
+/// One feature turned on (`+name`) or off (`-name`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlvmFeature {
+    pub name: String,
+    pub enabled: bool,
+}
+
+impl LlvmFeature {
+    /// Parses `+name`, `-name` or `name` (enabled) and checks the name is known.
+    pub fn parse(spec: &str) -> PluginResult<Self> {
+        let (name, enabled) = match spec.strip_prefix('-') {
+            Some(name) => (name, false),
+            None => (spec.strip_prefix('+').unwrap_or(spec), true),
+        };
+        if !WASM_LLVM_FEATURES.contains(&name) {
+            return Err(PluginError::UnsupportedTarget {
+                reason: format!(
+                    "unknown wasm feature `{name}`; known features: {}",
+                    WASM_LLVM_FEATURES.join(", ")
+                ),
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            enabled,
+        })
+    }
+
+    pub fn flag(&self) -> String {
+        format!("{}{}", if self.enabled { '+' } else { '-' }, self.name)
+    }
+}
+
+/// Code generation tuning for specific wasm engines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlvmTuning {
+    pub features: Vec<LlvmFeature>,
+    /// One of [`WASM_LLVM_CPUS`]
+    pub cpu: Option<String>,
+}
+
+impl LlvmTuning {
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.cpu.is_none()
+    }
+
+    /// Checks the CPU name and that `toolchain` can apply the tuning: TinyGo
+    /// takes any of it, Go only the features it has `GOWASM` options for.
+    pub fn validate(&self, toolchain: Toolchain) -> PluginResult<()> {
+        if let Some(cpu) = &self.cpu {
+            if !WASM_LLVM_CPUS.contains(&cpu.as_str()) {
+                return Err(PluginError::UnsupportedTarget {
+                    reason: format!(
+                        "unknown wasm CPU `{cpu}`; known CPUs: {}",
+                        WASM_LLVM_CPUS.join(", ")
+                    ),
+                });
+            }
+        }
+        if toolchain == Toolchain::TinyGo {
+            return Ok(());
+        }
+
+        if self.cpu.is_some() {
+            return Err(PluginError::UnsupportedTarget {
+                reason:
+                    "CPU tuning needs TinyGo; the standard Go toolchain has no CPU setting for wasm"
+                        .to_string(),
+            });
+        }
+        match self.features.iter().find(|feature| {
+            !feature.enabled || !GOWASM_FEATURES.iter().any(|(name, _)| *name == feature.name)
+        }) {
+            Some(feature) => Err(PluginError::UnsupportedTarget {
+                reason: format!(
+                    "the standard Go toolchain cannot apply `{}`; it only enables {} (via GOWASM), other features need TinyGo",
+                    feature.flag(),
+                    GOWASM_FEATURES
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// TinyGo's `-llvm-features=+a,-b` argument.
+    pub fn tinygo_features_argument(&self) -> Option<String> {
+        (!self.features.is_empty()).then(|| {
+            format!(
+                "-llvm-features={}",
+                self.features
+                    .iter()
+                    .map(LlvmFeature::flag)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        })
+    }
+
+    /// `GOWASM` value for the standard Go toolchain, after [`Self::validate`].
+    pub fn gowasm(&self) -> Option<String> {
+        let options: Vec<&str> = self
+            .features
+            .iter()
+            .filter_map(|feature| {
+                GOWASM_FEATURES
+                    .iter()
+                    .find(|(name, _)| *name == feature.name)
+                    .map(|(_, option)| *option)
+            })
+            .collect();
+        (!options.is_empty()).then(|| options.join(","))
+    }
+
+    /// TinyGo only sets the CPU through target files, so a CPU override, or
+    /// other target `properties`, is written as a target inheriting
+    /// `base_target` into `directory`, which should be private: the file
+    /// names the tools TinyGo runs.
+    pub fn write_tinygo_target(
+        &self,
+        base_target: &str,
+        properties: Option<&serde_json::Value>,
+        directory: &Path,
+    ) -> PluginResult<Option<PathBuf>> {
+        if self.cpu.is_none() && properties.is_none() {
+            return Ok(None);
+        }
+        let mut target = serde_json::json!({ "inherits": [base_target] });
+        if let Some(cpu) = &self.cpu {
+            target["cpu"] = serde_json::json!(cpu);
+        }
+        if let Some(serde_json::Value::Object(properties)) = properties {
+            for (key, value) in properties {
+                target[key] = value.clone();
+            }
+        }
+        let target_path = directory.join(format!(
+            "{base_target}-{}.json",
+            self.cpu.as_deref().unwrap_or("custom")
+        ));
+        PathResolver::write_private(&target_path, &target.to_string())?;
+        Ok(Some(target_path))
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{DeployBundler, GoExport, JsFramework, PluginError, PluginResult, WitGenerator};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const WASMEXPORT_LOADER_TEMPLATE: &str = include_str!("templates/wasmexport_loader.js");
+const WORKER_LOADER_TEMPLATE: &str = include_str!("templates/worker_loader.js");
+const WORKER_CLIENT_TEMPLATE: &str = include_str!("templates/worker_client.js");
+const WORKER_CLIENT_TYPES_TEMPLATE: &str = include_str!("templates/worker_client.d.ts");
+/// Methods of the worker client proxy next to the exported functions.
+const WORKER_CLIENT_METHODS: [&str; 2] = ["call", "terminate"];
+const REACT_HOOK_TEMPLATE: &str = include_str!("templates/react_hook.js");
+const VUE_COMPOSABLE_TEMPLATE: &str = include_str!("templates/vue_composable.js");
+const CUSTOM_ELEMENT_TEMPLATE: &str = include_str!("templates/custom_element.js");
+const SINGLE_FILE_TEMPLATE: &str = include_str!("templates/single_file.html");
+const INDEX_TEMPLATE: &str = include_str!("templates/index.html");
+
+/// Generates JavaScript glue that instantiates compiled modules in the browser.
+pub struct LoaderGenerator;
+
+impl LoaderGenerator {
+    /// ES module loader for a `go:wasmexport` reactor module.
+    pub fn wasmexport_loader(wasm_filename: &str, exports: &[GoExport]) -> String {
+        let bindings: Vec<String> = exports
+            .iter()
+            .map(|export| {
+                let parameter_names: Vec<&str> = export
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.name.as_str())
+                    .collect();
+                // 64-bit integers cross the wasm boundary as BigInt
+                let arguments: Vec<String> = export
+                    .parameters
+                    .iter()
+                    .map(|parameter| match parameter.go_type.as_str() {
+                        "int64" | "uint64" => format!("BigInt({})", parameter.name),
+                        _ => parameter.name.clone(),
+                    })
+                    .collect();
+                format!(
+                    "    {name}: ({parameters}) => exports.{name}({arguments}),",
+                    name = export.export_name,
+                    parameters = parameter_names.join(", "),
+                    arguments = arguments.join(", "),
+                )
+            })
+            .collect();
+
+        WASMEXPORT_LOADER_TEMPLATE
+            .replace("{{WASM_FILE}}", wasm_filename)
+            .replace("{{EXPORT_BINDINGS}}", &bindings.join("\n"))
+    }
+
+    /// Writes `<name>.worker.js`, a module worker that instantiates the module
+    /// through the loader, and `<name>.client.js`, whose `createWorker()`
+    /// returns a proxy with an async method per export (typed in `<name>.client.d.ts`).
+    /// Exports named after the proxy's own `call` and `terminate` are rejected.
+    pub fn write_web_worker(
+        wasm_file_path: &Path,
+        exports: &[GoExport],
+    ) -> PluginResult<Vec<PathBuf>> {
+        if let Some(export) = exports
+            .iter()
+            .find(|export| WORKER_CLIENT_METHODS.contains(&export.export_name.as_str()))
+        {
+            return Err(PluginError::UnsupportedTarget {
+                reason: format!(
+                    "{} exports `{}`, which the Web Worker client reserves for its own {}; export it under another name",
+                    export.source_file,
+                    export.export_name,
+                    WORKER_CLIENT_METHODS.join(" and ")
+                ),
+            });
+        }
+        let file_name = |extension: &str| {
+            wasm_file_path
+                .with_extension(extension)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+        let fill = |template: &str| {
+            template
+                .replace("{{WASM_FILE}}", &file_name("wasm"))
+                .replace("{{LOADER_FILE}}", &file_name("js"))
+                .replace("{{WORKER_FILE}}", &file_name("worker.js"))
+                .replace("{{CLIENT_FILE}}", &file_name("client.js"))
+                .replace("{{TYPES_FILE}}", &file_name("client.d.ts"))
+                .replace("{{EXPORT_METHODS}}", &Self::proxy_methods(exports))
+                .replace("{{EXPORT_SIGNATURES}}", &Self::proxy_signatures(exports))
+        };
+
+        let mut written = Vec::new();
+        for (extension, template) in [
+            ("worker.js", WORKER_LOADER_TEMPLATE),
+            ("client.js", WORKER_CLIENT_TEMPLATE),
+            ("client.d.ts", WORKER_CLIENT_TYPES_TEMPLATE),
+        ] {
+            let path = wasm_file_path.with_extension(extension);
+            fs::write(&path, fill(template)).map_err(PluginError::Io)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Writes `<name>.<framework>.js`, wrapping the loader next to the module.
+    pub fn write_framework_wrapper(
+        wasm_file_path: &Path,
+        project_name: &str,
+        framework: JsFramework,
+    ) -> PluginResult<PathBuf> {
+        let (extension, template) = match framework {
+            JsFramework::React => ("react.js", REACT_HOOK_TEMPLATE),
+            JsFramework::Vue => ("vue.js", VUE_COMPOSABLE_TEMPLATE),
+            JsFramework::Element => ("element.js", CUSTOM_ELEMENT_TEMPLATE),
+        };
+        let file_name = |extension: &str| {
+            wasm_file_path
+                .with_extension(extension)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+        // Custom element names need a hyphen and must start with a letter
+        let element_name = format!("go-{}", WitGenerator::kebab_case(project_name));
+
+        let wrapper_path = wasm_file_path.with_extension(extension);
+        let wrapper_source = template
+            .replace("{{WASM_FILE}}", &file_name("wasm"))
+            .replace("{{LOADER_FILE}}", &file_name("js"))
+            .replace("{{ELEMENT_NAME}}", &element_name);
+        fs::write(&wrapper_path, wrapper_source).map_err(PluginError::Io)?;
+        Ok(wrapper_path)
+    }
+
+    /// Writes `index.html` next to the module with the glue inlined and the
+    /// module embedded as base64, so the page works from `file://` or any host.
+    ///
+    /// `wasmexport_loader` is the loader of a reactor module, which is then
+    /// available as `window.goWasm`; otherwise `wasm_exec` runs `main`.
+    pub fn write_single_file(
+        wasm_file_path: &Path,
+        title: &str,
+        wasmexport_loader: Option<&str>,
+        wasm_exec: &str,
+    ) -> PluginResult<PathBuf> {
+        let wasm_base64 =
+            DeployBundler::base64(&fs::read(wasm_file_path).map_err(PluginError::Io)?);
+        let scripts = match wasmexport_loader {
+            Some(loader_source) => format!(
+                "<script type=\"module\">\n{}\nload(\"data:application/wasm;base64,{wasm_base64}\").then((module) => {{\n  window.goWasm = module;\n  window.dispatchEvent(new CustomEvent(\"gowasm:ready\", {{ detail: module }}));\n}});\n</script>",
+                Self::inline_script(loader_source)
+            ),
+            None => format!(
+                "<script>\n{}\n</script>\n<script>\nconst wasm = Uint8Array.from(atob(\"{wasm_base64}\"), (c) => c.charCodeAt(0));\nconst go = new Go();\nWebAssembly.instantiate(wasm, go.importObject).then(({{ instance }}) => go.run(instance));\n</script>",
+                Self::inline_script(wasm_exec)
+            ),
+        };
+
+        let html_path = wasm_file_path.with_file_name("index.html");
+        let html = SINGLE_FILE_TEMPLATE
+            .replace(
+                "{{WASM_FILE}}",
+                &wasm_file_path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+            )
+            .replace("{{TITLE}}", title)
+            .replace("{{SCRIPTS}}", &scripts);
+        fs::write(&html_path, html).map_err(PluginError::Io)?;
+        Ok(html_path)
+    }
+
+    /// Writes an `index.html` next to the module that loads it through
+    /// `glue_path`: the loader of a reactor module, which is then available
+    /// as `window.goWasm`, or `wasm_exec.js`, which runs `main`.
+    pub fn write_index(
+        wasm_file_path: &Path,
+        title: &str,
+        glue_path: &Path,
+        wasmexport: bool,
+    ) -> PluginResult<PathBuf> {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+        let wasm_filename = file_name(wasm_file_path);
+        let glue_filename = file_name(glue_path);
+        let scripts = if wasmexport {
+            format!(
+                "<script type=\"module\">\nimport {{ load }} from \"./{glue_filename}\";\nload().then((module) => {{\n  window.goWasm = module;\n  window.dispatchEvent(new CustomEvent(\"gowasm:ready\", {{ detail: module }}));\n}});\n</script>"
+            )
+        } else {
+            format!(
+                "<script src=\"{glue_filename}\"></script>\n<script>\nconst go = new Go();\nWebAssembly.instantiateStreaming(fetch(\"{wasm_filename}\"), go.importObject).then(({{ instance }}) => go.run(instance));\n</script>"
+            )
+        };
+
+        let html_path = wasm_file_path.with_file_name("index.html");
+        let html = INDEX_TEMPLATE
+            .replace("{{WASM_FILE}}", &wasm_filename)
+            .replace("{{GLUE_FILE}}", &glue_filename)
+            .replace("{{TITLE}}", title)
+            .replace("{{SCRIPTS}}", &scripts);
+        fs::write(&html_path, html).map_err(PluginError::Io)?;
+        Ok(html_path)
+    }
+
+    // A literal `</script` would end the inline script early
+    fn inline_script(source: &str) -> String {
+        source.replace("</script", "<\\/script")
+    }
+
+    fn proxy_methods(exports: &[GoExport]) -> String {
+        exports
+            .iter()
+            .map(|export| {
+                let parameter_names: Vec<&str> = export
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.name.as_str())
+                    .collect();
+                let mut arguments = vec![format!("\"{}\"", export.export_name)];
+                arguments.extend(parameter_names.iter().map(|name| name.to_string()));
+                format!(
+                    "    {name}: ({parameters}) => call({arguments}),",
+                    name = export.export_name,
+                    parameters = parameter_names.join(", "),
+                    arguments = arguments.join(", "),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn proxy_signatures(exports: &[GoExport]) -> String {
+        exports
+            .iter()
+            .map(|export| {
+                let mut signature = String::new();
+                if !export.doc_comment.iter().all(|line| line.is_empty()) {
+                    signature.push_str(&format!(
+                        "  /** {} */\n",
+                        export.doc_comment.join(" ").trim()
+                    ));
+                }
+                let parameters: Vec<String> = export
+                    .parameters
+                    .iter()
+                    .map(|parameter| {
+                        // The loader converts 64-bit arguments with BigInt()
+                        let ts_type = match Self::typescript_type(&parameter.go_type) {
+                            "bigint" => "number | bigint",
+                            ts_type => ts_type,
+                        };
+                        format!("{}: {ts_type}", parameter.name)
+                    })
+                    .collect();
+                let result = export
+                    .results
+                    .first()
+                    .map_or("void", |go_type| Self::typescript_type(go_type));
+                signature.push_str(&format!(
+                    "  {}({}): Promise<{result}>;",
+                    export.export_name,
+                    parameters.join(", ")
+                ));
+                signature
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // go:wasmexport only allows scalar parameters and results
+    fn typescript_type(go_type: &str) -> &'static str {
+        match go_type {
+            "int64" | "uint64" => "bigint",
+            "bool" => "boolean",
+            _ => "number",
+        }
+    }
+
+    pub fn write_loader(wasm_file_path: &Path, loader_source: &str) -> PluginResult<PathBuf> {
+        let loader_path = wasm_file_path.with_extension("js");
+        fs::write(&loader_path, loader_source).map_err(PluginError::Io)?;
+        Ok(loader_path)
+    }
+
+    /// Copies the toolchain's `wasm_exec.js` next to the module, which
+    /// browsers need to run js/wasm builds. The copy is a fresh, writable
+    /// file: toolchains in the module cache or the Nix store are read-only,
+    /// and `fs::copy` would carry that over to the file the minifier rewrites.
+    pub fn copy_wasm_exec(wasm_file_path: &Path, wasm_exec_path: &Path) -> PluginResult<PathBuf> {
+        let output_directory = wasm_file_path.parent().unwrap_or(Path::new("."));
+        let runtime_path = output_directory.join("wasm_exec.js");
+        let runtime = fs::read(wasm_exec_path).map_err(PluginError::Io)?;
+        // A read-only copy left by an earlier build can't be written over
+        let _ = fs::remove_file(&runtime_path);
+        fs::write(&runtime_path, runtime).map_err(PluginError::Io)?;
+        Ok(runtime_path)
+    }
+}
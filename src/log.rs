@@ -0,0 +1,74 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+static SINK: RwLock<Option<Arc<dyn LogSink>>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    /// Commands run and their output, only produced for verbose builds
+    Debug,
+}
+
+/// Destination for the plugin's output, installed by an embedding host.
+pub trait LogSink: Send + Sync {
+    fn log(&self, level: LogLevel, message: &str);
+}
+
+/// Everything the library prints goes through here: to the host's
+/// [`LogSink`] if one is installed, else errors and warnings to stderr and the
+/// rest to stdout. A message may span several lines.
+pub struct Logger;
+
+impl Logger {
+    /// Replaces the current sink for the whole process.
+    pub fn set_sink(sink: impl LogSink + 'static) {
+        *SINK
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(sink));
+    }
+
+    /// Goes back to printing.
+    pub fn clear_sink() {
+        *SINK
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+
+    pub fn has_sink() -> bool {
+        Self::sink().is_some()
+    }
+
+    pub fn log(level: LogLevel, message: fmt::Arguments) {
+        match Self::sink() {
+            Some(sink) => sink.log(level, &message.to_string()),
+            None if level <= LogLevel::Warn => eprintln!("{message}"),
+            None => println!("{message}"),
+        }
+    }
+
+    pub fn error(message: fmt::Arguments) {
+        Self::log(LogLevel::Error, message);
+    }
+
+    pub fn warn(message: fmt::Arguments) {
+        Self::log(LogLevel::Warn, message);
+    }
+
+    pub fn info(message: fmt::Arguments) {
+        Self::log(LogLevel::Info, message);
+    }
+
+    pub fn debug(message: fmt::Arguments) {
+        Self::log(LogLevel::Debug, message);
+    }
+
+    // Cloned out so a sink that logs again doesn't deadlock
+    fn sink() -> Option<Arc<dyn LogSink>> {
+        SINK.read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
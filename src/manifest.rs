@@ -0,0 +1,227 @@
+use crate::{
+    BuildMode, CommandExecutor, CompileConfig, CompileResult, Logger, OptimizationLevel,
+    PluginError, PluginResult, TargetType, ToolRegistry, Toolchain, VersionStamp, WasmBuilder,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILENAME: &str = "wasmgo.manifest.json";
+
+/// Top-level index of a multi-profile output directory.
+pub const LAYOUT_FILENAME: &str = "wasmgo.layout.json";
+
+/// Machine-readable description of a build, written next to the compiled artifacts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub plugin: String,
+    pub plugin_version: String,
+    pub wasm_file: String,
+    pub toolchain: Toolchain,
+    pub build_mode: BuildMode,
+    /// WIT world implemented by component builds
+    pub component_world: Option<String>,
+    /// Preview1 adapter used to wrap the core module into a preview2 component
+    pub preview2_adapter: Option<String>,
+    pub target: TargetType,
+    pub optimization: OptimizationLevel,
+    /// Git version of the sources, as linked into the module
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionStamp>,
+    pub environment: ToolchainEnvironment,
+}
+
+/// Snapshot of the toolchain environment a build ran in.
+///
+/// Lets "works on my machine" differences be diagnosed from the artifact alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolchainEnvironment {
+    pub go_version: Option<String>,
+    pub goflags: Option<String>,
+    pub goexperiment: Option<String>,
+    pub goproxy: Option<String>,
+    pub host_goos: Option<String>,
+    pub host_goarch: Option<String>,
+    pub build_goos: String,
+    pub build_goarch: String,
+    pub tinygo_version: Option<String>,
+}
+
+impl ToolchainEnvironment {
+    pub fn capture(
+        project_path: &Path,
+        environment: &[(&str, &OsStr)],
+        build_goos: &str,
+        build_goarch: &str,
+    ) -> Self {
+        let go_env = Self::read_go_env(project_path, environment);
+        let non_empty = |key: &str| go_env.get(key).filter(|value| !value.is_empty()).cloned();
+
+        Self {
+            go_version: non_empty("GOVERSION"),
+            goflags: non_empty("GOFLAGS"),
+            goexperiment: non_empty("GOEXPERIMENT"),
+            goproxy: non_empty("GOPROXY"),
+            host_goos: non_empty("GOOS"),
+            host_goarch: non_empty("GOARCH"),
+            build_goos: build_goos.to_string(),
+            build_goarch: build_goarch.to_string(),
+            tinygo_version: Self::read_tinygo_version(),
+        }
+    }
+
+    fn read_go_env(project_path: &Path, environment: &[(&str, &OsStr)]) -> HashMap<String, String> {
+        CommandExecutor::execute_command_with_env(
+            "go",
+            &[
+                "env",
+                "-json",
+                "GOVERSION",
+                "GOFLAGS",
+                "GOEXPERIMENT",
+                "GOPROXY",
+                "GOOS",
+                "GOARCH",
+            ],
+            project_path,
+            environment,
+            false,
+        )
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| serde_json::from_slice(&output.stdout).ok())
+        .unwrap_or_default()
+    }
+
+    // `tinygo version` prints e.g. "tinygo version 0.31.2 linux/amd64 (using go version ...)"
+    fn read_tinygo_version() -> Option<String> {
+        ToolRegistry::version_output("tinygo")?
+            .split_whitespace()
+            .nth(2)
+            .map(|version| version.to_string())
+    }
+}
+
+impl BuildManifest {
+    pub fn write_to(&self, output_directory: &Path) -> PluginResult<PathBuf> {
+        let manifest_path = output_directory.join(MANIFEST_FILENAME);
+        let manifest_json =
+            serde_json::to_string_pretty(self).map_err(|e| PluginError::Io(e.into()))?;
+
+        fs::write(&manifest_path, manifest_json).map_err(PluginError::Io)?;
+        Ok(manifest_path)
+    }
+}
+
+/// Where each profile of a multi-profile build placed its artifacts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileArtifacts {
+    pub profile: String,
+    pub target: TargetType,
+    pub optimization: OptimizationLevel,
+    pub toolchain: Toolchain,
+    /// Profile directory, relative to the layout file
+    pub directory: String,
+    pub wasm_file: String,
+    pub manifest: String,
+}
+
+/// Index of the `<output>/<profile>/` directories of a multi-profile build.
+///
+/// Entries are merged with an existing layout, so profiles built by separate
+/// invocations into the same output directory are all listed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildLayout {
+    pub profiles: Vec<ProfileArtifacts>,
+}
+
+impl BuildLayout {
+    /// `release`, or `wasi-http-release` when several targets share the output directory.
+    pub fn profile_name(
+        target: &TargetType,
+        optimization: &OptimizationLevel,
+        include_target: bool,
+    ) -> String {
+        if include_target {
+            format!("{}-{}", target.name(), optimization.name())
+        } else {
+            optimization.name().to_string()
+        }
+    }
+
+    /// Builds every target/level pair into its own profile directory and
+    /// records them in the layout of the base output directory.
+    pub fn build_profiles(
+        builder: &dyn WasmBuilder,
+        base_configuration: &CompileConfig,
+        targets: &[TargetType],
+        levels: &[OptimizationLevel],
+    ) -> PluginResult<Vec<(String, CompileResult)>> {
+        // Joining keeps an absolute output directory as it is
+        let output_directory = base_configuration
+            .project_path
+            .join(&base_configuration.output_directory);
+
+        let mut layout = Self::load(&output_directory);
+        let mut results = Vec::new();
+        for target in targets {
+            for &optimization in levels {
+                let profile = Self::profile_name(target, &optimization, targets.len() > 1);
+                Logger::info(format_args!("🗂️  Building profile {profile}..."));
+                let configuration = CompileConfig {
+                    output_directory: base_configuration.output_directory.join(&profile),
+                    optimization_level: optimization,
+                    target_type: target.clone(),
+                    ..base_configuration.clone()
+                };
+                let result = builder.compile(&configuration)?;
+
+                let file_name = |path: &Path| {
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                };
+                layout.record(ProfileArtifacts {
+                    profile: profile.clone(),
+                    target: target.clone(),
+                    optimization,
+                    toolchain: base_configuration.toolchain,
+                    directory: profile.clone(),
+                    wasm_file: format!("{profile}/{}", file_name(&result.wasm_file_path)),
+                    manifest: format!("{profile}/{MANIFEST_FILENAME}"),
+                });
+                results.push((profile, result));
+            }
+        }
+
+        layout.write_to(&output_directory)?;
+        Ok(results)
+    }
+
+    /// Reads the layout of an output directory, or an empty one if there is none yet.
+    pub fn load(output_directory: &Path) -> Self {
+        fs::read_to_string(output_directory.join(LAYOUT_FILENAME))
+            .ok()
+            .and_then(|layout_json| serde_json::from_str(&layout_json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn record(&mut self, artifacts: ProfileArtifacts) {
+        self.profiles
+            .retain(|existing| existing.profile != artifacts.profile);
+        self.profiles.push(artifacts);
+        self.profiles.sort_by(|a, b| a.profile.cmp(&b.profile));
+    }
+
+    pub fn write_to(&self, output_directory: &Path) -> PluginResult<PathBuf> {
+        let layout_path = output_directory.join(LAYOUT_FILENAME);
+        let layout_json =
+            serde_json::to_string_pretty(self).map_err(|e| PluginError::Io(e.into()))?;
+
+        fs::write(&layout_path, layout_json).map_err(PluginError::Io)?;
+        Ok(layout_path)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::Dirs;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::sync::{OnceLock, RwLock};
+
+/// Selects the language of CLI messages, ahead of `LC_ALL`, `LC_MESSAGES`
+/// and `LANG`.
+pub const LANGUAGE_VARIABLE: &str = "WASMGO_LANG";
+
+/// Locale whose catalog every other one falls back to.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Built-in catalogs. A translation is a `locales/<locale>.toml` with the keys
+/// of `en.toml` plus a line here.
+const CATALOGS: &[(&str, &str)] = &[("en", include_str!("locales/en.toml"))];
+
+static ACTIVE: RwLock<Option<Catalog>> = RwLock::new(None);
+static FALLBACK: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+
+struct Catalog {
+    locale: String,
+    messages: BTreeMap<String, String>,
+}
+
+/// User-facing CLI text by key (`section.name`), in the selected locale.
+///
+/// Keys missing from a translation fall back to English, and unknown keys
+/// print as themselves. `<config dir>/locales/<locale>.toml` takes precedence
+/// over the built-in catalog, so a translation can be tried without
+/// rebuilding.
+pub struct Messages;
+
+impl Messages {
+    /// Uses `requested` if given, else the locale from the environment. Returns
+    /// the locale selected, which is [`DEFAULT_LOCALE`] when there is no catalog
+    /// for the requested one.
+    pub fn select(requested: Option<&str>) -> String {
+        let requested = requested
+            .map(str::to_string)
+            .unwrap_or_else(Self::environment_locale);
+        let catalog = Self::candidates(&requested)
+            .into_iter()
+            .find_map(|locale| Self::load(&locale).map(|messages| Catalog { locale, messages }))
+            .unwrap_or_else(|| Catalog {
+                locale: DEFAULT_LOCALE.to_string(),
+                messages: BTreeMap::new(),
+            });
+        let locale = catalog.locale.clone();
+        *ACTIVE
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(catalog);
+        locale
+    }
+
+    pub fn locale() -> String {
+        ACTIVE
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+            .map_or_else(
+                || DEFAULT_LOCALE.to_string(),
+                |catalog| catalog.locale.clone(),
+            )
+    }
+
+    /// Locales with a built-in catalog.
+    pub fn locales() -> Vec<&'static str> {
+        CATALOGS.iter().map(|(locale, _)| *locale).collect()
+    }
+
+    pub fn text(key: &str) -> String {
+        Self::format(key, &[])
+    }
+
+    /// The message for `key` with each `{name}` replaced by its argument.
+    pub fn format(key: &str, arguments: &[(&str, &dyn Display)]) -> String {
+        let active = ACTIVE
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let template = active
+            .as_ref()
+            .and_then(|catalog| catalog.messages.get(key))
+            .or_else(|| Self::fallback().get(key))
+            .map_or(key, String::as_str);
+        let mut message = template.to_string();
+        for (name, value) in arguments {
+            message = message.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        message
+    }
+
+    /// Flattens a catalog's sections into `section.name` keys.
+    pub fn parse(contents: &str) -> Result<BTreeMap<String, String>, toml::de::Error> {
+        fn flatten(prefix: &str, table: toml::Table, messages: &mut BTreeMap<String, String>) {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                match value {
+                    toml::Value::Table(table) => flatten(&key, table, messages),
+                    toml::Value::String(text) => {
+                        messages.insert(key, text);
+                    }
+                    other => {
+                        messages.insert(key, other.to_string());
+                    }
+                }
+            }
+        }
+        let mut messages = BTreeMap::new();
+        flatten("", toml::from_str(contents)?, &mut messages);
+        Ok(messages)
+    }
+
+    fn fallback() -> &'static BTreeMap<String, String> {
+        FALLBACK.get_or_init(|| {
+            CATALOGS
+                .iter()
+                .find(|(locale, _)| *locale == DEFAULT_LOCALE)
+                .and_then(|(_, contents)| Self::parse(contents).ok())
+                .unwrap_or_default()
+        })
+    }
+
+    /// The user's catalog for `locale`, else the built-in one.
+    fn load(locale: &str) -> Option<BTreeMap<String, String>> {
+        let user = Dirs::discover()
+            .map(|dirs| dirs.config.join("locales").join(format!("{locale}.toml")))
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| Self::parse(&contents).ok());
+        user.or_else(|| {
+            CATALOGS
+                .iter()
+                .find(|(name, _)| *name == locale)
+                .and_then(|(_, contents)| Self::parse(contents).ok())
+        })
+    }
+
+    /// `pt_BR.UTF-8` tries `pt-br`, then `pt`.
+    fn candidates(requested: &str) -> Vec<String> {
+        let locale = requested
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        if locale.is_empty() || locale == "c" || locale == "posix" {
+            return Vec::new();
+        }
+        let mut candidates = vec![locale.clone()];
+        if let Some((language, _)) = locale.split_once('-') {
+            candidates.push(language.to_string());
+        }
+        candidates
+    }
+
+    fn environment_locale() -> String {
+        [LANGUAGE_VARIABLE, "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|variable| {
+                std::env::var(variable)
+                    .ok()
+                    .filter(|value| !value.is_empty())
+            })
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    CompileConfig, CompileResult, Dirs, OptimizationLevel, PluginError, PluginResult,
+    StateDirectory, TargetType, Toolchain,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// One JSON object per line, in the history directory.
+pub const METRICS_FILENAME: &str = "metrics.jsonl";
+
+/// A recorded build. Nothing in it names the project or its files: the
+/// project is identified by a hash of its path, and failures by the kind of
+/// error rather than its message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildMetric {
+    /// Seconds since the Unix epoch
+    pub recorded_at: u64,
+    /// First 16 hex digits of the SHA-256 of the project's absolute path
+    pub project: String,
+    pub target: TargetType,
+    pub toolchain: Toolchain,
+    pub optimization: OptimizationLevel,
+    pub duration_ms: u64,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm_size: Option<u64>,
+    /// The module plus every additional artifact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<String>,
+}
+
+/// Builds sharing a target, toolchain and optimization level.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSummary {
+    pub target: TargetType,
+    pub toolchain: Toolchain,
+    pub optimization: OptimizationLevel,
+    pub builds: usize,
+    pub failures: usize,
+    pub median_duration_ms: u64,
+    pub max_duration_ms: u64,
+    /// Over successful builds
+    pub mean_wasm_size: Option<u64>,
+}
+
+/// Local, opt-in build metrics (the `metrics` build setting). Each build
+/// appends a [`BuildMetric`] to [`METRICS_FILENAME`]; nothing is sent
+/// anywhere.
+pub struct BuildMetrics;
+
+impl BuildMetrics {
+    pub fn path() -> Option<PathBuf> {
+        Dirs::discover().map(|dirs| dirs.state(StateDirectory::History).join(METRICS_FILENAME))
+    }
+
+    pub fn measure(
+        configuration: &CompileConfig,
+        result: &PluginResult<CompileResult>,
+        duration: Duration,
+    ) -> BuildMetric {
+        let (wasm_size, output_size) = match result {
+            Ok(result) => {
+                let size = |path: &Path| fs::metadata(path).map(|metadata| metadata.len()).ok();
+                let wasm_size = size(&result.wasm_file_path);
+                let output_size = std::iter::once(result.wasm_file_path.as_path())
+                    .chain(result.js_file_path.as_deref())
+                    .chain(
+                        result
+                            .additional_files
+                            .iter()
+                            .map(|file| file.path.as_path()),
+                    )
+                    .filter_map(size)
+                    .sum();
+                (wasm_size, Some(output_size))
+            }
+            Err(_) => (None, None),
+        };
+        let project_path = fs::canonicalize(&configuration.project_path)
+            .unwrap_or_else(|_| configuration.project_path.clone());
+        BuildMetric {
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            project: Sha256::digest(project_path.to_string_lossy().as_bytes())
+                .iter()
+                .take(8)
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+            target: configuration.target_type.clone(),
+            toolchain: configuration.toolchain,
+            optimization: configuration.optimization_level,
+            duration_ms: duration.as_millis() as u64,
+            success: result.is_ok(),
+            wasm_size,
+            output_size,
+            error_kind: result
+                .as_ref()
+                .err()
+                .map(|e| Self::error_kind(e).to_string()),
+        }
+    }
+
+    pub fn append(metric: &BuildMetric) -> PluginResult<()> {
+        let path = Self::path().ok_or_else(|| PluginError::InvalidConfiguration {
+            reason: "no data directory for build metrics".to_string(),
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(PluginError::Io)?;
+        }
+        let line = serde_json::to_string(metric).map_err(|e| PluginError::Io(e.into()))?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(PluginError::Io)?;
+        writeln!(file, "{line}").map_err(PluginError::Io)
+    }
+
+    /// Recorded builds, oldest first; unreadable lines are skipped.
+    pub fn read() -> PluginResult<Vec<BuildMetric>> {
+        let Some(path) = Self::path() else {
+            return Ok(Vec::new());
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => Ok(contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(PluginError::Io(e)),
+        }
+    }
+
+    pub fn clear() -> PluginResult<()> {
+        match Self::path().map(fs::remove_file) {
+            Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => Err(PluginError::Io(e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Groups builds by target, toolchain and optimization level, in order of
+    /// first appearance.
+    pub fn summarize(metrics: &[BuildMetric]) -> Vec<MetricsSummary> {
+        let mut groups: Vec<(MetricsSummary, Vec<u64>, Vec<u64>)> = Vec::new();
+        for metric in metrics {
+            let index = groups
+                .iter()
+                .position(|(summary, _, _)| {
+                    summary.target.name() == metric.target.name()
+                        && summary.toolchain == metric.toolchain
+                        && summary.optimization == metric.optimization
+                })
+                .unwrap_or_else(|| {
+                    groups.push((
+                        MetricsSummary {
+                            target: metric.target.clone(),
+                            toolchain: metric.toolchain,
+                            optimization: metric.optimization,
+                            builds: 0,
+                            failures: 0,
+                            median_duration_ms: 0,
+                            max_duration_ms: 0,
+                            mean_wasm_size: None,
+                        },
+                        Vec::new(),
+                        Vec::new(),
+                    ));
+                    groups.len() - 1
+                });
+            let (summary, durations, sizes) = &mut groups[index];
+            summary.builds += 1;
+            if !metric.success {
+                summary.failures += 1;
+            }
+            durations.push(metric.duration_ms);
+            sizes.extend(metric.wasm_size.filter(|_| metric.success));
+        }
+
+        groups
+            .into_iter()
+            .map(|(mut summary, mut durations, sizes)| {
+                durations.sort_unstable();
+                summary.median_duration_ms = durations[durations.len() / 2];
+                summary.max_duration_ms = durations.last().copied().unwrap_or_default();
+                summary.mean_wasm_size =
+                    (!sizes.is_empty()).then(|| sizes.iter().sum::<u64>() / sizes.len() as u64);
+                summary
+            })
+            .collect()
+    }
+
+    fn error_kind(error: &PluginError) -> &'static str {
+        match error {
+            PluginError::CompilationFailed { .. } => "compilation-failed",
+            PluginError::DeploymentFailed { .. } => "deployment-failed",
+            PluginError::CompileToolNotFound { .. } => "tool-not-found",
+            PluginError::InvalidProjectStructure { .. } => "invalid-project",
+            PluginError::MissingEntryFile { .. } => "missing-entry-file",
+            PluginError::UnsupportedToolchainVersion { .. } => "unsupported-toolchain",
+            PluginError::UnsupportedTarget { .. } => "unsupported-target",
+            PluginError::InvalidWasm { .. } => "invalid-wasm",
+            PluginError::ExecutionFailed { .. } => "execution-failed",
+            PluginError::InvalidConfiguration { .. } => "invalid-configuration",
+            PluginError::OutputDirectoryCreationFailed { .. } => "output-directory",
+            PluginError::Io(_) => "io",
+        }
+    }
+}
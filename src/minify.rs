@@ -0,0 +1,535 @@
+use crate::{PluginError, PluginResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Keywords after which a `/` starts a regular expression rather than a division.
+const REGEX_PRECEDING_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "case",
+    "do",
+    "else",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "instanceof",
+    "yield",
+    "await",
+];
+
+/// Minified JavaScript and the source map positions of its tokens.
+#[derive(Debug, Clone)]
+pub struct MinifiedScript {
+    pub code: String,
+    /// `(generated line, generated column, original line, original column)`, zero-based
+    pub mappings: Vec<(usize, usize, usize, usize)>,
+}
+
+/// Outcome of minifying a build's generated glue.
+#[derive(Debug, Clone, Default)]
+pub struct MinifyReport {
+    pub files: Vec<PathBuf>,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub source_maps: Vec<PathBuf>,
+}
+
+/// Conservative minifier for the generated JavaScript and HTML.
+///
+/// It drops comments (except license and copyright notices) and collapses
+/// whitespace, but never renames identifiers or joins lines where automatic
+/// semicolon insertion could change meaning.
+pub struct Minifier;
+
+impl Minifier {
+    /// Minifies the `.js`/`.mjs`/`.html` files among `paths` in place; with
+    /// `source_maps`, scripts get a `<file>.map` (original source embedded).
+    pub fn minify_files(paths: &[PathBuf], source_maps: bool) -> PluginResult<MinifyReport> {
+        let mut report = MinifyReport::default();
+        for path in paths {
+            let extension = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or_default();
+            if !matches!(extension, "js" | "mjs" | "html") {
+                continue;
+            }
+            let source = fs::read_to_string(path).map_err(PluginError::Io)?;
+            let minified = if extension == "html" {
+                Self::minify_html(&source)
+            } else if source_maps {
+                let script = Self::minify_js(&source);
+                let map_path = Self::write_source_map(path, &source, &script)?;
+                let map_name = Path::new(&map_path)
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                report.source_maps.push(map_path);
+                format!("{}\n//# sourceMappingURL={map_name}\n", script.code)
+            } else {
+                Self::minify_js(&source).code
+            };
+
+            fs::write(path, &minified).map_err(PluginError::Io)?;
+            report.bytes_before += source.len() as u64;
+            report.bytes_after += minified.len() as u64;
+            report.files.push(path.clone());
+        }
+        Ok(report)
+    }
+
+    pub fn minify_js(source: &str) -> MinifiedScript {
+        let chars: Vec<char> = source.chars().collect();
+        let mut scanner = ScriptScanner {
+            chars: &chars,
+            position: 0,
+            line: 0,
+            column: 0,
+            output: String::new(),
+            output_line: 0,
+            output_column: 0,
+            mappings: Vec::new(),
+            last_word: String::new(),
+            open_parentheses: Vec::new(),
+            closed_condition: false,
+        };
+        scanner.run();
+        MinifiedScript {
+            code: scanner.output,
+            mappings: scanner.mappings,
+        }
+    }
+
+    /// Drops comments and collapses whitespace runs to one space (or one
+    /// newline), which renders identically; scripts are minified, and
+    /// `<pre>`, `<textarea>` and `<style>` contents are kept verbatim.
+    pub fn minify_html(source: &str) -> String {
+        let lowercase = source.to_ascii_lowercase();
+        let mut output = String::with_capacity(source.len());
+        let mut index = 0;
+
+        while index < source.len() {
+            let rest = &source[index..];
+            if rest.starts_with("<!--") && !rest.starts_with("<!--[if") {
+                index += rest.find("-->").map_or(rest.len(), |end| end + 3);
+                continue;
+            }
+            if let Some(tag) = ["script", "pre", "textarea", "style"]
+                .iter()
+                .find(|tag| Self::opens_element(&lowercase[index..], tag))
+            {
+                let Some(open_end) = rest.find('>') else {
+                    output.push_str(rest);
+                    break;
+                };
+                let open_tag = &rest[..=open_end];
+                let closing = format!("</{tag}");
+                let content_end = lowercase[index + open_end + 1..]
+                    .find(&closing)
+                    .map_or(rest.len(), |end| open_end + 1 + end);
+                let content = &rest[open_end + 1..content_end];
+
+                output.push_str(open_tag);
+                if *tag == "script" && Self::is_javascript(&open_tag.to_ascii_lowercase()) {
+                    output.push_str(&Self::minify_js(content).code);
+                } else {
+                    output.push_str(content);
+                }
+                index += content_end;
+                continue;
+            }
+
+            let character = rest.chars().next().unwrap_or_default();
+            if character.is_whitespace() {
+                let run_length = rest
+                    .find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len());
+                // A dropped comment can leave two runs next to each other
+                let spans_lines = rest[..run_length].contains('\n');
+                match output.chars().last() {
+                    Some('\n') => {}
+                    Some(' ') if !spans_lines => {}
+                    Some(' ') => {
+                        output.pop();
+                        output.push('\n');
+                    }
+                    _ => output.push(if spans_lines { '\n' } else { ' ' }),
+                }
+                index += run_length;
+            } else {
+                output.push(character);
+                index += character.len_utf8();
+            }
+        }
+        output.trim().to_string() + "\n"
+    }
+
+    fn opens_element(rest: &str, tag: &str) -> bool {
+        rest.strip_prefix('<')
+            .and_then(|rest| rest.strip_prefix(tag))
+            .is_some_and(|rest| rest.starts_with(['>', ' ', '\t', '\n', '\r']))
+    }
+
+    fn is_javascript(open_tag: &str) -> bool {
+        match open_tag.split_once("type=") {
+            None => true,
+            Some((_, value)) => {
+                let value = value.trim_start_matches(['"', '\'']);
+                value.starts_with("module")
+                    || value.starts_with("text/javascript")
+                    || value.starts_with("application/javascript")
+            }
+        }
+    }
+
+    fn write_source_map(
+        path: &Path,
+        source: &str,
+        script: &MinifiedScript,
+    ) -> PluginResult<PathBuf> {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let source_map = serde_json::json!({
+            "version": 3,
+            "file": file_name,
+            "sources": [file_name],
+            "sourcesContent": [source],
+            "names": [],
+            "mappings": Self::encode_mappings(&script.mappings),
+        });
+        let mut map_name = path.file_name().unwrap_or_default().to_os_string();
+        map_name.push(".map");
+        let map_path = path.with_file_name(map_name);
+        fs::write(&map_path, source_map.to_string()).map_err(PluginError::Io)?;
+        Ok(map_path)
+    }
+
+    // Source map v3 `mappings`: `;`-separated lines of `,`-separated segments,
+    // each a base64 VLQ of deltas from the previous segment
+    fn encode_mappings(mappings: &[(usize, usize, usize, usize)]) -> String {
+        let mut encoded = String::new();
+        let mut current_line = 0;
+        let mut previous_column = 0i64;
+        let mut previous_original_line = 0i64;
+        let mut previous_original_column = 0i64;
+        let mut first_in_line = true;
+
+        for &(line, column, original_line, original_column) in mappings {
+            while current_line < line {
+                encoded.push(';');
+                current_line += 1;
+                previous_column = 0;
+                first_in_line = true;
+            }
+            if !first_in_line {
+                encoded.push(',');
+            }
+            first_in_line = false;
+            for value in [
+                column as i64 - previous_column,
+                0,
+                original_line as i64 - previous_original_line,
+                original_column as i64 - previous_original_column,
+            ] {
+                Self::push_vlq(&mut encoded, value);
+            }
+            previous_column = column as i64;
+            previous_original_line = original_line as i64;
+            previous_original_column = original_column as i64;
+        }
+        encoded
+    }
+
+    fn push_vlq(encoded: &mut String, value: i64) {
+        let mut remaining = if value < 0 {
+            ((-value) << 1) | 1
+        } else {
+            value << 1
+        };
+        loop {
+            let mut digit = remaining & 31;
+            remaining >>= 5;
+            if remaining > 0 {
+                digit |= 32;
+            }
+            encoded.push(BASE64_ALPHABET[digit as usize] as char);
+            if remaining == 0 {
+                break;
+            }
+        }
+    }
+}
+
+struct ScriptScanner<'a> {
+    chars: &'a [char],
+    position: usize,
+    line: usize,
+    column: usize,
+    output: String,
+    output_line: usize,
+    output_column: usize,
+    mappings: Vec<(usize, usize, usize, usize)>,
+    last_word: String,
+    /// For each open `(`, whether it holds an `if`/`for`/`while`/`with` condition
+    open_parentheses: Vec<bool>,
+    /// Whether the last `)` closed such a condition, after which `/` starts a regex
+    closed_condition: bool,
+}
+
+impl ScriptScanner<'_> {
+    fn run(&mut self) {
+        // Whitespace and dropped comments seen since the last token: `Some(true)` if they span lines
+        let mut pending_gap: Option<bool> = None;
+        self.map_position();
+
+        while let Some(character) = self.peek(0) {
+            if character.is_whitespace() {
+                let spans_lines = self.skip_while(char::is_whitespace);
+                pending_gap = Some(pending_gap.unwrap_or(false) || spans_lines);
+                continue;
+            }
+            if character == '/' && matches!(self.peek(1), Some('/' | '*')) {
+                let start = self.position;
+                let block = self.peek(1) == Some('*');
+                let spans_lines = if block {
+                    self.skip_block_comment()
+                } else {
+                    self.skip_while(|c| c != '\n');
+                    false
+                };
+                let comment: String = self.chars[start..self.position].iter().collect();
+                if comment.contains("Copyright") || comment.contains("@license") {
+                    self.flush_gap(pending_gap.take(), '/');
+                    self.emit_str(&comment);
+                    self.last_word.clear();
+                    pending_gap = (!block).then_some(true);
+                } else {
+                    pending_gap = Some(pending_gap.unwrap_or(false) || spans_lines);
+                }
+                continue;
+            }
+
+            self.flush_gap(pending_gap.take(), character);
+            match character {
+                '"' | '\'' => self.copy_string(character),
+                '`' => self.copy_template(),
+                '/' if self.starts_regex() => self.copy_regex(),
+                _ => {
+                    if Self::is_word(character) {
+                        let last_output = self.output.chars().last();
+                        if !last_output.is_some_and(Self::is_word) {
+                            self.last_word.clear();
+                        }
+                        self.last_word.push(character);
+                    } else {
+                        match character {
+                            '(' => self.open_parentheses.push(matches!(
+                                self.last_word.as_str(),
+                                "if" | "for" | "while" | "with"
+                            )),
+                            ')' => {
+                                self.closed_condition = self.open_parentheses.pop().unwrap_or(false)
+                            }
+                            _ => {}
+                        }
+                        self.last_word.clear();
+                    }
+                    self.copy_char();
+                }
+            }
+        }
+    }
+
+    fn flush_gap(&mut self, gap: Option<bool>, next: char) {
+        let Some(spans_lines) = gap else {
+            return;
+        };
+        let previous = self.output.chars().last();
+        let separator = match previous {
+            None => None,
+            // A newline is only removed where automatic semicolon insertion can't apply
+            Some(previous) if spans_lines => {
+                if "{;,([".contains(previous) || "})],;".contains(next) {
+                    None
+                } else {
+                    Some('\n')
+                }
+            }
+            Some(previous) => {
+                let joins_words = Self::is_word(previous) && Self::is_word(next);
+                // `a + +b` must not become `a++b`
+                let joins_operators = "+-".contains(previous) && "+-".contains(next);
+                (joins_words || joins_operators).then_some(' ')
+            }
+        };
+        if let Some(separator) = separator {
+            self.emit(separator);
+        }
+        self.map_position();
+    }
+
+    fn starts_regex(&self) -> bool {
+        match self.output.trim_end().chars().last() {
+            None => true,
+            Some(previous) if Self::is_word(previous) => {
+                REGEX_PRECEDING_KEYWORDS.contains(&self.last_word.as_str())
+            }
+            Some(')') => self.closed_condition,
+            Some(previous) => "(,=:[!&|?{};+-*%<>~^".contains(previous),
+        }
+    }
+
+    fn copy_string(&mut self, quote: char) {
+        self.copy_char();
+        while let Some(character) = self.peek(0) {
+            self.copy_char();
+            if character == '\\' {
+                self.copy_char();
+            } else if character == quote || character == '\n' {
+                break;
+            }
+        }
+        self.last_word.clear();
+    }
+
+    fn copy_template(&mut self) {
+        self.copy_char();
+        while let Some(character) = self.peek(0) {
+            match character {
+                '\\' => {
+                    self.copy_char();
+                    self.copy_char();
+                }
+                '`' => {
+                    self.copy_char();
+                    break;
+                }
+                '$' if self.peek(1) == Some('{') => {
+                    self.copy_char();
+                    self.copy_char();
+                    self.copy_substitution();
+                }
+                _ => self.copy_char(),
+            }
+        }
+        self.last_word.clear();
+    }
+
+    // Copies a `${...}` substitution verbatim, through its closing brace
+    fn copy_substitution(&mut self) {
+        let mut depth = 1;
+        while let Some(character) = self.peek(0) {
+            match character {
+                '"' | '\'' => self.copy_string(character),
+                '`' => self.copy_template(),
+                '{' => {
+                    depth += 1;
+                    self.copy_char();
+                }
+                '}' => {
+                    depth -= 1;
+                    self.copy_char();
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => self.copy_char(),
+            }
+        }
+    }
+
+    fn copy_regex(&mut self) {
+        self.copy_char();
+        let mut in_class = false;
+        while let Some(character) = self.peek(0) {
+            if character == '\n' {
+                break;
+            }
+            self.copy_char();
+            match character {
+                '\\' => self.copy_char(),
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                _ => {}
+            }
+        }
+        self.last_word.clear();
+    }
+
+    fn skip_block_comment(&mut self) -> bool {
+        let mut spans_lines = false;
+        self.advance();
+        self.advance();
+        while let Some(character) = self.peek(0) {
+            if character == '*' && self.peek(1) == Some('/') {
+                self.advance();
+                self.advance();
+                break;
+            }
+            spans_lines |= character == '\n';
+            self.advance();
+        }
+        spans_lines
+    }
+
+    fn skip_while(&mut self, predicate: impl Fn(char) -> bool) -> bool {
+        let mut spans_lines = false;
+        while let Some(character) = self.peek(0).filter(|&c| predicate(c)) {
+            spans_lines |= character == '\n';
+            self.advance();
+        }
+        spans_lines
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let character = self.peek(0)?;
+        self.position += 1;
+        if character == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        Some(character)
+    }
+
+    fn copy_char(&mut self) {
+        if let Some(character) = self.advance() {
+            self.emit(character);
+        }
+    }
+
+    fn emit(&mut self, character: char) {
+        self.output.push(character);
+        if character == '\n' {
+            self.output_line += 1;
+            self.output_column = 0;
+        } else {
+            self.output_column += 1;
+        }
+    }
+
+    fn emit_str(&mut self, text: &str) {
+        text.chars().for_each(|character| self.emit(character));
+    }
+
+    fn map_position(&mut self) {
+        self.mappings
+            .push((self.output_line, self.output_column, self.line, self.column));
+    }
+
+    fn is_word(character: char) -> bool {
+        character.is_alphanumeric() || character == '_' || character == '$'
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{PluginError, PluginResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories never copied into a build directory.
+const SKIPPED_DIRECTORIES: &[&str] = &[".git", ".hg", ".svn"];
+
+/// What [`SourceMirror::sync`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MirrorReport {
+    pub copied: usize,
+    pub unchanged: usize,
+    /// Files removed from the build directory because the project no longer has them
+    pub removed: usize,
+}
+
+/// Keeps a copy of a project's sources in a separate build directory, so
+/// out-of-tree builds never write into the project.
+pub struct SourceMirror;
+
+impl SourceMirror {
+    /// Copies new and changed files from `project` into `build_directory` and
+    /// removes files the project no longer has. Anything under `excluded`
+    /// (relative to either side) is left alone.
+    pub fn sync(
+        project: &Path,
+        build_directory: &Path,
+        excluded: &[PathBuf],
+    ) -> PluginResult<MirrorReport> {
+        let project = fs::canonicalize(project).map_err(PluginError::Io)?;
+        fs::create_dir_all(build_directory).map_err(PluginError::Io)?;
+        let build_directory = fs::canonicalize(build_directory).map_err(PluginError::Io)?;
+        if project == build_directory {
+            return Err(PluginError::InvalidConfiguration {
+                reason: "the build directory cannot be the project directory".to_string(),
+            });
+        }
+
+        let mut report = MirrorReport::default();
+        Self::copy_tree(
+            &project,
+            &build_directory,
+            Path::new(""),
+            excluded,
+            &mut report,
+        )?;
+        Self::remove_stale(
+            &project,
+            &build_directory,
+            Path::new(""),
+            excluded,
+            &mut report,
+        )?;
+        Ok(report)
+    }
+
+    fn is_skipped(
+        relative: &Path,
+        absolute: &Path,
+        build_directory: &Path,
+        excluded: &[PathBuf],
+    ) -> bool {
+        let name = relative.file_name().unwrap_or_default();
+        SKIPPED_DIRECTORIES.iter().any(|skipped| name == *skipped)
+            || excluded.iter().any(|excluded| relative == excluded)
+            // A build directory nested inside the project
+            || build_directory.starts_with(absolute)
+    }
+
+    fn copy_tree(
+        project: &Path,
+        build_directory: &Path,
+        relative: &Path,
+        excluded: &[PathBuf],
+        report: &mut MirrorReport,
+    ) -> PluginResult<()> {
+        let source_directory = project.join(relative);
+        fs::create_dir_all(build_directory.join(relative)).map_err(PluginError::Io)?;
+        for entry in fs::read_dir(&source_directory).map_err(PluginError::Io)? {
+            let entry = entry.map_err(PluginError::Io)?;
+            let entry_relative = relative.join(entry.file_name());
+            if Self::is_skipped(&entry_relative, &entry.path(), build_directory, excluded) {
+                continue;
+            }
+            let source = entry.path();
+            let destination = build_directory.join(&entry_relative);
+            let metadata = fs::metadata(&source).map_err(PluginError::Io)?;
+            if metadata.is_dir() {
+                Self::copy_tree(project, build_directory, &entry_relative, excluded, report)?;
+                continue;
+            }
+
+            // A copy is newer than its source until the source changes again
+            let up_to_date = fs::metadata(&destination).is_ok_and(|existing| {
+                existing.len() == metadata.len()
+                    && matches!(
+                        (existing.modified(), metadata.modified()),
+                        (Ok(copied), Ok(modified)) if copied >= modified
+                    )
+            });
+            if up_to_date {
+                report.unchanged += 1;
+            } else {
+                fs::copy(&source, &destination).map_err(PluginError::Io)?;
+                report.copied += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_stale(
+        project: &Path,
+        build_directory: &Path,
+        relative: &Path,
+        excluded: &[PathBuf],
+        report: &mut MirrorReport,
+    ) -> PluginResult<()> {
+        for entry in fs::read_dir(build_directory.join(relative)).map_err(PluginError::Io)? {
+            let entry = entry.map_err(PluginError::Io)?;
+            let entry_relative = relative.join(entry.file_name());
+            if excluded.iter().any(|excluded| &entry_relative == excluded) {
+                continue;
+            }
+            let source = project.join(&entry_relative);
+            let is_directory = entry.file_type().map_err(PluginError::Io)?.is_dir();
+            match (source.exists(), is_directory) {
+                (true, true) if source.is_dir() => {
+                    Self::remove_stale(project, build_directory, &entry_relative, excluded, report)?
+                }
+                (true, false) if source.is_file() => {}
+                (_, true) => {
+                    fs::remove_dir_all(entry.path()).map_err(PluginError::Io)?;
+                    report.removed += 1;
+                }
+                (_, false) => {
+                    fs::remove_file(entry.path()).map_err(PluginError::Io)?;
+                    report.removed += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{Dirs, PluginError, PluginResult, SelfUpdater, ToolRegistry};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Set to `1` (or any value but `0`) to turn update notices off.
+pub const NO_UPDATE_CHECK_VARIABLE: &str = "WASMGO_NO_UPDATE_CHECK";
+
+/// How long the last check's result is trusted.
+pub const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const STATE_FILENAME: &str = "update-check.json";
+
+const TINYGO_RELEASES_URL: &str = "https://api.github.com/repos/tinygo-org/tinygo/releases/latest";
+
+/// Latest versions as of the last check, kept in the cache directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UpdateCheckState {
+    /// Seconds since the Unix epoch
+    checked_at: u64,
+    #[serde(default)]
+    wasmgo: Option<String>,
+    #[serde(default)]
+    tinygo: Option<String>,
+}
+
+/// A newer release of wasmgo or of the installed TinyGo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateNotice {
+    pub tool: &'static str,
+    pub installed: String,
+    pub latest: String,
+    pub changelog_url: String,
+}
+
+/// Hidden CLI subcommand the background check runs as.
+pub const REFRESH_SUBCOMMAND: &str = "__refresh-update-check";
+
+/// Tells users about newer wasmgo and TinyGo releases after successful
+/// commands.
+///
+/// At most once per [`UPDATE_CHECK_INTERVAL`], the latest versions are fetched
+/// by a detached `<exe> __refresh-update-check` process; notices come from the
+/// cached result, so a slow or offline network never delays a command. Off
+/// when [`NO_UPDATE_CHECK_VARIABLE`] is set, in CI, and when stderr is not a
+/// terminal.
+pub struct UpdateNotifier;
+
+impl UpdateNotifier {
+    pub fn is_enabled() -> bool {
+        let opted_out = std::env::var_os(NO_UPDATE_CHECK_VARIABLE)
+            .is_some_and(|value| !value.is_empty() && value != "0");
+        !opted_out && std::env::var_os("CI").is_none() && std::io::stderr().is_terminal()
+    }
+
+    /// Starts a background check if the cached one is stale.
+    pub fn start() -> Self {
+        let stale = Self::is_enabled()
+            && Self::state_path().is_some()
+            && Self::read_state().is_none_or(|state| {
+                Self::now().saturating_sub(state.checked_at) >= UPDATE_CHECK_INTERVAL.as_secs()
+            });
+        if stale {
+            if let Ok(executable) = std::env::current_exe() {
+                let mut command = Command::new(executable);
+                command
+                    .arg(REFRESH_SUBCOMMAND)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null());
+                // Out of the terminal's process group, so Ctrl-C leaves it alone
+                #[cfg(unix)]
+                std::os::unix::process::CommandExt::process_group(&mut command, 0);
+                let _ = command.spawn();
+            }
+        }
+        Self
+    }
+
+    /// Newer releases known from the last check.
+    pub fn notices() -> Vec<UpdateNotice> {
+        let Some(state) = Self::read_state() else {
+            return Vec::new();
+        };
+        let newer = |latest: &str, installed: &str| {
+            matches!(
+                (SelfUpdater::parse_version(latest), SelfUpdater::parse_version(installed)),
+                (Some(latest), Some(installed)) if latest > installed
+            )
+        };
+
+        let mut notices = Vec::new();
+        let current = SelfUpdater::current_version();
+        if let Some(latest) = state.wasmgo.filter(|latest| newer(latest, current)) {
+            notices.push(UpdateNotice {
+                tool: "wasmgo",
+                installed: current.to_string(),
+                changelog_url: format!(
+                    "https://github.com/{}/releases/tag/v{latest}",
+                    crate::RELEASES_REPOSITORY
+                ),
+                latest,
+            });
+        }
+        // Probing TinyGo only when there is something to compare it with
+        let installed_tinygo = || {
+            ToolRegistry::version_output("tinygo")
+                .and_then(|output| output.split_whitespace().nth(2).map(str::to_string))
+        };
+        if let Some((latest, installed)) = state
+            .tinygo
+            .and_then(|latest| Some((latest, installed_tinygo()?)))
+        {
+            if newer(&latest, &installed) {
+                notices.push(UpdateNotice {
+                    tool: "TinyGo",
+                    installed,
+                    changelog_url: format!(
+                        "https://github.com/tinygo-org/tinygo/releases/tag/v{latest}"
+                    ),
+                    latest,
+                });
+            }
+        }
+        notices
+    }
+
+    /// Prints one line per notice to stderr. The background check is not
+    /// waited for; what it finds shows up after the next command.
+    pub fn print_notices(&self) {
+        if !Self::is_enabled() {
+            return;
+        }
+        for notice in Self::notices() {
+            let upgrade = if notice.tool == "wasmgo" {
+                "run `wasmgo upgrade`; "
+            } else {
+                ""
+            };
+            eprintln!(
+                "💡 {} {} is available (you have {}): {upgrade}changes at {}",
+                notice.tool, notice.latest, notice.installed, notice.changelog_url
+            );
+        }
+    }
+
+    /// Fetches the latest versions and caches them.
+    pub fn refresh() -> PluginResult<()> {
+        let path = Self::state_path().ok_or_else(|| PluginError::InvalidConfiguration {
+            reason: "no cache directory for the update check".to_string(),
+        })?;
+        let tinygo = SelfUpdater::fetch_json(TINYGO_RELEASES_URL, false)
+            .ok()
+            .and_then(|release| {
+                release["tag_name"]
+                    .as_str()
+                    .map(|tag| tag.trim_start_matches('v').to_string())
+            });
+        let state = UpdateCheckState {
+            checked_at: Self::now(),
+            wasmgo: SelfUpdater::latest_release(false)
+                .ok()
+                .map(|release| release.version),
+            tinygo,
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(PluginError::Io)?;
+        }
+        // Renamed into place, since the process may exit mid-write
+        let temporary = path.with_extension(format!("{}.tmp", std::process::id()));
+        let contents = serde_json::to_string(&state).map_err(|e| PluginError::Io(e.into()))?;
+        fs::write(&temporary, contents).map_err(PluginError::Io)?;
+        fs::rename(&temporary, &path).map_err(PluginError::Io)
+    }
+
+    fn state_path() -> Option<PathBuf> {
+        Dirs::discover().map(|dirs| dirs.cache.join(STATE_FILENAME))
+    }
+
+    fn read_state() -> Option<UpdateCheckState> {
+        let contents = fs::read_to_string(Self::state_path()?).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+}
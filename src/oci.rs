@@ -0,0 +1,151 @@
+use crate::{
+    CommandExecutor, HashedArtifacts, PathResolver, PluginError, PluginResult, WasmBinary, WasmKind,
+};
+use serde::Serialize;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::Path;
+
+/// Config media type of the CNCF Wasm OCI artifact layout.
+pub const WASM_CONFIG_MEDIA_TYPE: &str = "application/vnd.wasm.config.v0+json";
+
+/// Layer media type of the module or component itself.
+pub const WASM_LAYER_MEDIA_TYPE: &str = "application/wasm";
+
+/// Layer media type of the `wasmgo.manifest.json` pushed alongside the module.
+pub const BUILD_MANIFEST_MEDIA_TYPE: &str = "application/vnd.wasmgo.manifest.v1+json";
+
+const CONFIG_FILENAME: &str = "wasmgo.oci-config.json";
+
+/// Artifact config in the `application/vnd.wasm.config.v0+json` format.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WasmArtifactConfig {
+    architecture: String,
+    os: String,
+    /// Digests of the `application/wasm` layers
+    layer_digests: Vec<String>,
+}
+
+/// Publishes build artifacts to an OCI registry with `oras`.
+pub struct OciPublisher;
+
+impl OciPublisher {
+    /// Pushes `wasm_file_path` (and the build manifest, if given) to `reference`,
+    /// e.g. `ghcr.io/org/app:tag`, returning the digest of the pushed manifest.
+    ///
+    /// Registry credentials come from `oras login` / the docker credential store.
+    pub fn push(
+        reference: &str,
+        wasm_file_path: &Path,
+        build_manifest_path: Option<&Path>,
+        annotations: &[String],
+        verbose: bool,
+    ) -> PluginResult<String> {
+        if !CommandExecutor::is_tool_installed("oras") {
+            return Err(PluginError::CompileToolNotFound {
+                tool: "oras".to_string(),
+            });
+        }
+        if let Some(annotation) = annotations.iter().find(|a| !a.contains('=')) {
+            return Err(PluginError::DeploymentFailed {
+                reason: format!("annotation `{annotation}` is not in key=value form"),
+            });
+        }
+
+        let config = Self::config(wasm_file_path)?;
+
+        // oras names layers after the paths it is given and rejects absolute
+        // ones, so the push runs from a staging directory holding just the layers
+        let staging_directory =
+            PathResolver::private_directory(&std::env::temp_dir(), "wasmgo-oci-")?;
+        Self::push_staged(
+            reference,
+            staging_directory.path(),
+            &config,
+            wasm_file_path,
+            build_manifest_path,
+            annotations,
+            verbose,
+        )
+    }
+
+    fn push_staged(
+        reference: &str,
+        staging_directory: &Path,
+        config: &WasmArtifactConfig,
+        wasm_file_path: &Path,
+        build_manifest_path: Option<&Path>,
+        annotations: &[String],
+        verbose: bool,
+    ) -> PluginResult<String> {
+        let config_json =
+            serde_json::to_string_pretty(config).map_err(|e| PluginError::Io(e.into()))?;
+        PathResolver::write_private(&staging_directory.join(CONFIG_FILENAME), &config_json)?;
+
+        let mut layers = vec![(wasm_file_path, WASM_LAYER_MEDIA_TYPE)];
+        if let Some(build_manifest_path) = build_manifest_path {
+            layers.push((build_manifest_path, BUILD_MANIFEST_MEDIA_TYPE));
+        }
+        let mut layer_arguments = Vec::new();
+        for (path, media_type) in layers {
+            let name = path.file_name().unwrap_or_default();
+            fs::copy(path, staging_directory.join(name)).map_err(PluginError::Io)?;
+            let mut layer_argument = name.to_os_string();
+            layer_argument.push(format!(":{media_type}"));
+            layer_arguments.push(layer_argument);
+        }
+
+        let config_argument = format!("{CONFIG_FILENAME}:{WASM_CONFIG_MEDIA_TYPE}");
+        let mut arguments: Vec<&OsStr> = ["push", reference, "--config", &config_argument]
+            .into_iter()
+            .map(OsStr::new)
+            .collect();
+        for annotation in annotations {
+            arguments.extend([OsStr::new("--annotation"), OsStr::new(annotation)]);
+        }
+        arguments.extend(layer_arguments.iter().map(OsString::as_os_str));
+
+        let output =
+            CommandExecutor::execute_command("oras", &arguments, staging_directory, verbose)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            return Err(PluginError::DeploymentFailed {
+                reason: format!(
+                    "oras push failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+
+        Ok(stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Digest:"))
+            .map(|digest| digest.trim().to_string())
+            .unwrap_or_default())
+    }
+
+    fn config(wasm_file_path: &Path) -> PluginResult<WasmArtifactConfig> {
+        let binary = WasmBinary::read(wasm_file_path)?;
+        let os = if binary.kind == WasmKind::Component {
+            "wasip2"
+        } else if binary
+            .imported_modules()
+            .contains(&"wasi_snapshot_preview1")
+        {
+            "wasip1"
+        } else {
+            // js/wasm builds run against wasm_exec.js
+            "js"
+        };
+
+        Ok(WasmArtifactConfig {
+            architecture: "wasm".to_string(),
+            os: os.to_string(),
+            layer_digests: vec![format!(
+                "sha256:{}",
+                HashedArtifacts::sha256(wasm_file_path)?
+            )],
+        })
+    }
+}
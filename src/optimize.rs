@@ -0,0 +1,329 @@
+use crate::{
+    CommandExecutor, Dirs, Logger, OptimizationLevel, PluginError, PluginResult, StateDirectory,
+    ToolRegistry,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Post-processed modules kept for reuse, most recently used first.
+const MEMOIZED_OUTPUTS: usize = 16;
+
+/// wasm-opt optimization levels, as passed on its command line.
+pub const WASM_OPT_LEVELS: &[&str] = &["O0", "O1", "O2", "O3", "O4", "Os", "Oz"];
+
+// Features emitted by both TinyGo and the standard Go toolchain
+const WASM_OPT_FEATURES: &[&str] = &[
+    "--enable-bulk-memory",
+    "--enable-sign-ext",
+    "--enable-nontrapping-float-to-int",
+    "--enable-mutable-globals",
+];
+
+/// Functions that only format and print panic messages. Snipping them makes a
+/// panic trap with `unreachable` instead of reporting what went wrong.
+pub const DEFAULT_SNIP_PATTERNS: &[&str] = &[
+    r"runtime\.printpanicval.*",
+    r"runtime\.printitf",
+    r"runtime\.printanycustomtype",
+    r"runtime\.printpanics",
+    r"runtime\.runtimePanicAt",
+];
+
+/// Post-processing steps applied to a compiled module.
+#[derive(Debug, Clone, Default)]
+pub struct OptimizationPipeline {
+    /// wasm-opt level such as `Oz`; `None` skips wasm-opt
+    pub wasm_opt_level: Option<String>,
+    /// Additional wasm-opt arguments, e.g. `--converge` or `--enable-simd`
+    pub wasm_opt_passes: Vec<String>,
+    /// Remove debug info and producer custom sections
+    pub strip: bool,
+    /// wasm-snip regexes of functions to replace with `unreachable`, run before wasm-opt
+    pub snip_patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationStep {
+    pub name: String,
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    pub input_size: u64,
+    pub output_size: u64,
+    pub steps: Vec<OptimizationStep>,
+    /// Reused from an earlier run on an identical module with the same steps
+    #[serde(skip)]
+    pub memoized: bool,
+}
+
+impl OptimizationReport {
+    pub fn bytes_saved(&self) -> i64 {
+        self.input_size as i64 - self.output_size as i64
+    }
+
+    pub fn percent_saved(&self) -> f64 {
+        if self.input_size == 0 {
+            return 0.0;
+        }
+        self.bytes_saved() as f64 * 100.0 / self.input_size as f64
+    }
+}
+
+impl OptimizationPipeline {
+    /// Pipeline implied by a build's optimization level.
+    pub fn for_level(optimization_level: &OptimizationLevel) -> Self {
+        match optimization_level {
+            OptimizationLevel::Debug => Self::default(),
+            OptimizationLevel::Release => Self {
+                wasm_opt_level: Some("O2".to_string()),
+                ..Self::default()
+            },
+            OptimizationLevel::Size => Self {
+                wasm_opt_level: Some("Oz".to_string()),
+                strip: true,
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wasm_opt_level.is_none() && !self.strip && self.snip_patterns.is_empty()
+    }
+
+    /// Aggressive size mode: snips the panic formatting machinery ahead of `-Oz`.
+    pub fn aggressive() -> Self {
+        Self {
+            wasm_opt_level: Some("Oz".to_string()),
+            strip: true,
+            snip_patterns: DEFAULT_SNIP_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Runs the pipeline on `input_path`, writing the result to `output_path`
+    /// (which may be the same file).
+    ///
+    /// Outputs are memoized by the hash of the input module, the steps and
+    /// their flags, and the tools' versions, so an unchanged module (say, a
+    /// watch rebuild after an asset edit) is copied from the build cache
+    /// instead of being optimized again.
+    pub fn run(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        verbose: bool,
+    ) -> PluginResult<OptimizationReport> {
+        if let Some(level) = &self.wasm_opt_level {
+            if !WASM_OPT_LEVELS.contains(&level.as_str()) {
+                return Err(PluginError::CompilationFailed {
+                    reason: format!(
+                        "Unknown wasm-opt level {level}; expected one of {}",
+                        WASM_OPT_LEVELS.join(", ")
+                    ),
+                });
+            }
+        }
+
+        let input_size = Self::file_size(input_path)?;
+        if !self.snip_patterns.is_empty() {
+            Logger::warn(format_args!(
+                "⚠️  Snipping functions matching: {}",
+                self.snip_patterns.join(", ")
+            ));
+            Logger::warn(format_args!(
+                "   Snipped functions trap when called, so panics abort without a message"
+            ));
+        }
+
+        let memo = (!self.is_empty())
+            .then(|| self.memo_path(input_path))
+            .flatten();
+        if let Some(report) = memo
+            .as_deref()
+            .and_then(|memo| Self::reuse(memo, output_path))
+        {
+            if verbose {
+                Logger::debug(format_args!(
+                    "Reused the post-processed module for {}",
+                    input_path.display()
+                ));
+            }
+            return Ok(report);
+        }
+
+        if input_path != output_path {
+            fs::copy(input_path, output_path).map_err(PluginError::Io)?;
+        }
+
+        let mut steps = Vec::new();
+        if !self.snip_patterns.is_empty() {
+            let mut arguments = Vec::new();
+            for pattern in &self.snip_patterns {
+                arguments.extend(["-p", pattern.as_str()]);
+            }
+            steps.push(Self::run_step(
+                "wasm-snip",
+                "wasm-snip",
+                &arguments,
+                output_path,
+                verbose,
+            )?);
+        }
+
+        if let Some(level) = &self.wasm_opt_level {
+            let level_flag = format!("-{level}");
+            let mut arguments: Vec<&str> = vec![&level_flag];
+            arguments.extend(WASM_OPT_FEATURES);
+            arguments.extend(self.wasm_opt_passes.iter().map(String::as_str));
+            steps.push(Self::run_step(
+                &format!("wasm-opt -{level}"),
+                "wasm-opt",
+                &arguments,
+                output_path,
+                verbose,
+            )?);
+        }
+
+        if self.strip {
+            steps.push(if CommandExecutor::is_tool_installed("wasm-tools") {
+                Self::run_step("strip", "wasm-tools", &["strip"], output_path, verbose)?
+            } else {
+                Self::run_step(
+                    "strip",
+                    "wasm-opt",
+                    &["--strip-debug", "--strip-producers"],
+                    output_path,
+                    verbose,
+                )?
+            });
+        }
+
+        let report = OptimizationReport {
+            input_size,
+            output_size: Self::file_size(output_path)?,
+            steps,
+            memoized: false,
+        };
+        if let Some(memo) = &memo {
+            Self::memoize(memo, output_path, &report);
+        }
+        Ok(report)
+    }
+
+    /// `<builds>/optimized/<key>.wasm`, or `None` without a state directory or
+    /// a readable input.
+    fn memo_path(&self, input_path: &Path) -> Option<PathBuf> {
+        let root = Dirs::discover()?
+            .state(StateDirectory::Builds)
+            .join("optimized");
+        let mut hasher = Sha256::new();
+        hasher.update(fs::read(input_path).ok()?);
+        hasher.update(format!("{self:?}\n{WASM_OPT_FEATURES:?}\n"));
+        for tool in ["wasm-snip", "wasm-opt", "wasm-tools"] {
+            let version = ToolRegistry::version_output(tool).unwrap_or_default();
+            hasher.update(format!("{tool}: {version}\n"));
+        }
+        let key: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Some(root.join(key).with_extension("wasm"))
+    }
+
+    fn reuse(memo: &Path, output_path: &Path) -> Option<OptimizationReport> {
+        let report = fs::read_to_string(memo.with_extension("json")).ok()?;
+        let mut report: OptimizationReport = serde_json::from_str(&report).ok()?;
+        fs::copy(memo, output_path).ok()?;
+        // Refreshes the entry's age for `wasmgo prune`
+        let _ = fs::File::options()
+            .append(true)
+            .open(memo)
+            .and_then(|file| file.set_modified(std::time::SystemTime::now()));
+        report.memoized = true;
+        Some(report)
+    }
+
+    /// Failures to write the memo are ignored; the next run optimizes again.
+    fn memoize(memo: &Path, output_path: &Path, report: &OptimizationReport) {
+        let Some(directory) = memo.parent() else {
+            return;
+        };
+        let Ok(report) = serde_json::to_string(report) else {
+            return;
+        };
+        if fs::create_dir_all(directory).is_err()
+            || fs::copy(output_path, memo).is_err()
+            || fs::write(memo.with_extension("json"), report).is_err()
+        {
+            return;
+        }
+
+        let mut entries: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(directory)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "wasm")
+            })
+            .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path)))
+            .collect();
+        entries.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        for (_, stale) in entries.into_iter().skip(MEMOIZED_OUTPUTS) {
+            let _ = fs::remove_file(stale.with_extension("json"));
+            let _ = fs::remove_file(stale);
+        }
+    }
+
+    // Rewrites `module_path` in place with `<tool> <arguments> <module> -o <module>`
+    fn run_step(
+        step_name: &str,
+        tool: &str,
+        arguments: &[&str],
+        module_path: &Path,
+        verbose: bool,
+    ) -> PluginResult<OptimizationStep> {
+        if !CommandExecutor::is_tool_installed(tool) {
+            return Err(PluginError::CompileToolNotFound {
+                tool: tool.to_string(),
+            });
+        }
+
+        let size_before = Self::file_size(module_path)?;
+        let mut step_arguments: Vec<&OsStr> = arguments.iter().map(OsStr::new).collect();
+        step_arguments.push(module_path.as_os_str());
+        step_arguments.extend([OsStr::new("-o"), module_path.as_os_str()]);
+
+        let step_output =
+            CommandExecutor::execute_command(tool, &step_arguments, Path::new("."), verbose)?;
+        if !step_output.status.success() {
+            return Err(PluginError::CompilationFailed {
+                reason: format!(
+                    "{step_name} failed: {}",
+                    String::from_utf8_lossy(&step_output.stderr)
+                ),
+            });
+        }
+
+        Ok(OptimizationStep {
+            name: step_name.to_string(),
+            size_before,
+            size_after: Self::file_size(module_path)?,
+        })
+    }
+
+    fn file_size(path: &Path) -> PluginResult<u64> {
+        Ok(fs::metadata(path).map_err(PluginError::Io)?.len())
+    }
+}
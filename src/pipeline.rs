@@ -0,0 +1,308 @@
+use crate::{
+    BuildMode, BuildSpan, CompileConfig, CompileResult, ExportScanner, OptimizationLevel,
+    PluginResult, TargetType, Toolchain, WasmBuilder,
+};
+use serde::Serialize;
+use std::time::Duration;
+
+/// A step of a build, in the order builds run them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PipelinePhase {
+    Tidy,
+    Cache,
+    Bindings,
+    Compile,
+    Optimize,
+    Glue,
+    Validate,
+    Componentize,
+    SmokeTest,
+    Minify,
+    Provenance,
+}
+
+impl PipelinePhase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tidy => "tidy",
+            Self::Cache => "cache",
+            Self::Bindings => "bindings",
+            Self::Compile => "compile",
+            Self::Optimize => "optimize",
+            Self::Glue => "glue",
+            Self::Validate => "validate",
+            Self::Componentize => "componentize",
+            Self::SmokeTest => "smoke-test",
+            Self::Minify => "minify",
+            Self::Provenance => "provenance",
+        }
+    }
+
+    /// The [`BuildSpan`] timing the phase; the cache has none of its own,
+    /// since it wraps the whole build.
+    pub fn span_name(self) -> Option<&'static str> {
+        Some(match self {
+            Self::Tidy => "wasmgo.tidy",
+            Self::Cache => return None,
+            Self::Bindings => "wasmgo.bindings",
+            Self::Compile => "wasmgo.toolchain",
+            Self::Optimize => "wasmgo.optimize",
+            Self::Glue => "wasmgo.glue",
+            Self::Validate => "wasmgo.validate",
+            Self::Componentize => "wasmgo.componentize",
+            Self::SmokeTest => "wasmgo.smoke-test",
+            Self::Minify => "wasmgo.minify",
+            Self::Provenance => "wasmgo.provenance",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineStep {
+    pub phase: PipelinePhase,
+    /// What the phase does under this configuration
+    pub detail: String,
+    /// Time spent in the phase, after a timed build; `None` if it didn't run
+    pub duration: Option<Duration>,
+}
+
+/// The phases a configuration builds with, optionally timed by a build.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildPipeline {
+    pub steps: Vec<PipelineStep>,
+    /// Whole build, after a timed build
+    pub total: Option<Duration>,
+}
+
+impl BuildPipeline {
+    pub fn plan(configuration: &CompileConfig) -> Self {
+        let mut steps = Vec::new();
+        let mut step = |phase, detail: String| {
+            steps.push(PipelineStep {
+                phase,
+                detail,
+                duration: None,
+            })
+        };
+        let toolchain = configuration.toolchain;
+        let target = &configuration.target_type;
+
+        if configuration.tidy {
+            step(PipelinePhase::Tidy, "go mod tidy".to_string());
+        }
+        if configuration.cache || configuration.remote_cache.is_some() {
+            step(
+                PipelinePhase::Cache,
+                "restore, or store after building".to_string(),
+            );
+        }
+        if let Some(wit_world) = &configuration.wit_world {
+            step(
+                PipelinePhase::Bindings,
+                match &wit_world.world {
+                    Some(world) => format!(
+                        "wit-bindgen-go for {world} in {}",
+                        wit_world.wit_directory.display()
+                    ),
+                    None => format!("wit-bindgen-go for {}", wit_world.wit_directory.display()),
+                },
+            );
+        }
+        step(
+            PipelinePhase::Compile,
+            format!(
+                "{} build ({}, {})",
+                toolchain.command_name(),
+                target.name(),
+                configuration.optimization_level.name()
+            ),
+        );
+
+        if configuration.optimization_level == OptimizationLevel::Size
+            && configuration.wit_world.is_none()
+        {
+            step(
+                PipelinePhase::Optimize,
+                "wasm-opt -Oz, if binaryen is installed".to_string(),
+            );
+        }
+
+        let builds_extism = matches!(target, TargetType::ExtismPlugin);
+        let builds_wapc = matches!(target, TargetType::Wapc);
+        let builds_freestanding = matches!(target, TargetType::WasmUnknown);
+        let uses_wasmexport = toolchain == Toolchain::Go
+            && ExportScanner::has_wasmexport(&ExportScanner::scan_package(
+                &configuration.project_path,
+            ));
+        let glue: Vec<&str> = [
+            (
+                matches!(target, TargetType::CloudflareWorker),
+                "worker.mjs and wrangler.toml",
+            ),
+            (
+                uses_wasmexport
+                    && !builds_extism
+                    && !builds_wapc
+                    && !matches!(target, TargetType::Wasi),
+                "loader for the exported functions",
+            ),
+            (matches!(target, TargetType::Threads), "worker-pool loader"),
+            (
+                matches!(target, TargetType::Web | TargetType::WebApp)
+                    && !uses_wasmexport
+                    && configuration.wit_world.is_none()
+                    && !(toolchain == Toolchain::Go
+                        && configuration.build_mode == BuildMode::Reactor),
+                "wasm_exec.js",
+            ),
+            (configuration.single_file, "single-file page"),
+            (
+                matches!(target, TargetType::WebApp)
+                    && !configuration.single_file
+                    && configuration.wit_world.is_none(),
+                "index.html",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(included, glue)| included.then_some(glue))
+        .collect();
+        if !glue.is_empty() {
+            step(PipelinePhase::Glue, glue.join(", "));
+        }
+        if builds_extism || builds_wapc {
+            step(
+                PipelinePhase::Validate,
+                format!("{} exports and imports", target.name()),
+            );
+        } else if builds_freestanding {
+            step(PipelinePhase::Validate, "no JS or WASI imports".to_string());
+        }
+        if let Some(adapter) = &configuration.preview2_adapter {
+            step(
+                PipelinePhase::Componentize,
+                format!("wasm-tools component new with {}", adapter.display()),
+            );
+        }
+        if configuration.smoke_test {
+            step(
+                PipelinePhase::SmokeTest,
+                "instantiate under Node.js".to_string(),
+            );
+        }
+        if configuration.optimization_level != OptimizationLevel::Debug {
+            step(PipelinePhase::Minify, "JS and HTML glue".to_string());
+        }
+        if configuration.provenance {
+            step(PipelinePhase::Provenance, "in-toto statement".to_string());
+        }
+        Self { steps, total: None }
+    }
+
+    /// Builds with `configuration`, recording how long each phase took.
+    pub fn timed(
+        builder: &dyn WasmBuilder,
+        configuration: &CompileConfig,
+    ) -> (Self, PluginResult<CompileResult>) {
+        let mut pipeline = Self::plan(configuration);
+        let (result, timings) = BuildSpan::timed(|| builder.compile(configuration));
+        for step in &mut pipeline.steps {
+            let Some(span_name) = step.phase.span_name() else {
+                continue;
+            };
+            step.duration = timings
+                .iter()
+                .filter(|(name, _)| name == span_name)
+                .map(|(_, duration)| *duration)
+                .reduce(|total, duration| total + duration);
+        }
+        // The outermost build span ends last
+        pipeline.total = timings
+            .iter()
+            .rev()
+            .find(|(name, _)| name == "wasmgo.build")
+            .map(|(_, duration)| *duration);
+        (pipeline, result)
+    }
+
+    /// Graphviz source, one box per phase.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pipeline {\n    rankdir=LR;\n    node [shape=box];\n");
+        if let Some(total) = self.total {
+            dot.push_str(&format!(
+                "    label=\"build {}\";\n",
+                Self::format_duration(total)
+            ));
+        }
+        for step in &self.steps {
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\"];\n",
+                step.phase.name(),
+                self.label(step, "\\n").replace('"', "\\\"")
+            ));
+        }
+        for pair in self.steps.windows(2) {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\";\n",
+                pair[0].phase.name(),
+                pair[1].phase.name()
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// A Mermaid flowchart, for Markdown that renders it (GitHub, GitLab).
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        if let Some(total) = self.total {
+            mermaid.push_str(&format!(
+                "---\ntitle: build {}\n---\n",
+                Self::format_duration(total)
+            ));
+        }
+        mermaid.push_str("flowchart LR\n");
+        for step in &self.steps {
+            mermaid.push_str(&format!(
+                "    {}[\"{}\"]\n",
+                Self::mermaid_id(step.phase),
+                self.label(step, "<br/>").replace('"', "#quot;")
+            ));
+        }
+        for pair in self.steps.windows(2) {
+            mermaid.push_str(&format!(
+                "    {} --> {}\n",
+                Self::mermaid_id(pair[0].phase),
+                Self::mermaid_id(pair[1].phase)
+            ));
+        }
+        mermaid
+    }
+
+    fn label(&self, step: &PipelineStep, separator: &str) -> String {
+        let mut label = format!("{}{separator}{}", step.phase.name(), step.detail);
+        if self.total.is_some() {
+            let timing = match (step.duration, step.phase.span_name()) {
+                (Some(duration), _) => Self::format_duration(duration),
+                (None, Some(_)) => "not run".to_string(),
+                (None, None) => return label,
+            };
+            label.push_str(separator);
+            label.push_str(&timing);
+        }
+        label
+    }
+
+    // Mermaid node IDs can't contain `-`
+    fn mermaid_id(phase: PipelinePhase) -> String {
+        phase.name().replace('-', "_")
+    }
+
+    fn format_duration(duration: Duration) -> String {
+        if duration.as_secs() > 0 {
+            format!("{:.2}s", duration.as_secs_f64())
+        } else {
+            format!("{}ms", duration.as_millis())
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{PluginError, PluginResult};
+
+/// Flags usable on their own, as in `cfg(windows)`.
+const CONDITION_FLAGS: &[&str] = &["unix", "windows"];
+
+/// Keys usable as `key = "value"`, as in `cfg(target_os = "macos")`.
+const CONDITION_KEYS: &[&str] = &["target_os", "target_family", "target_arch"];
+
+/// A Cargo-style `cfg(...)` predicate over the host platform, used by
+/// `[target.'cfg(...)']` config sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlatformCondition {
+    /// `unix` or `windows`
+    Flag(String),
+    /// `target_os`, `target_family` or `target_arch` compared with a value
+    Equals {
+        key: String,
+        value: String,
+    },
+    All(Vec<PlatformCondition>),
+    Any(Vec<PlatformCondition>),
+    Not(Box<PlatformCondition>),
+}
+
+impl PlatformCondition {
+    /// Parses `cfg(<predicate>)`.
+    pub fn parse(expression: &str) -> PluginResult<Self> {
+        let invalid = |reason: &str| PluginError::InvalidConfiguration {
+            reason: format!("invalid condition `{expression}`: {reason}"),
+        };
+        let mut parser = ConditionParser {
+            input: expression,
+            position: 0,
+        };
+        if parser.identifier() != Some("cfg") || !parser.consume('(') {
+            return Err(invalid("expected cfg(...)"));
+        }
+        let condition = parser.predicate().map_err(|reason| invalid(&reason))?;
+        if !parser.consume(')') || !parser.at_end() {
+            return Err(invalid("expected a single predicate inside cfg(...)"));
+        }
+        Ok(condition)
+    }
+
+    /// Whether the platform this binary runs on satisfies the condition.
+    pub fn matches_host(&self) -> bool {
+        self.matches(
+            std::env::consts::OS,
+            std::env::consts::FAMILY,
+            std::env::consts::ARCH,
+        )
+    }
+
+    pub fn matches(&self, os: &str, family: &str, arch: &str) -> bool {
+        match self {
+            PlatformCondition::Flag(flag) => flag == family,
+            PlatformCondition::Equals { key, value } => match key.as_str() {
+                "target_os" => value == os,
+                "target_family" => value == family,
+                _ => value == arch,
+            },
+            PlatformCondition::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.matches(os, family, arch)),
+            PlatformCondition::Any(conditions) => conditions
+                .iter()
+                .any(|condition| condition.matches(os, family, arch)),
+            PlatformCondition::Not(condition) => !condition.matches(os, family, arch),
+        }
+    }
+}
+
+struct ConditionParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> ConditionParser<'a> {
+    fn predicate(&mut self) -> Result<PlatformCondition, String> {
+        let Some(name) = self.identifier() else {
+            return Err("expected a predicate".to_string());
+        };
+        match name {
+            "all" | "any" | "not" => {
+                if !self.consume('(') {
+                    return Err(format!("expected `(` after `{name}`"));
+                }
+                let mut conditions = Vec::new();
+                while !self.consume(')') {
+                    conditions.push(self.predicate()?);
+                    if !self.consume(',') && !self.peek(')') {
+                        return Err(format!("expected `,` or `)` in `{name}(...)`"));
+                    }
+                }
+                match name {
+                    "all" => Ok(PlatformCondition::All(conditions)),
+                    "any" => Ok(PlatformCondition::Any(conditions)),
+                    _ if conditions.len() == 1 => {
+                        Ok(PlatformCondition::Not(Box::new(conditions.remove(0))))
+                    }
+                    _ => Err("`not(...)` takes exactly one predicate".to_string()),
+                }
+            }
+            _ if self.consume('=') => {
+                if !CONDITION_KEYS.contains(&name) {
+                    return Err(format!(
+                        "unknown key `{name}`; known keys: {}",
+                        CONDITION_KEYS.join(", ")
+                    ));
+                }
+                let value = self
+                    .string()
+                    .ok_or_else(|| format!("expected a quoted value after `{name} =`"))?;
+                Ok(PlatformCondition::Equals {
+                    key: name.to_string(),
+                    value: value.to_string(),
+                })
+            }
+            _ if CONDITION_FLAGS.contains(&name) => Ok(PlatformCondition::Flag(name.to_string())),
+            _ => Err(format!(
+                "unknown predicate `{name}`; use {}, {} = \"...\", all(), any() or not()",
+                CONDITION_FLAGS.join(", "),
+                CONDITION_KEYS.join(", ")
+            )),
+        }
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = &self.input[self.position..];
+        let length = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        (length > 0).then(|| {
+            self.position += length;
+            &rest[..length]
+        })
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.input[self.position..].strip_prefix('"')?;
+        let end = rest.find('"')?;
+        self.position += end + 2;
+        Some(&rest[..end])
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        let found = self.peek(expected);
+        if found {
+            self.position += expected.len_utf8();
+        }
+        found
+    }
+
+    fn peek(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.input[self.position..].starts_with(expected)
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.position == self.input.len()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    CompileConfig, CompileResult, Dirs, PluginError, PluginResult, StateDirectory, WasmBuilder,
+};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+/// Go source that only exists in memory, such as a playground snippet or
+/// standard input.
+///
+/// The source is written to `main.go` in a directory of the per-user scratch
+/// state named after its hash and built as a single-file project, so resubmitting the same snippet
+/// reuses the earlier build's module and caches.
+pub struct Playground;
+
+impl Playground {
+    /// Writes the source and returns the `main.go` to use as the project path.
+    pub fn write_source(source: &str) -> PluginResult<PathBuf> {
+        let hash: String = Sha256::digest(source.as_bytes())
+            .iter()
+            .take(8)
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let dirs = Dirs::discover().ok_or_else(|| PluginError::InvalidConfiguration {
+            reason: "no per-user directory to write the source to; set HOME or WASMGO_HOME"
+                .to_string(),
+        })?;
+        let directory = dirs
+            .state(StateDirectory::Scratch)
+            .join("playground")
+            .join(hash);
+        fs::create_dir_all(&directory).map_err(PluginError::Io)?;
+        let file = directory.join("main.go");
+        fs::write(&file, source).map_err(PluginError::Io)?;
+        Ok(file)
+    }
+
+    /// Builds the source with `config`; a relative output directory is placed
+    /// next to the written `main.go`.
+    pub fn compile(
+        builder: &dyn WasmBuilder,
+        source: &str,
+        config: &CompileConfig,
+    ) -> PluginResult<CompileResult> {
+        if source.trim().is_empty() {
+            return Err(PluginError::InvalidProjectStructure {
+                reason: "no Go source given".to_string(),
+            });
+        }
+        builder.compile(&CompileConfig {
+            project_path: Self::write_source(source)?,
+            ..config.clone()
+        })
+    }
+}
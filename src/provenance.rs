@@ -0,0 +1,203 @@
+use crate::{
+    BugReport, BuildManifest, BuildMode, CommandExecutor, CompileConfig, HashedArtifacts,
+    OptimizationLevel, PluginError, PluginResult, TargetType, Toolchain, ToolchainEnvironment,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const IN_TOTO_STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+pub const SLSA_PROVENANCE_PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+
+const BUILD_TYPE: &str = "https://github.com/anistark/wasmgo/buildtypes/compile/v1";
+
+/// in-toto v1 Statement with a SLSA v1 provenance predicate.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceStatement {
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<ResourceDescriptor>,
+    pub predicate_type: String,
+    pub predicate: SlsaProvenance,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResourceDescriptor {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    pub digest: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlsaProvenance {
+    pub build_definition: BuildDefinition,
+    pub run_details: RunDetails,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    pub build_type: String,
+    pub external_parameters: BuildParameters,
+    pub internal_parameters: ToolchainEnvironment,
+    pub resolved_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// The user-controlled inputs of a build.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildParameters {
+    pub target: TargetType,
+    pub optimization: OptimizationLevel,
+    pub toolchain: Toolchain,
+    pub build_mode: BuildMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_world: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview2_adapter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goflags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goexperiment: Option<String>,
+    pub hashed_filenames: bool,
+    pub hermetic: bool,
+    pub no_network: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunDetails {
+    pub builder: BuilderIdentity,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuilderIdentity {
+    pub id: String,
+    /// Versions of wasmgo and the toolchains it drove
+    pub version: BTreeMap<String, String>,
+}
+
+/// Generates SLSA provenance for build artifacts.
+pub struct ProvenanceGenerator;
+
+impl ProvenanceGenerator {
+    /// Describes `artifacts` as the output of the build recorded in `manifest`,
+    /// with the project's git checkout (if any) as the source.
+    pub fn generate(
+        compile_configuration: &CompileConfig,
+        manifest: &BuildManifest,
+        artifacts: &[PathBuf],
+    ) -> PluginResult<ProvenanceStatement> {
+        let mut subject = Vec::new();
+        for artifact in artifacts {
+            subject.push(ResourceDescriptor {
+                name: Some(
+                    artifact
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                ),
+                digest: BTreeMap::from([(
+                    "sha256".to_string(),
+                    HashedArtifacts::sha256(artifact)?,
+                )]),
+                ..ResourceDescriptor::default()
+            });
+        }
+
+        let mut version = BTreeMap::from([("wasmgo".to_string(), manifest.plugin_version.clone())]);
+        if let Some(go_version) = &manifest.environment.go_version {
+            version.insert("go".to_string(), go_version.clone());
+        }
+        if let Some(tinygo_version) = &manifest.environment.tinygo_version {
+            version.insert("tinygo".to_string(), tinygo_version.clone());
+        }
+
+        Ok(ProvenanceStatement {
+            statement_type: IN_TOTO_STATEMENT_TYPE.to_string(),
+            subject,
+            predicate_type: SLSA_PROVENANCE_PREDICATE_TYPE.to_string(),
+            predicate: SlsaProvenance {
+                build_definition: BuildDefinition {
+                    build_type: BUILD_TYPE.to_string(),
+                    external_parameters: BuildParameters {
+                        target: manifest.target.clone(),
+                        optimization: manifest.optimization,
+                        toolchain: manifest.toolchain,
+                        build_mode: manifest.build_mode,
+                        component_world: manifest.component_world.clone(),
+                        preview2_adapter: manifest.preview2_adapter.clone(),
+                        goflags: compile_configuration.goflags.clone(),
+                        goexperiment: compile_configuration.goexperiment.clone(),
+                        hashed_filenames: compile_configuration.hashed_filenames,
+                        hermetic: compile_configuration.hermetic,
+                        no_network: compile_configuration.no_network,
+                    },
+                    internal_parameters: manifest.environment.clone(),
+                    resolved_dependencies: Self::git_source(&compile_configuration.project_path)
+                        .into_iter()
+                        .collect(),
+                },
+                run_details: RunDetails {
+                    builder: BuilderIdentity {
+                        id: format!(
+                            "https://github.com/anistark/wasmgo@v{}",
+                            manifest.plugin_version
+                        ),
+                        version,
+                    },
+                },
+            },
+        })
+    }
+
+    /// Writes the statement as `<name>.intoto.jsonl` next to the module.
+    ///
+    /// The statement is unsigned; sign it as a blob (e.g. `cosign sign-blob`)
+    /// or wrap it in a DSSE envelope to make it an attestation.
+    pub fn write_next_to(
+        wasm_file_path: &Path,
+        statement: &ProvenanceStatement,
+    ) -> PluginResult<PathBuf> {
+        let provenance_path = wasm_file_path.with_extension("intoto.jsonl");
+        let statement_json =
+            serde_json::to_string(statement).map_err(|e| PluginError::Io(e.into()))?;
+        fs::write(&provenance_path, format!("{statement_json}\n")).map_err(PluginError::Io)?;
+        Ok(provenance_path)
+    }
+
+    fn git_source(project_path: &Path) -> Option<ResourceDescriptor> {
+        let git = |arguments: &[&str]| {
+            CommandExecutor::execute_command("git", arguments, project_path, false)
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+
+        let commit = git(&["rev-parse", "HEAD"])?;
+        // Remotes can carry a token in their userinfo
+        let uri = git(&["remote", "get-url", "origin"])
+            .filter(|remote| !remote.is_empty())
+            .map(|remote| format!("git+{}", BugReport::scrub_url_credentials(&remote)));
+        let mut annotations = BTreeMap::new();
+        // Uncommitted changes mean the commit alone doesn't reproduce the build
+        if git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty()) {
+            annotations.insert("dirty".to_string(), "true".to_string());
+        }
+
+        Some(ResourceDescriptor {
+            uri,
+            digest: BTreeMap::from([("gitCommit".to_string(), commit)]),
+            annotations,
+            ..ResourceDescriptor::default()
+        })
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{Dirs, PluginError, PluginResult, StateDirectory};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Something [`StatePruner`] removes as a whole: a build cache entry, a
+/// memoized optimization, a toolchain, a template registry or a history file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateEntry {
+    /// Usually one; a memoized optimization is its module plus its report
+    pub paths: Vec<PathBuf>,
+    pub size: u64,
+    /// Newest modification time of anything in the entry; cache hits refresh it
+    pub modified: SystemTime,
+}
+
+impl StateEntry {
+    pub fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.modified).unwrap_or_default()
+    }
+}
+
+/// What a state directory holds.
+#[derive(Debug, Clone)]
+pub struct StateUsage {
+    pub directory: StateDirectory,
+    pub path: PathBuf,
+    pub entries: Vec<StateEntry>,
+}
+
+impl StateUsage {
+    pub fn size(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+}
+
+/// Which entries [`StatePruner::select`] picks. With neither limit set,
+/// nothing is removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrunePolicy {
+    /// Entries unused for longer than this
+    pub older_than: Option<Duration>,
+    /// The least recently used entries, until a directory holds at most this
+    /// many bytes
+    pub max_size: Option<u64>,
+}
+
+impl PrunePolicy {
+    /// `90m`, `12h`, `30d` or `2w`.
+    pub fn parse_age(age: &str) -> PluginResult<Duration> {
+        let invalid = || PluginError::InvalidConfiguration {
+            reason: format!("Invalid age {age:?}; expected a number with m, h, d or w, e.g. 30d"),
+        };
+        let age = age.trim();
+        let (number, unit) = age.split_at(age.len() - age.chars().last().map_or(0, char::len_utf8));
+        let number: u64 = number.parse().map_err(|_| invalid())?;
+        let seconds = match unit {
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        Ok(Duration::from_secs(number * seconds))
+    }
+
+    /// Bytes, or a number with K, M or G (powers of 1024), e.g. `500M`.
+    pub fn parse_size(size: &str) -> PluginResult<u64> {
+        let invalid = || PluginError::InvalidConfiguration {
+            reason: format!(
+                "Invalid size {size:?}; expected bytes or a number with K, M or G, e.g. 2G"
+            ),
+        };
+        let size = size.trim().trim_end_matches(['B', 'b']);
+        let (number, multiplier) = match size.chars().last().map(|unit| unit.to_ascii_uppercase()) {
+            Some('K') => (&size[..size.len() - 1], 1024),
+            Some('M') => (&size[..size.len() - 1], 1024 * 1024),
+            Some('G') => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+            _ => (size, 1),
+        };
+        let number: f64 = number.trim().parse().map_err(|_| invalid())?;
+        if !number.is_finite() || number < 0.0 {
+            return Err(invalid());
+        }
+        Ok((number * multiplier as f64) as u64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.older_than.is_none() && self.max_size.is_none()
+    }
+}
+
+/// Reports and trims wasmgo's state directories.
+pub struct StatePruner;
+
+impl StatePruner {
+    /// Entries of `directory`, least recently used first. A missing
+    /// directory has none.
+    pub fn usage(dirs: &Dirs, directory: StateDirectory) -> StateUsage {
+        let path = dirs.state(directory);
+        // Memoized optimizations are `<key>.wasm` and `<key>.json`, kept or removed together
+        let mut groups: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+        for child in Self::children(&path) {
+            if directory == StateDirectory::Builds && child.ends_with("optimized") && child.is_dir()
+            {
+                for memo in Self::children(&child) {
+                    groups
+                        .entry(memo.with_extension(""))
+                        .or_default()
+                        .push(memo);
+                }
+            } else {
+                groups.entry(child.clone()).or_default().push(child);
+            }
+        }
+
+        let mut entries: Vec<StateEntry> = groups
+            .into_values()
+            .map(|paths| {
+                let (size, modified) = paths.iter().map(|path| Self::measure(path)).fold(
+                    (0, SystemTime::UNIX_EPOCH),
+                    |(size, modified), (path_size, path_modified)| {
+                        (size + path_size, modified.max(path_modified))
+                    },
+                );
+                StateEntry {
+                    paths,
+                    size,
+                    modified,
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.modified);
+        StateUsage {
+            directory,
+            path,
+            entries,
+        }
+    }
+
+    /// The entries `policy` removes from `usage`: those older than its age,
+    /// then the least recently used until the rest fits its size.
+    pub fn select(usage: &StateUsage, policy: &PrunePolicy, now: SystemTime) -> Vec<StateEntry> {
+        let mut remaining = usage.size();
+        let mut selected = Vec::new();
+        // Entries are sorted least recently used first
+        for entry in &usage.entries {
+            let expired = policy
+                .older_than
+                .is_some_and(|older_than| entry.age(now) > older_than);
+            let oversized = policy.max_size.is_some_and(|max_size| remaining > max_size);
+            if expired || oversized {
+                remaining -= entry.size;
+                selected.push(entry.clone());
+            }
+        }
+        selected
+    }
+
+    /// Deletes `entries`, returning the bytes freed.
+    pub fn remove(entries: &[StateEntry]) -> PluginResult<u64> {
+        let mut freed = 0;
+        for entry in entries {
+            for path in &entry.paths {
+                let removed = if path.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                match removed {
+                    Ok(()) => {}
+                    // Removed meanwhile, e.g. by a concurrent prune
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(PluginError::Io(e)),
+                }
+            }
+            freed += entry.size;
+        }
+        Ok(freed)
+    }
+
+    fn children(directory: &Path) -> Vec<PathBuf> {
+        fs::read_dir(directory)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Bytes under `path` and its newest modification time, not following
+    /// symlinks.
+    fn measure(path: &Path) -> (u64, SystemTime) {
+        let mut size = 0;
+        let mut modified = SystemTime::UNIX_EPOCH;
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(path) = pending.pop() {
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if let Ok(time) = metadata.modified() {
+                modified = modified.max(time);
+            }
+            if metadata.is_dir() {
+                pending.extend(Self::children(&path));
+            } else {
+                size += metadata.len();
+            }
+        }
+        (size, modified)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{CompileConfig, CompileResult, PluginError, PluginResult, WasmBuilder};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// A build requested through the queue, waiting, running or finished with
+/// callers still to collect its result.
+struct QueuedBuild {
+    ticket: u64,
+    project: PathBuf,
+    output: PathBuf,
+    /// The project and the whole configuration; equal fingerprints share a build
+    fingerprint: String,
+    result: Option<PluginResult<CompileResult>>,
+    /// Callers besides the one running it still waiting for the result
+    joined: usize,
+}
+
+#[derive(Default)]
+struct QueueState {
+    builds: Vec<QueuedBuild>,
+    next_ticket: u64,
+}
+
+/// Coalesces builds requested concurrently, for hosts that trigger them from
+/// several threads (a watcher and an explicit rebuild, say).
+///
+/// A request identical to one already waiting or running gets that build's
+/// result instead of compiling again. Requests for the same project or the
+/// same output directory with a different configuration run one after the
+/// other in the order they arrived; unrelated builds run in parallel.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use wasmgo::{BuildQueue, CompileConfig, Plugin, WasmGoPlugin};
+///
+/// let queue = Arc::new(BuildQueue::new());
+/// let plugin = WasmGoPlugin::new();
+/// let result = queue.compile(plugin.get_builder().as_ref(), &CompileConfig::default());
+/// ```
+#[derive(Default)]
+pub struct BuildQueue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+}
+
+impl BuildQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds `configuration` with `builder`, or waits for an identical build
+    /// already queued. Joined callers get a copy of its result.
+    pub fn compile(
+        &self,
+        builder: &dyn WasmBuilder,
+        configuration: &CompileConfig,
+    ) -> PluginResult<CompileResult> {
+        let project = Self::normalize(&configuration.project_path);
+        let output = Self::normalize(&project.join(&configuration.output_directory));
+        let fingerprint = format!("{}\n{configuration:?}", project.display());
+
+        let mut state = self.lock();
+        if let Some(build) = state
+            .builds
+            .iter_mut()
+            .find(|build| build.result.is_none() && build.fingerprint == fingerprint)
+        {
+            build.joined += 1;
+            let ticket = build.ticket;
+            return self.collect(state, ticket);
+        }
+
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.builds.push(QueuedBuild {
+            ticket,
+            project,
+            output,
+            fingerprint,
+            result: None,
+            joined: 0,
+        });
+        while Self::is_blocked(&state, ticket) {
+            state = self.wait(state);
+        }
+        drop(state);
+
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| builder.compile(configuration)));
+        let mut state = self.lock();
+        let index = Self::position(&state, ticket);
+        if state.builds[index].joined == 0 {
+            state.builds.remove(index);
+        } else {
+            // Joined callers of a panicked build get an error; the panic stays
+            // with the caller that ran it
+            state.builds[index].result = Some(match &outcome {
+                Ok(result) => Self::duplicate(result),
+                Err(_) => Err(PluginError::CompilationFailed {
+                    reason: "the build panicked".to_string(),
+                }),
+            });
+        }
+        // Later builds blocked on this one can start
+        drop(state);
+        self.changed.notify_all();
+
+        match outcome {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    /// Builds waiting or running.
+    pub fn pending(&self) -> usize {
+        self.lock()
+            .builds
+            .iter()
+            .filter(|build| build.result.is_none())
+            .count()
+    }
+
+    fn collect(
+        &self,
+        mut state: MutexGuard<'_, QueueState>,
+        ticket: u64,
+    ) -> PluginResult<CompileResult> {
+        loop {
+            let index = Self::position(&state, ticket);
+            let build = &mut state.builds[index];
+            if let Some(result) = &build.result {
+                build.joined -= 1;
+                if build.joined > 0 {
+                    return Self::duplicate(result);
+                }
+                if let Some(result) = state.builds.remove(index).result {
+                    return result;
+                }
+            }
+            state = self.wait(state);
+        }
+    }
+
+    /// Whether an earlier, unfinished build shares the project or output directory.
+    fn is_blocked(state: &QueueState, ticket: u64) -> bool {
+        let index = Self::position(state, ticket);
+        let build = &state.builds[index];
+        state.builds[..index].iter().any(|earlier| {
+            earlier.result.is_none()
+                && (earlier.project == build.project || earlier.output == build.output)
+        })
+    }
+
+    fn position(state: &QueueState, ticket: u64) -> usize {
+        state
+            .builds
+            .iter()
+            .position(|build| build.ticket == ticket)
+            .unwrap_or_else(|| unreachable!("builds stay queued until collected"))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn wait<'a>(&self, state: MutexGuard<'a, QueueState>) -> MutexGuard<'a, QueueState> {
+        self.changed
+            .wait(state)
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn normalize(path: &Path) -> PathBuf {
+        std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    fn duplicate(result: &PluginResult<CompileResult>) -> PluginResult<CompileResult> {
+        result.as_ref().map_err(Self::duplicate_error).cloned()
+    }
+
+    /// `PluginError` holds an `io::Error`, which can't be cloned; its kind and
+    /// message are kept.
+    fn duplicate_error(error: &PluginError) -> PluginError {
+        match error {
+            PluginError::CompilationFailed { reason } => PluginError::CompilationFailed {
+                reason: reason.clone(),
+            },
+            PluginError::DeploymentFailed { reason } => PluginError::DeploymentFailed {
+                reason: reason.clone(),
+            },
+            PluginError::CompileToolNotFound { tool } => {
+                PluginError::CompileToolNotFound { tool: tool.clone() }
+            }
+            PluginError::InvalidProjectStructure { reason } => {
+                PluginError::InvalidProjectStructure {
+                    reason: reason.clone(),
+                }
+            }
+            PluginError::MissingEntryFile { candidates } => PluginError::MissingEntryFile {
+                candidates: candidates.clone(),
+            },
+            PluginError::UnsupportedToolchainVersion { reason } => {
+                PluginError::UnsupportedToolchainVersion {
+                    reason: reason.clone(),
+                }
+            }
+            PluginError::UnsupportedTarget { reason } => PluginError::UnsupportedTarget {
+                reason: reason.clone(),
+            },
+            PluginError::InvalidWasm { path, reason } => PluginError::InvalidWasm {
+                path: path.clone(),
+                reason: reason.clone(),
+            },
+            PluginError::ExecutionFailed { reason } => PluginError::ExecutionFailed {
+                reason: reason.clone(),
+            },
+            PluginError::InvalidConfiguration { reason } => PluginError::InvalidConfiguration {
+                reason: reason.clone(),
+            },
+            PluginError::OutputDirectoryCreationFailed { path } => {
+                PluginError::OutputDirectoryCreationFailed { path: path.clone() }
+            }
+            PluginError::Io(e) => PluginError::Io(std::io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{PluginError, PluginResult, ShellStyle, ToolCommand};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SHELL_SCRIPT_FILENAME: &str = "build.sh";
+
+pub const POWERSHELL_SCRIPT_FILENAME: &str = "build.ps1";
+
+// Ends the here-documents recreating generated files
+const HEREDOC_DELIMITER: &str = "WASMGO_EOF";
+
+/// Scripts rerunning a build's toolchain commands outside wasmgo, with the
+/// environment variables wasmgo set for each; everything else comes from the
+/// calling shell.
+#[derive(Debug, Clone, Default)]
+pub struct ReproductionScript {
+    /// Files the commands read that wasmgo generated and removed afterwards,
+    /// such as TinyGo target files. The scripts write them back first.
+    pub generated_files: Vec<(PathBuf, String)>,
+    pub commands: Vec<ToolCommand>,
+}
+
+impl ReproductionScript {
+    /// A POSIX `sh` script; each command runs in a subshell from its working
+    /// directory.
+    pub fn to_shell(&self) -> String {
+        let mut script = format!("#!/bin/sh\n{}set -eu\n", Self::header());
+        for (path, contents) in &self.generated_files {
+            let quoted = ShellStyle::Posix.quote(path.as_os_str());
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                script.push_str(&format!(
+                    "\nmkdir -p {}",
+                    ShellStyle::Posix.quote(parent.as_os_str())
+                ));
+            }
+            script.push_str(&format!(
+                "\ncat > {quoted} <<'{HEREDOC_DELIMITER}'\n{}\n{HEREDOC_DELIMITER}\n",
+                contents.trim_end_matches('\n')
+            ));
+        }
+        for command in &self.commands {
+            script.push_str(&format!(
+                "\n({})\n",
+                command.reproduce_line(ShellStyle::Posix)
+            ));
+        }
+        script
+    }
+
+    /// A PowerShell script that stops at the first command that fails.
+    pub fn to_powershell(&self) -> String {
+        let mut script = format!("{}$ErrorActionPreference = 'Stop'\n", Self::header());
+        for (path, contents) in &self.generated_files {
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                script.push_str(&format!(
+                    "\nNew-Item -ItemType Directory -Force -Path {} | Out-Null",
+                    Self::quote_powershell(parent.as_os_str())
+                ));
+            }
+            // A here-string's closing '@ must start its line
+            script.push_str(&format!(
+                "\nSet-Content -LiteralPath {} -Value @'\n{}\n'@\n",
+                Self::quote_powershell(path.as_os_str()),
+                contents.trim_end_matches('\n')
+            ));
+        }
+        for command in &self.commands {
+            script.push('\n');
+            if let Some(directory) = command.working_directory() {
+                let directory =
+                    fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+                let directory = directory.to_string_lossy();
+                let directory = directory.strip_prefix(r"\\?\").unwrap_or(&directory);
+                script.push_str(&format!(
+                    "Push-Location -LiteralPath {}\n",
+                    Self::quote_powershell(OsStr::new(directory))
+                ));
+            }
+            for (key, value) in command.environment() {
+                script.push_str(&format!(
+                    "$env:{} = {}\n",
+                    key.to_string_lossy(),
+                    Self::quote_powershell(value)
+                ));
+            }
+            let line: Vec<String> = std::iter::once(command.program())
+                .chain(
+                    command
+                        .arguments()
+                        .iter()
+                        .map(|argument| argument.as_os_str()),
+                )
+                .map(Self::quote_powershell)
+                .collect();
+            script.push_str(&format!(
+                "& {}\n$exitCode = $LASTEXITCODE\n",
+                line.join(" ")
+            ));
+            if command.working_directory().is_some() {
+                script.push_str("Pop-Location\n");
+            }
+            script.push_str("if ($exitCode -ne 0) { exit $exitCode }\n");
+        }
+        script
+    }
+
+    /// Writes `build.sh` (executable on Unix) and `build.ps1` into
+    /// `output_directory`.
+    pub fn write_to(&self, output_directory: &Path) -> PluginResult<(PathBuf, PathBuf)> {
+        let shell_path = output_directory.join(SHELL_SCRIPT_FILENAME);
+        fs::write(&shell_path, self.to_shell()).map_err(PluginError::Io)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&shell_path, fs::Permissions::from_mode(0o755))
+                .map_err(PluginError::Io)?;
+        }
+        let powershell_path = output_directory.join(POWERSHELL_SCRIPT_FILENAME);
+        fs::write(&powershell_path, self.to_powershell()).map_err(PluginError::Io)?;
+        Ok((shell_path, powershell_path))
+    }
+
+    // `#` starts a comment in both languages
+    fn header() -> String {
+        format!(
+            "# Reproduces a wasmgo {} build: the commands it ran, in order, with the\n# environment variables it set. Other variables come from the calling shell.\n",
+            env!("CARGO_PKG_VERSION")
+        )
+    }
+
+    fn quote_powershell(argument: &OsStr) -> String {
+        format!("'{}'", argument.to_string_lossy().replace('\'', "''"))
+    }
+}
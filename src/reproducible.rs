@@ -0,0 +1,162 @@
+use crate::{CompileConfig, Logger, PluginError, PluginResult, WasmBinary, WasmBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A section whose bytes differ between the two builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionDifference {
+    pub name: String,
+    pub first_size: usize,
+    pub second_size: usize,
+}
+
+/// Outcome of comparing one artifact across the two builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactDifference {
+    pub file_name: String,
+    pub first_size: u64,
+    pub second_size: u64,
+    /// Offset of the first differing byte
+    pub first_difference: usize,
+    /// Differing sections, for WebAssembly files with matching layouts
+    pub sections: Vec<SectionDifference>,
+}
+
+/// Outcome of [`ReproducibilityCheck::run`].
+#[derive(Debug, Clone)]
+pub struct ReproducibilityReport {
+    pub first_build: PathBuf,
+    pub second_build: PathBuf,
+    /// Files compared by name, present in both builds
+    pub compared: Vec<String>,
+    pub differences: Vec<ArtifactDifference>,
+}
+
+impl ReproducibilityReport {
+    pub fn is_reproducible(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Builds a project twice, each time with fresh hermetic Go caches and its own
+/// output directory, and byte-compares the module and glue.
+pub struct ReproducibilityCheck;
+
+impl ReproducibilityCheck {
+    /// Builds into `<output>/reproducible/build-1` and `build-2`.
+    pub fn run(
+        builder: &dyn WasmBuilder,
+        base_configuration: &CompileConfig,
+    ) -> PluginResult<ReproducibilityReport> {
+        let mut builds = Vec::new();
+        for build in 1..=2 {
+            let output_directory = base_configuration
+                .output_directory
+                .join("reproducible")
+                .join(format!("build-{build}"));
+            let _ = fs::remove_dir_all(base_configuration.project_path.join(&output_directory));
+            let configuration = CompileConfig {
+                output_directory,
+                hermetic: true,
+                hermetic_directory: None,
+                // Hashed names would only match when the contents do, and
+                // provenance records when each build ran
+                hashed_filenames: false,
+                provenance: false,
+                ..base_configuration.clone()
+            };
+            Logger::info(format_args!("🔁 Build {build} of 2..."));
+            builds.push(builder.compile(&configuration)?);
+        }
+
+        let files = |result: &crate::CompileResult| -> Vec<PathBuf> {
+            std::iter::once(&result.wasm_file_path)
+                .chain(&result.js_file_path)
+                .cloned()
+                .collect()
+        };
+        let (first_files, second_files) = (files(&builds[0]), files(&builds[1]));
+        let mut compared = Vec::new();
+        let mut differences = Vec::new();
+        for first_path in &first_files {
+            let file_name = first_path.file_name().unwrap_or_default();
+            let Some(second_path) = second_files
+                .iter()
+                .find(|path| path.file_name() == Some(file_name))
+            else {
+                continue;
+            };
+            compared.push(file_name.to_string_lossy().to_string());
+            if let Some(difference) = Self::compare(first_path, second_path)? {
+                differences.push(difference);
+            }
+        }
+
+        let directory_of =
+            |path: &PathBuf| path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(ReproducibilityReport {
+            first_build: directory_of(&first_files[0]),
+            second_build: directory_of(&second_files[0]),
+            compared,
+            differences,
+        })
+    }
+
+    fn compare(first_path: &Path, second_path: &Path) -> PluginResult<Option<ArtifactDifference>> {
+        let first = fs::read(first_path).map_err(PluginError::Io)?;
+        let second = fs::read(second_path).map_err(PluginError::Io)?;
+        let Some(first_difference) = first
+            .iter()
+            .zip(&second)
+            .position(|(a, b)| a != b)
+            .or_else(|| (first.len() != second.len()).then(|| first.len().min(second.len())))
+        else {
+            return Ok(None);
+        };
+
+        let sections = match (WasmBinary::parse(&first), WasmBinary::parse(&second)) {
+            (Ok(first_binary), Ok(second_binary)) => {
+                let count = first_binary
+                    .sections
+                    .len()
+                    .max(second_binary.sections.len());
+                (0..count)
+                    .filter_map(|index| {
+                        let bytes_of = |binary: &WasmBinary, bytes: &[u8]| {
+                            binary.sections.get(index).map(|section| {
+                                (
+                                    section.name.clone(),
+                                    bytes[section.offset..section.offset + section.size].to_vec(),
+                                )
+                            })
+                        };
+                        let a = bytes_of(&first_binary, &first);
+                        let b = bytes_of(&second_binary, &second);
+                        if a == b {
+                            return None;
+                        }
+                        let (name, _) = a.as_ref().or(b.as_ref())?;
+                        Some(SectionDifference {
+                            name: name.clone(),
+                            first_size: a.as_ref().map_or(0, |(_, bytes)| bytes.len()),
+                            second_size: b.as_ref().map_or(0, |(_, bytes)| bytes.len()),
+                        })
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(Some(ArtifactDifference {
+            file_name: first_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            first_size: first.len() as u64,
+            second_size: second.len() as u64,
+            first_difference,
+            sections,
+        }))
+    }
+}
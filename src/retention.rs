@@ -0,0 +1,90 @@
+use crate::{PluginError, PluginResult};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Hex digits of the content hash embedded in hashed filenames.
+pub const CONTENT_HASH_LENGTH: usize = 16;
+
+/// Content-hashed artifact names (`app.<hash>.wasm`) and pruning of old builds.
+pub struct HashedArtifacts;
+
+impl HashedArtifacts {
+    /// Hex SHA-256 of a file's contents.
+    pub fn sha256(path: &Path) -> PluginResult<String> {
+        let contents = fs::read(path).map_err(PluginError::Io)?;
+        Ok(Sha256::digest(&contents)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+
+    /// Renames `dir/app.wasm` to `dir/app.<hash>.wasm`, replacing an identical earlier build.
+    pub fn rename_with_hash(path: &Path) -> PluginResult<PathBuf> {
+        let hash = Self::sha256(path)?;
+        let mut hashed_name = path.file_stem().unwrap_or_default().to_os_string();
+        hashed_name.push(".");
+        hashed_name.push(&hash[..CONTENT_HASH_LENGTH]);
+        if let Some(extension) = path.extension() {
+            hashed_name.push(".");
+            hashed_name.push(extension);
+        }
+
+        let hashed_path = path.with_file_name(hashed_name);
+        fs::rename(path, &hashed_path).map_err(PluginError::Io)?;
+        Ok(hashed_path)
+    }
+
+    /// Keeps the `keep` most recent hashed builds of `stem` in `output_directory`
+    /// and deletes every file belonging to older ones.
+    ///
+    /// Files of a build share its hash (`app.<hash>.wasm`, `app.<hash>.js`, ...);
+    /// a build's age is the newest modification time among them.
+    pub fn prune(
+        output_directory: &Path,
+        stem: &str,
+        keep: NonZeroUsize,
+    ) -> PluginResult<Vec<PathBuf>> {
+        let mut builds: Vec<(String, SystemTime, Vec<PathBuf>)> = Vec::new();
+        for entry in fs::read_dir(output_directory)
+            .map_err(PluginError::Io)?
+            .flatten()
+        {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(hash) = Self::hash_in_name(&file_name, stem) else {
+                continue;
+            };
+            let modified = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            match builds.iter_mut().find(|(existing, _, _)| existing == hash) {
+                Some((_, newest, files)) => {
+                    *newest = (*newest).max(modified);
+                    files.push(entry.path());
+                }
+                None => builds.push((hash.to_string(), modified, vec![entry.path()])),
+            }
+        }
+
+        builds.sort_by_key(|(_, newest, _)| std::cmp::Reverse(*newest));
+        let mut removed = Vec::new();
+        for (_, _, files) in builds.into_iter().skip(keep.get()) {
+            for file in files {
+                fs::remove_file(&file).map_err(PluginError::Io)?;
+                removed.push(file);
+            }
+        }
+        Ok(removed)
+    }
+
+    fn hash_in_name<'a>(file_name: &'a str, stem: &str) -> Option<&'a str> {
+        let rest = file_name.strip_prefix(stem)?.strip_prefix('.')?;
+        let hash = rest.split('.').next()?;
+        (hash.len() == CONTENT_HASH_LENGTH && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then_some(hash)
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{
+    CommandExecutor, InterruptHandler, Logger, PathResolver, PluginError, PluginResult, Toolchain,
+    WasmBinary, WasmKind,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+
+const NODE_RUNNER_TEMPLATE: &str = include_str!("templates/run_node.js");
+const NODE_WASI_RUNNER_TEMPLATE: &str = include_str!("templates/run_node_wasi.js");
+
+/// Import module of the `syscall/js` bridge, present in every js/wasm build.
+const GOJS_MODULE: &str = "gojs";
+
+/// A host directory made visible to the guest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreopenedDir {
+    pub host: String,
+    /// Path the guest opens it under
+    pub guest: String,
+}
+
+impl PreopenedDir {
+    /// Parses `HOST[::GUEST]`; the guest path defaults to the host path.
+    pub fn parse(spec: &str) -> PluginResult<Self> {
+        let (host, guest) = spec.split_once("::").unwrap_or((spec, spec));
+        if host.is_empty() || guest.is_empty() {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!("directory `{spec}` is not in HOST[::GUEST] form"),
+            });
+        }
+        Ok(Self {
+            host: host.to_string(),
+            guest: guest.to_string(),
+        })
+    }
+}
+
+/// Runtime, command line, environment and filesystem a module is executed with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WasiRunConfig {
+    /// Arguments after the program name
+    pub args: Vec<String>,
+    /// The guest's whole environment; nothing is inherited from the host
+    pub env: BTreeMap<String, String>,
+    pub dirs: Vec<PreopenedDir>,
+    /// Detected from the installed runtimes when unset
+    pub runtime: Option<WasmRuntime>,
+}
+
+impl WasiRunConfig {
+    /// Adds a `KEY=VALUE` variable, or `KEY` to pass the host's value through.
+    pub fn add_env(&mut self, spec: &str) -> PluginResult<()> {
+        let (key, value) = match spec.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (
+                spec.to_string(),
+                std::env::var(spec).map_err(|_| PluginError::ExecutionFailed {
+                    reason: format!("environment variable `{spec}` is not set on the host"),
+                })?,
+            ),
+        };
+        if key.is_empty() {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!("environment variable `{spec}` has no name"),
+            });
+        }
+        self.env.insert(key, value);
+        Ok(())
+    }
+
+    pub fn add_dir(&mut self, spec: &str) -> PluginResult<()> {
+        self.dirs.push(PreopenedDir::parse(spec)?);
+        Ok(())
+    }
+}
+
+/// Engine a built module is executed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WasmRuntime {
+    Wasmtime,
+    Wasmer,
+    Wazero,
+    /// WASI core modules through `node:wasi`, js/wasm modules through `wasm_exec.js`
+    Node,
+}
+
+impl WasmRuntime {
+    /// In auto-detection order.
+    pub const ALL: [WasmRuntime; 4] = [
+        WasmRuntime::Wasmtime,
+        WasmRuntime::Wasmer,
+        WasmRuntime::Wazero,
+        WasmRuntime::Node,
+    ];
+
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            WasmRuntime::Wasmtime => "wasmtime",
+            WasmRuntime::Wasmer => "wasmer",
+            WasmRuntime::Wazero => "wazero",
+            WasmRuntime::Node => "node",
+        }
+    }
+
+    pub fn homepage(&self) -> &'static str {
+        match self {
+            WasmRuntime::Wasmtime => "https://wasmtime.dev",
+            WasmRuntime::Wasmer => "https://wasmer.io",
+            WasmRuntime::Wazero => "https://wazero.io",
+            WasmRuntime::Node => "https://nodejs.org",
+        }
+    }
+
+    pub fn runs_components(&self) -> bool {
+        matches!(self, WasmRuntime::Wasmtime)
+    }
+
+    pub fn is_installed(&self) -> bool {
+        CommandExecutor::is_tool_installed(self.command_name())
+    }
+}
+
+/// How a module has to be executed, from its imports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleAbi {
+    /// `GOOS=js`, needing the toolchain's `wasm_exec.js` glue
+    GoJs,
+    WasiCore,
+    Component,
+}
+
+/// Executes built modules under the selected runtime, or the first
+/// installed one able to run them.
+pub struct ModuleRunner;
+
+impl ModuleRunner {
+    /// Runs the module with inherited stdio and returns its exit code.
+    pub fn run(
+        wasm_path: &Path,
+        toolchain: Toolchain,
+        run_config: &WasiRunConfig,
+        verbose: bool,
+    ) -> PluginResult<i32> {
+        let runner_directory =
+            PathResolver::private_directory(&std::env::temp_dir(), "wasmgo-run-")?;
+        let mut command = Self::command(wasm_path, toolchain, run_config, runner_directory.path())?;
+        if verbose {
+            Logger::debug(format_args!("Executing: {command:?}"));
+        }
+        let status = InterruptHandler::status(&mut command);
+        drop(runner_directory);
+        Ok(Self::exit_code(status?))
+    }
+
+    /// Runs the module with stdout captured, for comparing against expected
+    /// output; stderr stays attached to the terminal.
+    pub fn capture(
+        wasm_path: &Path,
+        toolchain: Toolchain,
+        run_config: &WasiRunConfig,
+        verbose: bool,
+    ) -> PluginResult<(i32, String)> {
+        let runner_directory =
+            PathResolver::private_directory(&std::env::temp_dir(), "wasmgo-run-")?;
+        let mut command = Self::command(wasm_path, toolchain, run_config, runner_directory.path())?;
+        if verbose {
+            Logger::debug(format_args!("Executing: {command:?}"));
+        }
+        let output = InterruptHandler::output(
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::inherit()),
+        );
+        drop(runner_directory);
+        let output = output?;
+        Ok((
+            Self::exit_code(output.status),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+        ))
+    }
+
+    // Killed by a signal: report it the way shells do
+    fn exit_code(status: ExitStatus) -> i32 {
+        status.code().unwrap_or(128)
+    }
+
+    /// Builds the runtime invocation without starting it. Node.js runner
+    /// scripts are written into `runner_directory`, which has to outlive the
+    /// command.
+    pub fn command(
+        wasm_path: &Path,
+        toolchain: Toolchain,
+        run_config: &WasiRunConfig,
+        runner_directory: &Path,
+    ) -> PluginResult<Command> {
+        for dir in &run_config.dirs {
+            if !Path::new(&dir.host).is_dir() {
+                return Err(PluginError::ExecutionFailed {
+                    reason: format!("preopened directory {} does not exist", dir.host),
+                });
+            }
+        }
+
+        let binary = WasmBinary::read(wasm_path)?;
+        let abi = match binary.kind {
+            WasmKind::Component => ModuleAbi::Component,
+            WasmKind::CoreModule if binary.imported_modules().contains(&GOJS_MODULE) => {
+                ModuleAbi::GoJs
+            }
+            WasmKind::CoreModule => ModuleAbi::WasiCore,
+        };
+        let runtime = Self::select_runtime(abi, run_config.runtime)?;
+
+        match (runtime, abi) {
+            (WasmRuntime::Node, ModuleAbi::GoJs) => {
+                Self::node_js_command(wasm_path, toolchain, run_config, runner_directory)
+            }
+            (WasmRuntime::Node, _) => {
+                Self::node_wasi_command(wasm_path, run_config, runner_directory)
+            }
+            (WasmRuntime::Wasmtime, _) => Ok(Self::wasmtime_command(wasm_path, run_config)),
+            (WasmRuntime::Wasmer, _) => Ok(Self::wasmer_command(wasm_path, run_config)),
+            (WasmRuntime::Wazero, _) => Ok(Self::wazero_command(wasm_path, run_config)),
+        }
+    }
+
+    fn select_runtime(abi: ModuleAbi, requested: Option<WasmRuntime>) -> PluginResult<WasmRuntime> {
+        let supports = |runtime: WasmRuntime| match abi {
+            ModuleAbi::GoJs => runtime == WasmRuntime::Node,
+            ModuleAbi::WasiCore => true,
+            ModuleAbi::Component => runtime.runs_components(),
+        };
+        let abi_name = match abi {
+            ModuleAbi::GoJs => "js/wasm modules",
+            ModuleAbi::WasiCore => "WASI modules",
+            ModuleAbi::Component => "components",
+        };
+
+        let runtime = match requested {
+            Some(runtime) if supports(runtime) => runtime,
+            Some(runtime) => {
+                return Err(PluginError::UnsupportedTarget {
+                    reason: format!("{} cannot run {abi_name}", runtime.command_name()),
+                })
+            }
+            None => {
+                let candidates: Vec<WasmRuntime> = WasmRuntime::ALL
+                    .into_iter()
+                    .filter(|&runtime| supports(runtime))
+                    .collect();
+                return candidates
+                    .iter()
+                    .copied()
+                    .find(WasmRuntime::is_installed)
+                    .ok_or_else(|| PluginError::CompileToolNotFound {
+                        tool: format!(
+                            "a runtime for {abi_name} ({})",
+                            candidates
+                                .iter()
+                                .map(WasmRuntime::command_name)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                    });
+            }
+        };
+        if !runtime.is_installed() {
+            return Err(PluginError::CompileToolNotFound {
+                tool: format!("{} ({})", runtime.command_name(), runtime.homepage()),
+            });
+        }
+        Ok(runtime)
+    }
+
+    fn wasmtime_command(wasm_path: &Path, run_config: &WasiRunConfig) -> Command {
+        let mut command = Command::new("wasmtime");
+        command.arg("run");
+        for dir in &run_config.dirs {
+            command
+                .arg("--dir")
+                .arg(format!("{}::{}", dir.host, dir.guest));
+        }
+        for (key, value) in &run_config.env {
+            command.arg("--env").arg(format!("{key}={value}"));
+        }
+        command.arg(wasm_path).args(&run_config.args);
+        command
+    }
+
+    fn wasmer_command(wasm_path: &Path, run_config: &WasiRunConfig) -> Command {
+        let mut command = Command::new("wasmer");
+        command.arg("run");
+        for dir in &run_config.dirs {
+            command
+                .arg("--mapdir")
+                .arg(format!("{}:{}", dir.guest, dir.host));
+        }
+        for (key, value) in &run_config.env {
+            command.arg("--env").arg(format!("{key}={value}"));
+        }
+        command.arg(wasm_path).arg("--").args(&run_config.args);
+        command
+    }
+
+    fn wazero_command(wasm_path: &Path, run_config: &WasiRunConfig) -> Command {
+        let mut command = Command::new("wazero");
+        command.arg("run");
+        for dir in &run_config.dirs {
+            command.arg(format!("-mount={}:{}", dir.host, dir.guest));
+        }
+        for (key, value) in &run_config.env {
+            command.arg(format!("-env={key}={value}"));
+        }
+        command.arg(wasm_path).args(&run_config.args);
+        command
+    }
+
+    fn node_wasi_command(
+        wasm_path: &Path,
+        run_config: &WasiRunConfig,
+        runner_directory: &Path,
+    ) -> PluginResult<Command> {
+        let preopens: BTreeMap<&str, &str> = run_config
+            .dirs
+            .iter()
+            .map(|dir| (dir.guest.as_str(), dir.host.as_str()))
+            .collect();
+        let configuration = serde_json::json!({
+            "args": run_config.args,
+            "env": run_config.env,
+            "preopens": preopens,
+        });
+        let runner_path = runner_directory.join("run_wasi.js");
+        fs::write(&runner_path, NODE_WASI_RUNNER_TEMPLATE).map_err(PluginError::Io)?;
+
+        let mut command = Command::new("node");
+        // node:wasi prints an experimental warning on every start
+        command
+            .arg("--no-warnings")
+            .arg(runner_path)
+            .arg(fs::canonicalize(wasm_path).map_err(PluginError::Io)?)
+            .arg(configuration.to_string());
+        Ok(command)
+    }
+
+    fn node_js_command(
+        wasm_path: &Path,
+        toolchain: Toolchain,
+        run_config: &WasiRunConfig,
+        runner_directory: &Path,
+    ) -> PluginResult<Command> {
+        if !run_config.dirs.is_empty() {
+            return Err(PluginError::UnsupportedTarget {
+                reason: "preopened directories need a WASI module; js/wasm modules reach the host filesystem through Node.js".to_string(),
+            });
+        }
+
+        let runner_path = runner_directory.join("run.js");
+        fs::write(&runner_path, NODE_RUNNER_TEMPLATE).map_err(PluginError::Io)?;
+        fs::copy(
+            toolchain.locate_wasm_exec()?,
+            runner_directory.join("wasm_exec.js"),
+        )
+        .map_err(PluginError::Io)?;
+        let environment =
+            serde_json::to_string(&run_config.env).map_err(|e| PluginError::ExecutionFailed {
+                reason: e.to_string(),
+            })?;
+
+        let mut command = Command::new("node");
+        command
+            .arg(runner_path)
+            .arg(fs::canonicalize(wasm_path).map_err(PluginError::Io)?)
+            .arg(environment)
+            .args(&run_config.args);
+        Ok(command)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    CommandExecutor, CompileConfig, Logger, OptimizationLevel, PathResolver, PluginResult,
+    Toolchain, WasmBuilder,
+};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const MAIN_GO_TEMPLATE: &str = include_str!("templates/selftest_main.go");
+const GO_MOD_TEMPLATE: &str = include_str!("templates/selftest_go.mod");
+const NODE_RUNNER_TEMPLATE: &str = include_str!("templates/selftest_runner.js");
+
+/// Line the embedded program prints when it runs.
+pub const SELFTEST_GREETING: &str = "hello from wasmgo selftest";
+
+/// Outcome of building (and running) the embedded program with one toolchain.
+#[derive(Debug, Clone)]
+pub struct SelfTestEntry {
+    pub toolchain: Toolchain,
+    pub wasm_size: Option<u64>,
+    pub build_time: Duration,
+    pub error: Option<String>,
+    /// `None` when instantiation was not requested or `node` is unavailable
+    pub instantiated: Option<Result<(), String>>,
+}
+
+impl SelfTestEntry {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && !matches!(self.instantiated, Some(Err(_)))
+    }
+}
+
+/// End-to-end check of the local environment using a hello-world program
+/// shipped inside the binary.
+pub struct SelfTest;
+
+impl SelfTest {
+    /// Toolchains installed on this machine.
+    pub fn detect_toolchains() -> Vec<Toolchain> {
+        [Toolchain::TinyGo, Toolchain::Go]
+            .into_iter()
+            .filter(|toolchain| CommandExecutor::is_tool_installed(toolchain.command_name()))
+            .collect()
+    }
+
+    /// Writes the program to a temp directory and builds it with each toolchain,
+    /// optionally running the result under Node.js with the toolchain's `wasm_exec.js`.
+    pub fn run(
+        builder: &dyn WasmBuilder,
+        toolchains: &[Toolchain],
+        instantiate: bool,
+        verbose: bool,
+    ) -> PluginResult<Vec<SelfTestEntry>> {
+        // Removed with everything built in it when this goes out of scope
+        let temp_directory =
+            PathResolver::private_directory(&std::env::temp_dir(), "wasmgo-selftest-")?;
+        let project_directory = temp_directory.path().to_path_buf();
+        PathResolver::write_private(&project_directory.join("main.go"), MAIN_GO_TEMPLATE)?;
+        PathResolver::write_private(&project_directory.join("go.mod"), GO_MOD_TEMPLATE)?;
+        let runs_node = instantiate && CommandExecutor::is_tool_installed("node");
+
+        let mut entries = Vec::new();
+        for &toolchain in toolchains {
+            let configuration = CompileConfig {
+                project_path: project_directory.clone(),
+                output_directory: PathBuf::from(format!("dist-{}", toolchain.command_name())),
+                verbose,
+                optimization_level: OptimizationLevel::Release,
+                toolchain,
+                ..CompileConfig::default()
+            };
+
+            Logger::info(format_args!(
+                "🧪 Building with {}...",
+                toolchain.display_name()
+            ));
+            let started = Instant::now();
+            let result = builder.compile(&configuration);
+            let build_time = started.elapsed();
+
+            entries.push(match result {
+                Ok(compile_result) => {
+                    let wasm_file_path = PathBuf::from(&compile_result.wasm_file_path);
+                    SelfTestEntry {
+                        toolchain,
+                        wasm_size: fs::metadata(&wasm_file_path)
+                            .map(|metadata| metadata.len())
+                            .ok(),
+                        build_time,
+                        error: None,
+                        instantiated: runs_node
+                            .then(|| Self::run_under_node(toolchain, &wasm_file_path)),
+                    }
+                }
+                Err(e) => SelfTestEntry {
+                    toolchain,
+                    wasm_size: None,
+                    build_time,
+                    error: Some(e.to_string()),
+                    instantiated: None,
+                },
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn run_under_node(toolchain: Toolchain, wasm_file_path: &Path) -> Result<(), String> {
+        let run_directory = wasm_file_path.parent().unwrap_or(Path::new("."));
+        let wasm_exec = toolchain.locate_wasm_exec().map_err(|e| e.to_string())?;
+        fs::copy(&wasm_exec, run_directory.join("wasm_exec.js")).map_err(|e| e.to_string())?;
+        PathResolver::write_private(&run_directory.join("runner.js"), NODE_RUNNER_TEMPLATE)
+            .map_err(|e| e.to_string())?;
+
+        let wasm_file_name = wasm_file_path.file_name().unwrap_or_default();
+        let output = CommandExecutor::execute_command(
+            "node",
+            &[OsStr::new("runner.js"), wasm_file_name],
+            run_directory,
+            false,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if output.status.success() && stdout.contains(SELFTEST_GREETING) {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected \"{SELFTEST_GREETING}\", got: {}",
+                format!("{stdout}{}", String::from_utf8_lossy(&output.stderr)).trim()
+            ))
+        }
+    }
+}
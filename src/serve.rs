@@ -0,0 +1,170 @@
+use crate::{PluginError, PluginResult};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+
+/// Minimal HTTP server for a build's output directory.
+///
+/// Serves GET and HEAD with `application/wasm` for modules and the
+/// cross-origin isolation headers (COOP/COEP) that threads and
+/// `SharedArrayBuffer` need. Nothing is cached, so a rebuild shows up on the
+/// next request.
+pub struct StaticServer {
+    root: PathBuf,
+    listener: TcpListener,
+}
+
+impl StaticServer {
+    /// Binds `address`, e.g. `127.0.0.1:8000`; port 0 picks a free one.
+    pub fn bind(root: &Path, address: &str) -> PluginResult<Self> {
+        let root = fs::canonicalize(root).map_err(PluginError::Io)?;
+        let listener = TcpListener::bind(address).map_err(|e| PluginError::ExecutionFailed {
+            reason: format!("cannot listen on {address}: {e}"),
+        })?;
+        Ok(Self { root, listener })
+    }
+
+    pub fn local_addr(&self) -> PluginResult<SocketAddr> {
+        self.listener.local_addr().map_err(PluginError::Io)
+    }
+
+    /// URL of a file under the served directory.
+    pub fn url_for(&self, path: &Path) -> PluginResult<String> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+        let relative: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        Ok(format!(
+            "http://{}/{}",
+            self.local_addr()?,
+            relative.join("/")
+        ))
+    }
+
+    /// Serves requests until the process exits, one thread per connection.
+    pub fn serve(&self) -> PluginResult<()> {
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let root = self.root.clone();
+            std::thread::spawn(move || {
+                let _ = Self::handle(&root, stream);
+            });
+        }
+        Ok(())
+    }
+
+    pub fn content_type(path: &Path) -> &'static str {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("wasm") => "application/wasm",
+            Some("js" | "mjs") => "text/javascript; charset=utf-8",
+            Some("html") => "text/html; charset=utf-8",
+            Some("css") => "text/css; charset=utf-8",
+            Some("json" | "map") => "application/json",
+            Some("svg") => "image/svg+xml",
+            Some("png") => "image/png",
+            Some("txt" | "ts") => "text/plain; charset=utf-8",
+            _ => "application/octet-stream",
+        }
+    }
+
+    fn handle(root: &Path, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Headers are read and ignored
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut words = request_line.split_whitespace();
+        let (method, target) = (
+            words.next().unwrap_or_default(),
+            words.next().unwrap_or("/"),
+        );
+        let head = method == "HEAD";
+        if method != "GET" && !head {
+            return Self::respond(stream, "405 Method Not Allowed", None, head);
+        }
+        match Self::resolve(root, target) {
+            Some(file) => Self::respond(stream, "200 OK", Some(&file), head),
+            None => Self::respond(stream, "404 Not Found", None, head),
+        }
+    }
+
+    /// File for a request target, confined to the root; `/` and directories
+    /// serve their index.html.
+    fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+        let path = target.split(['?', '#']).next().unwrap_or_default();
+        let path = Self::percent_decode(path)?;
+        let relative = Path::new(path.trim_start_matches('/'));
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return None;
+        }
+        let mut file = root.join(relative);
+        if file.is_dir() {
+            file.push("index.html");
+        }
+        let file = fs::canonicalize(file).ok()?;
+        (file.starts_with(root) && file.is_file()).then_some(file)
+    }
+
+    fn percent_decode(path: &str) -> Option<String> {
+        let bytes = path.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes[index] == b'%' {
+                let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            } else {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+        String::from_utf8(decoded).ok()
+    }
+
+    /// Sends the file, or the status text as the body of an error.
+    fn respond(
+        mut stream: TcpStream,
+        status: &str,
+        file: Option<&Path>,
+        head: bool,
+    ) -> io::Result<()> {
+        let (content_type, length) = match file {
+            Some(file) => (Self::content_type(file), fs::metadata(file)?.len()),
+            None => ("text/plain; charset=utf-8", status.len() as u64),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\n\
+             Content-Type: {content_type}\r\n\
+             Content-Length: {length}\r\n\
+             Cache-Control: no-store\r\n\
+             Cross-Origin-Opener-Policy: same-origin\r\n\
+             Cross-Origin-Embedder-Policy: require-corp\r\n\
+             Cross-Origin-Resource-Policy: cross-origin\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             Connection: close\r\n\r\n"
+        )?;
+        if !head {
+            match file {
+                Some(file) => {
+                    io::copy(&mut File::open(file)?, &mut stream)?;
+                }
+                None => stream.write_all(status.as_bytes())?,
+            }
+        }
+        stream.flush()
+    }
+}
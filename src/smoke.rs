@@ -0,0 +1,95 @@
+use crate::{
+    CommandExecutor, ExternalKind, PathResolver, PluginError, PluginResult, WasmBinary, WasmKind,
+};
+use std::path::Path;
+
+const NODE_INSTANTIATE_TEMPLATE: &str = include_str!("templates/smoke_instantiate.js");
+
+/// Outcome of [`SmokeTest::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmokeTestReport {
+    /// Exports confirmed to exist
+    pub exports: Vec<String>,
+    /// Whether the module was instantiated; [`Self::skipped`] says why not
+    pub instantiated: bool,
+    pub skipped: Option<String>,
+}
+
+/// Post-build check that a module loads: its expected exports exist and it
+/// instantiates under Node.js's engine, with every import stubbed so nothing runs.
+/// There is no embedded engine; without Node.js only the exports are checked.
+pub struct SmokeTest;
+
+impl SmokeTest {
+    pub fn run(wasm_path: &Path, expected_exports: &[String]) -> PluginResult<SmokeTestReport> {
+        let failed = |reason: String| PluginError::InvalidWasm {
+            path: wasm_path.to_string_lossy().to_string(),
+            reason,
+        };
+        let binary = WasmBinary::read(wasm_path)?;
+        if binary.kind == WasmKind::Component {
+            return Ok(SmokeTestReport {
+                exports: Vec::new(),
+                instantiated: false,
+                skipped: Some("components need a component-model host".to_string()),
+            });
+        }
+
+        let missing: Vec<&str> = expected_exports
+            .iter()
+            .filter(|name| !binary.exports.iter().any(|export| &export.name == *name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(failed(format!(
+                "expected exports are missing: {}",
+                missing.join(", ")
+            )));
+        }
+        let mut report = SmokeTestReport {
+            exports: expected_exports.to_vec(),
+            instantiated: false,
+            skipped: None,
+        };
+
+        // Stubs can only stand in for functions
+        if let Some(import) = binary
+            .imports
+            .iter()
+            .find(|import| import.kind != ExternalKind::Function)
+        {
+            report.skipped = Some(format!(
+                "imports {:?} {}.{}, which only the host can provide",
+                import.kind, import.module, import.name
+            ));
+            return Ok(report);
+        }
+        if !CommandExecutor::is_tool_installed("node") {
+            report.skipped = Some("node is not installed".to_string());
+            return Ok(report);
+        }
+
+        let script_directory =
+            PathResolver::private_directory(&std::env::temp_dir(), "wasmgo-smoke-")?;
+        let script = script_directory.path().join("instantiate.cjs");
+        PathResolver::write_private(&script, NODE_INSTANTIATE_TEMPLATE)?;
+        let output = CommandExecutor::execute_command(
+            "node",
+            &[script.as_os_str(), wasm_path.as_os_str()],
+            Path::new("."),
+            false,
+        )?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Node prints the script location before the error itself
+            let error = stderr
+                .lines()
+                .find(|line| line.contains("Error"))
+                .unwrap_or(stderr.trim());
+            return Err(failed(format!("does not instantiate: {}", error.trim())));
+        }
+
+        report.instantiated = true;
+        Ok(report)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::CommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Variable the git version is linked into unless configured otherwise:
+/// `var version string` in package main.
+pub const DEFAULT_VERSION_VARIABLE: &str = "main.version";
+
+/// The git version of the sources a module was built from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionStamp {
+    /// `git describe --tags --always --dirty`, e.g. `v1.2.0-3-g1a2b3c4-dirty`
+    pub describe: String,
+    pub commit: String,
+    /// Uncommitted changes to tracked files
+    pub dirty: bool,
+}
+
+impl VersionStamp {
+    /// The checkout `project_path` (a directory or a single file) is in, if any.
+    pub fn detect(project_path: &Path) -> Option<Self> {
+        let directory = match project_path.parent() {
+            Some(parent) if project_path.is_file() && parent.as_os_str().is_empty() => {
+                Path::new(".")
+            }
+            Some(parent) if project_path.is_file() => parent,
+            _ => project_path,
+        };
+        let git = |arguments: &[&str]| {
+            CommandExecutor::execute_command("git", arguments, directory, false)
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|output| !output.is_empty())
+        };
+
+        let commit = git(&["rev-parse", "HEAD"])?;
+        let describe = git(&["describe", "--tags", "--always", "--dirty"])?;
+        Some(Self {
+            dirty: describe.ends_with("-dirty"),
+            describe,
+            commit,
+        })
+    }
+
+    /// The linker flag setting `variable` (`-X` syntax, `importpath.name`) to
+    /// the described version.
+    pub fn ldflags_argument(&self, variable: &str) -> String {
+        format!("-ldflags=-X={variable}={}", self.describe)
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{PathResolver, PluginError, PluginResult, ToolCommand, ToolRegistry};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Base URL of the collector; traces go to `<endpoint>/v1/traces`.
+pub const OTLP_ENDPOINT_VARIABLE: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Full traces URL, taking precedence over [`OTLP_ENDPOINT_VARIABLE`].
+pub const OTLP_TRACES_ENDPOINT_VARIABLE: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+
+const DEFAULT_TIMEOUT_MILLISECONDS: u64 = 10_000;
+
+thread_local! {
+    static TRACE: RefCell<Option<Trace>> = const { RefCell::new(None) };
+    static TIMINGS: RefCell<Option<Vec<(String, Duration)>>> = const { RefCell::new(None) };
+}
+
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Spans of the build running on this thread.
+struct Trace {
+    trace_id: String,
+    open: Vec<String>,
+    finished: Vec<Value>,
+}
+
+#[derive(Debug)]
+struct SpanRecord {
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    start: u64,
+    attributes: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+/// One timed phase of a build, exported over OTLP when the `otel` feature is
+/// enabled and a collector endpoint is configured.
+///
+/// The first span started on a thread begins a trace and, when it ends,
+/// sends the trace; spans started while it is open become its children.
+/// Endpoint, headers, timeout and resource attributes are read from the
+/// standard `OTEL_*` variables. Without the feature or an endpoint, spans
+/// record nothing. Export uses `curl` and never fails a build.
+///
+/// Independently of tracing, [`Self::timed`] collects how long each span took.
+#[derive(Debug)]
+pub struct BuildSpan {
+    record: Option<SpanRecord>,
+    timing: Option<(String, Instant)>,
+}
+
+impl BuildSpan {
+    /// Whether builds are traced at all.
+    pub fn is_enabled() -> bool {
+        let disabled = std::env::var("OTEL_SDK_DISABLED")
+            .is_ok_and(|value| value.eq_ignore_ascii_case("true"));
+        cfg!(feature = "otel") && !disabled && Self::traces_endpoint().is_some()
+    }
+
+    pub fn start(name: &str) -> Self {
+        let timing = TIMINGS
+            .with(|timings| timings.borrow().is_some())
+            .then(|| (name.to_string(), Instant::now()));
+        let parent_span_id = TRACE.with(|trace| {
+            let mut trace = trace.borrow_mut();
+            if trace.is_none() {
+                if !Self::is_enabled() {
+                    return None;
+                }
+                *trace = Some(Trace {
+                    trace_id: Self::new_id(16),
+                    open: Vec::new(),
+                    finished: Vec::new(),
+                });
+            }
+            let trace = trace.as_mut()?;
+            Some(trace.open.last().cloned())
+        });
+        let Some(parent_span_id) = parent_span_id else {
+            return Self {
+                record: None,
+                timing,
+            };
+        };
+        let span_id = Self::new_id(8);
+        TRACE.with(|trace| {
+            if let Some(trace) = trace.borrow_mut().as_mut() {
+                trace.open.push(span_id.clone());
+            }
+        });
+        Self {
+            record: Some(SpanRecord {
+                span_id,
+                parent_span_id,
+                name: name.to_string(),
+                start: Self::now(),
+                attributes: Vec::new(),
+                error: None,
+            }),
+            timing,
+        }
+    }
+
+    /// Starts a span only if no trace is open, so nested builds stay inside
+    /// the outermost one.
+    pub fn start_root(name: &str) -> Self {
+        if TRACE.with(|trace| trace.borrow().is_some()) {
+            return Self {
+                record: None,
+                timing: None,
+            };
+        }
+        Self::start(name)
+    }
+
+    /// Runs `build` on this thread, returning the duration of every span
+    /// that ended during it, in the order they ended.
+    pub fn timed<T>(build: impl FnOnce() -> T) -> (T, Vec<(String, Duration)>) {
+        let outer = TIMINGS.with(|timings| timings.borrow_mut().replace(Vec::new()));
+        let result = build();
+        let timings = TIMINGS.with(|timings| std::mem::replace(&mut *timings.borrow_mut(), outer));
+        (result, timings.unwrap_or_default())
+    }
+
+    /// Runs `phase` inside a span named `name`, marking it failed on error.
+    pub fn trace<T>(name: &str, phase: impl FnOnce() -> PluginResult<T>) -> PluginResult<T> {
+        let mut span = Self::start(name);
+        let result = phase();
+        span.record_result(&result);
+        result
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.record.is_some()
+    }
+
+    pub fn set_attribute(&mut self, key: &str, value: impl ToString) {
+        if let Some(record) = &mut self.record {
+            record.attributes.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    /// Records the span's attributes describing a toolchain: its name and the
+    /// version it reports.
+    pub fn set_toolchain(&mut self, command_name: &str) {
+        if !self.is_recording() {
+            return;
+        }
+        self.set_attribute("wasmgo.toolchain", command_name);
+        if let Some(version) = ToolRegistry::version_output(command_name) {
+            self.set_attribute("wasmgo.toolchain.version", version.trim());
+        }
+    }
+
+    pub fn fail(&mut self, message: impl ToString) {
+        if let Some(record) = &mut self.record {
+            record.error = Some(message.to_string());
+        }
+    }
+
+    pub fn record_result<T>(&mut self, result: &PluginResult<T>) {
+        if let Err(error) = result {
+            self.fail(error);
+        }
+    }
+
+    fn finish(record: SpanRecord) {
+        let mut attributes = record.attributes;
+        let status = match &record.error {
+            Some(message) => {
+                attributes.push(("error.message".to_string(), message.clone()));
+                json!({ "code": 2, "message": message })
+            }
+            None => json!({ "code": 1 }),
+        };
+        let trace = TRACE.with(|trace| {
+            let mut slot = trace.borrow_mut();
+            let trace = slot.as_mut()?;
+            let mut span = json!({
+                "traceId": trace.trace_id,
+                "spanId": record.span_id,
+                "name": record.name,
+                "kind": 1,
+                "startTimeUnixNano": record.start.to_string(),
+                "endTimeUnixNano": Self::now().to_string(),
+                "attributes": Self::attributes(&attributes),
+                "status": status,
+            });
+            if let Some(parent) = &record.parent_span_id {
+                span["parentSpanId"] = json!(parent);
+            }
+            trace.finished.push(span);
+            trace.open.retain(|id| *id != record.span_id);
+            if trace.open.is_empty() {
+                slot.take()
+            } else {
+                None
+            }
+        });
+        if let Some(trace) = trace {
+            if let Err(error) = Self::export(trace.finished) {
+                crate::Logger::warn(format_args!("⚠️  Could not export build trace: {error}"));
+            }
+        }
+    }
+
+    fn export(spans: Vec<Value>) -> PluginResult<()> {
+        let Some(endpoint) = Self::traces_endpoint() else {
+            return Ok(());
+        };
+        if !crate::CommandExecutor::is_tool_installed("curl") {
+            return Err(PluginError::CompileToolNotFound {
+                tool: "curl".to_string(),
+            });
+        }
+        let service_name = std::env::var("OTEL_SERVICE_NAME")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "wasmgo".to_string());
+        let mut resource = vec![
+            ("service.name".to_string(), service_name),
+            (
+                "service.version".to_string(),
+                env!("CARGO_PKG_VERSION").to_string(),
+            ),
+            ("os.type".to_string(), std::env::consts::OS.to_string()),
+            ("host.arch".to_string(), std::env::consts::ARCH.to_string()),
+        ];
+        resource.extend(Self::key_value_list("OTEL_RESOURCE_ATTRIBUTES"));
+        let payload = json!({
+            "resourceSpans": [{
+                "resource": { "attributes": Self::attributes(&resource) },
+                "scopeSpans": [{
+                    "scope": { "name": "wasmgo", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }],
+            }],
+        });
+
+        // Headers often carry an API key, so neither they nor the payload show
+        // up in a command line
+        let directory = PathResolver::private_directory(&std::env::temp_dir(), ".wasmgo-otlp-")?;
+        let payload_file = directory.path().join("spans.json");
+        let header_file = directory.path().join("headers");
+        let mut headers = String::from("Content-Type: application/json\n");
+        for (key, value) in Self::key_value_list("OTEL_EXPORTER_OTLP_HEADERS")
+            .into_iter()
+            .chain(Self::key_value_list("OTEL_EXPORTER_OTLP_TRACES_HEADERS"))
+        {
+            headers.push_str(&format!("{key}: {value}\n"));
+        }
+        let timeout = std::env::var("OTEL_EXPORTER_OTLP_TIMEOUT")
+            .ok()
+            .and_then(|milliseconds| milliseconds.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_TIMEOUT_MILLISECONDS);
+
+        let result = PathResolver::write_private(&payload_file, &payload.to_string())
+            .and_then(|_| PathResolver::write_private(&header_file, &headers))
+            .and_then(|_| {
+                let mut data_argument = std::ffi::OsString::from("@");
+                data_argument.push(&payload_file);
+                let mut header_argument = std::ffi::OsString::from("@");
+                header_argument.push(&header_file);
+                let output = ToolCommand::new("curl")
+                    .args([
+                        "-fsS",
+                        "-o",
+                        if cfg!(windows) { "NUL" } else { "/dev/null" },
+                    ])
+                    .arg("--max-time")
+                    .arg(format!("{:.3}", timeout as f64 / 1000.0))
+                    .arg("-H")
+                    .arg(header_argument)
+                    .arg("--data-binary")
+                    .arg(data_argument)
+                    .arg(&endpoint)
+                    .output(false)?;
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    Err(PluginError::ExecutionFailed {
+                        reason: format!(
+                            "{endpoint}: {}",
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        ),
+                    })
+                }
+            });
+        drop(directory);
+        result
+    }
+
+    fn traces_endpoint() -> Option<String> {
+        let nonempty = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        nonempty(OTLP_TRACES_ENDPOINT_VARIABLE).or_else(|| {
+            nonempty(OTLP_ENDPOINT_VARIABLE)
+                .map(|endpoint| format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        })
+    }
+
+    /// `key1=value1,key2=value2`, as the `OTEL_*` list variables are written.
+    fn key_value_list(variable: &str) -> Vec<(String, String)> {
+        std::env::var(variable)
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                let key = key.trim();
+                (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
+
+    fn attributes(attributes: &[(String, String)]) -> Value {
+        attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+            .collect()
+    }
+
+    /// Hex ID of `bytes` bytes, unique per process, thread and call.
+    fn new_id(bytes: usize) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(Self::now().to_le_bytes());
+        hasher.update(std::process::id().to_le_bytes());
+        hasher.update(ID_COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        hasher.update(format!("{:?}", std::thread::current().id()).as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .take(bytes)
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Nanoseconds since the Unix epoch
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    }
+}
+
+impl Drop for BuildSpan {
+    fn drop(&mut self) {
+        if let Some((name, started)) = self.timing.take() {
+            TIMINGS.with(|timings| {
+                if let Some(timings) = timings.borrow_mut().as_mut() {
+                    timings.push((name, started.elapsed()));
+                }
+            });
+        }
+        if let Some(record) = self.record.take() {
+            Self::finish(record);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+// Generated by wasmgo. Cloudflare Worker entry bridging `fetch` to Go.
+//
+// The Go program registers its handler and then blocks:
+//
+//   js.Global().Set("wasmgoHandleRequest", js.FuncOf(func(this js.Value, args []js.Value) any {
+//       request, env, ctx := args[0], args[1], args[2]
+//       // ... return a Response, or a Promise resolving to one
+//   }))
+//   select {}
+import "./wasm_exec.js";
+import module from "./{{WASM_FILE}}";
+
+const HANDLER = "{{HANDLER}}";
+
+let ready;
+
+function start() {
+  ready ??= (async () => {
+    const go = new Go();
+    const instance = await WebAssembly.instantiate(module, go.importObject);
+    go.run(instance).catch((error) => console.error("Go program exited:", error));
+    if (typeof globalThis[HANDLER] !== "function") {
+      throw new Error(`{{WASM_FILE}} did not register globalThis.${HANDLER}`);
+    }
+  })();
+  return ready;
+}
+
+export default {
+  async fetch(request, env, ctx) {
+    try {
+      await start();
+    } catch (error) {
+      ready = undefined;
+      return new Response(`Failed to start Go: ${error}`, { status: 500 });
+    }
+    return globalThis[HANDLER](request, env, ctx);
+  },
+};
@@ -0,0 +1,30 @@
+// Generated by wasmgo. <{{ELEMENT_NAME}}> custom element around {{LOADER_FILE}}.
+//
+//   <{{ELEMENT_NAME}} src="/assets/{{WASM_FILE}}"></{{ELEMENT_NAME}}>
+//   element.addEventListener("ready", ({ detail: module }) => module.add(1, 2));
+import { load } from "./{{LOADER_FILE}}";
+
+class GoWasmElement extends HTMLElement {
+  #ready;
+  module = null;
+
+  connectedCallback() {
+    this.whenReady().then(
+      (module) => this.dispatchEvent(new CustomEvent("ready", { detail: module })),
+      (error) => this.dispatchEvent(new CustomEvent("error", { detail: error })),
+    );
+  }
+
+  // Resolves with the loaded module; `src` overrides the module URL
+  whenReady() {
+    this.#ready ??= load(this.getAttribute("src") ?? undefined).then((module) => {
+      this.module = module;
+      return module;
+    });
+    return this.#ready;
+  }
+}
+
+if (!customElements.get("{{ELEMENT_NAME}}")) {
+  customElements.define("{{ELEMENT_NAME}}", GoWasmElement);
+}
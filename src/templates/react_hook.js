@@ -0,0 +1,27 @@
+// Generated by wasmgo. React hook around {{LOADER_FILE}}; the module is
+// instantiated once and shared by every component using the hook.
+//
+//   const { module, loading, error } = useGoWasm();
+//   if (module) console.log(module.add(1, 2));
+import { useEffect, useState } from "react";
+import { load } from "./{{LOADER_FILE}}";
+
+let modulePromise;
+
+export function useGoWasm() {
+  const [state, setState] = useState({ module: null, loading: true, error: null });
+
+  useEffect(() => {
+    let active = true;
+    modulePromise ??= load();
+    modulePromise.then(
+      (module) => active && setState({ module, loading: false, error: null }),
+      (error) => active && setState({ module: null, loading: false, error }),
+    );
+    return () => {
+      active = false;
+    };
+  }, []);
+
+  return state;
+}
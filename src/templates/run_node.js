@@ -0,0 +1,24 @@
+// Generated by wasmgo run: runs a js/wasm module under Node.js with the
+// configured argv and environment.
+const fs = require("fs");
+globalThis.require = require;
+globalThis.fs = fs;
+globalThis.path = require("path");
+globalThis.TextEncoder ??= require("util").TextEncoder;
+globalThis.TextDecoder ??= require("util").TextDecoder;
+globalThis.performance ??= require("perf_hooks").performance;
+globalThis.crypto ??= require("crypto").webcrypto;
+
+require("./wasm_exec.js");
+
+const [modulePath, environment, ...args] = process.argv.slice(2);
+const go = new Go();
+go.argv = [globalThis.path.basename(modulePath), ...args];
+go.env = JSON.parse(environment);
+go.exit = (code) => process.exit(code);
+WebAssembly.instantiate(fs.readFileSync(modulePath), go.importObject)
+  .then((result) => go.run(result.instance))
+  .catch((error) => {
+    console.error(error);
+    process.exit(1);
+  });
@@ -0,0 +1,21 @@
+// Generated by wasmgo run: runs a WASI preview1 module under Node.js with the
+// configured argv, environment and preopened directories.
+const fs = require("fs");
+const path = require("path");
+const { WASI } = require("wasi");
+
+const [modulePath, configuration] = process.argv.slice(2);
+const { args, env, preopens } = JSON.parse(configuration);
+const wasi = new WASI({
+  version: "preview1",
+  args: [path.basename(modulePath), ...args],
+  env,
+  preopens,
+  returnOnExit: true,
+});
+WebAssembly.instantiate(fs.readFileSync(modulePath), wasi.getImportObject())
+  .then(({ instance }) => process.exit(wasi.start(instance)))
+  .catch((error) => {
+    console.error(error);
+    process.exit(1);
+  });
@@ -0,0 +1,3 @@
+module wasmgo-selftest
+
+go 1.21
@@ -0,0 +1,20 @@
+// Generated by wasmgo selftest: runs a js/wasm module under Node.js.
+const fs = require("fs");
+globalThis.require = require;
+globalThis.fs = fs;
+globalThis.path = require("path");
+globalThis.TextEncoder ??= require("util").TextEncoder;
+globalThis.TextDecoder ??= require("util").TextDecoder;
+globalThis.performance ??= require("perf_hooks").performance;
+globalThis.crypto ??= require("crypto").webcrypto;
+
+require("./wasm_exec.js");
+
+const go = new Go();
+go.exit = (code) => process.exit(code);
+WebAssembly.instantiate(fs.readFileSync(process.argv[2]), go.importObject)
+  .then((result) => go.run(result.instance))
+  .catch((error) => {
+    console.error(error);
+    process.exit(1);
+  });
@@ -0,0 +1,13 @@
+// Generated by wasmgo --smoke-test: instantiates a module against stub imports
+// so only a start section, never main, runs.
+const fs = require("fs");
+
+const wasmModule = new WebAssembly.Module(fs.readFileSync(process.argv[2]));
+const imports = {};
+for (const { module: name, name: field } of WebAssembly.Module.imports(wasmModule)) {
+  imports[name] ??= {};
+  imports[name][field] = () => {
+    throw new Error(`${name}.${field} called during instantiation`);
+  };
+}
+new WebAssembly.Instance(wasmModule, imports);
@@ -0,0 +1,72 @@
+// Generated by wasmgo. Runs {{WASM_FILE}}, built with shared memory and
+// wasi-threads, on a pool of workers so goroutines can run in parallel.
+//
+// SharedArrayBuffer is only available to cross-origin isolated pages: serve
+// the page with `Cross-Origin-Opener-Policy: same-origin` and
+// `Cross-Origin-Embedder-Policy: require-corp` (as `wasmgo compile --serve` does).
+
+const WORKER_URL = new URL("{{THREAD_FILE}}", import.meta.url);
+
+// Resolves with main's exit code once it returns or any thread calls exit.
+export async function run({
+  source = new URL("{{WASM_FILE}}", import.meta.url),
+  poolSize = navigator.hardwareConcurrency || 4,
+} = {}) {
+  if (!globalThis.crossOriginIsolated) {
+    throw new Error(
+      "{{WASM_FILE}} uses shared memory; serve this page with Cross-Origin-Opener-Policy: same-origin and Cross-Origin-Embedder-Policy: require-corp",
+    );
+  }
+  const module = await WebAssembly.compileStreaming(fetch(source));
+  const memory = new WebAssembly.Memory({
+    initial: {{INITIAL_PAGES}},
+    maximum: {{MAXIMUM_PAGES}},
+    shared: true,
+  });
+  // Next thread ID, handed out by the workers themselves since thread-spawn
+  // must return it synchronously
+  const nextThreadId = new Int32Array(new SharedArrayBuffer(4));
+  nextThreadId[0] = 1;
+
+  const newWorker = () => new Worker(WORKER_URL, { type: "module" });
+  const idle = Array.from({ length: poolSize }, newWorker);
+  const running = new Set();
+
+  return new Promise((resolve, reject) => {
+    const stop = () => {
+      for (const worker of [...running, ...idle]) {
+        worker.terminate();
+      }
+      running.clear();
+      idle.length = 0;
+    };
+    const start = (threadId, startArg) => {
+      const worker = idle.pop() ?? newWorker();
+      if (idle.length < poolSize) {
+        idle.push(newWorker());
+      }
+      running.add(worker);
+      worker.onmessage = ({ data }) => {
+        if (data.type === "spawn") {
+          start(data.threadId, data.startArg);
+          return;
+        }
+        running.delete(worker);
+        worker.terminate();
+        if (data.type === "error") {
+          stop();
+          reject(new Error(data.message));
+        } else if (data.threadId === 0 || data.exited) {
+          stop();
+          resolve(data.code);
+        }
+      };
+      worker.onerror = (event) => {
+        stop();
+        reject(event.error ?? new Error(event.message));
+      };
+      worker.postMessage({ module, memory, nextThreadId, threadId, startArg });
+    };
+    start(0, 0);
+  });
+}
@@ -0,0 +1,101 @@
+// Generated by wasmgo. One thread of {{WASM_FILE}}: thread 0 runs main
+// (_start), the others run wasi_thread_start for the threads it spawns.
+
+const ERRNO_SUCCESS = 0;
+const ERRNO_BADF = 8;
+const ERRNO_NOSYS = 52;
+
+class ProcessExit {
+  constructor(code) {
+    this.code = code;
+  }
+}
+
+// Minimal wasi_snapshot_preview1 host over shared memory, which TextDecoder
+// and getRandomValues only accept copies of.
+function createWasiImports(memory) {
+  const decoder = new TextDecoder();
+  const view = () => new DataView(memory.buffer);
+  const lineBuffers = { 1: "", 2: "" };
+
+  const implemented = {
+    args_sizes_get(argcPtr, bufSizePtr) {
+      view().setUint32(argcPtr, 0, true);
+      view().setUint32(bufSizePtr, 0, true);
+      return ERRNO_SUCCESS;
+    },
+    args_get: () => ERRNO_SUCCESS,
+    environ_sizes_get(countPtr, bufSizePtr) {
+      view().setUint32(countPtr, 0, true);
+      view().setUint32(bufSizePtr, 0, true);
+      return ERRNO_SUCCESS;
+    },
+    environ_get: () => ERRNO_SUCCESS,
+    clock_time_get(clockId, precision, timePtr) {
+      const nanos = clockId === 0
+        ? BigInt(Date.now()) * 1000000n
+        : BigInt(Math.round((performance.timeOrigin + performance.now()) * 1e6));
+      view().setBigUint64(timePtr, nanos, true);
+      return ERRNO_SUCCESS;
+    },
+    random_get(bufPtr, bufLen) {
+      const random = crypto.getRandomValues(new Uint8Array(bufLen));
+      new Uint8Array(memory.buffer, bufPtr, bufLen).set(random);
+      return ERRNO_SUCCESS;
+    },
+    fd_write(fd, iovsPtr, iovsLen, nwrittenPtr) {
+      let written = 0;
+      for (let i = 0; i < iovsLen; i++) {
+        const ptr = view().getUint32(iovsPtr + i * 8, true);
+        const len = view().getUint32(iovsPtr + i * 8 + 4, true);
+        const text = decoder.decode(new Uint8Array(memory.buffer, ptr, len).slice());
+        if (fd === 1 || fd === 2) {
+          const lines = (lineBuffers[fd] + text).split("\n");
+          lineBuffers[fd] = lines.pop();
+          lines.forEach((line) => (fd === 1 ? console.log(line) : console.error(line)));
+        }
+        written += len;
+      }
+      view().setUint32(nwrittenPtr, written, true);
+      return ERRNO_SUCCESS;
+    },
+    fd_prestat_get: () => ERRNO_BADF,
+    sched_yield: () => ERRNO_SUCCESS,
+    proc_exit(code) {
+      throw new ProcessExit(code);
+    },
+  };
+
+  return new Proxy(implemented, {
+    get: (target, name) => target[name] ?? (() => ERRNO_NOSYS),
+  });
+}
+
+onmessage = async ({ data: { module, memory, nextThreadId, threadId, startArg } }) => {
+  const imports = {
+    "{{MEMORY_MODULE}}": { "{{MEMORY_NAME}}": memory },
+    wasi: {
+      "thread-spawn"(spawnArg) {
+        const spawned = Atomics.add(nextThreadId, 0, 1);
+        postMessage({ type: "spawn", threadId: spawned, startArg: spawnArg });
+        return spawned;
+      },
+    },
+    wasi_snapshot_preview1: createWasiImports(memory),
+  };
+  try {
+    const instance = await WebAssembly.instantiate(module, imports);
+    if (threadId === 0) {
+      instance.exports._start();
+    } else {
+      instance.exports.wasi_thread_start(threadId, startArg);
+    }
+    postMessage({ type: "exit", threadId, code: 0, exited: false });
+  } catch (error) {
+    if (error instanceof ProcessExit) {
+      postMessage({ type: "exit", threadId, code: error.code, exited: true });
+    } else {
+      postMessage({ type: "error", threadId, message: String(error?.stack ?? error) });
+    }
+  }
+};
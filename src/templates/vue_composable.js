@@ -0,0 +1,25 @@
+// Generated by wasmgo. Vue composable around {{LOADER_FILE}}; the module is
+// instantiated once and shared by every component using it.
+//
+//   const { module, loading, error } = useGoWasm();
+//   const sum = computed(() => module.value?.add(1, 2));
+import { ref, shallowRef } from "vue";
+import { load } from "./{{LOADER_FILE}}";
+
+let modulePromise;
+
+export function useGoWasm() {
+  const module = shallowRef(null);
+  const loading = ref(true);
+  const error = shallowRef(null);
+
+  modulePromise ??= load();
+  modulePromise
+    .then(
+      (loaded) => (module.value = loaded),
+      (failure) => (error.value = failure),
+    )
+    .finally(() => (loading.value = false));
+
+  return { module, loading, error };
+}
@@ -0,0 +1,25 @@
+#!/bin/sh
+# Generated by wasmgo. Serves {{COMPONENT}} with `wasmtime serve` and checks
+# that a request to / is answered without a server error.
+set -eu
+
+cd "$(dirname "$0")"
+ADDR="${ADDR:-127.0.0.1:8080}"
+
+wasmtime serve -Scli --addr "$ADDR" {{COMPONENT}} &
+SERVER_PID=$!
+trap 'kill $SERVER_PID 2>/dev/null || true' EXIT
+
+for _ in 1 2 3 4 5 6 7 8 9 10; do
+    STATUS=$(curl -s -o /dev/null -w '%{http_code}' "http://$ADDR/" || true)
+    if [ "$STATUS" != "000" ]; then
+        break
+    fi
+    sleep 0.5
+done
+
+case "$STATUS" in
+    000) echo "❌ {{COMPONENT}} did not start listening on $ADDR"; exit 1 ;;
+    5*) echo "❌ {{COMPONENT}} answered with HTTP $STATUS"; exit 1 ;;
+    *) echo "✅ {{COMPONENT}} answered with HTTP $STATUS" ;;
+esac
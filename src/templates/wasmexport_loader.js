@@ -0,0 +1,80 @@
+// Generated by wasmgo. Loads {{WASM_FILE}} (a go:wasmexport reactor module)
+// and exposes its exported functions as plain JavaScript functions.
+
+const ERRNO_SUCCESS = 0;
+const ERRNO_BADF = 8;
+const ERRNO_NOSYS = 52;
+
+// Minimal wasi_snapshot_preview1 host: enough for the Go runtime to start,
+// write to the console and read clocks/randomness. Everything else reports ENOSYS.
+function createWasiImports(getMemory) {
+  const decoder = new TextDecoder();
+  const view = () => new DataView(getMemory().buffer);
+  const lineBuffers = { 1: "", 2: "" };
+
+  const implemented = {
+    args_sizes_get(argcPtr, bufSizePtr) {
+      view().setUint32(argcPtr, 0, true);
+      view().setUint32(bufSizePtr, 0, true);
+      return ERRNO_SUCCESS;
+    },
+    args_get: () => ERRNO_SUCCESS,
+    environ_sizes_get(countPtr, bufSizePtr) {
+      view().setUint32(countPtr, 0, true);
+      view().setUint32(bufSizePtr, 0, true);
+      return ERRNO_SUCCESS;
+    },
+    environ_get: () => ERRNO_SUCCESS,
+    clock_time_get(clockId, precision, timePtr) {
+      const nanos = clockId === 0
+        ? BigInt(Date.now()) * 1000000n
+        : BigInt(Math.round(performance.now() * 1e6));
+      view().setBigUint64(timePtr, nanos, true);
+      return ERRNO_SUCCESS;
+    },
+    random_get(bufPtr, bufLen) {
+      crypto.getRandomValues(new Uint8Array(getMemory().buffer, bufPtr, bufLen));
+      return ERRNO_SUCCESS;
+    },
+    fd_write(fd, iovsPtr, iovsLen, nwrittenPtr) {
+      let written = 0;
+      for (let i = 0; i < iovsLen; i++) {
+        const ptr = view().getUint32(iovsPtr + i * 8, true);
+        const len = view().getUint32(iovsPtr + i * 8 + 4, true);
+        const text = decoder.decode(new Uint8Array(getMemory().buffer, ptr, len));
+        if (fd === 1 || fd === 2) {
+          const lines = (lineBuffers[fd] + text).split("\n");
+          lineBuffers[fd] = lines.pop();
+          lines.forEach((line) => (fd === 1 ? console.log(line) : console.error(line)));
+        }
+        written += len;
+      }
+      view().setUint32(nwrittenPtr, written, true);
+      return ERRNO_SUCCESS;
+    },
+    fd_prestat_get: () => ERRNO_BADF,
+    sched_yield: () => ERRNO_SUCCESS,
+    proc_exit(code) {
+      throw new Error(`{{WASM_FILE}} exited with code ${code}`);
+    },
+  };
+
+  return new Proxy(implemented, {
+    get: (target, name) => target[name] ?? (() => ERRNO_NOSYS),
+  });
+}
+
+export async function load(source = new URL("{{WASM_FILE}}", import.meta.url)) {
+  const bytes = await (await fetch(source)).arrayBuffer();
+  let memory;
+  const { instance } = await WebAssembly.instantiate(bytes, {
+    wasi_snapshot_preview1: createWasiImports(() => memory),
+  });
+  memory = instance.exports.memory;
+  instance.exports._initialize();
+
+  const exports = instance.exports;
+  return {
+{{EXPORT_BINDINGS}}
+  };
+}
@@ -0,0 +1,10 @@
+// Generated by wasmgo. Types for {{CLIENT_FILE}}: the functions exported by
+// {{WASM_FILE}}, called in its Web Worker.
+
+export interface GoWasmWorker {
+{{EXPORT_SIGNATURES}}
+  call(name: string, ...args: unknown[]): Promise<unknown>;
+  terminate(): void;
+}
+
+export function createWorker(url?: string | URL): GoWasmWorker;
@@ -0,0 +1,45 @@
+// Generated by wasmgo. Main-thread client for {{WORKER_FILE}}, which runs
+// {{WASM_FILE}} in a dedicated Web Worker.
+//
+//   const worker = createWorker();
+//   const sum = await worker.add(1, 2);
+//
+// Each exported function is an async method; types are in {{TYPES_FILE}}.
+
+export function createWorker(url = new URL("{{WORKER_FILE}}", import.meta.url)) {
+  const worker = new Worker(url, { type: "module" });
+  const pending = new Map();
+  let nextId = 0;
+
+  worker.onmessage = ({ data: { id, result, error } }) => {
+    const request = pending.get(id);
+    pending.delete(id);
+    if (error === undefined) {
+      request.resolve(result);
+    } else {
+      request.reject(new Error(error));
+    }
+  };
+  worker.onerror = (event) => {
+    for (const request of pending.values()) {
+      request.reject(new Error(event.message));
+    }
+    pending.clear();
+  };
+
+  const call = (name, ...args) => {
+    const id = nextId++;
+    return new Promise((resolve, reject) => {
+      pending.set(id, { resolve, reject });
+      worker.postMessage({ id, name, args });
+    });
+  };
+
+  return {
+{{EXPORT_METHODS}}
+    call,
+    terminate() {
+      worker.terminate();
+    },
+  };
+}
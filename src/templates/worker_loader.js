@@ -0,0 +1,17 @@
+// Generated by wasmgo. Dedicated Web Worker hosting {{WASM_FILE}}: calls
+// posted by {{CLIENT_FILE}} run here, off the main thread.
+import { load } from "./{{LOADER_FILE}}";
+
+const ready = load(new URL("{{WASM_FILE}}", import.meta.url));
+
+self.onmessage = async ({ data: { id, name, args } }) => {
+  try {
+    const module = await ready;
+    if (typeof module[name] !== "function") {
+      throw new Error(`{{WASM_FILE}} does not export ${name}`);
+    }
+    self.postMessage({ id, result: await module[name](...args) });
+  } catch (error) {
+    self.postMessage({ id, error: String(error?.message ?? error) });
+  }
+};
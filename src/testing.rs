@@ -0,0 +1,247 @@
+//! Fixtures for integration-testing code that drives the plugin, without real compiles.
+//!
+//! ```no_run
+//! use wasmgo::testing::{assert_artifact, assert_build_succeeded, FakeToolchain, TempGoProject};
+//! use wasmgo::{Plugin, WasmGoPlugin};
+//!
+//! let toolchain = FakeToolchain::new().unwrap();
+//! toolchain.install();
+//! let project = TempGoProject::new("hello").unwrap();
+//!
+//! let result = WasmGoPlugin::new()
+//!     .get_builder()
+//!     .compile(&project.compile_config());
+//! let result = assert_build_succeeded(&result);
+//! assert_artifact(result, "hello.wasm");
+//! ```
+
+use crate::{
+    CompileConfig, CompileResult, PathResolver, PluginError, PluginResult, ToolRegistry, WasmBinary,
+};
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Smallest valid core module, produced by fake builds unless configured otherwise.
+pub const EMPTY_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+fn fixture_directory(kind: &str) -> PluginResult<tempfile::TempDir> {
+    PathResolver::private_directory(&std::env::temp_dir(), &format!("wasmgo-{kind}-"))
+}
+
+/// A Go module in a private temp directory, removed on drop.
+pub struct TempGoProject {
+    directory: PathBuf,
+    _parent: tempfile::TempDir,
+}
+
+impl TempGoProject {
+    /// Creates `go.mod` for module `name` and a hello-world `main.go`.
+    ///
+    /// Names that are not valid module paths still name the directory, for
+    /// exercising unusual project locations against [`FakeToolchain`].
+    pub fn new(name: impl AsRef<OsStr>) -> PluginResult<Self> {
+        let name = name.as_ref();
+        let parent = fixture_directory("project")?;
+        // The builder names artifacts after the project directory
+        let directory = parent.path().join(name);
+        fs::create_dir_all(&directory).map_err(PluginError::Io)?;
+        let project = Self {
+            directory,
+            _parent: parent,
+        };
+        project.write_file(
+            "go.mod",
+            &format!("module {}\n\ngo 1.24\n", name.to_string_lossy()),
+        )?;
+        project.write_file(
+            "main.go",
+            "package main\n\nimport \"fmt\"\n\nfunc main() {\n\tfmt.Println(\"hello\")\n}\n",
+        )?;
+        Ok(project)
+    }
+
+    /// Adds or replaces a file, creating parent directories.
+    pub fn with_file(self, relative_path: &str, contents: &str) -> PluginResult<Self> {
+        self.write_file(relative_path, contents)?;
+        Ok(self)
+    }
+
+    pub fn write_file(&self, relative_path: &str, contents: &str) -> PluginResult<()> {
+        let path = self.directory.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(PluginError::Io)?;
+        }
+        fs::write(path, contents).map_err(PluginError::Io)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Default configuration building this project into its `dist/` directory.
+    pub fn compile_config(&self) -> CompileConfig {
+        CompileConfig {
+            project_path: self.directory.clone(),
+            output_directory: PathBuf::from("dist"),
+            ..CompileConfig::default()
+        }
+    }
+}
+
+/// Shell-script stand-ins for `go` and `tinygo` that write a fixed module to
+/// the `-o` path of `build` and log every invocation. Removed on drop.
+///
+/// Only available on Unix, where the scripts can be executed directly.
+#[cfg(unix)]
+pub struct FakeToolchain {
+    directory: tempfile::TempDir,
+}
+
+#[cfg(unix)]
+impl FakeToolchain {
+    /// Fakes TinyGo 0.33.0 and Go 1.24.1 producing [`EMPTY_MODULE`].
+    pub fn new() -> PluginResult<Self> {
+        let directory = fixture_directory("toolchain")?;
+        let toolchain = Self { directory };
+        toolchain.set_module(EMPTY_MODULE)?;
+        fs::create_dir_all(toolchain.directory.path().join("root/targets"))
+            .map_err(PluginError::Io)?;
+        fs::create_dir_all(toolchain.directory.path().join("root/lib/wasm"))
+            .map_err(PluginError::Io)?;
+        for wasm_exec in ["root/targets/wasm_exec.js", "root/lib/wasm/wasm_exec.js"] {
+            fs::write(
+                toolchain.directory.path().join(wasm_exec),
+                "// fake wasm_exec.js\n",
+            )
+            .map_err(PluginError::Io)?;
+        }
+        toolchain.write_script(
+            "tinygo",
+            "version) echo \"tinygo version 0.33.0 linux/amd64\";;\n  env) echo \"$FAKE_ROOT\";;",
+        )?;
+        toolchain.write_script(
+            "go",
+            "version) echo \"go version go1.24.1 linux/amd64\";;\n  env) if [ \"$2\" = -json ]; then echo '{\"GOVERSION\":\"go1.24.1\"}'; else echo \"$FAKE_ROOT\"; fi;;",
+        )?;
+        Ok(toolchain)
+    }
+
+    /// Module written by subsequent fake builds.
+    pub fn set_module(&self, module: &[u8]) -> PluginResult<()> {
+        fs::write(self.directory.path().join("module.wasm"), module).map_err(PluginError::Io)
+    }
+
+    /// Directory holding the scripts, to put first on `PATH`.
+    pub fn bin_directory(&self) -> &Path {
+        self.directory.path()
+    }
+
+    /// `PATH` with the fake tools first.
+    pub fn path_variable(&self) -> OsString {
+        let existing = std::env::var_os("PATH").unwrap_or_default();
+        let directories = std::iter::once(self.directory.path().to_path_buf())
+            .chain(std::env::split_paths(&existing).filter(|path| !path.as_os_str().is_empty()));
+        // Only fails for directories containing the separator itself
+        std::env::join_paths(directories)
+            .unwrap_or_else(|_| self.directory.path().as_os_str().to_os_string())
+    }
+
+    /// Puts the fake tools first on this process's `PATH`.
+    ///
+    /// `PATH` is process-wide, so tests using different fakes must not run in parallel.
+    pub fn install(&self) {
+        std::env::set_var("PATH", self.path_variable());
+        ToolRegistry::refresh();
+    }
+
+    /// Invocations so far, one `tool arg...` line each.
+    pub fn calls(&self) -> Vec<String> {
+        fs::read_to_string(self.directory.path().join("calls.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    fn write_script(&self, tool: &str, cases: &str) -> PluginResult<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = self.directory.path().display();
+        let script = format!(
+            "#!/bin/sh\nFAKE_ROOT='{directory}/root'\necho \"{tool} $*\" >> '{directory}/calls.log'\ncase \"$1\" in\n  {cases}\n  build) out=\"\"; while [ $# -gt 0 ]; do [ \"$1\" = -o ] && out=\"$2\"; shift; done\n         cp '{directory}/module.wasm' \"$out\";;\nesac\nexit 0\n"
+        );
+        let path = self.directory.path().join(tool);
+        fs::write(&path, script).map_err(PluginError::Io)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).map_err(PluginError::Io)
+    }
+}
+
+/// Panics with the error unless the build succeeded.
+#[track_caller]
+pub fn assert_build_succeeded(result: &PluginResult<CompileResult>) -> &CompileResult {
+    match result {
+        Ok(compile_result) => compile_result,
+        Err(e) => panic!("expected the build to succeed, got: {e}"),
+    }
+}
+
+/// Panics unless the build failed with an error mentioning `expected`.
+#[track_caller]
+pub fn assert_build_failed(result: &PluginResult<CompileResult>, expected: &str) {
+    match result {
+        Ok(compile_result) => panic!(
+            "expected the build to fail with \"{expected}\", got {}",
+            compile_result.wasm_file_path.display()
+        ),
+        Err(e) => assert!(
+            e.to_string().contains(expected),
+            "expected the build error to mention \"{expected}\", got: {e}"
+        ),
+    }
+}
+
+/// Panics unless the build produced an existing file named `file_name`; returns its path.
+#[track_caller]
+pub fn assert_artifact(result: &CompileResult, file_name: &str) -> PathBuf {
+    let artifact = std::iter::once(&result.wasm_file_path)
+        .chain(&result.js_file_path)
+        .chain(
+            result
+                .additional_files
+                .iter()
+                .map(|artifact| &artifact.path),
+        )
+        .find(|path| path.file_name().is_some_and(|name| name == file_name))
+        .cloned();
+    match artifact {
+        Some(path) if path.is_file() => path,
+        Some(path) => panic!("artifact {} was reported but not written", path.display()),
+        None => panic!(
+            "no artifact named {file_name}; the build produced {} and {:?}",
+            result.wasm_file_path.display(),
+            result
+                .additional_files
+                .iter()
+                .map(|artifact| &artifact.path)
+                .collect::<Vec<_>>()
+        ),
+    }
+}
+
+/// Panics unless the built module exports every function in `expected`.
+#[track_caller]
+pub fn assert_wasm_exports(result: &CompileResult, expected: &[&str]) {
+    let binary = match WasmBinary::read(&result.wasm_file_path) {
+        Ok(binary) => binary,
+        Err(e) => panic!("{e}"),
+    };
+    let exported = binary.exported_function_names();
+    for name in expected {
+        assert!(
+            exported.contains(name),
+            "{} does not export {name}; exports: {exported:?}",
+            result.wasm_file_path.display()
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{ExternalKind, PluginError, PluginResult, WasmBinary};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const THREADS_LOADER_TEMPLATE: &str = include_str!("templates/threads_loader.js");
+const THREADS_WORKER_TEMPLATE: &str = include_str!("templates/threads_worker.js");
+
+/// LLVM features threads builds turn on.
+pub const THREADS_LLVM_FEATURES: &[&str] = &["atomics", "bulk-memory"];
+
+/// Ceiling of a threads build's linear memory (256 MiB); a shared memory must
+/// declare its maximum up front.
+pub const THREADS_MAXIMUM_MEMORY: u64 = 256 * 1024 * 1024;
+
+/// The experimental threads target: a TinyGo wasip1 module with shared
+/// memory and atomics that spawns threads through wasi-threads
+/// (`wasi:thread-spawn`), plus the worker-pool glue that runs it in a browser.
+pub struct ThreadsTarget;
+
+impl ThreadsTarget {
+    /// Properties of the TinyGo target file derived from wasip1.
+    pub fn tinygo_target_properties() -> serde_json::Value {
+        serde_json::json!({
+            "scheduler": "threads",
+            "ldflags": [
+                "--shared-memory",
+                "--import-memory",
+                format!("--max-memory={THREADS_MAXIMUM_MEMORY}"),
+            ],
+        })
+    }
+
+    /// Checks the toolchain produced a threaded module. Returns its imported
+    /// shared memory: module, name, initial and maximum pages.
+    pub fn validate(wasm_file_path: &Path) -> PluginResult<(String, String, u64, u64)> {
+        let binary = WasmBinary::read(wasm_file_path)?;
+        // Older TinyGo versions ignore or lack the threads scheduler for wasip1
+        let unsupported = |missing: &str| PluginError::UnsupportedTarget {
+            reason: format!(
+                "{} has no {missing}; this TinyGo cannot build threaded wasm",
+                wasm_file_path.display()
+            ),
+        };
+        let memory_import = binary
+            .imports
+            .iter()
+            .find(|import| import.kind == ExternalKind::Memory)
+            .ok_or_else(|| unsupported("imported memory"))?;
+        // Imported memories come first in the memory index space
+        let limits = binary
+            .memories
+            .first()
+            .filter(|limits| limits.shared)
+            .ok_or_else(|| unsupported("shared memory"))?;
+        if !binary
+            .exports
+            .iter()
+            .any(|export| export.name == "wasi_thread_start")
+        {
+            return Err(unsupported("wasi_thread_start export"));
+        }
+        Ok((
+            memory_import.module.clone(),
+            memory_import.name.clone(),
+            limits.minimum_pages,
+            limits
+                .maximum_pages
+                .unwrap_or(THREADS_MAXIMUM_MEMORY / 65536),
+        ))
+    }
+
+    /// Validates the module and writes `<name>.js`, whose `run()` starts it
+    /// on a worker pool, and `<name>.thread.js`, the worker running each
+    /// thread. Returns both paths.
+    pub fn write_glue(wasm_file_path: &Path) -> PluginResult<(PathBuf, PathBuf)> {
+        let (memory_module, memory_name, initial_pages, maximum_pages) =
+            Self::validate(wasm_file_path)?;
+        let file_name = |extension: &str| {
+            wasm_file_path
+                .with_extension(extension)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+        let fill = |template: &str| {
+            template
+                .replace("{{WASM_FILE}}", &file_name("wasm"))
+                .replace("{{THREAD_FILE}}", &file_name("thread.js"))
+                .replace("{{MEMORY_MODULE}}", &memory_module)
+                .replace("{{MEMORY_NAME}}", &memory_name)
+                .replace("{{INITIAL_PAGES}}", &initial_pages.to_string())
+                .replace("{{MAXIMUM_PAGES}}", &maximum_pages.to_string())
+        };
+
+        let loader_path = wasm_file_path.with_extension("js");
+        fs::write(&loader_path, fill(THREADS_LOADER_TEMPLATE)).map_err(PluginError::Io)?;
+        let worker_path = wasm_file_path.with_extension("thread.js");
+        fs::write(&worker_path, fill(THREADS_WORKER_TEMPLATE)).map_err(PluginError::Io)?;
+        Ok((loader_path, worker_path))
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{CommandExecutor, OptimizationLevel, PluginError, PluginResult, ToolRegistry};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Compiler backend used to produce the WebAssembly module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Toolchain {
+    #[default]
+    TinyGo,
+    Go,
+}
+
+/// A WebAssembly target reported by an installed toolchain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolchainTarget {
+    pub toolchain: Toolchain,
+    /// TinyGo `-target` name or Go `GOOS/GOARCH` port
+    pub name: String,
+    pub description: String,
+}
+
+impl Toolchain {
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            Toolchain::TinyGo => "tinygo",
+            Toolchain::Go => "go",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Toolchain::TinyGo => "TinyGo",
+            Toolchain::Go => "Go",
+        }
+    }
+
+    /// The `wasm_exec.js` runtime glue shipped with the installed toolchain.
+    ///
+    /// It must match the compiler that produced the module, so it is never bundled.
+    pub fn locate_wasm_exec(&self) -> PluginResult<PathBuf> {
+        let candidates: &[&str] = match self {
+            Toolchain::TinyGo => &["targets/wasm_exec.js"],
+            // Moved from misc/wasm to lib/wasm in Go 1.24
+            Toolchain::Go => &["lib/wasm/wasm_exec.js", "misc/wasm/wasm_exec.js"],
+        };
+
+        let root = self.root_directory()?;
+        candidates
+            .iter()
+            .map(|candidate| root.join(candidate))
+            .find(|path| path.is_file())
+            .ok_or_else(|| PluginError::CompileToolNotFound {
+                tool: format!(
+                    "wasm_exec.js for {} (looked in {}={})",
+                    self.display_name(),
+                    self.root_variable(),
+                    root.display()
+                ),
+            })
+    }
+
+    /// WebAssembly targets the installed toolchain supports: the wasm entries of
+    /// `tinygo targets`, or the `*/wasm` ports of `go tool dist list`.
+    pub fn wasm_targets(&self) -> PluginResult<Vec<ToolchainTarget>> {
+        let arguments: &[&str] = match self {
+            Toolchain::TinyGo => &["targets"],
+            Toolchain::Go => &["tool", "dist", "list"],
+        };
+        let output = CommandExecutor::execute_command(
+            self.command_name(),
+            arguments,
+            Path::new("."),
+            false,
+        )?;
+        if !output.status.success() {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!(
+                    "{} {} failed: {}",
+                    self.command_name(),
+                    arguments.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+        let names = String::from_utf8_lossy(&output.stdout).to_string();
+        let names = names.lines().map(str::trim).filter(|name| !name.is_empty());
+
+        let targets = match self {
+            Toolchain::TinyGo => {
+                let targets_directory = self.root_directory().ok().map(|root| root.join("targets"));
+                names
+                    .filter_map(|name| {
+                        let definition: Option<serde_json::Value> =
+                            targets_directory.as_ref().and_then(|directory| {
+                                let content =
+                                    std::fs::read_to_string(directory.join(format!("{name}.json")))
+                                        .ok()?;
+                                serde_json::from_str(&content).ok()
+                            });
+                        let field = |key: &str| {
+                            definition
+                                .as_ref()
+                                .and_then(|definition| definition.get(key))
+                                .and_then(serde_json::Value::as_str)
+                                .map(str::to_string)
+                        };
+                        let llvm_target = field("llvm-target");
+                        let is_wasm = llvm_target
+                            .as_deref()
+                            .map_or(name.contains("wasm") || name.contains("wasi"), |triple| {
+                                triple.starts_with("wasm")
+                            });
+                        is_wasm.then(|| {
+                            let description = Self::known_target(name)
+                                .map(str::to_string)
+                                .unwrap_or_else(|| match (field("goos"), llvm_target) {
+                                    (Some(goos), Some(triple)) => {
+                                        format!("GOOS={goos}, LLVM {triple}")
+                                    }
+                                    (None, Some(triple)) => format!("LLVM {triple}"),
+                                    (Some(goos), None) => format!("GOOS={goos}"),
+                                    (None, None) => "no description available".to_string(),
+                                });
+                            ToolchainTarget {
+                                toolchain: *self,
+                                name: name.to_string(),
+                                description,
+                            }
+                        })
+                    })
+                    .collect()
+            }
+            Toolchain::Go => names
+                .filter_map(|port| {
+                    let (goos, goarch) = port.split_once('/')?;
+                    (goarch == "wasm").then(|| ToolchainTarget {
+                        toolchain: *self,
+                        name: port.to_string(),
+                        description: Self::known_target(goos)
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("GOOS={goos} GOARCH=wasm")),
+                    })
+                })
+                .collect(),
+        };
+        Ok(targets)
+    }
+
+    /// Short descriptions for target names both toolchains use.
+    fn known_target(name: &str) -> Option<&'static str> {
+        match name {
+            "wasm" | "js" => Some("browsers and Node.js through wasm_exec.js (GOOS=js)"),
+            "wasi" | "wasip1" => Some("WASI preview 1 runtimes such as wasmtime and wazero"),
+            "wasip2" => Some("WASI preview 2 components"),
+            "wasm-unknown" => Some("freestanding module without JS or WASI imports"),
+            _ => None,
+        }
+    }
+
+    fn root_variable(&self) -> &'static str {
+        match self {
+            Toolchain::TinyGo => "TINYGOROOT",
+            Toolchain::Go => "GOROOT",
+        }
+    }
+
+    fn root_directory(&self) -> PluginResult<PathBuf> {
+        let root_output = CommandExecutor::execute_command(
+            self.command_name(),
+            &["env", self.root_variable()],
+            Path::new("."),
+            false,
+        )?;
+        Ok(CommandExecutor::output_path(&root_output.stdout))
+    }
+
+    /// Compiler flags implementing an optimization level.
+    pub fn optimization_arguments(&self, level: &OptimizationLevel) -> &'static [&'static str] {
+        match (self, level) {
+            (Toolchain::TinyGo, OptimizationLevel::Debug) => &["-opt=1"],
+            (Toolchain::TinyGo, OptimizationLevel::Release) => &["-opt=2"],
+            (Toolchain::TinyGo, OptimizationLevel::Size) => &["-opt=z", "-no-debug"],
+            (Toolchain::Go, OptimizationLevel::Debug) => &["-gcflags=all=-N -l"],
+            (Toolchain::Go, OptimizationLevel::Release) => &[],
+            (Toolchain::Go, OptimizationLevel::Size) => &["-ldflags=-s -w"],
+        }
+    }
+}
+
+/// A Go release version such as `go1.24.1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GoVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GoVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses `go1.24`, `go1.24.1`, `1.24rc1` and similar spellings.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().trim_start_matches("go");
+        let mut components = version.split('.').map(|component| {
+            let digits: String = component
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits.parse::<u32>().ok()
+        });
+
+        let major = components.next()??;
+        let minor = components.next().flatten().unwrap_or(0);
+        let patch = components.next().flatten().unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+
+    /// Version of the `go` binary on PATH, from the cached `go version`.
+    pub fn installed() -> Option<Self> {
+        // "go version go1.24.1 linux/amd64"
+        ToolRegistry::version_output("go")?
+            .split_whitespace()
+            .nth(2)
+            .and_then(Self::parse)
+    }
+
+    /// Go release TinyGo builds against, from the cached `tinygo version`.
+    pub fn used_by_tinygo() -> Option<Self> {
+        // "tinygo version 0.33.0 linux/amd64 (using go version go1.22.5 and LLVM version 18.1.2)"
+        let output = ToolRegistry::version_output("tinygo")?;
+        let (_, rest) = output.split_once("using go version")?;
+        rest.split_whitespace().next().and_then(Self::parse)
+    }
+
+    /// The Go version `toolchain` compiles with.
+    pub fn for_toolchain(toolchain: Toolchain) -> Option<Self> {
+        match toolchain {
+            Toolchain::TinyGo => Self::used_by_tinygo(),
+            Toolchain::Go => Self::installed(),
+        }
+    }
+}
+
+impl std::fmt::Display for GoVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
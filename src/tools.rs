@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+static PROBES: OnceLock<Mutex<HashMap<String, ToolProbe>>> = OnceLock::new();
+
+/// What running a tool's version command found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolProbe {
+    pub installed: bool,
+    /// Standard output of the version command when it succeeded
+    pub version_output: Option<String>,
+}
+
+/// Process-wide cache of external tool detection.
+///
+/// Each tool is probed once (`tinygo version`, `wasm-opt --version`, ...) and
+/// the result reused by every later check in the same command. Call
+/// [`Self::refresh`] after something may have installed or removed tools.
+pub struct ToolRegistry;
+
+impl ToolRegistry {
+    pub fn probe(tool_name: &str) -> ToolProbe {
+        if let Some(probe) = Self::probes().get(tool_name) {
+            return probe.clone();
+        }
+        // Probed without the lock so slow tools don't block other lookups
+        let probe = Self::run_probe(tool_name);
+        Self::probes().insert(tool_name.to_string(), probe.clone());
+        probe
+    }
+
+    pub fn is_installed(tool_name: &str) -> bool {
+        Self::probe(tool_name).installed
+    }
+
+    pub fn version_output(tool_name: &str) -> Option<String> {
+        Self::probe(tool_name).version_output
+    }
+
+    /// Forgets every cached probe, so the next check runs the tools again.
+    pub fn refresh() {
+        Self::probes().clear();
+    }
+
+    fn probes() -> std::sync::MutexGuard<'static, HashMap<String, ToolProbe>> {
+        PROBES
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn run_probe(tool_name: &str) -> ToolProbe {
+        let version_arg = match tool_name {
+            "tinygo" | "go" | "wazero" => "version",
+            _ => "--version",
+        };
+
+        let output = Command::new(tool_name)
+            .arg(version_arg)
+            .stdin(Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success());
+        ToolProbe {
+            installed: output.is_some(),
+            version_output: output
+                .map(|output| String::from_utf8_lossy(&output.stdout).to_string()),
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{CommandExecutor, PathResolver, PluginError, PluginResult, ToolCommand};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// GitHub repository whose releases carry prebuilt binaries.
+pub const RELEASES_REPOSITORY: &str = "anistark/wasmgo";
+
+const CRATE_NAME: &str = "wasmgo";
+
+/// Where a newer release was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseSource {
+    GitHub,
+    CratesIo,
+}
+
+/// The newest published wasmgo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    pub source: ReleaseSource,
+    /// Prebuilt binary or archive for this host, from the GitHub release
+    pub asset_url: Option<String>,
+    /// SHA-256 published next to the asset (`<asset>.sha256`)
+    pub checksum_url: Option<String>,
+}
+
+impl Release {
+    /// Whether it is newer than the running binary.
+    pub fn is_newer(&self) -> bool {
+        match (
+            SelfUpdater::parse_version(&self.version),
+            SelfUpdater::parse_version(SelfUpdater::current_version()),
+        ) {
+            (Some(latest), Some(current)) => latest > current,
+            _ => false,
+        }
+    }
+}
+
+/// How [`SelfUpdater::install`] replaced the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpgradeMethod {
+    /// Downloaded the prebuilt binary and renamed it over this one
+    Prebuilt(PathBuf),
+    /// Ran `cargo install`, which replaces the binary in cargo's bin directory
+    CargoInstall,
+}
+
+/// Checks for and installs newer wasmgo releases.
+///
+/// GitHub releases are asked first, then crates.io. Downloads go through
+/// `curl`; a prebuilt binary is verified against its published checksum, run
+/// once with `--version`, and only then renamed over the running executable.
+/// Without a prebuilt binary and checksum for the host, the release is built
+/// with `cargo install`.
+pub struct SelfUpdater;
+
+impl SelfUpdater {
+    pub fn current_version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    pub fn latest_release(verbose: bool) -> PluginResult<Release> {
+        Self::require("curl")?;
+        match Self::latest_github_release(verbose) {
+            Ok(release) => Ok(release),
+            Err(github_error) => {
+                Self::latest_crates_io_release(verbose).map_err(|e| PluginError::ExecutionFailed {
+                    reason: format!(
+                        "no release information: GitHub: {github_error}; crates.io: {e}"
+                    ),
+                })
+            }
+        }
+    }
+
+    /// Installs `release`, preferring its prebuilt binary unless `use_cargo`.
+    pub fn install(
+        release: &Release,
+        use_cargo: bool,
+        verbose: bool,
+    ) -> PluginResult<UpgradeMethod> {
+        match (&release.asset_url, &release.checksum_url, use_cargo) {
+            (Some(asset_url), Some(_), false) => {
+                let executable = std::env::current_exe().map_err(PluginError::Io)?;
+                let executable = fs::canonicalize(&executable).unwrap_or(executable);
+                Self::install_prebuilt(release, asset_url, &executable, verbose)?;
+                Ok(UpgradeMethod::Prebuilt(executable))
+            }
+            _ => {
+                Self::cargo_install(&release.version, verbose)?;
+                Ok(UpgradeMethod::CargoInstall)
+            }
+        }
+    }
+
+    /// `1.2.3` or `v1.2.3`; pre-releases are not offered.
+    pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+        let mut parts = version.trim().trim_start_matches('v').split('.');
+        let mut next = || parts.next()?.parse::<u64>().ok();
+        let version = (next()?, next()?, next()?);
+        parts.next().is_none().then_some(version)
+    }
+
+    fn latest_github_release(verbose: bool) -> PluginResult<Release> {
+        let url = format!("https://api.github.com/repos/{RELEASES_REPOSITORY}/releases/latest");
+        let release = Self::fetch_json(&url, verbose)?;
+        let version = release["tag_name"]
+            .as_str()
+            .map(|tag| tag.trim_start_matches('v').to_string())
+            .ok_or_else(|| PluginError::ExecutionFailed {
+                reason: format!("{url} has no tag_name"),
+            })?;
+        let assets: Vec<(String, String)> = release["assets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|asset| {
+                Some((
+                    asset["name"].as_str()?.to_string(),
+                    asset["browser_download_url"].as_str()?.to_string(),
+                ))
+            })
+            .collect();
+        let asset = assets
+            .iter()
+            .find(|(name, _)| Self::matches_host(name) && !name.ends_with(".sha256"));
+        let checksum_url = asset.and_then(|(name, _)| {
+            assets
+                .iter()
+                .find(|(candidate, _)| *candidate == format!("{name}.sha256"))
+                .map(|(_, url)| url.clone())
+        });
+        Ok(Release {
+            version,
+            source: ReleaseSource::GitHub,
+            asset_url: asset.map(|(_, url)| url.clone()),
+            checksum_url,
+        })
+    }
+
+    fn latest_crates_io_release(verbose: bool) -> PluginResult<Release> {
+        let url = format!("https://crates.io/api/v1/crates/{CRATE_NAME}");
+        let metadata = Self::fetch_json(&url, verbose)?;
+        let version = metadata["crate"]["max_stable_version"]
+            .as_str()
+            .ok_or_else(|| PluginError::ExecutionFailed {
+                reason: format!("{url} has no max_stable_version"),
+            })?;
+        Ok(Release {
+            version: version.to_string(),
+            source: ReleaseSource::CratesIo,
+            asset_url: None,
+            checksum_url: None,
+        })
+    }
+
+    /// Asset names carry the target triple, e.g.
+    /// `wasmgo-x86_64-unknown-linux-gnu.tar.gz`.
+    fn matches_host(asset_name: &str) -> bool {
+        let name = asset_name.to_lowercase();
+        let operating_systems: &[&str] = match std::env::consts::OS {
+            "macos" => &["apple", "darwin", "macos"],
+            "windows" => &["windows"],
+            os => &[os],
+        };
+        let architectures: &[&str] = match std::env::consts::ARCH {
+            "aarch64" => &["aarch64", "arm64"],
+            "x86_64" => &["x86_64", "amd64"],
+            arch => &[arch],
+        };
+        name.starts_with(CRATE_NAME)
+            && operating_systems.iter().any(|os| name.contains(os))
+            && architectures.iter().any(|arch| name.contains(arch))
+    }
+
+    fn install_prebuilt(
+        release: &Release,
+        asset_url: &str,
+        executable: &Path,
+        verbose: bool,
+    ) -> PluginResult<()> {
+        // Next to the executable, so the final rename stays on one filesystem
+        let staging = PathResolver::private_directory(
+            executable.parent().unwrap_or(Path::new(".")),
+            ".wasmgo-upgrade-",
+        )?;
+        Self::stage_and_swap(release, asset_url, executable, staging.path(), verbose)
+    }
+
+    fn stage_and_swap(
+        release: &Release,
+        asset_url: &str,
+        executable: &Path,
+        staging: &Path,
+        verbose: bool,
+    ) -> PluginResult<()> {
+        let asset_name = asset_url.rsplit('/').next().unwrap_or("asset");
+        let download = staging.join(asset_name);
+        Self::download(asset_url, &download, verbose)?;
+        let checksum_url =
+            release
+                .checksum_url
+                .as_deref()
+                .ok_or_else(|| PluginError::ExecutionFailed {
+                    reason: format!("{asset_name} has no published checksum ({asset_name}.sha256)"),
+                })?;
+        let checksum_file = staging.join(format!("{asset_name}.sha256"));
+        Self::download(checksum_url, &checksum_file, verbose)?;
+        Self::verify_checksum(&download, &checksum_file)?;
+
+        let binary_name = format!("{CRATE_NAME}{}", std::env::consts::EXE_SUFFIX);
+        let binary = if [".tar.gz", ".tgz", ".tar.xz", ".zip"]
+            .iter()
+            .any(|extension| asset_name.ends_with(extension))
+        {
+            Self::require("tar")?;
+            let unpacked = staging.join("unpacked");
+            fs::create_dir_all(&unpacked).map_err(PluginError::Io)?;
+            // bsdtar, the tar shipped with Windows and macOS, also reads zip
+            let mut command = ToolCommand::new("tar");
+            command.arg("-xf").arg(&download).arg("-C").arg(&unpacked);
+            Self::run(command, verbose)?;
+            Self::find_file(&unpacked, &binary_name).ok_or_else(|| {
+                PluginError::ExecutionFailed {
+                    reason: format!("{asset_name} does not contain {binary_name}"),
+                }
+            })?
+        } else {
+            download
+        };
+
+        let replacement = staging.join(format!("{binary_name}.new"));
+        fs::rename(&binary, &replacement).map_err(PluginError::Io)?;
+        Self::check_binary(&replacement, &release.version, verbose)?;
+        Self::swap(&replacement, executable)
+    }
+
+    fn download(url: &str, destination: &Path, verbose: bool) -> PluginResult<()> {
+        let mut command = ToolCommand::new("curl");
+        command
+            .args(["-fsSL", "--retry", "2", "-o"])
+            .arg(destination)
+            .arg(url);
+        Self::run(command, verbose)
+    }
+
+    /// GETs a JSON document with curl.
+    pub fn fetch_json(url: &str, verbose: bool) -> PluginResult<serde_json::Value> {
+        Self::require("curl")?;
+        let mut command = ToolCommand::new("curl");
+        command.args([
+            "-fsSL",
+            "--retry",
+            "2",
+            "--max-time",
+            "15",
+            "-H",
+            "Accept: application/json",
+        ]);
+        // crates.io rejects requests without a user agent
+        command.args([
+            "-A".to_string(),
+            format!("{CRATE_NAME}/{}", Self::current_version()),
+        ]);
+        // Lifts GitHub's rate limit; passed in a file so it never shows in a
+        // printed command line
+        let token = std::env::var("GITHUB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty() && url.starts_with("https://api.github.com/"));
+        // Removed when it drops, after curl has run
+        let mut header_directory = None;
+        if let Some(token) = token {
+            let directory =
+                PathResolver::private_directory(&std::env::temp_dir(), ".wasmgo-upgrade-")?;
+            let header_file = directory.path().join("headers");
+            PathResolver::write_private(&header_file, &format!("Authorization: Bearer {token}\n"))?;
+            let mut header_argument = std::ffi::OsString::from("@");
+            header_argument.push(&header_file);
+            command.arg("-H").arg(header_argument);
+            header_directory = Some(directory);
+        }
+        command.arg(url);
+        let output = command.output(verbose);
+        drop(header_directory);
+        let output = output?;
+        if !output.status.success() {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!("{url}: {}", String::from_utf8_lossy(&output.stderr).trim()),
+            });
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| PluginError::ExecutionFailed {
+            reason: format!("{url}: {e}"),
+        })
+    }
+
+    fn verify_checksum(file: &Path, checksum_file: &Path) -> PluginResult<()> {
+        let published = fs::read_to_string(checksum_file).map_err(PluginError::Io)?;
+        let published = published
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let actual: String = Sha256::digest(fs::read(file).map_err(PluginError::Io)?)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        if published != actual {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!(
+                    "checksum mismatch for {}: expected {published}, got {actual}",
+                    file.display()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Runs the new binary once, so a wrong or broken download never replaces
+    /// a working one.
+    fn check_binary(binary: &Path, version: &str, verbose: bool) -> PluginResult<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(binary, fs::Permissions::from_mode(0o755))
+                .map_err(PluginError::Io)?;
+        }
+        let mut command = ToolCommand::new(binary);
+        command.arg("--version");
+        let output = command.output(verbose)?;
+        let reported = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() || !reported.contains(version) {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!(
+                    "downloaded binary does not report version {version}: {}",
+                    reported.trim()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Renames `replacement` over `executable`. Windows can't replace a running
+    /// executable, but can rename it out of the way first.
+    fn swap(replacement: &Path, executable: &Path) -> PluginResult<()> {
+        if cfg!(windows) {
+            let previous = executable.with_extension("old.exe");
+            let _ = fs::remove_file(&previous);
+            fs::rename(executable, &previous).map_err(PluginError::Io)?;
+            if let Err(e) = fs::rename(replacement, executable) {
+                let _ = fs::rename(&previous, executable);
+                return Err(PluginError::Io(e));
+            }
+            Ok(())
+        } else {
+            fs::rename(replacement, executable).map_err(PluginError::Io)
+        }
+    }
+
+    fn cargo_install(version: &str, verbose: bool) -> PluginResult<()> {
+        Self::require("cargo")?;
+        let mut command = ToolCommand::new("cargo");
+        command.args([
+            "install",
+            CRATE_NAME,
+            "--locked",
+            "--features",
+            "cli",
+            "--version",
+            version,
+        ]);
+        Self::run(command, verbose)
+    }
+
+    fn run(command: ToolCommand, verbose: bool) -> PluginResult<()> {
+        let output = command.output(verbose)?;
+        if !output.status.success() {
+            return Err(PluginError::ExecutionFailed {
+                reason: format!(
+                    "{} failed: {}",
+                    command.program().to_string_lossy(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn find_file(directory: &Path, name: &str) -> Option<PathBuf> {
+        for entry in fs::read_dir(directory).ok()?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Some(found) = Self::find_file(&path, name) {
+                    return Some(found);
+                }
+            } else if path.file_name().is_some_and(|file_name| file_name == name) {
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    fn require(tool: &str) -> PluginResult<()> {
+        if CommandExecutor::is_tool_installed(tool) {
+            Ok(())
+        } else {
+            Err(PluginError::CompileToolNotFound {
+                tool: tool.to_string(),
+            })
+        }
+    }
+}
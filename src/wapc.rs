@@ -0,0 +1,92 @@
+use crate::{ExternalKind, PluginError, PluginResult, WasmBinary, WasmKind};
+use std::path::Path;
+
+/// Host module waPC guests import the protocol functions from.
+pub const WAPC_HOST_MODULE: &str = "wapc";
+
+/// Entry point the host invokes for every operation.
+pub const WAPC_GUEST_CALL: &str = "__guest_call";
+
+/// Imports every guest needs to read its request and answer it.
+const REQUIRED_IMPORTS: &[&str] = &["__guest_request", "__guest_response", "__guest_error"];
+
+/// Further functions a waPC host provides.
+const OPTIONAL_IMPORTS: &[&str] = &[
+    "__host_call",
+    "__host_response_len",
+    "__host_response",
+    "__host_error_len",
+    "__host_error",
+    "__console_log",
+];
+
+/// Checks that a module implements the waPC guest protocol.
+pub struct WapcValidator;
+
+impl WapcValidator {
+    /// Returns the host functions the guest calls beyond the required ones.
+    pub fn validate(wasm_file_path: &Path) -> PluginResult<Vec<String>> {
+        let binary = WasmBinary::read(wasm_file_path)?;
+        let invalid = |reason: String| PluginError::InvalidWasm {
+            path: wasm_file_path.to_string_lossy().to_string(),
+            reason,
+        };
+
+        if binary.kind != WasmKind::CoreModule {
+            return Err(invalid(
+                "waPC guests are core modules, not components".to_string(),
+            ));
+        }
+
+        let guest_call = binary
+            .exports
+            .iter()
+            .find(|export| export.kind == ExternalKind::Function && export.name == WAPC_GUEST_CALL)
+            .ok_or_else(|| invalid(format!("missing {WAPC_GUEST_CALL} export; register handlers with wapc.RegisterFunctions")))?;
+        let takes_sizes = binary
+            .function_type(guest_call.index)
+            .is_some_and(|function_type| {
+                function_type.params == ["i32", "i32"] && function_type.results == ["i32"]
+            });
+        if !takes_sizes {
+            return Err(invalid(format!(
+                "{WAPC_GUEST_CALL} must have the signature (operation_size: i32, payload_size: i32) -> i32"
+            )));
+        }
+
+        let wapc_imports: Vec<&str> = binary
+            .imports
+            .iter()
+            .filter(|import| import.module == WAPC_HOST_MODULE)
+            .map(|import| import.name.as_str())
+            .collect();
+        let missing: Vec<&str> = REQUIRED_IMPORTS
+            .iter()
+            .copied()
+            .filter(|required| !wapc_imports.contains(required))
+            .collect();
+        if !missing.is_empty() {
+            return Err(invalid(format!(
+                "missing {WAPC_HOST_MODULE} imports {}",
+                missing.join(", ")
+            )));
+        }
+        let unknown: Vec<&str> = wapc_imports
+            .iter()
+            .copied()
+            .filter(|name| !REQUIRED_IMPORTS.contains(name) && !OPTIONAL_IMPORTS.contains(name))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(invalid(format!(
+                "imports {} are not part of the waPC protocol",
+                unknown.join(", ")
+            )));
+        }
+
+        Ok(wapc_imports
+            .into_iter()
+            .filter(|name| OPTIONAL_IMPORTS.contains(name))
+            .map(str::to_string)
+            .collect())
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::process::Output;
+
+/// What a toolchain warning is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningKind {
+    /// A deprecated flag, target or API
+    Deprecation,
+    /// A function's stack frame exceeds LLVM's limit
+    LargeStackFrame,
+    /// A requested wasm feature the target doesn't support and ignores
+    FeatureDowngrade,
+    Other,
+}
+
+impl WarningKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Deprecation => "deprecation",
+            Self::LargeStackFrame => "large stack frame",
+            Self::FeatureDowngrade => "feature downgrade",
+            Self::Other => "warning",
+        }
+    }
+}
+
+/// A non-fatal message from a successful toolchain run, with how often it
+/// was printed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildWarning {
+    pub kind: WarningKind,
+    pub message: String,
+    pub occurrences: usize,
+}
+
+impl BuildWarning {
+    /// The distinct warnings in a compiler run's stderr and stdout, in the
+    /// order first printed.
+    pub fn collect(output: &Output) -> Vec<Self> {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Self::parse(&format!("{stderr}\n{stdout}"))
+    }
+
+    pub fn parse(output: &str) -> Vec<Self> {
+        let mut warnings: Vec<Self> = Vec::new();
+        for line in output.lines().map(str::trim) {
+            let Some(kind) = Self::classify(line) else {
+                continue;
+            };
+            match warnings.iter_mut().find(|warning| warning.message == line) {
+                Some(warning) => warning.occurrences += 1,
+                None => warnings.push(Self {
+                    kind,
+                    message: line.to_string(),
+                    occurrences: 1,
+                }),
+            }
+        }
+        warnings
+    }
+
+    fn classify(line: &str) -> Option<WarningKind> {
+        let lower = line.to_ascii_lowercase();
+        // LLVM: '+simd128' is not a recognized feature for this target (ignoring feature)
+        if lower.contains("not a recognized feature") || lower.contains("ignoring feature") {
+            return Some(WarningKind::FeatureDowngrade);
+        }
+        // Not `go: downloading example.com/deprecated`
+        if lower.contains(" is deprecated") || lower.contains("deprecated:") {
+            return Some(WarningKind::Deprecation);
+        }
+        if !lower.contains("warning:") {
+            return None;
+        }
+        Some(if lower.contains("deprecated") {
+            WarningKind::Deprecation
+        } else if lower.contains("stack frame size") || lower.contains("stack size") {
+            WarningKind::LargeStackFrame
+        } else if lower.contains("not supported") || lower.contains("unsupported") {
+            WarningKind::FeatureDowngrade
+        } else {
+            WarningKind::Other
+        })
+    }
+}
+
+impl fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.kind.name(), self.message)?;
+        if self.occurrences > 1 {
+            write!(f, " (×{})", self.occurrences)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{PluginError, PluginResult};
+use serde::Serialize;
+use std::path::Path;
+
+const WASM_MAGIC: &[u8; 4] = b"\0asm";
+const CORE_MODULE_VERSION: [u8; 4] = [0x01, 0x00, 0x00, 0x00];
+const COMPONENT_VERSION: [u8; 4] = [0x0d, 0x00, 0x01, 0x00];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WasmKind {
+    CoreModule,
+    Component,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalKind {
+    Function,
+    Table,
+    Memory,
+    Global,
+    Tag,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WasmSection {
+    pub id: u8,
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WasmImport {
+    pub module: String,
+    pub name: String,
+    pub kind: ExternalKind,
+    /// Type index for function imports
+    pub type_index: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WasmExport {
+    pub name: String,
+    pub kind: ExternalKind,
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionType {
+    pub params: Vec<String>,
+    pub results: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryLimits {
+    pub minimum_pages: u64,
+    pub maximum_pages: Option<u64>,
+    pub shared: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataSegment {
+    /// Constant memory offset for active segments
+    pub memory_offset: Option<i64>,
+    /// Offset of the segment's bytes within the binary
+    pub file_offset: usize,
+    #[serde(skip)]
+    pub bytes: Vec<u8>,
+}
+
+/// Sections, imports and exports of a WebAssembly binary.
+///
+/// Components are recognised by their header; only core modules are parsed further.
+#[derive(Debug, Clone, Serialize)]
+pub struct WasmBinary {
+    pub kind: WasmKind,
+    pub size: usize,
+    pub sections: Vec<WasmSection>,
+    pub types: Vec<FunctionType>,
+    pub imports: Vec<WasmImport>,
+    /// Type index of each function defined by the module
+    pub functions: Vec<u32>,
+    pub memories: Vec<MemoryLimits>,
+    pub exports: Vec<WasmExport>,
+    pub data_segments: Vec<DataSegment>,
+}
+
+impl WasmBinary {
+    pub fn read(path: &Path) -> PluginResult<Self> {
+        let bytes = std::fs::read(path).map_err(PluginError::Io)?;
+        Self::parse(&bytes).map_err(|reason| PluginError::InvalidWasm {
+            path: path.to_string_lossy().to_string(),
+            reason,
+        })
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 8 || &bytes[..4] != WASM_MAGIC {
+            return Err("missing \\0asm header".to_string());
+        }
+
+        let kind = match &bytes[4..8] {
+            version if version == CORE_MODULE_VERSION => WasmKind::CoreModule,
+            version if version == COMPONENT_VERSION => WasmKind::Component,
+            version => return Err(format!("unsupported binary version {version:02x?}")),
+        };
+
+        let mut binary = Self {
+            kind,
+            size: bytes.len(),
+            sections: Vec::new(),
+            types: Vec::new(),
+            imports: Vec::new(),
+            functions: Vec::new(),
+            memories: Vec::new(),
+            exports: Vec::new(),
+            data_segments: Vec::new(),
+        };
+        if kind == WasmKind::Component {
+            return Ok(binary);
+        }
+
+        let mut reader = Reader::new(bytes, 8);
+        while !reader.at_end() {
+            let section_offset = reader.position;
+            let id = reader.byte()?;
+            let size = reader.u32()? as usize;
+            let content_start = reader.position;
+            let content = reader.bytes(size)?;
+            let mut section = Reader::new(bytes, content_start);
+            section.end = content_start + content.len();
+
+            let name = match id {
+                0 => format!("custom:{}", section.name()?),
+                _ => Self::section_name(id).to_string(),
+            };
+            binary.sections.push(WasmSection {
+                id,
+                name,
+                offset: section_offset,
+                size: reader.position - section_offset,
+            });
+
+            // Malformed or unknown payloads are tolerated: the section list stays accurate
+            let _ = match id {
+                1 => binary.parse_types(&mut section),
+                2 => binary.parse_imports(&mut section),
+                3 => binary.parse_functions(&mut section),
+                5 => binary.parse_memories(&mut section),
+                7 => binary.parse_exports(&mut section),
+                11 => binary.parse_data(&mut section),
+                _ => Ok(()),
+            };
+        }
+
+        Ok(binary)
+    }
+
+    pub fn section_name(id: u8) -> &'static str {
+        match id {
+            0 => "custom",
+            1 => "type",
+            2 => "import",
+            3 => "function",
+            4 => "table",
+            5 => "memory",
+            6 => "global",
+            7 => "export",
+            8 => "start",
+            9 => "element",
+            10 => "code",
+            11 => "data",
+            12 => "datacount",
+            13 => "tag",
+            _ => "unknown",
+        }
+    }
+
+    /// Signature of a function by its index in the function index space.
+    pub fn function_type(&self, function_index: u32) -> Option<&FunctionType> {
+        let imported_functions: Vec<&WasmImport> = self
+            .imports
+            .iter()
+            .filter(|import| import.kind == ExternalKind::Function)
+            .collect();
+
+        let type_index = match imported_functions.get(function_index as usize) {
+            Some(import) => import.type_index?,
+            None => *self
+                .functions
+                .get(function_index as usize - imported_functions.len())?,
+        };
+        self.types.get(type_index as usize)
+    }
+
+    pub fn exported_function_names(&self) -> Vec<&str> {
+        self.exports
+            .iter()
+            .filter(|export| export.kind == ExternalKind::Function)
+            .map(|export| export.name.as_str())
+            .collect()
+    }
+
+    pub fn imported_modules(&self) -> Vec<&str> {
+        let mut modules: Vec<&str> = self
+            .imports
+            .iter()
+            .map(|import| import.module.as_str())
+            .collect();
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+
+    pub fn section_size(&self, name: &str) -> usize {
+        self.sections
+            .iter()
+            .filter(|section| section.name == name)
+            .map(|section| section.size)
+            .sum()
+    }
+
+    fn parse_types(&mut self, reader: &mut Reader) -> Result<(), String> {
+        for _ in 0..reader.u32()? {
+            if reader.byte()? != 0x60 {
+                return Err("unsupported type form".to_string());
+            }
+            let params = reader.value_types()?;
+            let results = reader.value_types()?;
+            self.types.push(FunctionType { params, results });
+        }
+        Ok(())
+    }
+
+    fn parse_imports(&mut self, reader: &mut Reader) -> Result<(), String> {
+        for _ in 0..reader.u32()? {
+            let module = reader.name()?;
+            let name = reader.name()?;
+            let (kind, type_index) = match reader.byte()? {
+                0x00 => (ExternalKind::Function, Some(reader.u32()?)),
+                0x01 => {
+                    reader.byte()?;
+                    reader.limits()?;
+                    (ExternalKind::Table, None)
+                }
+                0x02 => {
+                    self.memories.push(reader.limits()?);
+                    (ExternalKind::Memory, None)
+                }
+                0x03 => {
+                    reader.byte()?;
+                    reader.byte()?;
+                    (ExternalKind::Global, None)
+                }
+                0x04 => {
+                    reader.byte()?;
+                    (ExternalKind::Tag, Some(reader.u32()?))
+                }
+                other => return Err(format!("unknown import kind {other:#x}")),
+            };
+            self.imports.push(WasmImport {
+                module,
+                name,
+                kind,
+                type_index,
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_functions(&mut self, reader: &mut Reader) -> Result<(), String> {
+        for _ in 0..reader.u32()? {
+            self.functions.push(reader.u32()?);
+        }
+        Ok(())
+    }
+
+    fn parse_memories(&mut self, reader: &mut Reader) -> Result<(), String> {
+        for _ in 0..reader.u32()? {
+            self.memories.push(reader.limits()?);
+        }
+        Ok(())
+    }
+
+    fn parse_exports(&mut self, reader: &mut Reader) -> Result<(), String> {
+        for _ in 0..reader.u32()? {
+            let name = reader.name()?;
+            let kind = match reader.byte()? {
+                0x00 => ExternalKind::Function,
+                0x01 => ExternalKind::Table,
+                0x02 => ExternalKind::Memory,
+                0x03 => ExternalKind::Global,
+                0x04 => ExternalKind::Tag,
+                other => return Err(format!("unknown export kind {other:#x}")),
+            };
+            let index = reader.u32()?;
+            self.exports.push(WasmExport { name, kind, index });
+        }
+        Ok(())
+    }
+
+    fn parse_data(&mut self, reader: &mut Reader) -> Result<(), String> {
+        for _ in 0..reader.u32()? {
+            let memory_offset = match reader.u32()? {
+                0 => reader.constant_expression()?,
+                1 => None,
+                2 => {
+                    reader.u32()?;
+                    reader.constant_expression()?
+                }
+                other => return Err(format!("unknown data segment flags {other}")),
+            };
+            let length = reader.u32()? as usize;
+            let file_offset = reader.position;
+            let bytes = reader.bytes(length)?.to_vec();
+            self.data_segments.push(DataSegment {
+                memory_offset,
+                file_offset,
+                bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    end: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], position: usize) -> Self {
+        Self {
+            bytes,
+            position,
+            end: bytes.len(),
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.end
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        if self.position >= self.end {
+            return Err("unexpected end of binary".to_string());
+        }
+        let byte = self.bytes[self.position];
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.end)
+            .ok_or("section extends past end of binary")?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn leb128(&mut self, signed: bool) -> Result<i128, String> {
+        let mut result: i128 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            result |= ((byte & 0x7f) as i128) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if signed && shift < 128 && byte & 0x40 != 0 {
+                    result |= -1i128 << shift;
+                }
+                return Ok(result);
+            }
+            if shift >= 70 {
+                return Err("LEB128 value too long".to_string());
+            }
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(self.leb128(false)? as u32)
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(self.leb128(false)? as u64)
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let length = self.u32()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(length)?).to_string())
+    }
+
+    fn value_types(&mut self) -> Result<Vec<String>, String> {
+        let count = self.u32()?;
+        (0..count)
+            .map(|_| {
+                Ok(match self.byte()? {
+                    0x7f => "i32",
+                    0x7e => "i64",
+                    0x7d => "f32",
+                    0x7c => "f64",
+                    0x7b => "v128",
+                    0x70 => "funcref",
+                    0x6f => "externref",
+                    other => return Err(format!("unsupported value type {other:#x}")),
+                }
+                .to_string())
+            })
+            .collect()
+    }
+
+    fn limits(&mut self) -> Result<MemoryLimits, String> {
+        let flags = self.byte()?;
+        let minimum_pages = self.u64()?;
+        let maximum_pages = if flags & 0x01 != 0 {
+            Some(self.u64()?)
+        } else {
+            None
+        };
+        Ok(MemoryLimits {
+            minimum_pages,
+            maximum_pages,
+            shared: flags & 0x02 != 0,
+        })
+    }
+
+    // Evaluates `i32.const n end` / `i64.const n end`; other expressions yield None
+    fn constant_expression(&mut self) -> Result<Option<i64>, String> {
+        let value = match self.byte()? {
+            0x41 | 0x42 => Some(self.leb128(true)? as i64),
+            _ => None,
+        };
+        while self.byte()? != 0x0b {}
+        Ok(value)
+    }
+}
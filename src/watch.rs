@@ -0,0 +1,268 @@
+use crate::{
+    CommandExecutor, CompileConfig, CompileResult, InterruptHandler, PluginError, PluginResult,
+    ShellStyle, WasmBuilder,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Files whose changes require a rebuild, besides `.go` sources.
+const WATCHED_FILE_NAMES: &[&str] = &["go.mod", "go.sum", "go.work", "go.work.sum"];
+
+/// Placeholder for the built module's path in post-rebuild commands.
+pub const ARTIFACT_PLACEHOLDER: &str = "{wasm}";
+
+/// Time between source scans of [`GoBuilder::watch`](crate::GoBuilder::watch).
+pub const WATCH_INTERVAL: Duration = Duration::from_millis(300);
+
+/// What [`SourceWatcher::rebuild`] reports, in order: a build, then for
+/// every change the changed paths and the rebuild.
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// Watched files changed, including deleted ones; a rebuild follows. Empty
+    /// for a rebuild requested some other way (see [`SourceWatcher::rebuild_on`]).
+    Changed(Vec<PathBuf>),
+    Built(PluginResult<CompileResult>),
+}
+
+/// Polls a Go project's sources for changes.
+///
+/// Polling keeps the plugin free of platform file-notification APIs; a
+/// project's sources are few enough that a scan every few hundred
+/// milliseconds is cheap.
+pub struct SourceWatcher {
+    project_directory: PathBuf,
+    ignored_directories: Vec<PathBuf>,
+    interval: Duration,
+    snapshot: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl SourceWatcher {
+    /// Watches `project_directory`, ignoring `ignored_directories` (the build
+    /// output) and hidden directories.
+    pub fn new(
+        project_directory: &Path,
+        ignored_directories: &[PathBuf],
+        interval: Duration,
+    ) -> Self {
+        let mut watcher = Self {
+            project_directory: project_directory.to_path_buf(),
+            ignored_directories: ignored_directories.to_vec(),
+            interval,
+            snapshot: BTreeMap::new(),
+        };
+        watcher.snapshot = watcher.scan();
+        watcher
+    }
+
+    /// Builds `configuration`, then rebuilds whenever its sources change,
+    /// until `on_event` breaks. The build output is not watched.
+    pub fn rebuild(
+        builder: &dyn WasmBuilder,
+        configuration: &CompileConfig,
+        interval: Duration,
+        on_event: impl FnMut(WatchEvent) -> ControlFlow<()>,
+    ) {
+        let mut watcher = Self::for_build(configuration, interval);
+        Self::rebuild_on(
+            builder,
+            configuration,
+            std::iter::from_fn(|| Some(watcher.wait_for_change())),
+            on_event,
+        );
+    }
+
+    /// Like [`Self::rebuild`], but rebuilds for each item of `changes`, such
+    /// as a channel fed by a watcher from [`Self::for_build`] and by other
+    /// rebuild requests. Stops when `changes` ends or `on_event` breaks.
+    pub fn rebuild_on(
+        builder: &dyn WasmBuilder,
+        configuration: &CompileConfig,
+        mut changes: impl Iterator<Item = Vec<PathBuf>>,
+        mut on_event: impl FnMut(WatchEvent) -> ControlFlow<()>,
+    ) {
+        while on_event(WatchEvent::Built(builder.compile(configuration))).is_continue() {
+            let Some(changed) = changes.next() else {
+                break;
+            };
+            if on_event(WatchEvent::Changed(changed)).is_break() {
+                break;
+            }
+        }
+    }
+
+    /// Watches the project of `configuration`, but not its build output.
+    pub fn for_build(configuration: &CompileConfig, interval: Duration) -> Self {
+        let output_directory = configuration
+            .project_path
+            .join(&configuration.output_directory);
+        Self::new(&configuration.project_path, &[output_directory], interval)
+    }
+
+    /// Blocks until watched files change and stay unchanged for one interval,
+    /// so editors saving several files trigger a single rebuild. Returns the
+    /// changed paths, including deleted ones.
+    pub fn wait_for_change(&mut self) -> Vec<PathBuf> {
+        loop {
+            thread::sleep(self.interval);
+            let mut current = self.scan();
+            if current == self.snapshot {
+                continue;
+            }
+            loop {
+                thread::sleep(self.interval);
+                let settled = self.scan();
+                if settled == current {
+                    break;
+                }
+                current = settled;
+            }
+
+            let changed = current
+                .iter()
+                .filter(|(path, modified)| self.snapshot.get(*path) != Some(modified))
+                .map(|(path, _)| path.clone())
+                .chain(
+                    self.snapshot
+                        .keys()
+                        .filter(|path| !current.contains_key(*path))
+                        .cloned(),
+                )
+                .collect();
+            self.snapshot = current;
+            return changed;
+        }
+    }
+
+    fn scan(&self) -> BTreeMap<PathBuf, SystemTime> {
+        // Resolved on every scan: the output directory may not exist before the first build
+        let ignored: Vec<PathBuf> = self
+            .ignored_directories
+            .iter()
+            .filter_map(|directory| fs::canonicalize(directory).ok())
+            .collect();
+        let mut files = BTreeMap::new();
+        Self::scan_directory(&self.project_directory, &ignored, &mut files);
+        files
+    }
+
+    fn scan_directory(
+        directory: &Path,
+        ignored: &[PathBuf],
+        files: &mut BTreeMap<PathBuf, SystemTime>,
+    ) {
+        let Ok(entries) = fs::read_dir(directory) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                let skipped = file_name.starts_with('.')
+                    || fs::canonicalize(&path).is_ok_and(|path| ignored.contains(&path));
+                if !skipped {
+                    Self::scan_directory(&path, ignored, files);
+                }
+            } else if file_name.ends_with(".go") || WATCHED_FILE_NAMES.contains(&file_name.as_str())
+            {
+                files.insert(path, metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+            }
+        }
+    }
+}
+
+/// Shell command run after each successful rebuild, with [`ARTIFACT_PLACEHOLDER`]
+/// replaced by the quoted module path.
+pub struct PostBuildCommand {
+    template: String,
+}
+
+impl PostBuildCommand {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.to_string(),
+        }
+    }
+
+    pub fn render(&self, wasm_path: &Path) -> String {
+        self.template.replace(
+            ARTIFACT_PLACEHOLDER,
+            &ShellStyle::host().quote(wasm_path.as_os_str()),
+        )
+    }
+
+    /// Runs the rendered command through the platform shell and returns its exit code.
+    pub fn run(&self, wasm_path: &Path) -> PluginResult<i32> {
+        let command_line = self.render(wasm_path);
+        let status = if cfg!(windows) {
+            InterruptHandler::status(Command::new("cmd").args(["/C", &command_line]))
+        } else {
+            InterruptHandler::status(Command::new("sh").args(["-c", &command_line]))
+        }?;
+        Ok(status.code().unwrap_or(128))
+    }
+}
+
+/// Scheduling priority of the watch process, which its compiles inherit.
+pub struct ProcessPriority;
+
+impl ProcessPriority {
+    /// Lowers this process's CPU priority (`renice`, or the below-normal priority
+    /// class on Windows) and, on Linux, its I/O priority to idle (`ionice`).
+    /// Toolchain subprocesses started afterwards inherit both.
+    pub fn lower() -> PluginResult<()> {
+        let pid = std::process::id().to_string();
+        let (tool, arguments): (&str, Vec<String>) = if cfg!(windows) {
+            (
+                "powershell",
+                vec![
+                    "-NoProfile".to_string(),
+                    "-Command".to_string(),
+                    format!("(Get-Process -Id {pid}).PriorityClass = 'BelowNormal'"),
+                ],
+            )
+        } else {
+            (
+                "renice",
+                vec![
+                    "-n".to_string(),
+                    "10".to_string(),
+                    "-p".to_string(),
+                    pid.clone(),
+                ],
+            )
+        };
+        Self::run(tool, &arguments)?;
+
+        // Best effort: ionice is Linux-only and CPU priority alone already helps
+        if cfg!(target_os = "linux") {
+            let _ = Self::run(
+                "ionice",
+                &["-c".to_string(), "3".to_string(), "-p".to_string(), pid],
+            );
+        }
+        Ok(())
+    }
+
+    fn run(tool: &str, arguments: &[String]) -> PluginResult<()> {
+        let arguments: Vec<&str> = arguments.iter().map(String::as_str).collect();
+        let output = CommandExecutor::execute_command(tool, &arguments, Path::new("."), false)?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(PluginError::ExecutionFailed {
+                reason: format!(
+                    "{tool} failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            })
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{GoExport, PluginError, PluginResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Infers a WIT interface from the functions a Go module exports.
+pub struct WitGenerator;
+
+impl WitGenerator {
+    pub fn from_exports(project_name: &str, exports: &[GoExport]) -> String {
+        let package_name = Self::kebab_case(project_name);
+        let mut wit = format!(
+            "// Generated by wasmgo from the exported functions of {project_name}.\n\
+             package wasmgo:{package_name};\n\nworld {package_name} {{\n"
+        );
+
+        for export in exports {
+            for line in export.doc_comment.iter().filter(|line| !line.is_empty()) {
+                wit.push_str(&format!("  /// {line}\n"));
+            }
+
+            let mut unmapped_types = Vec::new();
+            let mut map_type = |go_type: &str| {
+                Self::wit_type(go_type).unwrap_or_else(|| {
+                    unmapped_types.push(go_type.to_string());
+                    "u32".to_string()
+                })
+            };
+
+            let parameters: Vec<String> = export
+                .parameters
+                .iter()
+                .map(|parameter| {
+                    format!(
+                        "{}: {}",
+                        Self::kebab_case(&parameter.name),
+                        map_type(&parameter.go_type)
+                    )
+                })
+                .collect();
+            let results: Vec<String> = export
+                .results
+                .iter()
+                .map(|go_type| map_type(go_type))
+                .collect();
+            let result_signature = match results.as_slice() {
+                [] => String::new(),
+                [single] => format!(" -> {single}"),
+                multiple => format!(" -> tuple<{}>", multiple.join(", ")),
+            };
+
+            for go_type in unmapped_types {
+                wit.push_str(&format!(
+                    "  // Go type `{go_type}` has no WIT equivalent; passed as a u32 pointer\n"
+                ));
+            }
+            wit.push_str(&format!(
+                "  export {}: func({}){};\n",
+                Self::kebab_case(&export.export_name),
+                parameters.join(", "),
+                result_signature
+            ));
+        }
+
+        wit.push_str("}\n");
+        wit
+    }
+
+    pub fn write_next_to(wasm_file_path: &Path, wit_source: &str) -> PluginResult<PathBuf> {
+        let wit_path = wasm_file_path.with_extension("wit");
+        fs::write(&wit_path, wit_source).map_err(PluginError::Io)?;
+        Ok(wit_path)
+    }
+
+    /// Best-effort mapping of a Go type to its WIT equivalent. `int`, `uint` and
+    /// `uintptr` are 32 bits wide in exports on wasm32.
+    pub fn wit_type(go_type: &str) -> Option<String> {
+        if let Some(element_type) = go_type.strip_prefix("[]") {
+            return Self::wit_type(element_type).map(|element| format!("list<{element}>"));
+        }
+
+        let wit_type = match go_type {
+            "bool" => "bool",
+            "int8" => "s8",
+            "int16" => "s16",
+            "int32" | "rune" | "int" => "s32",
+            "int64" => "s64",
+            "uint8" | "byte" => "u8",
+            "uint16" => "u16",
+            "uint32" | "uint" | "uintptr" => "u32",
+            "uint64" => "u64",
+            "float32" => "f32",
+            "float64" => "f64",
+            "string" => "string",
+            _ => return None,
+        };
+        Some(wit_type.to_string())
+    }
+
+    /// WIT identifiers are lowercase kebab-case: `addNumbers`/`add_numbers` become `add-numbers`.
+    pub fn kebab_case(identifier: &str) -> String {
+        let mut kebab = String::new();
+        let mut previous_lowercase = false;
+        for c in identifier.chars() {
+            if c.is_ascii_uppercase() {
+                if previous_lowercase {
+                    kebab.push('-');
+                }
+                kebab.push(c.to_ascii_lowercase());
+                previous_lowercase = false;
+            } else if c.is_ascii_alphanumeric() {
+                kebab.push(c);
+                previous_lowercase = true;
+            } else if !kebab.ends_with('-') && !kebab.is_empty() {
+                kebab.push('-');
+                previous_lowercase = false;
+            }
+        }
+        kebab.trim_end_matches('-').to_string()
+    }
+}
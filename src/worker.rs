@@ -0,0 +1,53 @@
+use crate::{LoaderGenerator, PluginError, PluginResult, WitGenerator};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Global function the Go program registers to handle requests.
+pub const WORKER_HANDLER: &str = "wasmgoHandleRequest";
+
+// Oldest date with the module worker and CompiledWasm behaviour the entry relies on
+const WORKER_COMPATIBILITY_DATE: &str = "2024-09-23";
+
+const WORKER_TEMPLATE: &str = include_str!("templates/cloudflare_worker.mjs");
+const WRANGLER_TEMPLATE: &str = include_str!("templates/wrangler.toml");
+
+/// Emits the Cloudflare Workers entry and wrangler layout around a `js/wasm` module.
+pub struct WorkerGenerator;
+
+impl WorkerGenerator {
+    pub fn worker_entry(wasm_filename: &str) -> String {
+        WORKER_TEMPLATE
+            .replace("{{WASM_FILE}}", wasm_filename)
+            .replace("{{HANDLER}}", WORKER_HANDLER)
+    }
+
+    pub fn wrangler_config(project_name: &str) -> String {
+        WRANGLER_TEMPLATE
+            .replace("{{NAME}}", &WitGenerator::kebab_case(project_name))
+            .replace("{{COMPATIBILITY_DATE}}", WORKER_COMPATIBILITY_DATE)
+    }
+
+    /// Writes `worker.mjs`, `wasm_exec.js` and `wrangler.toml` next to the module,
+    /// returning the entry path and the other files.
+    pub fn write_layout(
+        wasm_file_path: &Path,
+        project_name: &str,
+        wasm_exec_path: &Path,
+    ) -> PluginResult<(PathBuf, Vec<PathBuf>)> {
+        let output_directory = wasm_file_path.parent().unwrap_or(Path::new("."));
+        let wasm_filename = wasm_file_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+
+        let entry_path = output_directory.join("worker.mjs");
+        fs::write(&entry_path, Self::worker_entry(&wasm_filename)).map_err(PluginError::Io)?;
+
+        let runtime_path = LoaderGenerator::copy_wasm_exec(wasm_file_path, wasm_exec_path)?;
+
+        let wrangler_path = output_directory.join("wrangler.toml");
+        fs::write(&wrangler_path, Self::wrangler_config(project_name)).map_err(PluginError::Io)?;
+
+        Ok((entry_path, vec![runtime_path, wrangler_path]))
+    }
+}
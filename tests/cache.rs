@@ -0,0 +1,32 @@
+//! Build cache keys cover sources outside the project that reach the compiler.
+
+use std::fs;
+use wasmgo::{BuildCache, CompileConfig, GoModFile};
+
+#[test]
+fn key_changes_with_a_locally_replaced_module() {
+    let temp = tempfile::tempdir().unwrap();
+    let project = temp.path().join("app");
+    let library = temp.path().join("lib");
+    fs::create_dir_all(&project).unwrap();
+    fs::create_dir_all(&library).unwrap();
+    fs::write(
+        project.join("go.mod"),
+        "module example.com/app\n\nreplace (\n\texample.com/lib => ../lib\n\texample.com/other v1.0.0 => example.com/fork v1.0.1\n)\n",
+    )
+    .unwrap();
+    fs::write(project.join("main.go"), "package main\n\nfunc main() {}\n").unwrap();
+    fs::write(library.join("lib.go"), "package lib\n").unwrap();
+
+    assert_eq!(
+        GoModFile::read(&project).unwrap().local_replacements,
+        [std::path::PathBuf::from("../lib")]
+    );
+    let configuration = CompileConfig {
+        project_path: project.clone(),
+        ..CompileConfig::default()
+    };
+    let before = BuildCache::key(&configuration).unwrap();
+    fs::write(library.join("lib.go"), "package lib\n\nconst Answer = 42\n").unwrap();
+    assert_ne!(BuildCache::key(&configuration).unwrap(), before);
+}
@@ -0,0 +1,129 @@
+//! Commands keep arguments with spaces intact, and display lines quote them
+//! for the shell they are meant to be pasted into.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use wasmgo::{ShellStyle, ToolCommand};
+
+const SPACED_PROGRAM: &str = r"C:\Program Files\Go\bin\go.exe";
+
+#[test]
+fn windows_quoting_follows_argv_parsing_rules() {
+    let quote = |argument: &str| ShellStyle::Windows.quote(OsStr::new(argument));
+    assert_eq!(quote("-opt=2"), "-opt=2");
+    assert_eq!(quote(r"C:\dist\app.wasm"), r"C:\dist\app.wasm");
+    assert_eq!(quote(SPACED_PROGRAM), r#""C:\Program Files\Go\bin\go.exe""#);
+    // A trailing backslash would otherwise escape the closing quote
+    assert_eq!(quote(r"C:\out dir\"), r#""C:\out dir\\""#);
+    assert_eq!(quote(r#"say "hi""#), r#""say \"hi\"""#);
+    assert_eq!(quote(r#"a\"b c"#), r#""a\\\"b c""#);
+    assert_eq!(quote("fish & chips"), r#""fish & chips""#);
+    assert_eq!(quote(""), r#""""#);
+}
+
+#[test]
+fn posix_quoting_keeps_spaces_and_quotes_in_one_word() {
+    let quote = |argument: &str| ShellStyle::Posix.quote(OsStr::new(argument));
+    assert_eq!(quote("./dist/app.wasm"), "./dist/app.wasm");
+    assert_eq!(quote("/tmp/my project"), "'/tmp/my project'");
+    assert_eq!(quote("it's"), r"'it'\''s'");
+    assert_eq!(quote(""), "''");
+}
+
+#[test]
+fn spaced_paths_stay_single_arguments() {
+    let output = Path::new(r"C:\Users\Jane Doe\my project\dist\my project.wasm");
+    let mut command = ToolCommand::new(SPACED_PROGRAM);
+    command
+        .args(["build", "-o"])
+        .arg(output)
+        .arg(".")
+        .env("GOOS", "js")
+        .env("GOOS", "wasip1")
+        .current_dir(r"C:\Users\Jane Doe\my project");
+
+    assert_eq!(command.program(), SPACED_PROGRAM);
+    assert_eq!(command.arguments().len(), 4);
+    assert_eq!(command.arguments()[2], output.as_os_str());
+    assert_eq!(command.environment().len(), 1);
+    assert_eq!(
+        command.display_line(ShellStyle::Windows),
+        r#"set "GOOS=wasip1" && "C:\Program Files\Go\bin\go.exe" build -o "C:\Users\Jane Doe\my project\dist\my project.wasm" ."#
+    );
+    assert_eq!(
+        command.reproduce_line(ShellStyle::Windows),
+        format!(
+            r#"cd /d "C:\Users\Jane Doe\my project" && {}"#,
+            command.display_line(ShellStyle::Windows)
+        )
+    );
+}
+
+#[test]
+fn posix_lines_reproduce_from_a_spaced_directory() {
+    let mut command = ToolCommand::new("tinygo");
+    command
+        .args(["build", "-o", "./dist/my project.wasm", "-target=wasm", "."])
+        .env("GOFLAGS", "-mod=vendor -trimpath")
+        .current_dir("/nonexistent/my project");
+    assert_eq!(
+        command.reproduce_line(ShellStyle::Posix),
+        "cd '/nonexistent/my project' && GOFLAGS='-mod=vendor -trimpath' tinygo build -o './dist/my project.wasm' -target=wasm ."
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn subprocesses_receive_spaced_arguments_unsplit() {
+    let temp = tempfile::tempdir().unwrap();
+    let directory = temp.path().join("my project");
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let mut command = ToolCommand::new("sh");
+    command
+        .args(["-c", r#"printf '%s\n' "$#" "$@" "$PWD" "$SPACED""#, "sh"])
+        .arg(directory.join("dist dir/app.wasm"))
+        .arg("two  spaces")
+        .env("SPACED", "a b")
+        .current_dir(&directory);
+    let output = command.output(false).unwrap();
+    assert!(output.status.success());
+
+    let canonical = std::fs::canonicalize(&directory).unwrap();
+    let expected = format!(
+        "2\n{}\ntwo  spaces\n{}\na b\n",
+        directory.join("dist dir/app.wasm").display(),
+        canonical.display()
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+#[cfg(unix)]
+#[test]
+fn failure_summaries_reproduce_spaced_builds() {
+    use std::os::unix::process::ExitStatusExt;
+    use wasmgo::FailureSummary;
+
+    let temp = tempfile::tempdir().unwrap();
+    let directory = temp.path().join("my project");
+    std::fs::create_dir_all(&directory).unwrap();
+    let mut command = ToolCommand::new("tinygo");
+    command
+        .args(["build", "-o", "dist/my project.wasm", "."])
+        .current_dir(&directory);
+    let output = std::process::Output {
+        status: std::process::ExitStatus::from_raw(1 << 8),
+        stdout: Vec::new(),
+        stderr: b"main.go:3:1: syntax error\n".to_vec(),
+    };
+
+    let summary = FailureSummary::new(&output, &command);
+    assert_eq!(summary.status, "tinygo exited with status 1");
+    assert_eq!(
+        summary.reproduce,
+        format!(
+            "cd '{}' && tinygo build -o 'dist/my project.wasm' .",
+            std::fs::canonicalize(&directory).unwrap().display()
+        )
+    );
+}
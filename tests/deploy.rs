@@ -0,0 +1,25 @@
+//! GitHub Pages sites are only written over empty directories or pages checkouts.
+
+use std::fs;
+use wasmgo::GhPagesDeployer;
+
+#[test]
+fn prepare_site_refuses_a_directory_that_is_not_a_pages_checkout() {
+    let temp = tempfile::tempdir().unwrap();
+    let wasm = temp.path().join("app.wasm");
+    fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+
+    let project = temp.path().join("project");
+    fs::create_dir_all(project.join(".git")).unwrap();
+    fs::write(project.join("main.go"), "package main\n").unwrap();
+    assert!(
+        GhPagesDeployer::prepare_site(std::slice::from_ref(&wasm), &project, "/", "gh-pages")
+            .is_err()
+    );
+    assert!(project.join("main.go").is_file());
+
+    let site = temp.path().join("site");
+    GhPagesDeployer::prepare_site(std::slice::from_ref(&wasm), &site, "/", "gh-pages").unwrap();
+    assert!(site.join("app.wasm").is_file());
+    assert!(site.join(".nojekyll").is_file());
+}
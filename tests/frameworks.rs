@@ -0,0 +1,55 @@
+//! The embedded registry parses, and user entries override it by name.
+
+use wasmgo::FrameworkRegistry;
+
+#[test]
+fn builtin_registry_has_unique_names_and_templates() {
+    let registry = FrameworkRegistry::builtin();
+    for (index, framework) in registry.frameworks.iter().enumerate() {
+        assert!(
+            registry.frameworks[..index]
+                .iter()
+                .all(|earlier| earlier.name != framework.name),
+            "duplicate {}",
+            framework.name
+        );
+    }
+    let standard = registry.get("standard").expect("standard framework");
+    assert!(standard.template.contains_key("main.go"));
+    assert!(standard.detect.modules.is_empty());
+}
+
+#[test]
+fn user_entries_replace_or_extend_the_builtin_ones() {
+    let mut registry = FrameworkRegistry::builtin();
+    let builtin_count = registry.frameworks.len();
+    registry.merge(
+        FrameworkRegistry::parse(
+            r#"
+[[framework]]
+name = "extism"
+title = "Extism (pinned)"
+description = "Pinned PDK"
+requires = ["github.com/extism/go-pdk v1.0.0"]
+
+[[framework]]
+name = "spin"
+title = "Spin"
+description = "Fermyon Spin components"
+tools = ["spin"]
+
+[framework.detect]
+modules = ["github.com/fermyon/spin"]
+"#,
+            "test",
+        )
+        .unwrap(),
+    );
+    assert_eq!(registry.frameworks.len(), builtin_count + 1);
+    assert_eq!(registry.get("extism").unwrap().title, "Extism (pinned)");
+    assert_eq!(
+        registry.get("spin").unwrap().detect.modules,
+        ["github.com/fermyon/spin"]
+    );
+    assert!(FrameworkRegistry::parse("[[framework]]\nname = \"x\"\n", "test").is_err());
+}
@@ -0,0 +1,34 @@
+//! wasm-unknown builds reject host packages in source and host ABIs in the module.
+
+use std::fs;
+use wasmgo::FreestandingValidator;
+
+#[test]
+fn host_packages_and_wasi_imports_are_rejected() {
+    let packages = FreestandingValidator::imported_packages(
+        "package main
+
+import \"unsafe\"
+import (
+\t\"strconv\"
+\tsys \"syscall\" // for the host
+)
+
+func main() {}
+",
+    );
+    assert_eq!(packages, ["unsafe", "strconv", "syscall"]);
+
+    let directory = tempfile::tempdir().unwrap();
+    let wasm = directory.path().join("app.wasm");
+    let mut module = b"\0asm\x01\0\0\0\x01\x04\x01\x60\0\0\x02\x23\x01\x16".to_vec();
+    module.extend(b"wasi_snapshot_preview1\x08fd_write\0\0");
+    fs::write(&wasm, module).unwrap();
+    let error = FreestandingValidator::validate(&wasm)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("wasi_snapshot_preview1.fd_write"), "{error}");
+
+    fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+    assert!(FreestandingValidator::validate(&wasm).unwrap().is_empty());
+}
@@ -0,0 +1,29 @@
+//! The built-in catalogs parse and translations only use English keys.
+
+use wasmgo::{Messages, DEFAULT_LOCALE};
+
+#[test]
+fn english_catalog_fills_placeholders_and_falls_back_to_the_key() {
+    assert_eq!(Messages::select(Some("en_US.UTF-8")), DEFAULT_LOCALE);
+    assert_eq!(
+        Messages::format("compile.failed", &[("error", &"boom")]),
+        "❌ Compilation failed: boom"
+    );
+    assert_eq!(Messages::text("no.such.key"), "no.such.key");
+    assert_eq!(Messages::select(Some("xx")), DEFAULT_LOCALE);
+}
+
+#[test]
+fn translations_use_only_english_keys() {
+    let catalog = |locale: &str| {
+        let path = format!("{}/src/locales/{locale}.toml", env!("CARGO_MANIFEST_DIR"));
+        Messages::parse(&std::fs::read_to_string(path).unwrap()).unwrap()
+    };
+    let english = catalog(DEFAULT_LOCALE);
+    assert!(english.contains_key("compile.success"));
+    for locale in Messages::locales() {
+        for key in catalog(locale).keys() {
+            assert!(english.contains_key(key), "{locale}: unknown key {key}");
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Minified glue still parses and behaves like the original under Node.js.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasmgo::{Minifier, Toolchain};
+
+/// Script exercising regex-versus-division, template and ASI handling; it
+/// prints what it computed, which the minified copy has to reproduce.
+const EDGE_CASES: &str = r#"
+// A newline before ++ binds it to the next statement
+let a = 1
+let b = a
+++b
+const half = a / 2 / 1
+const pattern = /\/\*not a comment\*\//g
+const slashes = /[/]+/.test("//")
+const kind = typeof /x/
+function source() { return /y\/z/.source }
+const ratio = (a + b) / 2 /* divided */ / 1
+const name = "go"
+const nested = `a ${`b ${name} ${ {x: 1}.x }`} // not a comment`
+const braces = `${"}"}{${'`'}` + `/* kept */`
+const url = "http://example.com/*x*/" + '//'
+function early() {
+  return
+  42
+}
+const plus = a + +b
+const minus = a - -b
+const increment = a +
+  ++b
+/*! @license MIT */
+let matched = false
+if (a) matched = /\/\//.test("//")
+// After a condition's `)`, `/` starts a regex
+let after = false
+if (a) /x\/\//.test("x//") && (after = true)
+const quotient = [4][0] / 2 / 1
+const re2 = a ? /:/ : /;/
+const obj = { r: /}/ }
+label: for (;;) { break label }
+const tpl = `${a}/${b}`
+const div = tpl.length / 1 // c
+const list = [
+  1,
+  2
+]
+console.log(JSON.stringify([a, b, half, pattern.source, slashes, kind, source(), ratio,
+  nested, braces, url, early(), plus, minus, increment, list, matched, after, quotient,
+  re2.source, obj.r.source, tpl, div]))
+"#;
+
+fn node_installed() -> bool {
+    Command::new("node").arg("--version").output().is_ok()
+}
+
+/// Whether Node.js accepts `source` as a file named `file_name`.
+fn parses(directory: &Path, file_name: &str, source: &str) -> Result<(), String> {
+    let path = directory.join(file_name);
+    fs::write(&path, source).unwrap();
+    let output = Command::new("node")
+        .arg("--check")
+        .arg(&path)
+        .output()
+        .unwrap();
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+fn run(directory: &Path, source: &str) -> String {
+    let path = directory.join("run.js");
+    fs::write(&path, source).unwrap();
+    let output = Command::new("node").arg(&path).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}\n{source}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn minified_edge_cases_behave_like_the_source() {
+    let minified = Minifier::minify_js(EDGE_CASES).code;
+    assert!(minified.contains("/*! @license MIT */"));
+    assert!(!minified.contains("divided"));
+    assert!(minified.len() < EDGE_CASES.len());
+    if !node_installed() {
+        return;
+    }
+    let directory = tempfile::tempdir().unwrap();
+    assert_eq!(
+        run(directory.path(), &minified),
+        run(directory.path(), EDGE_CASES),
+        "{minified}"
+    );
+}
+
+#[test]
+fn minified_templates_and_wasm_exec_still_parse() {
+    if !node_installed() {
+        return;
+    }
+    let templates = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/templates");
+    let mut scripts: Vec<_> = fs::read_dir(&templates)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "js" || extension == "mjs")
+        })
+        .collect();
+    // The toolchains' runtimes, where installed
+    scripts.extend(
+        [Toolchain::Go, Toolchain::TinyGo]
+            .into_iter()
+            .filter_map(|toolchain| toolchain.locate_wasm_exec().ok()),
+    );
+    assert!(!scripts.is_empty());
+
+    let directory = tempfile::tempdir().unwrap();
+    for path in scripts {
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        let source = fs::read_to_string(&path).unwrap();
+        if parses(directory.path(), &file_name, &source).is_err() {
+            continue;
+        }
+        let minified = Minifier::minify_js(&source).code;
+        if let Err(error) = parses(directory.path(), &file_name, &minified) {
+            panic!("minified {} no longer parses: {error}", path.display());
+        }
+    }
+}
+
+#[test]
+fn minified_html_keeps_working_inline_scripts() {
+    let templates = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/templates");
+    let directory = tempfile::tempdir().unwrap();
+    for page in ["index.html", "single_file.html"] {
+        let source = fs::read_to_string(templates.join(page)).unwrap();
+        let minified = Minifier::minify_html(&source);
+        assert!(minified.len() < source.len(), "{page}");
+        assert_eq!(
+            minified.matches("<script").count(),
+            source.matches("<script").count(),
+            "{page}"
+        );
+        if !node_installed() {
+            continue;
+        }
+        let lowercase = minified.to_ascii_lowercase();
+        let mut rest = lowercase.as_str();
+        let mut offset = 0;
+        while let Some(start) = rest.find("<script") {
+            let open_end = start + rest[start..].find('>').unwrap() + 1;
+            let close = open_end + rest[open_end..].find("</script").unwrap();
+            let open_tag = &rest[start..open_end];
+            let script = &minified[offset + open_end..offset + close];
+            if !open_tag.contains("src=") && !script.trim().is_empty() {
+                let file_name = if open_tag.contains("module") {
+                    "inline.mjs"
+                } else {
+                    "inline.js"
+                };
+                if let Err(error) = parses(directory.path(), file_name, script) {
+                    panic!("minified script in {page} no longer parses: {error}\n{script}");
+                }
+            }
+            offset += close;
+            rest = &lowercase[offset..];
+        }
+    }
+}
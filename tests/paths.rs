@@ -0,0 +1,168 @@
+//! Paths with spaces, non-ASCII characters and (on Unix) bytes that are not
+//! UTF-8 must reach the filesystem and subprocesses unchanged.
+
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use wasmgo::{
+    Artifact, ArtifactKind, CommandExecutor, ExportScanner, HashedArtifacts, PathResolver, Plugin,
+    SourceMirror, WasmGoPlugin,
+};
+
+const MAIN_GO: &str =
+    "package main\n\n//export add\nfunc add(a, b int32) int32 { return a + b }\n\nfunc main() {}\n";
+
+fn unusual_names() -> Vec<OsString> {
+    let mut names = vec![
+        OsString::from("with spaces"),
+        OsString::from("ünïcødé-プロジェクト"),
+    ];
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        names.push(OsStr::from_bytes(b"not-utf8-\xff\xfe").to_os_string());
+    }
+    names
+}
+
+fn go_project(parent: &Path, name: &OsStr) -> PathBuf {
+    let project = parent.join(name);
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("go.mod"), "module example\n\ngo 1.24\n").unwrap();
+    fs::write(project.join("main.go"), MAIN_GO).unwrap();
+    project
+}
+
+#[test]
+fn detects_and_scans_projects_in_unusual_directories() {
+    let temp = tempfile::tempdir().unwrap();
+    let plugin = WasmGoPlugin::new();
+    for name in unusual_names() {
+        let project = go_project(temp.path(), &name);
+        assert!(plugin.can_handle_project(&project), "{name:?}");
+        assert!(plugin.get_builder().validate_project(&project).is_ok());
+
+        let exports = ExportScanner::scan_package(&project);
+        assert_eq!(exports.len(), 1, "{name:?}");
+        assert_eq!(exports[0].export_name, "add");
+    }
+}
+
+#[test]
+fn mirrors_unusual_file_names() {
+    let temp = tempfile::tempdir().unwrap();
+    let project = temp.path().join("project");
+    for name in unusual_names() {
+        let directory = project.join(&name);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join(&name), MAIN_GO).unwrap();
+    }
+
+    let build_directory = temp.path().join("build dir");
+    let report = SourceMirror::sync(&project, &build_directory, &[]).unwrap();
+    assert_eq!(report.copied, unusual_names().len());
+    for name in unusual_names() {
+        let copy = build_directory.join(&name).join(&name);
+        assert_eq!(fs::read_to_string(copy).unwrap(), MAIN_GO);
+    }
+
+    let report = SourceMirror::sync(&project, &build_directory, &[]).unwrap();
+    assert_eq!(report.copied, 0);
+    assert_eq!(report.unchanged, unusual_names().len());
+}
+
+#[test]
+fn hashed_names_keep_the_original_stem() {
+    let temp = tempfile::tempdir().unwrap();
+    for name in unusual_names() {
+        let mut file_name = name.clone();
+        file_name.push(".wasm");
+        let module = temp.path().join(&file_name);
+        fs::write(&module, b"\0asm\x01\0\0\0").unwrap();
+
+        let hashed = HashedArtifacts::rename_with_hash(&module).unwrap();
+        assert!(hashed.is_file());
+        assert!(!module.exists());
+        assert_eq!(hashed.extension(), Some(OsStr::new("wasm")));
+        let stem = hashed.file_stem().unwrap().as_encoded_bytes();
+        let mut prefix = name.as_encoded_bytes().to_vec();
+        prefix.push(b'.');
+        assert!(stem.starts_with(&prefix), "{hashed:?}");
+    }
+}
+
+#[test]
+fn artifacts_and_directories_accept_unusual_paths() {
+    let temp = tempfile::tempdir().unwrap();
+    for name in unusual_names() {
+        let directory = PathResolver::join_paths(temp.path(), &name);
+        PathResolver::ensure_output_directory_exists(&directory).unwrap();
+        PathResolver::validate_directory_exists(&directory).unwrap();
+        assert!(PathResolver::is_writable_directory(&directory));
+        assert!(PathResolver::is_writable_directory(&directory.join(&name)));
+
+        let path = directory.join("app.wasm");
+        fs::write(&path, b"\0asm\x01\0\0\0").unwrap();
+        let artifact = Artifact::new(ArtifactKind::Wasm, &path);
+        assert_eq!(artifact.path, path);
+        assert_eq!(artifact.size, 8);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn subprocesses_receive_unusual_arguments_and_directories() {
+    let temp = tempfile::tempdir().unwrap();
+    for name in unusual_names() {
+        let directory = temp.path().join(&name);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join(&name), "contents").unwrap();
+
+        let output =
+            CommandExecutor::execute_command("ls", &[name.as_os_str()], &directory, false).unwrap();
+        assert!(output.status.success(), "{name:?}");
+        assert_eq!(
+            CommandExecutor::output_path(&output.stdout),
+            Path::new(&name)
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn output_paths_keep_non_utf8_bytes() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CommandExecutor::output_path(b"/usr/lib/\xff\xfe/tinygo\n");
+    assert_eq!(path.as_os_str().as_bytes(), b"/usr/lib/\xff\xfe/tinygo");
+}
+
+#[cfg(all(unix, feature = "testing"))]
+#[test]
+fn compiles_projects_in_unusual_directories() {
+    use wasmgo::testing::{assert_build_succeeded, FakeToolchain, TempGoProject};
+    use wasmgo::CompileConfig;
+
+    let toolchain = FakeToolchain::new().unwrap();
+    toolchain.install();
+    for name in unusual_names() {
+        let fixture = TempGoProject::new(&name)
+            .unwrap()
+            .with_file("main.go", MAIN_GO)
+            .unwrap();
+        let project = fixture.path();
+        let result = WasmGoPlugin::new().get_builder().compile(&CompileConfig {
+            output_directory: PathBuf::from("out dir"),
+            ..fixture.compile_config()
+        });
+        let result = assert_build_succeeded(&result);
+
+        let mut file_name = name.clone();
+        file_name.push(".wasm");
+        assert_eq!(
+            result.wasm_file_path,
+            project.join("out dir").join(file_name)
+        );
+        assert!(result.wasm_file_path.is_file());
+    }
+}
@@ -0,0 +1,32 @@
+//! The planned phases follow the configuration, in build order.
+
+use wasmgo::{BuildPipeline, CompileConfig, OptimizationLevel, PipelinePhase};
+
+#[test]
+fn plan_lists_configured_phases_in_order() {
+    let pipeline = BuildPipeline::plan(&CompileConfig {
+        tidy: true,
+        provenance: true,
+        smoke_test: true,
+        ..CompileConfig::default()
+    });
+    let phases: Vec<_> = pipeline.steps.iter().map(|step| step.phase).collect();
+    assert_eq!(
+        phases,
+        [
+            PipelinePhase::Tidy,
+            PipelinePhase::Compile,
+            PipelinePhase::SmokeTest,
+            PipelinePhase::Minify,
+            PipelinePhase::Provenance,
+        ]
+    );
+    assert!(pipeline.to_mermaid().contains("compile --> smoke_test\n"));
+    assert!(pipeline.to_dot().contains("\"tidy\" -> \"compile\";"));
+
+    let debug = BuildPipeline::plan(&CompileConfig {
+        optimization_level: OptimizationLevel::Debug,
+        ..CompileConfig::default()
+    });
+    assert_eq!(debug.steps.len(), 1);
+}
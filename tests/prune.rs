@@ -0,0 +1,45 @@
+//! Pruning removes expired entries, then the least recently used until the rest fits.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use wasmgo::{PrunePolicy, StateDirectory, StateEntry, StatePruner, StateUsage};
+
+#[test]
+fn prune_selects_expired_then_least_recently_used() {
+    let now = SystemTime::now();
+    let day = Duration::from_secs(24 * 60 * 60);
+    let entry = |name: &str, size: u64, days: u32| StateEntry {
+        paths: vec![PathBuf::from(name)],
+        size,
+        modified: now - day * days,
+    };
+    let usage = StateUsage {
+        directory: StateDirectory::Builds,
+        path: PathBuf::from("builds"),
+        entries: vec![entry("a", 10, 40), entry("b", 50, 20), entry("c", 30, 1)],
+    };
+
+    let selected = |policy: PrunePolicy| -> Vec<PathBuf> {
+        StatePruner::select(&usage, &policy, now)
+            .into_iter()
+            .flat_map(|entry| entry.paths)
+            .collect()
+    };
+    assert_eq!(
+        selected(PrunePolicy {
+            older_than: Some(PrunePolicy::parse_age("30d").unwrap()),
+            max_size: None,
+        }),
+        [PathBuf::from("a")]
+    );
+    assert_eq!(
+        selected(PrunePolicy {
+            older_than: None,
+            max_size: Some(PrunePolicy::parse_size("40").unwrap()),
+        }),
+        [PathBuf::from("a"), PathBuf::from("b")]
+    );
+    assert!(selected(PrunePolicy::default()).is_empty());
+    assert_eq!(PrunePolicy::parse_size("1.5K").unwrap(), 1536);
+    assert!(PrunePolicy::parse_age("30").is_err());
+}
@@ -0,0 +1,106 @@
+//! Concurrent requests for the same build share one compile, and conflicting
+//! builds of a project never overlap.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+use wasmgo::{
+    BuildQueue, CompileConfig, CompileResult, OptimizationLevel, PluginResult, WasmBuilder,
+};
+
+#[derive(Default)]
+struct SlowBuilder {
+    compiles: AtomicUsize,
+    running: AtomicUsize,
+    most_running: AtomicUsize,
+}
+
+impl WasmBuilder for SlowBuilder {
+    fn language_name(&self) -> &str {
+        "go"
+    }
+
+    fn entry_file_candidates(&self) -> &[&str] {
+        &["main.go"]
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["go"]
+    }
+
+    fn check_dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn validate_project(&self, _project_path: &Path) -> PluginResult<()> {
+        Ok(())
+    }
+
+    fn compile(&self, config: &CompileConfig) -> PluginResult<CompileResult> {
+        let ticket = self.compiles.fetch_add(1, Ordering::SeqCst);
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most_running.fetch_max(running, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(200));
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(CompileResult {
+            wasm_file_path: config.output_directory.join(format!("{ticket}.wasm")),
+            js_file_path: None,
+            additional_files: Vec::new(),
+            is_wasm_bindgen: false,
+            warnings: Vec::new(),
+            wasm_opt: None,
+        })
+    }
+}
+
+fn config(optimization_level: OptimizationLevel) -> CompileConfig {
+    CompileConfig {
+        project_path: PathBuf::from("/tmp/wasmgo-queue-project"),
+        output_directory: PathBuf::from("dist"),
+        optimization_level,
+        ..CompileConfig::default()
+    }
+}
+
+#[test]
+fn identical_requests_share_one_build() {
+    let queue = BuildQueue::new();
+    let builder = SlowBuilder::default();
+    let paths: Vec<PathBuf> = thread::scope(|scope| {
+        let requests: Vec<_> = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    queue
+                        .compile(&builder, &config(OptimizationLevel::Release))
+                        .map(|result| result.wasm_file_path)
+                })
+            })
+            .collect();
+        requests
+            .into_iter()
+            .map(|request| request.join().unwrap().unwrap())
+            .collect()
+    });
+    assert_eq!(builder.compiles.load(Ordering::SeqCst), 1);
+    assert!(paths.iter().all(|path| path == &paths[0]));
+    assert_eq!(queue.pending(), 0);
+}
+
+#[test]
+fn conflicting_requests_run_one_at_a_time() {
+    let queue = BuildQueue::new();
+    let builder = SlowBuilder::default();
+    thread::scope(|scope| {
+        for level in [
+            OptimizationLevel::Debug,
+            OptimizationLevel::Release,
+            OptimizationLevel::Size,
+        ] {
+            let (queue, builder) = (&queue, &builder);
+            scope.spawn(move || queue.compile(builder, &config(level)).unwrap());
+        }
+    });
+    assert_eq!(builder.compiles.load(Ordering::SeqCst), 3);
+    assert_eq!(builder.most_running.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,29 @@
+//! Reproduction scripts quote each argument for the shell that runs them.
+
+use wasmgo::{ReproductionScript, ToolCommand};
+
+#[test]
+fn scripts_quote_arguments_and_environment() {
+    let mut command = ToolCommand::new("tinygo");
+    command
+        .args([
+            "build",
+            "-o",
+            "out dir/app.wasm",
+            "-ldflags=-X 'main.v=1'",
+            ".",
+        ])
+        .env("GOFLAGS", "-mod=vendor");
+    let script = ReproductionScript {
+        generated_files: Vec::new(),
+        commands: vec![command],
+    };
+
+    assert!(script.to_shell().contains(
+        r#"(GOFLAGS=-mod=vendor tinygo build -o 'out dir/app.wasm' '-ldflags=-X '\''main.v=1'\''' .)"#
+    ));
+    let powershell = script.to_powershell();
+    assert!(powershell.contains("$env:GOFLAGS = '-mod=vendor'\n"));
+    assert!(powershell
+        .contains("& 'tinygo' 'build' '-o' 'out dir/app.wasm' '-ldflags=-X ''main.v=1''' '.'\n"));
+}
@@ -0,0 +1,27 @@
+//! Smoke tests instantiate modules from a private copy of the Node.js script.
+
+use std::fs;
+use std::process::Command;
+use wasmgo::SmokeTest;
+
+/// A module exporting an empty `_start`.
+const START_MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+    0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type: () -> ()
+    0x03, 0x02, 0x01, 0x00, // function 0
+    0x07, 0x0a, 0x01, 0x06, b'_', b's', b't', b'a', b'r', b't', 0x00, 0x00, // export
+    0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // empty body
+];
+
+#[test]
+fn instantiates_under_node_when_it_is_installed() {
+    let temp = tempfile::tempdir().unwrap();
+    let wasm = temp.path().join("app.wasm");
+    fs::write(&wasm, START_MODULE).unwrap();
+
+    let report = SmokeTest::run(&wasm, &["_start".to_string()]).unwrap();
+    assert_eq!(report.exports, ["_start"]);
+    let node_installed = Command::new("node").arg("--version").output().is_ok();
+    assert_eq!(report.instantiated, node_installed, "{:?}", report.skipped);
+    assert!(SmokeTest::run(&wasm, &["missing".to_string()]).is_err());
+}
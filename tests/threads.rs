@@ -0,0 +1,16 @@
+//! Threads builds only pass when the module imports a shared memory.
+
+use std::fs;
+use wasmgo::ThreadsTarget;
+
+#[test]
+fn modules_without_shared_memory_are_rejected() {
+    let directory = tempfile::tempdir().unwrap();
+    let wasm = directory.path().join("app.wasm");
+    // Empty module: magic and version only
+    fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+
+    let error = ThreadsTarget::write_glue(&wasm).unwrap_err().to_string();
+    assert!(error.contains("no imported memory"), "{error}");
+    assert!(!wasm.with_extension("js").exists());
+}
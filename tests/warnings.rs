@@ -0,0 +1,29 @@
+//! Toolchain warnings are classified and repeated lines counted once.
+
+use wasmgo::{BuildWarning, WarningKind};
+
+#[test]
+fn warnings_are_classified_and_deduplicated() {
+    let warnings = BuildWarning::parse(
+        "go: downloading example.com/deprecated v1.0.0
+'+simd128' is not a recognized feature for this target (ignoring feature)
+'+simd128' is not a recognized feature for this target (ignoring feature)
+warning: flag -no-debug is deprecated, use -opt instead
+warning: <unknown>:0:0: stack frame size (70000) exceeds limit (65536) in function 'main.run'
+main.go:3:2: warning: unused variable
+main.go:4:1: undefined: x
+",
+    );
+    let kinds: Vec<_> = warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            WarningKind::FeatureDowngrade,
+            WarningKind::Deprecation,
+            WarningKind::LargeStackFrame,
+            WarningKind::Other,
+        ]
+    );
+    assert_eq!(warnings[0].occurrences, 2);
+    assert!(warnings[0].to_string().ends_with("(×2)"));
+}
@@ -0,0 +1,33 @@
+//! Live reload: `GoBuilder::watch` rebuilds after source changes.
+
+#[cfg(all(unix, feature = "testing"))]
+#[test]
+fn watch_rebuilds_after_a_source_change() {
+    use std::fs;
+    use std::ops::ControlFlow;
+    use wasmgo::testing::{FakeToolchain, TempGoProject};
+    use wasmgo::{GoBuilder, WatchEvent};
+
+    let toolchain = FakeToolchain::new().unwrap();
+    toolchain.install();
+    let project = TempGoProject::new("example").unwrap();
+    let main_go = project.path().join("main.go");
+
+    let mut events = Vec::new();
+    GoBuilder::new().watch(&project.compile_config(), |event| {
+        let built = matches!(event, WatchEvent::Built(_));
+        match &event {
+            WatchEvent::Built(result) => assert!(result.is_ok(), "{result:?}"),
+            WatchEvent::Changed(changed) => assert_eq!(changed, std::slice::from_ref(&main_go)),
+        }
+        events.push(built);
+        if events.len() == 1 {
+            fs::write(&main_go, "package main\n\nfunc main() { println() }\n").unwrap();
+        }
+        match events.len() {
+            3 => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    });
+    assert_eq!(events, [true, false, true]);
+}
@@ -0,0 +1,96 @@
+//! Browser builds ship the toolchain's `wasm_exec.js` next to the module.
+
+#[cfg(all(unix, feature = "testing"))]
+#[test]
+fn web_builds_bundle_wasm_exec() {
+    use wasmgo::testing::{assert_build_succeeded, FakeToolchain, TempGoProject};
+    use wasmgo::{CompileConfig, Plugin, TargetType, WasmGoPlugin};
+
+    let toolchain = FakeToolchain::new().unwrap();
+    toolchain.install();
+    let project = TempGoProject::new("example").unwrap();
+
+    let result = WasmGoPlugin::new().get_builder().compile(&CompileConfig {
+        target_type: TargetType::Web,
+        ..project.compile_config()
+    });
+    let result = assert_build_succeeded(&result);
+    let wasm_exec = result.js_file_path.as_ref().unwrap();
+    assert_eq!(
+        wasm_exec,
+        &result.wasm_file_path.with_file_name("wasm_exec.js")
+    );
+    assert!(wasm_exec.is_file());
+}
+
+#[cfg(all(unix, feature = "testing"))]
+#[test]
+fn webapp_builds_write_an_index_page() {
+    use std::fs;
+    use wasmgo::testing::{assert_build_succeeded, FakeToolchain, TempGoProject};
+    use wasmgo::{
+        ArtifactKind, CompileConfig, OptimizationLevel, Plugin, TargetType, WasmGoPlugin,
+    };
+
+    let toolchain = FakeToolchain::new().unwrap();
+    toolchain.install();
+    let project = TempGoProject::new("example").unwrap();
+
+    let result = WasmGoPlugin::new().get_builder().compile(&CompileConfig {
+        target_type: TargetType::WebApp,
+        optimization_level: OptimizationLevel::Debug,
+        ..project.compile_config()
+    });
+    let result = assert_build_succeeded(&result);
+    let page = result
+        .additional_files
+        .iter()
+        .find(|artifact| artifact.kind == ArtifactKind::Html)
+        .unwrap();
+    assert_eq!(
+        page.path,
+        result.wasm_file_path.with_file_name("index.html")
+    );
+    let html = fs::read_to_string(&page.path).unwrap();
+    assert!(html.contains("<script src=\"wasm_exec.js\"></script>"));
+    assert!(html.contains(&format!(
+        "fetch(\"{}\")",
+        result.wasm_file_path.file_name().unwrap().to_string_lossy()
+    )));
+}
+
+#[cfg(unix)]
+#[test]
+fn wasm_exec_copies_of_read_only_toolchains_stay_writable() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use wasmgo::LoaderGenerator;
+
+    let temp = tempfile::tempdir().unwrap();
+    let toolchain_copy = temp.path().join("toolchain_wasm_exec.js");
+    fs::write(&toolchain_copy, "\"use strict\";\n").unwrap();
+    fs::set_permissions(&toolchain_copy, fs::Permissions::from_mode(0o444)).unwrap();
+    let wasm = temp.path().join("app.wasm");
+
+    for _ in 0..2 {
+        let copy = LoaderGenerator::copy_wasm_exec(&wasm, &toolchain_copy).unwrap();
+        let mode = fs::metadata(&copy).unwrap().permissions().mode();
+        assert_ne!(mode & 0o200, 0, "wasm_exec.js copied with mode {mode:o}");
+        fs::write(&copy, "\"use strict\";").unwrap();
+    }
+}
+
+#[test]
+fn worker_clients_reject_exports_named_after_their_own_methods() {
+    use wasmgo::{ExportScanner, LoaderGenerator};
+
+    let temp = tempfile::tempdir().unwrap();
+    let wasm = temp.path().join("app.wasm");
+    let exports = ExportScanner::scan_source(
+        "package main\n\n//go:wasmexport terminate\nfunc terminate() {}\n",
+        "main.go",
+    );
+    let error = LoaderGenerator::write_web_worker(&wasm, &exports).unwrap_err();
+    assert!(error.to_string().contains("terminate"), "{error}");
+    assert!(!wasm.with_extension("client.js").exists());
+}